@@ -0,0 +1,60 @@
+import fs from "node:fs";
+
+const todoFile = () => fs.readFileSync(process.env.TODOTXT_PATH, "utf8");
+
+/** Subjects of the todos currently listed, in order. */
+async function subjects() {
+  const items = await $$("ul[aria-label='Todos'] li span[role='button']");
+  const texts = [];
+  for (const item of items) {
+    if (await item.isDisplayed()) {
+      texts.push(await item.getText());
+    }
+  }
+  return texts;
+}
+
+async function expectSubjects(expected) {
+  await browser.waitUntil(async () => JSON.stringify(await subjects()) === JSON.stringify(expected), {
+    timeoutMsg: `expected todos ${JSON.stringify(expected)}, got ${JSON.stringify(await subjects())}`,
+  });
+}
+
+describe("todo list", () => {
+  it("shows the todos from todo.txt on launch", async () => {
+    await expectSubjects(["Call Bob", "Buy milk"]);
+  });
+
+  it("adds a todo", async () => {
+    await $("button[aria-label='Add Todo']").click();
+    await $("dialog input[aria-label='Todo text']").setValue("Water plants +garden");
+    await $("dialog button[type='submit']").click();
+
+    await expectSubjects(["Call Bob", "Buy milk", "Water plants"]);
+    expect(todoFile()).toContain("Water plants +garden");
+  });
+
+  it("completes a todo", async () => {
+    await $(`input[aria-label='Mark "Call Bob" as done']`).click();
+
+    await $(`input[aria-label='Mark "Call Bob" as not done']`).waitForExist();
+    expect(todoFile()).toContain("x Call Bob +work");
+  });
+
+  it("filters to completed todos", async () => {
+    await $("button[aria-label^='Completed']").click();
+    await expectSubjects(["Call Bob"]);
+
+    await $("button[aria-label^='Inbox']").click();
+    await expectSubjects(["Call Bob", "Buy milk", "Water plants"]);
+  });
+
+  it("keeps changes across a restart", async () => {
+    await browser.reloadSession();
+
+    await $("button[aria-label^='Completed']").click();
+    await expectSubjects(["Call Bob"]);
+    await $("button[aria-label^='Inbox']").click();
+    await expectSubjects(["Call Bob", "Buy milk", "Water plants"]);
+  });
+});
@@ -0,0 +1,52 @@
+// Drives the debug build of the app through tauri-driver (cargo install tauri-driver).
+// The app is pointed at a temp copy of fixture.txt via TODOTXT_PATH, and at
+// temp config and data dirs so the user's settings don't leak in.
+import fs from "node:fs";
+import os from "node:os";
+import path from "node:path";
+import { spawn, spawnSync } from "node:child_process";
+import { fileURLToPath } from "node:url";
+
+const here = path.dirname(fileURLToPath(import.meta.url));
+const workspace = path.resolve(here, "../..");
+const application = path.join(workspace, "target", "debug", process.platform === "win32" ? "gui.exe" : "gui");
+
+const sandbox = fs.mkdtempSync(path.join(os.tmpdir(), "todotxt-e2e-"));
+process.env.TODOTXT_PATH = path.join(sandbox, "todo.txt");
+process.env.XDG_CONFIG_HOME = path.join(sandbox, "config");
+process.env.XDG_DATA_HOME = path.join(sandbox, "data");
+
+let tauriDriver;
+
+export const config = {
+  host: "127.0.0.1",
+  port: 4444,
+  specs: ["./specs/**/*.js"],
+  maxInstances: 1,
+  capabilities: [{ maxInstances: 1, "tauri:options": { application } }],
+  reporters: ["spec"],
+  framework: "mocha",
+  mochaOpts: { ui: "bdd", timeout: 60000 },
+
+  onPrepare: () => {
+    const build = spawnSync("cargo", ["tauri", "build", "--debug", "--no-bundle"], {
+      cwd: path.join(workspace, "gui"),
+      stdio: "inherit",
+    });
+    if (build.status !== 0) {
+      throw new Error("Building the app failed");
+    }
+  },
+
+  // Every spec file starts from a fresh copy of the fixture
+  beforeSession: () => {
+    fs.copyFileSync(path.join(here, "fixture.txt"), process.env.TODOTXT_PATH);
+    tauriDriver = spawn(path.join(os.homedir(), ".cargo", "bin", "tauri-driver"), [], {
+      stdio: [null, process.stdout, process.stderr],
+    });
+  },
+
+  afterSession: () => tauriDriver?.kill(),
+
+  onComplete: () => fs.rmSync(sandbox, { recursive: true, force: true }),
+};
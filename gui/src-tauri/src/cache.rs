@@ -0,0 +1,110 @@
+//! The last parsed todo.txt, kept so commands in quick succession don't each
+//! read and parse the file again. A copy is handed out as long as the file
+//! has the modification time and size it had when it was parsed. Only if
+//! those changed, or the time is too recent to tell an edit within the same
+//! tick of a coarse clock apart, is the file read again, and parsed again
+//! only if its content hashes to something else. The app's own saves
+//! [`refresh`] it right away.
+//!
+//! Every parse gets a new [`TodoList::revision`], counting on across files,
+//! so the frontend can tell which of two lists it got is the newer one.
+
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use todotxt::TodoList;
+
+/// How long after a change the modification time is trusted to tell it from
+/// the next one. Some file systems count in whole seconds, FAT in two.
+const COARSE_CLOCK: Duration = Duration::from_secs(2);
+
+struct Cached {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+    /// When the file was read
+    read: SystemTime,
+    hash: u64,
+    list: TodoList,
+}
+
+impl Cached {
+    /// Whether the file at `path` is still the one read, judging by `meta`.
+    fn unchanged(&self, path: &Path, meta: &fs::Metadata) -> bool {
+        self.path == path
+            && self.len == meta.len()
+            && meta
+                .modified()
+                .is_ok_and(|modified| modified == self.modified)
+            && self
+                .read
+                .duration_since(self.modified)
+                .is_ok_and(|age| age > COARSE_CLOCK)
+    }
+}
+
+static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn lock() -> MutexGuard<'static, Option<Cached>> {
+    // A panic elsewhere while holding the lock leaves nothing half-written
+    CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn hash(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Reads the file at `path` into the cache, parsing it again unless its
+/// content is what was parsed last.
+fn read(cache: &mut Option<Cached>, path: &Path, reparse: bool) -> io::Result<TodoList> {
+    // Taken before reading, so a change while reading shows next time
+    let meta = fs::metadata(path)?;
+    let read = SystemTime::now();
+    let content = fs::read(path)?;
+    let hash = hash(&content);
+    let list = match cache.take() {
+        Some(cached) if !reparse && cached.path == path && cached.hash == hash => {
+            tracing::trace!(path = %path.display(), "content unchanged");
+            cached.list
+        }
+        _ => {
+            let mut list = TodoList::from_reader(content.as_slice())?;
+            list.set_path(path);
+            list.set_revision(REVISION.fetch_add(1, Ordering::Relaxed) + 1);
+            list
+        }
+    };
+    *cache = Some(Cached {
+        path: path.to_path_buf(),
+        len: meta.len(),
+        modified: meta.modified()?,
+        read,
+        hash,
+        list: list.clone(),
+    });
+    Ok(list)
+}
+
+/// The list at `path`, read again only if the file looks changed.
+pub fn load(path: &Path) -> io::Result<TodoList> {
+    let meta = fs::metadata(path)?;
+    let mut cache = lock();
+    if let Some(cached) = cache.as_ref().filter(|c| c.unchanged(path, &meta)) {
+        tracing::trace!(path = %path.display(), "list from cache");
+        return Ok(cached.list.clone());
+    }
+    read(&mut cache, path, false)
+}
+
+/// Parses the file at `path` again, e.g. right after the app wrote it.
+pub fn refresh(path: &Path) -> io::Result<()> {
+    read(&mut lock(), path, true).map(drop)
+}
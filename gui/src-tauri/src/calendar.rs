@@ -0,0 +1,283 @@
+//! A minimal HTTP server for calendar subscriptions and the web viewer.
+//!
+//! Calendar apps poll `GET /calendar.ics?token=<token>` and get the due
+//! dates rendered from the todo file at that moment, see
+//! [`todotxt::calendar::ics`]. The read-only web viewer (the frontend built
+//! with its `web` feature) reads `GET /todos.json?token=<token>`, a
+//! [`WebList`] with today's date and the project separator, and if
+//! [`CalendarSettings::web_dir`] is set its files are served from there.
+//! Nothing else is. The token may also come as `Authorization: Bearer
+//! <token>`; requests whose token isn't allowed the endpoint, see
+//! [`CalendarSettings::authorize`], are refused. In demo mode both show
+//! lorem ipsum, like any export, see [`todotxt::redact`].
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use todotxt::{calendar, TodoItem};
+use types::settings::{CalendarSettings, Endpoint};
+use types::WebList;
+
+use crate::{cache, settings};
+
+/// How long a client has to send the request line and headers, in all.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The most bytes of request line and headers read.
+const MAX_HEAD: u64 = 8 * 1024;
+const PATH: &str = "/calendar.ics";
+const TODOS_PATH: &str = "/todos.json";
+
+/// Serves the calendar of `todo_path` on the port of `settings` from a
+/// background thread, to this computer only unless
+/// [`CalendarSettings::network`] is set. Each connection gets a thread of
+/// its own, so a slow client doesn't hold up the others. The rest of the
+/// settings are read from `settings_path` as requests come in.
+pub fn serve(
+    settings: CalendarSettings,
+    todo_path: PathBuf,
+    settings_path: PathBuf,
+) -> io::Result<()> {
+    let address = if settings.network {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let listener = TcpListener::bind((address, settings.port))?;
+    let shared = Arc::new((settings, todo_path, settings_path));
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                let (settings, todo_path, settings_path) = &*shared;
+                if let Err(e) = handle(stream, settings, todo_path, settings_path) {
+                    tracing::warn!("calendar: {e}");
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn endpoint(path: &str) -> Option<Endpoint> {
+    match path {
+        PATH => Some(Endpoint::Calendar),
+        TODOS_PATH => Some(Endpoint::Todos),
+        _ => None,
+    }
+}
+
+/// A stream that stops reading at `until`, however slowly the other side
+/// sends.
+struct Deadline {
+    stream: TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self
+            .until
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+            .ok_or_else(|| io::Error::new(ErrorKind::TimedOut, "request took too long"))?;
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+/// `value` with `%XX` escapes decoded, `None` if that isn't UTF-8 or an
+/// escape is cut short.
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn handle(
+    stream: TcpStream,
+    settings: &CalendarSettings,
+    todo_path: &Path,
+    settings_path: &Path,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let until = Instant::now() + TIMEOUT;
+    let mut reader = BufReader::new(Deadline { stream, until }.take(MAX_HEAD));
+
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Only the token matters of the headers, but all have to be read before
+    // answering
+    let mut bearer = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 || !header.ends_with('\n') {
+            return respond(
+                &mut writer,
+                "431 Request Header Fields Too Large",
+                "text/plain",
+                "",
+            );
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(str::to_string);
+            }
+        }
+    }
+
+    let mut parts = request.split_whitespace();
+    let (Some("GET"), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&mut writer, "405 Method Not Allowed", "text/plain", "");
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let Some(endpoint) = endpoint(path) else {
+        return serve_file(&mut writer, settings, path);
+    };
+    let token = bearer.or_else(|| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .and_then(percent_decode)
+    });
+    if !token.is_some_and(|token| settings.authorize(&token, endpoint)) {
+        return respond(&mut writer, "403 Forbidden", "text/plain", "");
+    }
+
+    let list = cache::load(todo_path)?;
+    let app_settings = settings::load(settings_path).map_err(io::Error::other)?;
+    let items: Vec<TodoItem> = list
+        .items()
+        .iter()
+        .map(|item| crate::redact_for(&app_settings, item))
+        .collect();
+    match endpoint {
+        Endpoint::Calendar => {
+            let body = calendar::ics(&items, calendar::now());
+            respond(&mut writer, "200 OK", "text/calendar; charset=utf-8", &body)
+        }
+        Endpoint::Todos => {
+            let todos = items.iter().map(crate::item_response).collect();
+            let web_list = WebList {
+                todos: crate::revised(&list, todos),
+                today: app_settings.today().format("%Y-%m-%d").to_string(),
+                project_separator: app_settings.project_separator,
+            };
+            let body = serde_json::to_string(&web_list).map_err(io::Error::other)?;
+            respond(&mut writer, "200 OK", "application/json", &body)
+        }
+    }
+}
+
+/// The file `name` below `web_dir`, `None` if there's none or it resolves
+/// to somewhere else, e.g. through `..`, a `\` on Windows or a symlink.
+fn web_file(web_dir: &str, name: &str) -> Option<PathBuf> {
+    let dir = fs::canonicalize(web_dir).ok()?;
+    let file = fs::canonicalize(dir.join(name)).ok()?;
+    (file.starts_with(&dir) && file.is_file()).then_some(file)
+}
+
+/// Answers with the file at `path` below the web viewer's directory. The
+/// files hold no tasks, so they need no token.
+fn serve_file(writer: &mut impl Write, settings: &CalendarSettings, path: &str) -> io::Result<()> {
+    let name = match path.trim_start_matches('/') {
+        "" => "index.html",
+        name => name,
+    };
+    let body = match settings.web_dir.as_str() {
+        "" => None,
+        web_dir => web_file(web_dir, name).and_then(|file| fs::read(file).ok()),
+    };
+    let Some(body) = body else {
+        return respond(writer, "404 Not Found", "text/plain", "");
+    };
+    let content_type = match name.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript",
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    };
+    respond_bytes(writer, "200 OK", content_type, &body)
+}
+
+fn respond(
+    writer: &mut impl Write,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    respond_bytes(writer, status, content_type, body.as_bytes())
+}
+
+/// The web viewer may be hosted elsewhere, so any origin may read answers;
+/// the token still decides what they hold.
+fn respond_bytes(
+    writer: &mut impl Write,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    writer.write_all(body)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_file() {
+        let root = std::env::temp_dir().join(format!("calendar-web-{}", std::process::id()));
+        let web_dir = root.join("dist-web");
+        fs::create_dir_all(web_dir.join("assets")).unwrap();
+        fs::write(web_dir.join("index.html"), "<html>").unwrap();
+        fs::write(web_dir.join("assets/app.js"), "").unwrap();
+        fs::write(root.join("todo.txt"), "Call Bob").unwrap();
+        let dir = web_dir.to_str().unwrap();
+
+        assert!(web_file(dir, "index.html").is_some());
+        assert!(web_file(dir, "assets/app.js").is_some());
+        assert!(web_file(dir, "assets/../index.html").is_some());
+        assert!(web_file(dir, "../todo.txt").is_none());
+        assert!(web_file(dir, root.join("todo.txt").to_str().unwrap()).is_none());
+        assert!(web_file(dir, "assets").is_none());
+        assert!(web_file(dir, "missing.html").is_none());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("abc123").as_deref(), Some("abc123"));
+        assert_eq!(percent_decode("a%2Bb%3D%3d").as_deref(), Some("a+b=="));
+        assert_eq!(percent_decode("caf%C3%A9").as_deref(), Some("café"));
+        assert_eq!(percent_decode("cut%4"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%FF"), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! A few commands for the terminal, e.g. `gui add Call Mom @phone`. If the
+//! app or the daemon is running they go through it, so the window shows the
+//! change at once and nothing writes todo.txt behind its back; otherwise
+//! they edit the file themselves. See [`daemon::Request`].
+//!
+//! `gui capture <text>` puts a thought into inbox.txt to be refiled later,
+//! e.g. bound to a hotkey of the desktop.
+
+use std::path::PathBuf;
+
+use todotxt::TodoList;
+
+use crate::daemon::{self, Request, Response};
+use crate::settings::{self, Settings, SETTINGS_FILE};
+
+/// The app's identifier in tauri.conf.json, which names its directories.
+const IDENTIFIER: &str = "de.ds82.todotxt.gui";
+
+const USAGE: &str = "usage: gui list | gui add <text> | gui capture <text> | gui do <id>";
+
+/// Runs the command in the arguments, returning the exit code, or `None`
+/// if they don't name one and the app should start.
+pub fn run() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let request = match args.first().map(String::as_str) {
+        Some("list") => Request::List,
+        Some("add") if args.len() > 1 => Request::Add {
+            text: args[1..].join(" "),
+        },
+        Some("capture") if args.len() > 1 => Request::Capture {
+            text: args[1..].join(" "),
+        },
+        Some("do") => match args.get(1).map(|id| id.parse()) {
+            Some(Ok(id)) => Request::Toggle { id },
+            _ => {
+                eprintln!("{USAGE}");
+                return Some(2);
+            }
+        },
+        Some("add") | Some("capture") | Some("help") | Some("--help") => {
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+        _ => return None,
+    };
+    let response = daemon::socket_path()
+        .ok()
+        .and_then(|path| daemon::request(&path, &request))
+        .unwrap_or_else(|| offline(request));
+    match response {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{output}");
+            }
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("gui: {e}");
+            Some(1)
+        }
+    }
+}
+
+/// Where Tauri puts the app's `kind` of directory, found without the app
+/// running to ask.
+fn app_dir(kind: Option<PathBuf>) -> Result<PathBuf, String> {
+    kind.map(|dir| dir.join(IDENTIFIER))
+        .ok_or_else(|| "no home directory".to_string())
+}
+
+/// Does what `request` asks on todo.txt the way the app would, with nothing
+/// running to ask.
+fn offline(request: Request) -> Response {
+    let settings = settings::load(&app_dir(dirs::config_dir())?.join(SETTINGS_FILE))?;
+    let mut list = TodoList::from_file(crate::todo_path()).map_err(|e| e.to_string())?;
+    match request {
+        Request::Follow | Request::Save { .. } => return Err("not a daemon".to_string()),
+        Request::List => {
+            let lines: Vec<String> = list
+                .items()
+                .iter()
+                .map(|item| format!("{} {}", item.id, item.raw()))
+                .collect();
+            return Ok(lines.join("\n"));
+        }
+        Request::Add { text } => {
+            let dir = app_dir(dirs::data_dir())?.join(crate::plugins::PLUGINS_DIR);
+            let plugins = crate::load_plugins_in(&dir, &settings)?;
+            crate::add_to_list(&mut list, &settings, &plugins, &text);
+        }
+        Request::Toggle { id } => crate::toggle_in_list(&mut list, &settings, id)?,
+        Request::Capture { text } => return capture(&settings, &text),
+    }
+    list.save().map_err(|e| e.to_string())?;
+    Ok(String::new())
+}
+
+/// Adds `text` to inbox.txt next to todo.txt like the app's capture,
+/// creating the file if need be.
+fn capture(settings: &Settings, text: &str) -> Response {
+    let line = crate::captured_line(text, settings.today())?;
+    let mut inbox = crate::load_inbox()?;
+    inbox.add(&line);
+    inbox.save().map_err(|e| e.to_string())?;
+    Ok(String::new())
+}
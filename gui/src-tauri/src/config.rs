@@ -0,0 +1,135 @@
+//! The app's configuration as one portable file, to set up another machine
+//! the same way: the settings with saved filters, snippets, WIP limits and
+//! the keymap. Secrets stay behind, and so does anything that runs or
+//! serves something on this machine or lets others reach it: hooks, plugin
+//! grants, the reveal-in-editor template, sync devices and the web viewer's
+//! directory. An import keeps the ones of the machine it's imported on, so
+//! a file from elsewhere can't make it run a program or open it up.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::migrate;
+use crate::settings::Settings;
+
+/// Tells a bundle from any other JSON file.
+const KIND: &str = "todotxt-config";
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    kind: String,
+    /// Kept as JSON, so older settings can be upgraded before they're read
+    settings: Value,
+}
+
+/// `settings` with what stays on a machine taken from `local`. Tokens are
+/// matched by name; one `local` doesn't have is left without its secret.
+fn keep_local(settings: &mut Settings, local: &Settings) {
+    settings.sync.pairing_code = local.sync.pairing_code.clone();
+    settings.sync.devices = local.sync.devices.clone();
+    settings.calendar.token = local.calendar.token.clone();
+    settings.calendar.web_dir = local.calendar.web_dir.clone();
+    settings.calendar.network = local.calendar.network;
+    for token in &mut settings.calendar.tokens {
+        token.token = local
+            .calendar
+            .tokens
+            .iter()
+            .find(|t| t.name == token.name)
+            .map(|t| t.token.clone())
+            .unwrap_or_default();
+    }
+    settings.hooks = local.hooks.clone();
+    settings.plugin_grants = local.plugin_grants.clone();
+    settings.reveal_template = local.reveal_template.clone();
+}
+
+/// The bundle of `settings`, as JSON.
+pub fn export(settings: &Settings) -> Result<String, String> {
+    let mut exported = settings.clone();
+    keep_local(&mut exported, &Settings::default());
+    let bundle = Bundle {
+        kind: KIND.to_string(),
+        settings: serde_json::to_value(exported).map_err(|e| e.to_string())?,
+    };
+    serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())
+}
+
+/// The settings in the bundle `content`, upgraded to this version, with
+/// what stays on this machine taken from `current`.
+pub fn import(content: &str, current: &Settings) -> Result<Settings, String> {
+    let bundle: Bundle =
+        serde_json::from_str(content).map_err(|_| "Not a settings export".to_string())?;
+    if bundle.kind != KIND {
+        return Err("Not a settings export".to_string());
+    }
+    let mut value = bundle.settings;
+    let Some(map) = value.as_object_mut() else {
+        return Err("The exported settings aren't a JSON object".to_string());
+    };
+    migrate::upgrade(map);
+    let mut settings: Settings = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    keep_local(&mut settings, current);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::settings::{ApiToken, Hook, HookEvent, SyncDevice};
+
+    #[test]
+    fn test_export_import() {
+        let mut settings = Settings::default();
+        settings.sync.pairing_code = "pairing-secret".to_string();
+        settings.sync.devices.push(SyncDevice {
+            name: "Desktop".to_string(),
+            address: "10.0.0.2:7878".to_string(),
+        });
+        settings.calendar.token = "calendar-secret".to_string();
+        settings.calendar.tokens.push(ApiToken {
+            name: "dashboard".to_string(),
+            token: "dash-secret".to_string(),
+            ..Default::default()
+        });
+        settings.hooks.push(Hook {
+            event: HookEvent::TaskAdded,
+            command: "/usr/bin/notify".to_string(),
+        });
+        settings.reveal_template = "gvim +{line} {path}".to_string();
+        settings.calendar.network = true;
+        settings.display.zoom = 120;
+
+        let exported = export(&settings).unwrap();
+        for local in [
+            "pairing-secret",
+            "10.0.0.2",
+            "calendar-secret",
+            "dash-secret",
+            "notify",
+            "gvim",
+        ] {
+            assert!(!exported.contains(local), "{local} was exported");
+        }
+
+        // Another machine keeps its own
+        let mut other = Settings::default();
+        other.sync.pairing_code = "other".to_string();
+        other.calendar.tokens.push(ApiToken {
+            name: "dashboard".to_string(),
+            token: "other dash".to_string(),
+            ..Default::default()
+        });
+        let imported = import(&exported, &other).unwrap();
+        assert_eq!(imported.display.zoom, 120);
+        assert_eq!(imported.sync.pairing_code, "other");
+        assert!(imported.sync.devices.is_empty());
+        assert!(imported.hooks.is_empty());
+        assert!(!imported.calendar.network);
+        assert_eq!(imported.reveal_template, other.reveal_template);
+        assert_eq!(imported.calendar.tokens[0].token, "other dash");
+
+        // And the same machine gets everything back
+        assert_eq!(import(&exported, &settings).unwrap(), settings);
+    }
+}
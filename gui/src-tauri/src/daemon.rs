@@ -0,0 +1,280 @@
+//! Running without a window, as `gui --daemon`, e.g. on a server: the
+//! daemon owns the background work, the sync server, the calendar server,
+//! the reminder scheduler and keeping the feed and snapshot up to date with
+//! todo.txt. A window opened while it runs leaves that work to it and
+//! follows it instead, for reminders and changes made by sync, and has it
+//! write todo.txt, see [`Request::Save`].
+//!
+//! Whichever instance owns the list, the daemon or else the window, takes
+//! [`Request`]s on a socket at [`socket_path`], a JSON line each answered
+//! with one, so the command line (see [`crate::cli`]) changes the list
+//! through it rather than behind its back. The socket is a Unix domain
+//! socket; elsewhere the running instance can't be reached, windows do the
+//! work themselves and the command line edits todo.txt.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use todotxt::reminders::Notification;
+
+/// Whether the app was started as the daemon.
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--daemon")
+}
+
+const SOCKET_FILE: &str = "todotxt-gui.sock";
+
+/// Where the running instance listens, in the user's runtime directory so
+/// the command line finds it without knowing the app's directories. Without
+/// one it is a directory of the user's own in the temporary directory, which
+/// other users share; that fails if the directory is someone else's or open
+/// to others.
+#[cfg(unix)]
+pub fn socket_path() -> io::Result<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(PathBuf::from(dir).join(SOCKET_FILE));
+    }
+    // SAFETY: geteuid has no preconditions and can't fail
+    let uid = unsafe { libc::geteuid() };
+    let dir = std::env::temp_dir().join(format!("todotxt-gui-{uid}"));
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+    // Not following links, so another user can't point it elsewhere
+    let meta = std::fs::symlink_metadata(&dir)?;
+    if !meta.is_dir() || meta.uid() != uid || meta.permissions().mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} isn't private to this user", dir.display()),
+        ));
+    }
+    Ok(dir.join(SOCKET_FILE))
+}
+
+#[cfg(not(unix))]
+pub fn socket_path() -> io::Result<PathBuf> {
+    Ok(std::env::temp_dir().join(SOCKET_FILE))
+}
+
+/// What is asked of the running instance, the first line of a connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Request {
+    /// Keep the connection for [`DaemonEvent`]s, only the daemon takes these
+    Follow,
+    /// The list as `<id> <line>`, one per line
+    List,
+    Add {
+        text: String,
+    },
+    /// Check the todo off, or back on
+    Toggle {
+        id: usize,
+    },
+    /// Add to inbox.txt, to be refiled later
+    Capture {
+        text: String,
+    },
+    /// Write todo.txt as `content`, for a window following the daemon, with
+    /// the changes made by `actor`. The lines of `archived`, if any, move to
+    /// done.txt.
+    Save {
+        content: String,
+        actor: String,
+        #[serde(default)]
+        archived: String,
+    },
+}
+
+/// The answer to a [`Request`], e.g. what to print.
+pub type Response = Result<String, String>;
+
+/// What the daemon tells the windows following it, one JSON line each.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum DaemonEvent {
+    Reminder(Notification),
+    /// todo.txt changed from `previous`, e.g. by a sync
+    Changed {
+        previous: String,
+    },
+}
+
+/// The windows following the daemon.
+#[derive(Clone, Default)]
+pub struct Clients(Arc<Mutex<Vec<Box<dyn Write + Send>>>>);
+
+impl Clients {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn add(&self, client: Box<dyn Write + Send>) {
+        if let Ok(mut clients) = self.0.lock() {
+            clients.push(client);
+        }
+    }
+
+    /// Sends `event` to every window, forgetting those that are gone.
+    /// Returns how many got it.
+    pub fn broadcast(&self, event: &DaemonEvent) -> usize {
+        let Ok(line) = serde_json::to_string(event) else {
+            return 0;
+        };
+        let Ok(mut clients) = self.0.lock() else {
+            return 0;
+        };
+        clients.retain_mut(|client| {
+            writeln!(client, "{line}")
+                .and_then(|()| client.flush())
+                .is_ok()
+        });
+        clients.len()
+    }
+}
+
+/// Answers requests on the socket at `path` with `handle`, from background
+/// threads. Windows can follow only if there are `clients` to keep them in,
+/// i.e. in the daemon. Fails if another instance answers there already.
+#[cfg(unix)]
+pub fn serve(
+    path: &Path,
+    clients: Option<Clients>,
+    handle: impl Fn(Request) -> Response + Send + Sync + 'static,
+) -> io::Result<()> {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::thread;
+
+    if UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another instance is running",
+        ));
+    }
+    // Left behind by an instance that didn't exit cleanly
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)?;
+    let handle = Arc::new(handle);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let clients = clients.clone();
+            let handle = Arc::clone(&handle);
+            thread::spawn(move || {
+                let mut writer = stream.try_clone()?;
+                let mut line = String::new();
+                BufReader::new(stream).read_line(&mut line)?;
+                let request = serde_json::from_str::<Request>(&line);
+                let response = match (&request, &clients) {
+                    (Ok(Request::Follow), None) => Err("not a daemon".to_string()),
+                    (Ok(Request::Follow), Some(_)) => Ok(String::new()),
+                    (Ok(request), _) => handle(request.clone()),
+                    (Err(e), _) => Err(format!("invalid request: {e}")),
+                };
+                writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+                if let (Ok(Request::Follow), Some(clients)) = (request, clients) {
+                    clients.add(Box::new(writer));
+                }
+                io::Result::Ok(())
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(
+    _path: &Path,
+    _clients: Option<Clients>,
+    _handle: impl Fn(Request) -> Response + Send + Sync + 'static,
+) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the socket needs Unix domain sockets",
+    ))
+}
+
+/// Asks the instance at `path`, `None` if none runs there.
+#[cfg(unix)]
+pub fn request(path: &Path, request: &Request) -> Option<Response> {
+    use std::io::{BufRead, BufReader};
+
+    let mut stream = std::os::unix::net::UnixStream::connect(path).ok()?;
+    let mut ask = || -> io::Result<Response> {
+        writeln!(stream, "{}", serde_json::to_string(request)?)?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        Ok(serde_json::from_str(&line)?)
+    };
+    Some(ask().unwrap_or_else(|e| Err(e.to_string())))
+}
+
+#[cfg(not(unix))]
+pub fn request(_path: &Path, _request: &Request) -> Option<Response> {
+    None
+}
+
+/// Follows the daemon at `path` from a background thread, handing what it
+/// sends to `on_event`. Returns whether a daemon runs there.
+#[cfg(unix)]
+pub fn follow(path: &Path, on_event: impl Fn(DaemonEvent) + Send + 'static) -> bool {
+    use std::io::{BufRead, BufReader};
+    use std::thread;
+
+    let Ok(mut stream) = std::os::unix::net::UnixStream::connect(path) else {
+        return false;
+    };
+    let Ok(request) = serde_json::to_string(&Request::Follow) else {
+        return false;
+    };
+    if writeln!(stream, "{request}").is_err() {
+        return false;
+    }
+    let mut lines = BufReader::new(stream).lines();
+    // A window answers too, but turns followers away
+    let accepted = lines
+        .next()
+        .and_then(Result::ok)
+        .and_then(|line| serde_json::from_str::<Response>(&line).ok())
+        .is_some_and(|response| response.is_ok());
+    if !accepted {
+        return false;
+    }
+    thread::spawn(move || {
+        for line in lines {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str(&line) {
+                Ok(event) => on_event(event),
+                Err(e) => tracing::warn!("daemon: {e}"),
+            }
+        }
+        tracing::warn!("daemon: connection closed");
+    });
+    true
+}
+
+#[cfg(not(unix))]
+pub fn follow(_path: &Path, _on_event: impl Fn(DaemonEvent) + Send + 'static) -> bool {
+    false
+}
+
+/// Shows `notification` on the desktop, for reminders no window took.
+#[cfg(target_os = "linux")]
+pub fn notify_desktop(notification: &Notification) {
+    let result = std::process::Command::new("notify-send")
+        .arg(&notification.title)
+        .arg(&notification.body)
+        .status();
+    if let Err(e) = result {
+        tracing::warn!("notify-send: {e}");
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn notify_desktop(notification: &Notification) {
+    tracing::info!(title = %notification.title, body = %notification.body, "reminder");
+}
@@ -0,0 +1,34 @@
+//! Unsaved dialog input, see [`types::Draft`]. Kept in a file of its own
+//! in the app data dir so a crash loses at most the last keystroke.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use types::Draft;
+
+pub const DRAFT_FILE: &str = "draft.json";
+
+/// Reads the draft at `path`, which is empty if nothing was left unsaved.
+pub fn load(path: &Path) -> Result<Draft, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Draft::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Writes `draft` to `path`, removing the file once there's nothing left to restore.
+pub fn save(draft: &Draft, path: &Path) -> Result<(), String> {
+    if draft.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(draft).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
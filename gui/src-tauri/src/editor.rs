@@ -0,0 +1,110 @@
+//! Handing the list, or one task, to an external editor. A copy goes to a
+//! temporary file, opened with `$VISUAL` or `$EDITOR` if set and the
+//! system's default app otherwise, and each save there is taken back into
+//! the list, see [`todotxt::handoff`]. The copy is watched as a job, since
+//! there's no telling when the editor is closed; cancelling the job, or an
+//! hour without a save, stops the watch.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::jobs::Job;
+
+const POLL: Duration = Duration::from_secs(1);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The editor the user picked in their environment, if any.
+pub fn editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// Where "Reveal in editor" goes, see [`reveal`].
+pub enum Reveal {
+    /// Opened by the app registered for its scheme
+    Url(String),
+    /// Run without a shell; the first word is the program
+    Command(Vec<String>),
+}
+
+/// The [`types::settings::Settings::reveal_template`] `template` with
+/// `{path}` and `{line}` filled in; `None` if it's empty. A command is split
+/// into words first, so a path with spaces stays one argument.
+pub fn reveal(template: &str, path: &Path, line: usize) -> Option<Reveal> {
+    let template = template.trim();
+    let path = path.display().to_string();
+    let line = line.to_string();
+    if template.contains("://") {
+        let url = template
+            .replace("{path}", &path.replace(' ', "%20"))
+            .replace("{line}", &line);
+        return Some(Reveal::Url(url));
+    }
+    let words: Vec<String> = template
+        .split_whitespace()
+        .map(|word| word.replace("{path}", &path).replace("{line}", &line))
+        .collect();
+    (!words.is_empty()).then_some(Reveal::Command(words))
+}
+
+/// Where the copy of the list, or of the task `id`, goes.
+pub fn copy_path(id: Option<usize>) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("todotxt-edit-{}", std::process::id()));
+    match id {
+        None => dir.join("todo.txt"),
+        Some(id) => dir.join(format!("task-{id}.txt")),
+    }
+}
+
+pub fn write_copy(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Hands each save of the copy at `path` to `import`, reporting what it
+/// returns, until `job` is cancelled or no save came for a while. A failed
+/// import doesn't end the watch, so the copy can be fixed and saved again.
+/// The copy is removed afterwards.
+pub fn watch(
+    job: &Job,
+    path: &Path,
+    mut import: impl FnMut(&str) -> Result<String, String>,
+) -> Result<(), String> {
+    job.progress(0, 0, "Waiting for the file to be saved in the editor");
+    let mut last = modified(path);
+    let mut last_save = Instant::now();
+    let result = loop {
+        if job.cancelled() {
+            break Err("Stopped taking back edits".to_string());
+        }
+        if last_save.elapsed() > IDLE_TIMEOUT {
+            break Ok(());
+        }
+        thread::sleep(POLL);
+        let now = modified(path);
+        if now == last {
+            continue;
+        }
+        last = now;
+        last_save = Instant::now();
+        let message = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| import(&content))
+            .unwrap_or_else(|e| format!("Not taken back: {e}"));
+        job.progress(0, 0, &message);
+    };
+    if let Err(e) = fs::remove_file(path) {
+        tracing::warn!("editor: {e}");
+    }
+    result
+}
@@ -0,0 +1,222 @@
+//! Focus sessions: a countdown on one task in the tray, optionally with the
+//! system's Do Not Disturb mode on until the session ends. One session runs
+//! at a time; starting another ends the one before. Whatever a session
+//! changed is put back when it ends, is stopped or the app quits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager};
+use types::settings::FocusSettings;
+use types::{FocusSession, FOCUS_EVENT};
+
+const TRAY_ID: &str = "focus";
+const TICK: Duration = Duration::from_secs(1);
+
+/// The running session, kept as app state.
+#[derive(Default)]
+pub struct Focus {
+    current: Mutex<Option<Running>>,
+}
+
+impl Focus {
+    pub fn current(&self) -> Result<Option<FocusSession>, String> {
+        let current = self.current.lock().map_err(|e| e.to_string())?;
+        Ok(current.as_ref().map(|running| running.session.clone()))
+    }
+}
+
+struct Running {
+    session: FocusSession,
+    /// Tells the countdown thread to stop
+    stop: Arc<AtomicBool>,
+    dnd: Option<dnd::Restore>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// `ms` as a countdown, e.g. `24:59`.
+fn countdown(ms: u64) -> String {
+    let seconds = ms.div_ceil(1000);
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Whether the app can turn on Do Not Disturb on this system.
+pub fn dnd_supported() -> bool {
+    dnd::supported()
+}
+
+/// Starts a session of `settings.minutes` on the todo `id`, ending the one
+/// running. Do Not Disturb failing to turn on doesn't keep the session from
+/// starting; the session says whether it's on.
+pub fn start(
+    app: &AppHandle,
+    id: usize,
+    subject: String,
+    settings: &FocusSettings,
+) -> Result<FocusSession, String> {
+    stop(app)?;
+    let dnd = if settings.do_not_disturb {
+        dnd::enable()
+            .inspect_err(|e| tracing::warn!("focus: {e}"))
+            .ok()
+    } else {
+        None
+    };
+    let session = FocusSession {
+        id,
+        subject,
+        ends_at: now_ms() + u64::from(settings.minutes) * 60_000,
+        do_not_disturb: dnd.is_some(),
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    *app.state::<Focus>()
+        .current
+        .lock()
+        .map_err(|e| e.to_string())? = Some(Running {
+        session: session.clone(),
+        stop: stop.clone(),
+        dnd,
+    });
+    if let Err(e) = show_tray(app, &session) {
+        tracing::warn!("focus: {e}");
+    }
+    tick(app.clone(), stop, session.ends_at);
+    app.emit(FOCUS_EVENT, Some(&session))
+        .map_err(|e| e.to_string())?;
+    Ok(session)
+}
+
+/// Ends the running session, if any, and puts back what it changed.
+pub fn stop(app: &AppHandle) -> Result<Option<FocusSession>, String> {
+    finish(app, None)
+}
+
+/// Ends the running session, or only `stop`'s session if given, so a
+/// countdown running out can't end the session started after its own.
+fn finish(app: &AppHandle, stop: Option<&Arc<AtomicBool>>) -> Result<Option<FocusSession>, String> {
+    let running = {
+        let mut current = app
+            .state::<Focus>()
+            .current
+            .lock()
+            .map_err(|e| e.to_string())?;
+        match (current.as_ref(), stop) {
+            (Some(running), Some(stop)) if !Arc::ptr_eq(&running.stop, stop) => None,
+            _ => current.take(),
+        }
+    };
+    let Some(running) = running else {
+        return Ok(None);
+    };
+    running.stop.store(true, Ordering::Relaxed);
+    if let Some(restore) = running.dnd {
+        if let Err(e) = dnd::restore(restore) {
+            tracing::warn!("focus: {e}");
+        }
+    }
+    app.remove_tray_by_id(TRAY_ID);
+    app.emit(FOCUS_EVENT, None::<FocusSession>)
+        .map_err(|e| e.to_string())?;
+    Ok(Some(running.session))
+}
+
+fn show_tray(app: &AppHandle, session: &FocusSession) -> tauri::Result<()> {
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID).tooltip(&session.subject);
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+/// Counts down in the tray from a background thread until `ends_at` or
+/// until `stop` is set.
+fn tick(app: AppHandle, stop: Arc<AtomicBool>, ends_at: u64) {
+    thread::spawn(move || loop {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let left = ends_at.saturating_sub(now_ms());
+        if left == 0 {
+            if let Err(e) = finish(&app, Some(&stop)) {
+                tracing::warn!("focus: {e}");
+            }
+            return;
+        }
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            if let Err(e) = tray.set_title(Some(countdown(left))) {
+                tracing::warn!("focus: {e}");
+            }
+        }
+        thread::sleep(TICK);
+    });
+}
+
+/// GNOME's Do Not Disturb switch, which hides notification banners. Other
+/// systems have no switch an app may flip, so [`supported`] is false there.
+#[cfg(target_os = "linux")]
+mod dnd {
+    use std::process::Command;
+
+    const SCHEMA: &str = "org.gnome.desktop.notifications";
+    const KEY: &str = "show-banners";
+
+    /// What the setting was before a session turned Do Not Disturb on.
+    pub struct Restore {
+        show_banners: String,
+    }
+
+    fn gsettings(args: &[&str]) -> Result<String, String> {
+        let output = Command::new("gsettings")
+            .args(args)
+            .output()
+            .map_err(|e| format!("gsettings: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "gsettings: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn supported() -> bool {
+        gsettings(&["get", SCHEMA, KEY]).is_ok()
+    }
+
+    pub fn enable() -> Result<Restore, String> {
+        let show_banners = gsettings(&["get", SCHEMA, KEY])?;
+        gsettings(&["set", SCHEMA, KEY, "false"])?;
+        Ok(Restore { show_banners })
+    }
+
+    pub fn restore(restore: Restore) -> Result<(), String> {
+        gsettings(&["set", SCHEMA, KEY, &restore.show_banners]).map(drop)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod dnd {
+    /// Never made, as Do Not Disturb can't be turned on here.
+    pub enum Restore {}
+
+    pub fn supported() -> bool {
+        false
+    }
+
+    pub fn enable() -> Result<Restore, String> {
+        Err("Do Not Disturb can't be turned on by apps on this system".to_string())
+    }
+
+    pub fn restore(restore: Restore) -> Result<(), String> {
+        match restore {}
+    }
+}
@@ -0,0 +1,83 @@
+//! User-configured programs that run when the list changes, e.g. to post to
+//! a chat when a `+release` task is done.
+//!
+//! A hook is an executable, started with the event name as its only argument
+//! and a JSON payload on stdin. It runs with an empty environment apart from
+//! `PATH` and `HOME`, in the temp directory, and is killed if it takes longer
+//! than [`TIMEOUT`].
+
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use types::settings::{Hook, HookEvent};
+
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs one hook and waits for it to finish.
+pub fn run(command: &str, event: HookEvent, payload: &str) -> Result<(), String> {
+    let mut cmd = Command::new(command);
+    cmd.arg(event.name())
+        .env_clear()
+        .current_dir(env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    for key in ["PATH", "HOME"] {
+        if let Some(value) = env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+    let started = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Hook {command} failed to start: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that doesn't read its payload would block the write once the
+        // pipe is full, so it's written aside while the timeout runs. Killing
+        // the hook closes the pipe and ends the write. A hook that closes it
+        // early without reading is fine.
+        let payload = payload.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(payload.as_bytes());
+        });
+    }
+
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("Hook {command} failed: {status}")),
+            None if started.elapsed() > TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Hook {command} timed out"));
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+/// Runs the hooks for `event` one after another, stopping at the first failure.
+pub fn check(hooks: &[Hook], event: HookEvent, payload: &str) -> Result<(), String> {
+    hooks
+        .iter()
+        .filter(|hook| hook.event == event)
+        .try_for_each(|hook| run(&hook.command, event, payload))
+}
+
+/// Runs the hooks for `event` in the background, only logging failures.
+pub fn fire(hooks: &[Hook], event: HookEvent, payload: String) {
+    let hooks: Vec<Hook> = hooks.iter().filter(|h| h.event == event).cloned().collect();
+    if hooks.is_empty() {
+        return;
+    }
+    thread::spawn(move || {
+        for hook in hooks {
+            if let Err(e) = run(&hook.command, event, &payload) {
+                tracing::warn!("{e}");
+            }
+        }
+    });
+}
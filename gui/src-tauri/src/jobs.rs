@@ -0,0 +1,107 @@
+//! Long-running work, like a sync, on a background thread instead of the
+//! command's. A job reports its progress as [`JOB_PROGRESS_EVENT`]s and how
+//! it ended as a [`JOB_FINISHED_EVENT`], and can be cancelled with
+//! `cancel_job`. Cancelling only asks: the job stops at its next
+//! [`Job::cancelled`] check, so it never leaves a file half written.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use tauri::{AppHandle, Emitter, Manager};
+use types::{JobFinished, JobProgress, JOB_FINISHED_EVENT, JOB_PROGRESS_EVENT};
+
+/// The running jobs, kept as app state.
+#[derive(Default)]
+pub struct Jobs {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl Jobs {
+    /// Asks the job `id` to stop, returning false if it isn't running.
+    pub fn cancel(&self, id: u64) -> Result<bool, String> {
+        let running = self.running.lock().map_err(|e| e.to_string())?;
+        Ok(running
+            .get(&id)
+            .map(|cancel| cancel.store(true, Ordering::Relaxed))
+            .is_some())
+    }
+}
+
+/// The job a piece of work runs as.
+pub struct Job {
+    id: u64,
+    name: String,
+    cancel: Arc<AtomicBool>,
+    app: AppHandle,
+}
+
+impl Job {
+    /// Reports that `done` of `total` steps are done and the job is now at `message`.
+    pub fn progress(&self, done: u32, total: u32, message: &str) {
+        let progress = JobProgress {
+            id: self.id,
+            name: self.name.clone(),
+            done,
+            total,
+            message: message.to_string(),
+        };
+        if let Err(e) = self.app.emit(JOB_PROGRESS_EVENT, progress) {
+            tracing::warn!("jobs: {e}");
+        }
+    }
+
+    /// Whether the job was asked to stop.
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `work` as the job `name` on a background thread, returning its id.
+/// A job that was cancelled and then stopped with an error counts as
+/// cancelled; one that finished anyway counts as done.
+pub fn spawn(
+    app: &AppHandle,
+    name: &str,
+    work: impl FnOnce(&Job) -> Result<(), String> + Send + 'static,
+) -> Result<u64, String> {
+    let jobs = app.state::<Jobs>();
+    let id = jobs.next_id.fetch_add(1, Ordering::Relaxed);
+    let cancel = Arc::new(AtomicBool::new(false));
+    jobs.running
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(id, cancel.clone());
+    let job = Job {
+        id,
+        name: name.to_string(),
+        cancel,
+        app: app.clone(),
+    };
+    thread::spawn(move || {
+        let result = work(&job);
+        if let Ok(mut running) = job.app.state::<Jobs>().running.lock() {
+            running.remove(&job.id);
+        }
+        let (error, cancelled) = match result {
+            Ok(()) => (None, false),
+            Err(_) if job.cancelled() => (None, true),
+            Err(e) => {
+                tracing::error!("jobs: {} failed: {e}", job.name);
+                (Some(e), false)
+            }
+        };
+        let finished = JobFinished {
+            id: job.id,
+            name: job.name.clone(),
+            error,
+            cancelled,
+        };
+        if let Err(e) = job.app.emit(JOB_FINISHED_EVENT, finished) {
+            tracing::warn!("jobs: {e}");
+        }
+    });
+    Ok(id)
+}
@@ -1,88 +1,2296 @@
-use serde::Serialize;
-use todotxt::TodoList;
+mod cache;
+mod calendar;
+pub mod cli;
+mod config;
+mod daemon;
+mod drafts;
+mod editor;
+mod focus;
+mod hooks;
+mod jobs;
+mod logging;
+mod migrate;
+mod overlay;
+mod plugins;
+mod reminders;
+mod settings;
+mod sound;
+mod sync;
 
-const TODO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../todo.txt");
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
-#[derive(Serialize)]
-struct TodoResponse {
-    id: usize,
-    subject: String,
-    raw: String,
-    finished: bool,
-    priority: u8,
-    contexts: Vec<String>,
-    projects: Vec<String>,
+use chrono::{Datelike, Days};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, State};
+use tauri_plugin_opener::OpenerExt;
+use todotxt::activity::{self, Action, Entry};
+use todotxt::archive::{self, ArchiveIndex, ArchivePreview, Compaction};
+use todotxt::auto;
+use todotxt::backup::{self, Backup};
+use todotxt::check::{self, Finding};
+use todotxt::checklist::{self, CheckItem, Checklists, Progress};
+use todotxt::diff::{self, DiffLine};
+use todotxt::export::{self, ExportFormat};
+use todotxt::feed;
+use todotxt::filter::{Filter, Flag};
+use todotxt::handoff;
+use todotxt::inbox;
+use todotxt::markdown;
+use todotxt::natural;
+use todotxt::order;
+use todotxt::parse;
+use todotxt::patch;
+use todotxt::pick;
+use todotxt::plan::{self, PlanField};
+use todotxt::plugin::Capability;
+use todotxt::redact;
+use todotxt::relative;
+use todotxt::reminders::Notification;
+use todotxt::replace::{self, Pattern, Replacement};
+use todotxt::report::{self, ReportFormat};
+use todotxt::rules::{self, Overflow};
+use todotxt::similarity;
+use todotxt::snapshot::{self, Snapshot};
+use todotxt::stats::{self, Counts, DueSummary, Streaks};
+use todotxt::stream;
+use todotxt::tags::{self, TagKind, TagUsage};
+use todotxt::{Date, LineError, SplitOptions, SplitOriginal, TodoItem, TodoList};
+
+use daemon::DaemonEvent;
+use editor::Reveal;
+use focus::Focus;
+use jobs::Jobs;
+use settings::{Settings, SETTINGS_FILE};
+use types::settings::{HookEvent, NormalizeSettings};
+use types::{
+    BulkAction, Draft, FocusSession, MigrationReport, PluginInfo, RecentChange, Revised, TaskLinks,
+    Todo, TodoChange, FILE_CHANGED_EVENT, INBOX_CHANGED_EVENT, REMINDER_EVENT,
+    SETTINGS_CHANGED_EVENT,
+};
+
+const DEFAULT_TODO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../todo.txt");
+/// Points the app at another todo.txt, e.g. a fixture in the e2e tests.
+/// done.txt and the activity log are kept next to it.
+const TODO_PATH_ENV: &str = "TODOTXT_PATH";
+
+fn todo_path() -> PathBuf {
+    std::env::var_os(TODO_PATH_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_TODO_PATH))
+}
+
+/// The list at [`todo_path`], only parsed again if the file changed, see
+/// [`cache`].
+fn load_todos() -> std::io::Result<TodoList> {
+    cache::load(&todo_path())
+}
+
+fn done_path() -> PathBuf {
+    todo_path().with_file_name("done.txt")
+}
+
+fn inbox_path() -> PathBuf {
+    todo_path().with_file_name(inbox::INBOX_FILE)
+}
+
+fn log_path() -> PathBuf {
+    todo_path().with_file_name("todo.log.jsonl")
+}
+
+fn checklists_path() -> PathBuf {
+    todo_path().with_file_name(checklist::CHECKLIST_FILE)
+}
+
+/// Emitted with fresh [`Counts`] whenever the todo file is written.
+const COUNTS_CHANGED_EVENT: &str = "counts-changed";
+
+fn to_response(list: &TodoList) -> Vec<Todo> {
+    list.items().iter().map(item_response).collect()
+}
+
+fn item_response(item: &TodoItem) -> Todo {
+    Todo {
+        id: item.id,
+        line: item.line(),
+        subject: item
+            .subject()
+            .split_whitespace()
+            .filter(|w| !w.starts_with('@') && !w.starts_with('+'))
+            .collect::<Vec<_>>()
+            .join(" "),
+        raw: item.raw(),
+        finished: item.finished(),
+        priority: item.priority(),
+        creation_date: item.creation_date().map(|d| d.to_string()),
+        due_date: item.due_date().map(|d| d.to_string()),
+        due_time: item.due_time().map(|t| t.format("%H:%M").to_string()),
+        threshold_date: item.threshold_date().map(|d| d.to_string()),
+        assignee: item.assignee().map(str::to_string),
+        estimate: item.estimate(),
+        starred: item.starred(),
+        auto_done: item.auto_done(),
+        flag: item.flag(),
+        contexts: item.unique_contexts(),
+        projects: item.unique_projects(),
+    }
+}
+
+/// Writes `list` back to disk, logs what changed and tells the frontend, see [`emit_changes`].
+fn save_list(app: &AppHandle, list: &TodoList) -> Result<(), String> {
+    save_list_as(app, list, "app")
+}
+
+/// Like [`save_list`], naming `actor` as the one who made the changes. Runs
+/// the before-save hooks first and the hooks for added and completed tasks
+/// after, then rewrites the feed. Lines are tidied up first if the settings
+/// say so.
+fn save_list_as(app: &AppHandle, list: &TodoList, actor: &str) -> Result<(), String> {
+    if let Some(saved) = save_through_daemon(app, list, actor, &TodoList::new()) {
+        return saved;
+    }
+    check_save(app, list, actor)?.write(app)
+}
+
+/// Whether the window follows the daemon, which then writes todo.txt for
+/// it, see [`save_through_daemon`].
+struct Following(AtomicBool);
+
+/// Has the daemon save `list` and archive `moved`, for a window following
+/// it, and shows the window what changed. `None` if the window doesn't
+/// follow one, or it is gone and the window has to write the files itself.
+fn save_through_daemon(
+    app: &AppHandle,
+    list: &TodoList,
+    actor: &str,
+    moved: &TodoList,
+) -> Option<Result<(), String>> {
+    if !app.state::<Following>().0.load(Ordering::Relaxed) {
+        return None;
+    }
+    let request = daemon::Request::Save {
+        content: list.to_content(),
+        actor: actor.to_string(),
+        archived: moved.to_content(),
+    };
+    let before = load_todos().unwrap_or_default();
+    // Held until read back, so the daemon's note of the change doesn't show
+    // as another program's
+    let known = app.state::<KnownContent>();
+    let mut known = known.lock();
+    let response = daemon::socket_path()
+        .ok()
+        .and_then(|path| daemon::request(&path, &request));
+    let Some(response) = response else {
+        tracing::warn!("The daemon is gone, writing todo.txt directly");
+        app.state::<Following>().0.store(false, Ordering::Relaxed);
+        return None;
+    };
+    Some(response.and_then(|_| {
+        cache::refresh(&todo_path()).map_err(|e| e.to_string())?;
+        let saved = load_todos().map_err(|e| e.to_string())?;
+        *known = Some(saved.to_content());
+        drop(known);
+        emit_changes(app, &before, &saved)
+    }))
 }
 
-fn to_response(list: &TodoList) -> Vec<TodoResponse> {
-    list.items()
+/// A todo.txt the before-save hooks let through, not written yet.
+struct CheckedSave {
+    settings: Settings,
+    list: TodoList,
+    before: TodoList,
+    changes: Vec<Entry>,
+}
+
+/// The first half of [`save_list_as`]: tidies `list` up and asks the
+/// before-save hooks, without writing anything.
+fn check_save(app: &AppHandle, list: &TodoList, actor: &str) -> Result<CheckedSave, String> {
+    let settings = settings::load(&settings_path(app)?)?;
+    // Saving takes note of what was written, which the caller's copy
+    // doesn't need to
+    let mut list = list.clone();
+    if settings.normalize.enabled {
+        list.normalize(settings.normalize.options());
+    }
+    let before = load_todos().unwrap_or_default();
+    let changes = activity::changes(&before, &list, actor, activity::now());
+    let payload = serde_json::to_string(&changes).map_err(|e| e.to_string())?;
+    hooks::check(&settings.hooks, HookEvent::BeforeSave, &payload)?;
+    Ok(CheckedSave {
+        settings,
+        list,
+        before,
+        changes,
+    })
+}
+
+impl CheckedSave {
+    /// The second half of [`save_list_as`].
+    fn write(self, app: &AppHandle) -> Result<(), String> {
+        let CheckedSave {
+            settings,
+            mut list,
+            before,
+            changes,
+        } = self;
+        let list = &mut list;
+        let hooks = &settings.hooks;
+        // Like the activity log, a failed backup doesn't fail the change
+        if let Err(e) = backup::rotate(
+            &todo_path(),
+            &backup::dir(&todo_path()),
+            activity::now(),
+            false,
+        ) {
+            tracing::warn!("Failed to back up todo.txt: {e}");
+        }
+        // Held while writing, so the watcher doesn't take it for another
+        // program's change
+        let known = app.state::<KnownContent>();
+        let mut known = known.lock();
+        list.save().map_err(|e| e.to_string())?;
+        *known = Some(list.to_content());
+        drop(known);
+        if let Err(e) = cache::refresh(&todo_path()) {
+            tracing::warn!("Failed to read todo.txt back: {e}");
+        }
+        log_entries(&changes);
+        for change in &changes {
+            let event = match change.action {
+                Action::Add => HookEvent::TaskAdded,
+                Action::Complete => HookEvent::TaskCompleted,
+                _ => continue,
+            };
+            let item = list
+                .items()
+                .iter()
+                .find(|item| change.after.as_deref() == Some(item.raw().as_str()));
+            if let Some(item) = item {
+                let payload =
+                    serde_json::to_string(&item_response(item)).map_err(|e| e.to_string())?;
+                hooks::fire(hooks, event, payload);
+            }
+        }
+        write_outputs(&settings, list);
+        // Ids follow the lines, so after tasks moved or went away the list as
+        // read back is what the frontend has to match
+        let saved = load_todos().map_err(|e| e.to_string())?;
+        emit_changes(app, &before, &saved)
+    }
+}
+
+/// Rewrites the files other programs read the list from, the feed and the
+/// widget snapshot, if they are set up. Like the activity log, these failing
+/// to write doesn't fail the change itself.
+fn write_outputs(settings: &Settings, list: &TodoList) {
+    write_feed(settings, list);
+    write_snapshot(settings, list);
+}
+
+fn write_feed(settings: &Settings, list: &TodoList) {
+    let feed_settings = &settings.feed;
+    if !feed_settings.enabled || feed_settings.path.is_empty() {
+        return;
+    }
+    let today = settings.today();
+    let items = list.items().iter().filter(|item| {
+        feed_settings
+            .filter
+            .matches(item, &settings.project_separator, today)
+    });
+    let content = feed::atom(items, "todo.txt", feed::now());
+    if let Err(e) = std::fs::write(&feed_settings.path, content) {
+        tracing::warn!("Failed to write feed: {e}");
+    }
+}
+
+fn write_snapshot(settings: &Settings, list: &TodoList) {
+    let snapshot_settings = &settings.snapshot;
+    if !snapshot_settings.enabled || snapshot_settings.path.is_empty() {
+        return;
+    }
+    let today = settings.today();
+    let items = list.items().iter().filter(|item| {
+        snapshot_settings
+            .filter
+            .matches(item, &settings.project_separator, today)
+    });
+    let snapshot = Snapshot {
+        tasks: snapshot::top(items, snapshot_settings.limit),
+        counts: snapshot_settings
+            .include_counts
+            .then(|| stats::counts(list.items(), today)),
+        next_due: snapshot_settings
+            .include_next_due
+            .then(|| snapshot::next_due(list.items()))
+            .flatten(),
+    };
+    let result = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&snapshot_settings.path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tracing::warn!("Failed to write widget snapshot: {e}");
+    }
+}
+
+/// Appends the changes from `before` to `after` to the activity log.
+fn log_changes(before: &TodoList, after: &TodoList, actor: &str) {
+    log_entries(&activity::changes(before, after, actor, activity::now()));
+}
+
+/// The log is a debugging aid, so failing to write it doesn't fail the change itself.
+fn log_entries(entries: &[Entry]) {
+    if let Err(e) = activity::append(log_path(), entries) {
+        tracing::warn!("Failed to write activity log: {e}");
+    }
+}
+
+/// Sends every window the todos that changed from `before` to `after`, see
+/// [`TodoChange`], followed by the new counts. Commands that write the list
+/// return nothing; the frontend keeps its copy up to date from these events.
+fn emit_changes(app: &AppHandle, before: &TodoList, after: &TodoList) -> Result<(), String> {
+    for change in TodoChange::between(&to_response(before), &to_response(after)) {
+        let event = change.event();
+        match change {
+            TodoChange::Added(todo) | TodoChange::Updated(todo) => {
+                app.emit(event, revised(after, todo))
+            }
+            TodoChange::Removed(id) => app.emit(event, revised(after, id)),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    let counts = stats::counts(after.items(), today(app));
+    app.emit(COUNTS_CHANGED_EVENT, counts)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_counts(app: AppHandle) -> Result<Counts, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(stats::counts(list.items(), today(&app)))
+}
+
+/// Pending todos overdue, due today and due this week, see [`stats::due_summary`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_due_summary(app: AppHandle) -> Result<DueSummary, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(stats::due_summary(list.items(), today(&app)))
+}
+
+/// Today with the day ending when the settings say, see [`Settings::today`],
+/// or at midnight if they can't be read.
+fn today(app: &AppHandle) -> Date {
+    settings_path(app)
+        .and_then(|path| settings::load(&path))
+        .map(|settings| settings.today())
+        .unwrap_or_else(|_| stats::today())
+}
+
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_todos() -> Result<Revised<Vec<Todo>>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(revised(&list, to_response(&list)))
+}
+
+/// `value` as read from `list` at its current revision.
+fn revised<T>(list: &TodoList, value: T) -> Revised<T> {
+    Revised {
+        revision: list.revision(),
+        value,
+    }
+}
+
+/// The file of the watched list called `name`, or the user's own todo.txt
+/// for `None`. Only commands that read take a list; everything that writes
+/// works on the user's own file, which keeps watched lists read-only.
+fn list_path(settings: &Settings, name: Option<&str>) -> Result<PathBuf, String> {
+    let Some(name) = name else {
+        return Ok(todo_path());
+    };
+    settings
+        .watched_lists
         .iter()
-        .map(|item| TodoResponse {
-            id: item.id,
-            subject: item
-                .subject()
-                .split_whitespace()
-                .filter(|w| !w.starts_with('@') && !w.starts_with('+'))
-                .collect::<Vec<_>>()
-                .join(" "),
-            raw: item.raw(),
-            finished: item.finished(),
-            priority: item.priority(),
-            contexts: item.contexts().to_vec(),
-            projects: item.projects().to_vec(),
-        })
-        .collect()
+        .find(|list| list.name == name)
+        .map(|list| PathBuf::from(&list.path))
+        .ok_or_else(|| format!("No watched list called {name}"))
+}
+
+/// Todos matching every criterion of `filter`, from the watched list `list` if given.
+#[tauri::command]
+#[tracing::instrument(skip(app, filter, list), err)]
+fn query_todos(
+    app: AppHandle,
+    filter: Filter,
+    list: Option<String>,
+) -> Result<Revised<Vec<Todo>>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let list = cache::load(&list_path(&settings, list.as_deref())?).map_err(|e| e.to_string())?;
+    let today = settings.today();
+    let mut todos: Vec<Todo> = list
+        .items()
+        .iter()
+        .filter(|item| filter.matches(item, &settings.project_separator, today))
+        .map(item_response)
+        .collect();
+    // Starred todos are pinned on top, in file order like the rest
+    todos.sort_by_key(|todo| !todo.starred);
+    Ok(revised(&list, todos))
+}
+
+/// A todo matching `filter` for "what should I do now?", picked with `roll`
+/// (0 up to 1) as in [`pick::pick`], leaving out those in `skip`.
+#[tauri::command]
+#[tracing::instrument(skip(app, filter, skip), err)]
+fn pick_todo(
+    app: AppHandle,
+    filter: Filter,
+    skip: Vec<usize>,
+    roll: f64,
+) -> Result<Option<Todo>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let today = settings.today();
+    let items = list.items().iter().filter(|item| {
+        !skip.contains(&item.id) && filter.matches(item, &settings.project_separator, today)
+    });
+    Ok(pick::pick(items, today, roll).map(item_response))
+}
+
+/// The todos matching `filter` as text, e.g. for the clipboard.
+#[tauri::command]
+#[tracing::instrument(skip(app, filter, list), err)]
+fn render_filtered(
+    app: AppHandle,
+    filter: Filter,
+    format: ExportFormat,
+    list: Option<String>,
+) -> Result<String, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let list =
+        TodoList::from_file(list_path(&settings, list.as_deref())?).map_err(|e| e.to_string())?;
+    let today = settings.today();
+    let items: Vec<TodoItem> = list
+        .items()
+        .iter()
+        .filter(|item| filter.matches(item, &settings.project_separator, today))
+        .map(|item| redact_for(&settings, item))
+        .collect();
+    Ok(export::render(&items, format))
 }
 
+/// Adds `text` as a new task, with the task defaults from the settings applied
+/// and relative dates such as `due:tomorrow` resolved, see [`relative`], and
+/// then rewritten by the plugins' transforms. Returns the WIP limits the new
+/// task went over, see [`rules::enforce`].
 #[tauri::command]
-fn get_todos() -> Result<Vec<TodoResponse>, String> {
-    let list = TodoList::from_file(TODO_PATH).map_err(|e| e.to_string())?;
-    Ok(to_response(&list))
+#[tracing::instrument(skip(app, text), err)]
+fn add_todo(app: AppHandle, text: &str) -> Result<Vec<Overflow>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let plugins = load_plugins(&app, &settings)?;
+    let overflows = add_to_list(&mut list, &settings, &plugins, text);
+    save_list(&app, &list)?;
+    Ok(overflows)
 }
 
+/// What [`add_todo`] does to the list, also for the command line when the
+/// app isn't running.
+fn add_to_list(
+    list: &mut TodoList,
+    settings: &Settings,
+    plugins: &[plugins::Plugin],
+    text: &str,
+) -> Vec<Overflow> {
+    let today = settings.today();
+    let id = list.add_with_defaults(
+        &relative::normalize(text, today),
+        &settings.task_defaults,
+        today,
+    );
+    for plugin in plugins {
+        let Some(item) = list.get(id) else { break };
+        // A broken plugin doesn't keep tasks from being added
+        match plugin.transform(&item.raw()) {
+            Ok(line) if !line.trim().is_empty() => {
+                if let Some(item) = list.get_mut(id) {
+                    item.set_raw(&line);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("{e}"),
+        }
+    }
+    rules::enforce(list, id, &settings.wip_limits)
+}
+
+/// Like [`add_todo`] for dictated `text`, with a spoken date such as
+/// "tomorrow" turned into a due date, see [`natural::parse_quick_add`].
 #[tauri::command]
-fn add_todo(text: &str) -> Result<Vec<TodoResponse>, String> {
-    let mut list = TodoList::from_file(TODO_PATH).map_err(|e| e.to_string())?;
-    list.add(text);
-    list.save().map_err(|e| e.to_string())?;
-    Ok(to_response(&list))
+#[tracing::instrument(skip(app, text), err)]
+fn add_spoken_todo(app: AppHandle, text: &str) -> Result<Vec<Overflow>, String> {
+    let text = natural::parse_quick_add(text, today(&app));
+    add_todo(app, &text)
 }
 
 #[tauri::command]
-fn toggle_todo(id: usize) -> Result<Vec<TodoResponse>, String> {
-    let mut list = TodoList::from_file(TODO_PATH).map_err(|e| e.to_string())?;
+#[tracing::instrument(skip(app), err)]
+fn toggle_todo(app: AppHandle, id: usize) -> Result<(), String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    toggle_in_list(&mut list, &settings, id)?;
+    save_list(&app, &list)
+}
+
+/// What [`toggle_todo`] does to the list: checks the todo off on the
+/// settings' today, or back on.
+fn toggle_in_list(list: &mut TodoList, settings: &Settings, id: usize) -> Result<(), String> {
+    list.set_completion(settings.completion);
     let item = list.get(id).ok_or("Todo not found")?;
     if item.finished() {
         list.uncomplete(id);
     } else {
-        list.complete(id);
+        list.complete_on(id, settings.today());
+    }
+    Ok(())
+}
+
+/// Replaces the line of the todo `id` with `text`. Returns the WIP limits
+/// it went over, e.g. when it was moved to a busy context.
+#[tauri::command]
+#[tracing::instrument(skip(app, text), err)]
+fn edit_todo(app: AppHandle, id: usize, text: &str) -> Result<Vec<Overflow>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let text = relative::normalize(text, settings.today());
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    item.set_raw(&text);
+    let overflows = rules::enforce(&mut list, id, &settings.wip_limits);
+    save_list(&app, &list)?;
+    Ok(overflows)
+}
+
+/// Replaces the todo `id` with one todo per line of `lines`, see
+/// [`TodoList::split`], with the original kept, completed or deleted.
+#[tauri::command]
+#[tracing::instrument(skip(app, lines), err)]
+fn split_todo(
+    app: AppHandle,
+    id: usize,
+    lines: Vec<String>,
+    original: SplitOriginal,
+) -> Result<(), String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    list.set_completion(settings.completion);
+    let today = settings.today();
+    let lines: Vec<String> = lines
+        .iter()
+        .map(|line| relative::normalize(line, today))
+        .collect();
+    let options = SplitOptions {
+        original,
+        completed_on: Some(today),
+    };
+    list.split(id, &lines, options).ok_or("Todo not found")?;
+    save_list(&app, &list)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, assignee), err)]
+fn assign_todo(app: AppHandle, id: usize, assignee: Option<&str>) -> Result<(), String> {
+    let assignee = assignee.map(str::trim).filter(|a| !a.is_empty());
+    if assignee.is_some_and(|a| a.contains(char::is_whitespace)) {
+        return Err("Assignee names can't contain spaces".to_string());
     }
-    list.save().map_err(|e| e.to_string())?;
-    Ok(to_response(&list))
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    item.set_assignee(assignee);
+    save_list(&app, &list)
+}
+
+/// Adds `tag`, a `+project`, `@context` or `key:value`, to the todo `id`.
+/// Returns the WIP limits it went over.
+#[tauri::command]
+#[tracing::instrument(skip(app, tag), err)]
+fn add_todo_tag(app: AppHandle, id: usize, tag: &str) -> Result<Vec<Overflow>, String> {
+    let tag = tag.trim();
+    if parse::tag(tag).is_none() {
+        return Err(format!("{tag} isn't a +project, @context or key:value tag"));
+    }
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    if !item.add_tag(tag) {
+        return Ok(Vec::new());
+    }
+    let overflows = rules::enforce(&mut list, id, &settings.wip_limits);
+    save_list(&app, &list)?;
+    Ok(overflows)
 }
 
 #[tauri::command]
-fn edit_todo(id: usize, text: &str) -> Result<Vec<TodoResponse>, String> {
-    let mut list = TodoList::from_file(TODO_PATH).map_err(|e| e.to_string())?;
+#[tracing::instrument(skip(app, tag), err)]
+fn remove_todo_tag(app: AppHandle, id: usize, tag: &str) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
     let item = list.get_mut(id).ok_or("Todo not found")?;
-    item.set_raw(text);
-    list.save().map_err(|e| e.to_string())?;
-    Ok(to_response(&list))
+    if item.remove_tag(tag) {
+        save_list(&app, &list)?;
+    }
+    Ok(())
 }
 
+/// Plans the todos `ids` onto `date`, a `YYYY-MM-DD` day, by setting their
+/// `field`, or clears it for `None`, see [`plan::plan`].
 #[tauri::command]
-fn delete_todo(id: usize) -> Result<Vec<TodoResponse>, String> {
-    let mut list = TodoList::from_file(TODO_PATH).map_err(|e| e.to_string())?;
+#[tracing::instrument(skip(app), err)]
+fn plan_todos(
+    app: AppHandle,
+    ids: Vec<usize>,
+    field: PlanField,
+    date: Option<&str>,
+) -> Result<(), String> {
+    let date = date
+        .map(|date| Date::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("{date}: {e}")))
+        .transpose()?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    if plan::plan(&mut list, &ids, field, date) > 0 {
+        save_list(&app, &list)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn set_starred(app: AppHandle, id: usize, starred: bool) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    item.set_starred(starred);
+    save_list(&app, &list)
+}
+
+/// Flags the todo `id` with a color, or takes its flag off for `None`.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn set_flag(app: AppHandle, id: usize, flag: Option<Flag>) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    item.set_flag(flag);
+    save_list(&app, &list)
+}
+
+/// Moves the todo `id` to its place in `ids`, the new manual order of a
+/// view, see [`order::place`]. The lines stay where they are in the file.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn reorder_todo(app: AppHandle, ids: Vec<usize>, id: usize) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    list.get(id).ok_or("Todo not found")?;
+    order::place(&mut list, &ids, id);
+    save_list(&app, &list)
+}
+
+/// Lets the todo `id` complete itself once its due date passes, see [`auto`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn set_auto_done(app: AppHandle, id: usize, auto_done: bool) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get_mut(id).ok_or("Todo not found")?;
+    item.set_auto_done(auto_done);
+    save_list(&app, &list)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn delete_todo(app: AppHandle, id: usize) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
     list.remove(id).ok_or("Todo not found")?;
-    list.save().map_err(|e| e.to_string())?;
-    Ok(to_response(&list))
+    save_list(&app, &list)
+}
+
+/// done.txt, empty if there is none yet.
+fn load_done() -> Result<TodoList, String> {
+    if done_path().exists() {
+        TodoList::from_file(done_path()).map_err(|e| e.to_string())
+    } else {
+        let mut done = TodoList::new();
+        done.set_path(done_path());
+        Ok(done)
+    }
+}
+
+/// Moves the finished todos but those in `skip` to done.txt, see
+/// [`archive::archive_done`]. With `dry_run` only tells what would move.
+#[tauri::command]
+#[tracing::instrument(skip(app, skip), err)]
+fn archive_done(app: AppHandle, skip: Vec<usize>, dry_run: bool) -> Result<ArchivePreview, String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    if dry_run {
+        return Ok(archive::preview(&list, &skip));
+    }
+    let mut moved = TodoList::new();
+    let archived = archive::archive_done(&mut list, &mut moved, &skip);
+    if archived.moved.is_empty() {
+        return Ok(archived);
+    }
+    save_archive(&app, &list, &moved)?;
+    Ok(archived)
+}
+
+/// Saves `list` with the tasks of `moved` taken out of it and adds them to
+/// done.txt, the way every archiving does. The before-save hooks are asked
+/// before either file is written, and done.txt is written first, so an
+/// interruption can duplicate tasks but never lose them.
+fn save_archive(app: &AppHandle, list: &TodoList, moved: &TodoList) -> Result<(), String> {
+    if let Some(saved) = save_through_daemon(app, list, "archive", moved) {
+        return saved;
+    }
+    let checked = check_save(app, list, "archive")?;
+    let mut done = load_done()?;
+    for item in moved.items() {
+        done.add(&item.raw());
+    }
+    done.save().map_err(|e| e.to_string())?;
+    checked.write(app)
+}
+
+/// inbox.txt, empty if there is none yet.
+fn load_inbox() -> Result<TodoList, String> {
+    if inbox_path().exists() {
+        TodoList::from_file(inbox_path()).map_err(|e| e.to_string())
+    } else {
+        let mut inbox = TodoList::new();
+        inbox.set_path(inbox_path());
+        Ok(inbox)
+    }
+}
+
+/// Writes `inbox` back to disk and tells the frontend, for the count on
+/// its navigation entry.
+fn save_inbox(app: &AppHandle, inbox: &mut TodoList) -> Result<(), String> {
+    inbox.save().map_err(|e| e.to_string())?;
+    let saved = load_inbox()?;
+    app.emit(INBOX_CHANGED_EVENT, to_response(&saved))
+        .map_err(|e| e.to_string())
+}
+
+/// The todos captured to inbox.txt, waiting to be refiled.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_inbox() -> Result<Vec<Todo>, String> {
+    Ok(to_response(&load_inbox()?))
+}
+
+/// Adds `text` to inbox.txt, to be refiled into the list later.
+#[tauri::command]
+#[tracing::instrument(skip(app, text), err)]
+fn capture_todo(app: AppHandle, text: &str) -> Result<(), String> {
+    let line = captured_line(text, today(&app))?;
+    let mut inbox = load_inbox()?;
+    inbox.add(&line);
+    save_inbox(&app, &mut inbox)
+}
+
+/// The line [`capture_todo`] adds for `text`, with relative dates resolved.
+fn captured_line(text: &str, today: Date) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Nothing to capture".to_string());
+    }
+    Ok(relative::normalize(text, today))
+}
+
+/// Moves the captured todo `id` into the list with `tags` added, see
+/// [`inbox::refile`]. Returns the WIP limits it went over.
+#[tauri::command]
+#[tracing::instrument(skip(app, tags), err)]
+fn refile_todo(app: AppHandle, id: usize, tags: &str) -> Result<Vec<Overflow>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut inbox = load_inbox()?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let new_id = inbox::refile(&mut inbox, &mut list, id, tags).ok_or("Todo not found")?;
+    let overflows = rules::enforce(&mut list, new_id, &settings.wip_limits);
+    // The list first, so an interruption can duplicate the todo but never lose it
+    save_list(&app, &list)?;
+    save_inbox(&app, &mut inbox)?;
+    Ok(overflows)
+}
+
+/// The backups of todo.txt, newest first, see [`backup`].
+#[tauri::command]
+#[tracing::instrument(err)]
+fn list_backups() -> Result<Vec<Backup>, String> {
+    backup::list(&backup::dir(&todo_path())).map_err(|e| e.to_string())
+}
+
+/// The content of the backup `name`.
+fn read_backup(name: &str) -> Result<String, String> {
+    let path = backup::path(&backup::dir(&todo_path()), name).ok_or("No such backup")?;
+    std::fs::read_to_string(path).map_err(|e| e.to_string())
+}
+
+/// How the backup `name` differs from todo.txt: added lines are only in
+/// the backup, removed ones only in todo.txt.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn diff_backup(name: &str) -> Result<Vec<DiffLine>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(diff::lines(&list.to_content(), &read_backup(name)?))
+}
+
+/// Replaces todo.txt with the backup `name`, backing up the file as it is
+/// first so this can be undone the same way.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn restore_backup(app: AppHandle, name: &str) -> Result<(), String> {
+    let content = read_backup(name)?;
+    backup::rotate(
+        &todo_path(),
+        &backup::dir(&todo_path()),
+        activity::now(),
+        true,
+    )
+    .map_err(|e| e.to_string())?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    list.replace_content(&content)
+        .map_err(|errors| format!("The backup has {} invalid lines", errors.len()))?;
+    save_list_as(&app, &list, "restore")
+}
+
+/// Adds `lines` picked from a backup back to todo.txt.
+#[tauri::command]
+#[tracing::instrument(skip(app, lines), err)]
+fn restore_lines(app: AppHandle, lines: Vec<String>) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        list.add(line.trim());
+    }
+    save_list_as(&app, &list, "restore")
+}
+
+/// Applies `action` to the todos `ids` in one save.
+#[tauri::command]
+#[tracing::instrument(skip(app, ids), err)]
+fn bulk_action(app: AppHandle, ids: Vec<usize>, action: BulkAction) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    match action {
+        BulkAction::Archive => return archive_todos(&app, list, &ids),
+        BulkAction::Reschedule => {
+            let due = today(&app).checked_add_days(Days::new(7));
+            for id in &ids {
+                list.get_mut(*id).ok_or("Todo not found")?.set_due_date(due);
+            }
+        }
+        BulkAction::Delete => {
+            for id in unique_ids(&ids) {
+                list.remove(id).ok_or("Todo not found")?;
+            }
+        }
+    }
+    save_list(&app, &list)
+}
+
+/// `ids` in file order, each once, e.g. for the frontend selecting a todo
+/// twice.
+fn unique_ids(ids: &[usize]) -> Vec<usize> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Moves the todos `ids` to done.txt, marking the pending ones done today,
+/// see [`save_archive`].
+fn archive_todos(app: &AppHandle, mut list: TodoList, ids: &[usize]) -> Result<(), String> {
+    let today = today(app);
+    let ids = unique_ids(ids);
+    let mut moved = TodoList::new();
+    for &id in &ids {
+        let item = list.get_mut(id).ok_or("Todo not found")?;
+        if !item.finished() {
+            item.complete_on(today);
+        }
+        moved.add(&item.raw());
+    }
+    for id in ids {
+        list.remove(id);
+    }
+    save_archive(app, &list, &moved)
+}
+
+/// Groups of pending todos that read alike, see [`similarity::clusters`].
+#[tauri::command]
+#[tracing::instrument(err)]
+fn find_duplicates() -> Result<Vec<Vec<Todo>>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(
+        similarity::clusters(list.items(), similarity::DUPLICATE_THRESHOLD)
+            .into_iter()
+            .map(|ids| {
+                ids.into_iter()
+                    .filter_map(|id| list.get(id))
+                    .map(item_response)
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/// Merges the todos `ids` into the first of them, see [`similarity::merge`].
+#[tauri::command]
+#[tracing::instrument(skip(app, ids), err)]
+fn merge_todos(app: AppHandle, ids: Vec<usize>) -> Result<(), String> {
+    if ids.len() < 2 {
+        return Err("Pick at least two todos to merge".to_string());
+    }
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    similarity::merge(&mut list, &ids).ok_or("Todo not found")?;
+    save_list_as(&app, &list, "merge")
+}
+
+/// The logged changes that led to the todo `id`, newest first.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_activity(id: usize) -> Result<Vec<Entry>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let item = list.get(id).ok_or("Todo not found")?;
+    let entries = activity::read(log_path()).map_err(|e| e.to_string())?;
+    Ok(activity::history(&entries, &item.raw())
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// The todos the todo `id` links to and the ones linking to it.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_links(id: usize) -> Result<TaskLinks, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    list.get(id).ok_or("Todo not found")?;
+    let links = list.links();
+    let todos = |ids: &[usize]| -> Vec<Todo> {
+        ids.iter()
+            .filter_map(|id| list.get(*id))
+            .map(item_response)
+            .collect()
+    };
+    Ok(TaskLinks {
+        outgoing: todos(links.outgoing(id)),
+        backlinks: todos(links.backlinks(id)),
+    })
+}
+
+/// The items of the checklist of todo `id`, none if it has no checklist.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_checklist(id: usize) -> Result<Vec<CheckItem>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let Some(key) = list.get(id).ok_or("Todo not found")?.checklist() else {
+        return Ok(Vec::new());
+    };
+    let checklists = Checklists::load(checklists_path()).map_err(|e| e.to_string())?;
+    Ok(checklists.get(key).to_vec())
+}
+
+/// Replaces the checklist of todo `id` with `items` and returns its
+/// progress. The todo gets a `chk:` tag for a new checklist and loses it
+/// once the checklist is empty.
+#[tauri::command]
+#[tracing::instrument(skip(app, items), err)]
+fn set_checklist(
+    app: AppHandle,
+    id: usize,
+    items: Vec<CheckItem>,
+) -> Result<Option<Progress>, String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let path = checklists_path();
+    let mut checklists = Checklists::load(&path).map_err(|e| e.to_string())?;
+    let current = list
+        .get(id)
+        .ok_or("Todo not found")?
+        .checklist()
+        .map(str::to_string);
+    let key = current.clone().unwrap_or_else(|| checklists.next_key());
+    checklists.set(&key, items);
+    checklists.save(&path).map_err(|e| e.to_string())?;
+    let progress = checklists.progress(&key);
+    let tagged = progress.map(|_| key);
+    if tagged != current {
+        let item = list.get_mut(id).ok_or("Todo not found")?;
+        item.set_checklist(tagged.as_deref());
+        save_list(&app, &list)?;
+    }
+    Ok(progress)
+}
+
+/// Each todo with a checklist and how far along it is, see [`checklist::progress`].
+#[tauri::command]
+#[tracing::instrument(err)]
+fn checklist_progress() -> Result<Vec<(usize, Progress)>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let checklists = Checklists::load(checklists_path()).map_err(|e| e.to_string())?;
+    Ok(checklist::progress(list.items(), &checklists))
+}
+
+/// The `limit` todos changed most recently through the app or a sync,
+/// newest first, see [`activity::recent`].
+#[tauri::command]
+#[tracing::instrument(err)]
+fn recently_changed(limit: usize) -> Result<Vec<RecentChange>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let entries = activity::read(log_path()).map_err(|e| e.to_string())?;
+    Ok(activity::recent(&entries, list.items(), limit)
+        .into_iter()
+        .map(|(item, entry)| RecentChange {
+            todo: item_response(item),
+            entry: entry.clone(),
+        })
+        .collect())
+}
+
+/// Adds the deleted todo `line` back at the end of the list, as undoing
+/// its deletion in the history would.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn restore_todo(app: AppHandle, line: &str) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    list.add(line);
+    save_list_as(&app, &list, "undo")
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, entry), err)]
+fn undo_activity(app: AppHandle, entry: Entry) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    if !activity::undo(&mut list, &entry) {
+        return Err("The todo has changed since, so this can't be undone".to_string());
+    }
+    save_list_as(&app, &list, "undo")
+}
+
+/// Undoes several logged changes at once, e.g. a whole [`replace_in_tasks`].
+/// Nothing is undone if any of them can't be.
+#[tauri::command]
+#[tracing::instrument(skip(app, entries), err)]
+fn undo_activities(app: AppHandle, entries: Vec<Entry>) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    for entry in entries.iter().rev() {
+        if !activity::undo(&mut list, entry) {
+            return Err("Some todos have changed since, so this can't be undone".to_string());
+        }
+    }
+    save_list_as(&app, &list, "undo")
+}
+
+/// The lines among the todos matching `filter` that replacing `pattern` would change.
+fn replacements(
+    app: &AppHandle,
+    list: &TodoList,
+    pattern: &str,
+    regex: bool,
+    replacement: &str,
+    filter: &Filter,
+) -> Result<Vec<Replacement>, String> {
+    let pattern = Pattern::new(pattern, regex)?;
+    let settings = settings::load(&settings_path(app)?)?;
+    let today = settings.today();
+    let items = list
+        .items()
+        .iter()
+        .filter(|item| filter.matches(item, &settings.project_separator, today));
+    Ok(replace::preview(items, &pattern, replacement))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, pattern, replacement, filter), err)]
+fn preview_replace(
+    app: AppHandle,
+    pattern: &str,
+    regex: bool,
+    replacement: &str,
+    filter: Filter,
+) -> Result<Vec<Replacement>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    replacements(&app, &list, pattern, regex, replacement, &filter)
+}
+
+/// The lines saving would tidy up with `settings`, shown before turning it on.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn preview_normalize(settings: NormalizeSettings) -> Result<Vec<Replacement>, String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    Ok(list.normalize(settings.options()))
+}
+
+/// Replaces `pattern` in every todo matching `filter`, returning the logged
+/// changes so the frontend can offer to undo them together.
+#[tauri::command]
+#[tracing::instrument(skip(app, pattern, replacement, filter), err)]
+fn replace_in_tasks(
+    app: AppHandle,
+    pattern: &str,
+    regex: bool,
+    replacement: &str,
+    filter: Filter,
+) -> Result<Vec<Entry>, String> {
+    let before = load_todos().map_err(|e| e.to_string())?;
+    let mut list = before.clone();
+    let changes = replacements(&app, &list, pattern, regex, replacement, &filter)?;
+    if replace::apply(&mut list, &changes) == 0 {
+        return Ok(Vec::new());
+    }
+    save_list_as(&app, &list, "replace")?;
+    Ok(activity::changes(
+        &before,
+        &list,
+        "replace",
+        activity::now(),
+    ))
+}
+
+/// The lines moving the dates of the todos matching `filter` by `days`
+/// would change, see [`TodoList::shift_dates`].
+#[tauri::command]
+#[tracing::instrument(skip(app, filter), err)]
+fn preview_shift_dates(
+    app: AppHandle,
+    filter: Filter,
+    days: i64,
+) -> Result<Vec<Replacement>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let today = settings.today();
+    Ok(list.shift_dates(
+        |item| filter.matches(item, &settings.project_separator, today),
+        days,
+    ))
+}
+
+/// Moves the dates of the todos matching `filter` but those in `skip` by
+/// `days`, returning the logged changes so the frontend can offer to undo
+/// them together.
+#[tauri::command]
+#[tracing::instrument(skip(app, filter, skip), err)]
+fn shift_dates(
+    app: AppHandle,
+    filter: Filter,
+    days: i64,
+    skip: Vec<usize>,
+) -> Result<Vec<Entry>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let before = load_todos().map_err(|e| e.to_string())?;
+    let mut list = before.clone();
+    let today = settings.today();
+    let changed = list.shift_dates(
+        |item| !skip.contains(&item.id) && filter.matches(item, &settings.project_separator, today),
+        days,
+    );
+    if changed.is_empty() {
+        return Ok(Vec::new());
+    }
+    save_list_as(&app, &list, "shift")?;
+    Ok(activity::changes(&before, &list, "shift", activity::now()))
+}
+
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_tag_usage() -> Result<Vec<TagUsage>, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    Ok(tags::usage(&list))
+}
+
+/// Renames a tag everywhere, merging it into `to` if that tag exists already.
+/// A color picked for the old name moves along unless `to` has one of its own.
+#[tauri::command]
+#[tracing::instrument(skip(app, from, to), err)]
+fn rename_tag(app: AppHandle, kind: TagKind, from: &str, to: &str) -> Result<(), String> {
+    if to.is_empty() || to.contains(char::is_whitespace) {
+        return Err("Tag names must be non-empty and contain no spaces".to_string());
+    }
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    if list.rename_tag(kind, from, to) > 0 {
+        save_list(&app, &list)?;
+    }
+
+    let sigil = kind.sigil();
+    let path = settings_path(&app)?;
+    let mut settings = settings::load(&path)?;
+    if let Some(color) = settings.tag_colors.remove(&format!("{sigil}{from}")) {
+        settings
+            .tag_colors
+            .entry(format!("{sigil}{to}"))
+            .or_insert(color);
+        settings::save(&settings, &path)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, name), err)]
+fn delete_tag(app: AppHandle, kind: TagKind, name: &str) -> Result<(), String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    if list.remove_tag(kind, name) > 0 {
+        save_list(&app, &list)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_raw() -> Result<String, String> {
+    std::fs::read_to_string(todo_path()).map_err(|e| e.to_string())
+}
+
+/// Replaces the whole file with `content`, unless some lines don't parse.
+#[tauri::command]
+#[tracing::instrument(skip(app, content), err)]
+fn save_raw(app: AppHandle, content: &str) -> Result<Vec<LineError>, String> {
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    let today = today(&app);
+    let content: String = content
+        .lines()
+        .map(|line| relative::normalize(line, today) + "\n")
+        .collect();
+    match list.replace_content(&content) {
+        Ok(()) => {
+            save_list(&app, &list)?;
+            Ok(Vec::new())
+        }
+        Err(errors) => Ok(errors),
+    }
+}
+
+/// Opens a link from a todo's text in the browser, or the mail app for
+/// `mailto:`, see [`markdown::safe_url`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn open_url(app: AppHandle, url: &str) -> Result<(), String> {
+    if !markdown::safe_url(url) {
+        return Err(format!("Won't open {url}"));
+    }
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// Opens todo.txt at the line of the todo `id` in the editor
+/// [`Settings::reveal_template`] names.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn reveal_in_editor(app: AppHandle, id: usize) -> Result<(), String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let line = list.get(id).ok_or("Todo not found")?.line();
+    let settings = settings::load(&settings_path(&app)?)?;
+    match editor::reveal(&settings.reveal_template, &todo_path(), line)
+        .ok_or("No editor set up to reveal todos in")?
+    {
+        Reveal::Url(url) => app
+            .opener()
+            .open_url(url, None::<&str>)
+            .map_err(|e| e.to_string()),
+        Reveal::Command(words) => std::process::Command::new(&words[0])
+            .args(&words[1..])
+            .spawn()
+            .map(drop)
+            .map_err(|e| format!("{}: {e}", words[0])),
+    }
+}
+
+/// Opens the list, or only the todo `id`, in an external editor and takes
+/// back each save made there, as a job whose id is returned, see [`editor`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn open_in_editor(app: AppHandle, id: Option<usize>) -> Result<u64, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let mut base = match id {
+        None => list.to_content(),
+        Some(id) => list.get(id).ok_or("Todo not found")?.raw(),
+    };
+    let path = editor::copy_path(id);
+    editor::write_copy(&path, &base)?;
+    app.opener()
+        .open_path(path.display().to_string(), editor::editor())
+        .map_err(|e| e.to_string())?;
+    let name = match id {
+        None => "Editing todo.txt".to_string(),
+        Some(id) => format!("Editing todo #{id}"),
+    };
+    let handle = app.clone();
+    jobs::spawn(&app, &name, move |job| {
+        editor::watch(job, &path, |edited| {
+            let mut list = load_todos().map_err(|e| e.to_string())?;
+            let message = match id {
+                None => {
+                    let merged = handoff::import_list(&mut list, &base, edited)
+                        .map_err(|e| e.to_string())?;
+                    base = edited.to_string();
+                    if merged {
+                        "Taken back, merged with changes made meanwhile"
+                    } else {
+                        "Taken back"
+                    }
+                }
+                Some(_) => {
+                    base = handoff::import_task(&mut list, &base, edited)
+                        .map_err(|e| e.to_string())?;
+                    "Taken back"
+                }
+            };
+            save_list_as(&handle, &list, "editor")?;
+            Ok(format!("{message} at {}", activity::now().format("%H:%M")))
+        })
+    })
+}
+
+/// Problems in the todo file the parser lets through, see [`check::check`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn check_file(app: AppHandle) -> Result<Vec<Finding>, String> {
+    let content = std::fs::read_to_string(todo_path()).map_err(|e| e.to_string())?;
+    Ok(check::check(&content, today(&app)))
+}
+
+/// Applies every fix [`check_file`] offers and returns what is left to fix
+/// by hand. Lines that need fixing are kept, so this writes the content as is
+/// instead of going through [`TodoList::replace_content`] and [`save_list`],
+/// but backs the file up and tells the watcher like a save does.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn repair_file(app: AppHandle) -> Result<Vec<Finding>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let today = today(&app);
+    let content = std::fs::read_to_string(todo_path()).map_err(|e| e.to_string())?;
+    let repaired = check::repair(&content, today);
+    if repaired != content {
+        let before = TodoList::from_content(&content);
+        if let Err(e) = backup::rotate(
+            &todo_path(),
+            &backup::dir(&todo_path()),
+            activity::now(),
+            false,
+        ) {
+            tracing::warn!("Failed to back up todo.txt: {e}");
+        }
+        let known = app.state::<KnownContent>();
+        let mut known = known.lock();
+        patch::replace(&todo_path(), &repaired).map_err(|e| e.to_string())?;
+        let list = load_todos().map_err(|e| e.to_string())?;
+        *known = Some(list.to_content());
+        drop(known);
+        log_changes(&before, &list, "repair");
+        write_outputs(&settings, &list);
+        emit_changes(&app, &before, &list)?;
+    }
+    Ok(check::check(&repaired, today))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_streaks(app: AppHandle) -> Result<Streaks, String> {
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let mut per_day = stats::completions_per_day(list.items());
+    // done.txt grows forever, so it's streamed rather than loaded
+    if done_path().exists() {
+        let file = std::fs::File::open(done_path()).map_err(|e| e.to_string())?;
+        let done = stream::items(std::io::BufReader::new(file)).filter_map(|item| {
+            item.inspect_err(|e| tracing::warn!("Failed to read done.txt: {e}"))
+                .ok()
+        });
+        for (date, count) in stats::completions_per_day(done) {
+            *per_day.entry(date).or_insert(0) += count;
+        }
+    }
+    let index = ArchiveIndex::read(archive::index_path(&done_path())).map_err(|e| e.to_string())?;
+    let per_day = index.merged(per_day);
+    Ok(stats::streaks(&per_day, today(&app)))
+}
+
+/// Moves completions from past years out of done.txt into yearly files, see
+/// [`archive::compact`].
+#[tauri::command]
+#[tracing::instrument(err)]
+fn compact_archive() -> Result<Compaction, String> {
+    if !done_path().exists() {
+        return Ok(Compaction::default());
+    }
+    archive::compact(&done_path(), activity::now().year()).map_err(|e| e.to_string())
+}
+
+/// A summary of what was completed, added and is still open from `from` to
+/// `to` (`YYYY-MM-DD`), over todo.txt and done.txt, see [`report::generate`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn generate_report(
+    app: AppHandle,
+    from: &str,
+    to: &str,
+    format: ReportFormat,
+) -> Result<String, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let parse =
+        |date: &str| Date::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("{date}: {e}"));
+    let (from, to) = (parse(from)?, parse(to)?);
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let done = if done_path().exists() {
+        Some(TodoList::from_file(done_path()).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    let items: Vec<TodoItem> = list
+        .items()
+        .iter()
+        .chain(done.iter().flat_map(|d| d.items()))
+        .map(|item| redact_for(&settings, item))
+        .collect();
+    Ok(report::generate(&items, from, to, format))
+}
+
+/// `item` as exports show it, with lorem ipsum in demo mode.
+fn redact_for(settings: &Settings, item: &TodoItem) -> TodoItem {
+    if settings.display.demo_mode {
+        redact::item(item)
+    } else {
+        item.clone()
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_settings(app: AppHandle) -> Result<Settings, String> {
+    settings::load(&settings_path(&app)?)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, settings), err)]
+fn save_settings(app: AppHandle, settings: Settings) -> Result<Settings, String> {
+    settings::validate_project_separator(&settings.project_separator)?;
+    settings::save(&settings, &settings_path(&app)?)?;
+    // The feed or snapshot may have been switched on or changed
+    if let Ok(list) = load_todos() {
+        write_outputs(&settings, &list);
+    }
+    if let Err(e) = overlay::apply(&app, &settings.overlay) {
+        tracing::warn!("Failed to update the overlay: {e}");
+    }
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+    Ok(settings)
+}
+
+/// The sound played when a task is checked off, as a WAV file.
+#[tauri::command]
+#[tracing::instrument(err)]
+fn get_completion_sound() -> Result<Vec<u8>, String> {
+    Ok(sound::chime())
+}
+
+/// Contents of the custom CSS file, or nothing if none is set.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_custom_css(app: AppHandle) -> Result<String, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    match settings.custom_css.filter(|path| !path.is_empty()) {
+        Some(path) => std::fs::read_to_string(&path).map_err(|e| format!("{path}: {e}")),
+        None => Ok(String::new()),
+    }
+}
+
+fn plugins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(plugins::PLUGINS_DIR))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_plugins(app: AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    plugins::discover(&plugins_dir(&app)?, |name| {
+        settings
+            .plugin_grants
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// The plugins the user granted everything they ask for.
+fn load_plugins(app: &AppHandle, settings: &Settings) -> Result<Vec<plugins::Plugin>, String> {
+    load_plugins_in(&plugins_dir(app)?, settings)
+}
+
+/// The plugins in `dir` with what `settings` grant them.
+fn load_plugins_in(dir: &Path, settings: &Settings) -> Result<Vec<plugins::Plugin>, String> {
+    plugins::load(dir, |name| {
+        settings
+            .plugin_grants
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+fn find_plugin(app: &AppHandle, name: &str) -> Result<plugins::Plugin, String> {
+    let settings = settings::load(&settings_path(app)?)?;
+    load_plugins(app, &settings)?
+        .into_iter()
+        .find(|plugin| plugin.manifest.name == name)
+        .ok_or_else(|| format!("Plugin {name} isn't installed or allowed to run"))
+}
+
+/// Runs the command `export` of the plugin `plugin`. A plugin that may
+/// change tasks has its answer saved as the new list; any other's answer is
+/// returned to show.
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+fn run_plugin_command(
+    app: AppHandle,
+    plugin: String,
+    export: String,
+) -> Result<Option<String>, String> {
+    let plugin = find_plugin(&app, &plugin)?;
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let output = plugin.command(&export, &list.to_content())?;
+    if output.is_empty() {
+        return Ok(None);
+    }
+    if !plugin.may(Capability::WriteTasks) {
+        return Ok(Some(output));
+    }
+    let revision = list.revision();
+    let mut list = TodoList::from_content(&output);
+    list.set_path(todo_path());
+    // Edits made while the plugin ran aren't overwritten
+    if load_todos().map_err(|e| e.to_string())?.revision() != revision {
+        return Err("The list changed while the plugin ran, try again".to_string());
+    }
+    save_list_as(&app, &list, &format!("plugin {}", plugin.manifest.name))?;
+    Ok(None)
+}
+
+/// Ids of the todos the sidebar view `export` of the plugin `plugin` shows.
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+fn plugin_view(app: AppHandle, plugin: String, export: String) -> Result<Vec<usize>, String> {
+    let plugin = find_plugin(&app, &plugin)?;
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let lines: Vec<String> = list.items().iter().map(|item| item.raw()).collect();
+    let shown = plugin.view(&export, &lines)?;
+    Ok(list
+        .items()
+        .iter()
+        .zip(shown)
+        .filter(|(_, shown)| *shown)
+        .map(|(item, _)| item.id)
+        .collect())
+}
+
+/// Changes the project hierarchy separator. With `migrate`, existing project
+/// tags in todo.txt are rewritten from the old separator to the new one first.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn set_project_separator(
+    app: AppHandle,
+    separator: String,
+    migrate: bool,
+) -> Result<Settings, String> {
+    settings::validate_project_separator(&separator)?;
+    let path = settings_path(&app)?;
+    let mut settings = settings::load(&path)?;
+    if migrate {
+        let mut list = load_todos().map_err(|e| e.to_string())?;
+        if list.replace_project_separator(&settings.project_separator, &separator) > 0 {
+            save_list(&app, &list)?;
+        }
+    }
+    settings.project_separator = separator;
+    settings::save(&settings, &path)?;
+    Ok(settings)
+}
+
+fn draft_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(drafts::DRAFT_FILE))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn get_draft(app: AppHandle) -> Result<Draft, String> {
+    drafts::load(&draft_path(&app)?)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, draft), err)]
+fn save_draft(app: AppHandle, draft: Draft) -> Result<(), String> {
+    drafts::save(&draft, &draft_path(&app)?)
+}
+
+/// Zips the logs and anonymized settings into the downloads folder for a bug
+/// report, returning the path of the zip.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn export_diagnostics(app: AppHandle) -> Result<String, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let out = app
+        .path()
+        .download_dir()
+        .map_err(|e| e.to_string())?
+        .join(format!("todo-diagnostics-{}.zip", stats::today()));
+    logging::export_diagnostics(&logs_dir(&app)?, &settings, &out)?;
+    Ok(out.display().to_string())
+}
+
+/// Saves the settings without what stays on this machine to the downloads
+/// folder as one file to import on another machine, returning its path, see
+/// [`config`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn export_config(app: AppHandle) -> Result<String, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let out = app
+        .path()
+        .download_dir()
+        .map_err(|e| e.to_string())?
+        .join(format!("todo-settings-{}.json", stats::today()));
+    std::fs::write(&out, config::export(&settings)?).map_err(|e| e.to_string())?;
+    Ok(out.display().to_string())
+}
+
+/// The settings exported to `path` by [`export_config`], with what stays
+/// on this machine, for the frontend to save like any change.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn import_config(app: AppHandle, path: &str) -> Result<Settings, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let current = settings::load(&settings_path(&app)?)?;
+    config::import(&content, &current)
+}
+
+fn logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(logging::LOGS_DIR))
+        .map_err(|e| e.to_string())
+}
+
+/// Syncs with the paired device at `address` as a job, returning its id.
+#[tauri::command]
+#[tracing::instrument(skip(app, address), err)]
+fn sync_now(app: AppHandle, address: String) -> Result<u64, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    if let Some(e) = settings.sync.pairing_code_error() {
+        return Err(e);
+    }
+    let log_path = sync_log_path(&app)?;
+    let store = SyncStore(app.clone());
+    jobs::spawn(&app, &format!("Sync with {address}"), move |job| {
+        let total = sync::STEPS.len() as u32;
+        sync::sync_with(
+            &address,
+            &settings.sync.pairing_code,
+            &store,
+            &log_path,
+            |step| {
+                job.progress(step as u32, total, sync::STEPS[step]);
+                !job.cancelled()
+            },
+        )
+        .map_err(|e| format!("Sync with {address} failed: {e}"))?;
+        job.progress(total, total, "Done");
+        Ok(())
+    })
+}
+
+/// Where the op log of synced changes is kept, see [`sync`].
+fn sync_log_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("sync").join(sync::LOG_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// The user's todo.txt as synced, written like any change of the app, see
+/// [`save_list_as`].
+struct SyncStore(AppHandle);
+
+impl sync::Store for SyncStore {
+    fn read(&self) -> std::io::Result<(String, u64)> {
+        let list = load_todos()?;
+        Ok((list.to_content(), list.revision()))
+    }
+
+    fn write(&self, content: &str, revision: u64, peer: &str) -> std::io::Result<()> {
+        if load_todos()?.revision() != revision {
+            return Err(sync::changed_error());
+        }
+        let mut list = TodoList::from_content(content);
+        list.set_path(todo_path());
+        save_list_as(&self.0, &list, &format!("sync {peer}")).map_err(std::io::Error::other)
+    }
+}
+
+/// Devices on the local network serving sync, other than those paired
+/// already.
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+fn find_sync_devices(app: AppHandle) -> Result<Vec<String>, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let found = sync::discover().map_err(|e| e.to_string())?;
+    Ok(found
+        .into_iter()
+        .filter(|address| {
+            !settings
+                .sync
+                .devices
+                .iter()
+                .any(|device| &device.address == address)
+        })
+        .collect())
+}
+
+/// Asks the running job `id` to stop, see [`jobs`].
+#[tauri::command]
+#[tracing::instrument(skip(jobs), err)]
+fn cancel_job(jobs: State<'_, Jobs>, id: u64) -> Result<bool, String> {
+    jobs.cancel(id)
+}
+
+/// Starts a focus session on todo `id`, ending the one running, see [`focus`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn start_focus(app: AppHandle, id: usize) -> Result<FocusSession, String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    let list = load_todos().map_err(|e| e.to_string())?;
+    let subject = item_response(list.get(id).ok_or("Todo not found")?).subject;
+    focus::start(&app, id, subject, &settings.focus)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn stop_focus(app: AppHandle) -> Result<(), String> {
+    focus::stop(&app).map(drop)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(focus), err)]
+fn get_focus(focus: State<'_, Focus>) -> Result<Option<FocusSession>, String> {
+    focus.current()
+}
+
+/// Opens the overlay, see [`overlay`].
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn open_overlay(app: AppHandle) -> Result<(), String> {
+    let settings = settings::load(&settings_path(&app)?)?;
+    overlay::open(&app, &settings.overlay).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+fn close_overlay(app: AppHandle) -> Result<(), String> {
+    overlay::close(&app).map_err(|e| e.to_string())
+}
+
+/// Whether focus sessions can turn on Do Not Disturb on this system.
+#[tauri::command]
+fn do_not_disturb_supported() -> bool {
+    focus::dnd_supported()
+}
+
+/// Starts serving the list to paired devices if sync is enabled. Changes to
+/// the sync settings take effect on the next start.
+fn start_sync_server(app: &AppHandle) -> Result<(), String> {
+    let settings = settings::load(&settings_path(app)?)?;
+    if !settings.sync.enabled {
+        return Ok(());
+    }
+    if let Some(e) = settings.sync.pairing_code_error() {
+        return Err(e);
+    }
+    sync::serve(
+        settings.sync.port,
+        settings.sync.pairing_code,
+        SyncStore(app.clone()),
+        sync_log_path(app)?,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Starts looking for due reminders, which go to `deliver`.
+fn start_reminders(
+    app: &AppHandle,
+    deliver: impl Fn(Notification) + Send + 'static,
+) -> Result<(), String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    let settings_path = settings_path(app)?;
+    reminders::start(
+        todo_path(),
+        data_dir.join("reminders-last-check"),
+        move || settings::load(&settings_path),
+        deliver,
+    );
+    Ok(())
+}
+
+/// How often the sweep looks whether the day changed.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Completes the `auto:done` todos whose due date passed, once at the start
+/// and again whenever a new day begins, see [`auto`]. The activity log
+/// names the sweep as the one who completed them.
+fn start_auto_complete(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        let mut swept: Option<Date> = None;
+        loop {
+            let day = today(&handle);
+            if swept != Some(day) {
+                match sweep_auto_done(&handle, day) {
+                    Ok(()) => swept = Some(day),
+                    Err(e) => tracing::warn!("auto:done: {e}"),
+                }
+            }
+            std::thread::sleep(SWEEP_INTERVAL);
+        }
+    });
+}
+
+fn sweep_auto_done(app: &AppHandle, today: Date) -> Result<(), String> {
+    let settings = settings::load(&settings_path(app)?)?;
+    let mut list = load_todos().map_err(|e| e.to_string())?;
+    list.set_completion(settings.completion);
+    let completed = auto::complete(&mut list, today);
+    if completed.is_empty() {
+        return Ok(());
+    }
+    tracing::info!(count = completed.len(), "auto:done completed todos");
+    save_list_as(app, &list, "auto:done")
+}
+
+/// The servers and the reminder scheduler, run by the daemon if there is
+/// one and by the window otherwise, see [`daemon`].
+fn start_background_work(app: &AppHandle, deliver: impl Fn(Notification) + Send + 'static) {
+    if let Err(e) = start_sync_server(app) {
+        tracing::error!("Failed to start sync server: {e}");
+    }
+    if let Err(e) = start_reminders(app, deliver) {
+        tracing::error!("Failed to start reminders: {e}");
+    }
+    if let Err(e) = start_calendar_server(app) {
+        tracing::error!("Failed to start calendar server: {e}");
+    }
+    start_auto_complete(app);
+}
+
+/// Answers the command line on the socket of the instance that owns the
+/// list, changing it the way the window would, see [`cli`].
+fn handle_request(app: &AppHandle, request: daemon::Request) -> daemon::Response {
+    match request {
+        // Turned away by the socket itself
+        daemon::Request::Follow => Err("not a daemon".to_string()),
+        daemon::Request::List => {
+            let list = load_todos().map_err(|e| e.to_string())?;
+            let lines: Vec<String> = list
+                .items()
+                .iter()
+                .map(|item| format!("{} {}", item.id, item.raw()))
+                .collect();
+            Ok(lines.join("\n"))
+        }
+        daemon::Request::Add { text } => {
+            add_todo(app.clone(), &text)?;
+            Ok(String::new())
+        }
+        daemon::Request::Toggle { id } => {
+            toggle_todo(app.clone(), id)?;
+            Ok(String::new())
+        }
+        daemon::Request::Capture { text } => {
+            capture_todo(app.clone(), &text)?;
+            Ok(String::new())
+        }
+        daemon::Request::Save {
+            content,
+            actor,
+            archived,
+        } => {
+            let mut list = TodoList::from_content(&content);
+            list.set_path(todo_path());
+            let moved = TodoList::from_content(&archived);
+            if moved.items().is_empty() {
+                save_list_as(app, &list, &actor)?;
+            } else {
+                save_archive(app, &list, &moved)?;
+            }
+            Ok(String::new())
+        }
+    }
+}
+
+/// How often todo.txt is looked at for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The content of todo.txt the app last wrote or showed, to tell changes
+/// other programs make, e.g. a sync, from its own.
+struct KnownContent(Mutex<Option<String>>);
+
+impl KnownContent {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<String>> {
+        // A panic elsewhere while holding the lock leaves nothing half-written
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Tells the window what another program changed in todo.txt since the app
+/// last wrote or showed it, see [`diff::tasks`].
+fn notify_external_change(app: &AppHandle) {
+    let known = app.state::<KnownContent>();
+    let mut known = known.lock();
+    let list = match load_todos() {
+        Ok(list) => list,
+        Err(e) => {
+            tracing::warn!("Failed to read todo.txt: {e}");
+            return;
+        }
+    };
+    let content = list.to_content();
+    let Some(previous) = known
+        .replace(content.clone())
+        .filter(|previous| *previous != content)
+    else {
+        return;
+    };
+    drop(known);
+    if let Err(e) = emit_changes(app, &TodoList::from_content(&previous), &list) {
+        tracing::warn!("Failed to show changes to todo.txt: {e}");
+    }
+    let changes = diff::tasks(&previous, &content);
+    tracing::info!(changes = changes.len(), "todo.txt changed elsewhere");
+    if let Err(e) = app.emit(FILE_CHANGED_EVENT, changes) {
+        tracing::warn!("Failed to report changes to todo.txt: {e}");
+    }
+}
+
+/// Calls `on_change` with todo.txt and what it was before whenever it
+/// changes, be it by the app or another program.
+fn watch_todo_file(on_change: impl Fn(&TodoList, String) + Send + 'static) {
+    std::thread::spawn(move || {
+        let mut previous = load_todos().map(|list| list.to_content()).ok();
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let list = match load_todos() {
+                Ok(list) => list,
+                Err(e) => {
+                    tracing::warn!("watch: {e}");
+                    continue;
+                }
+            };
+            let content = list.to_content();
+            if previous.as_ref() == Some(&content) {
+                continue;
+            }
+            on_change(&list, previous.replace(content).unwrap_or_default());
+        }
+    });
+}
+
+/// Runs as the daemon, with the window closed, see [`daemon`].
+fn start_daemon(app: &AppHandle) -> Result<(), String> {
+    for window in app.webview_windows().into_values() {
+        window.destroy().map_err(|e| e.to_string())?;
+    }
+    let clients = app.state::<daemon::Clients>().inner().clone();
+    let handle = app.clone();
+    daemon::serve(
+        &daemon::socket_path().map_err(|e| e.to_string())?,
+        Some(clients.clone()),
+        move |request| handle_request(&handle, request),
+    )
+    .map_err(|e| e.to_string())?;
+    start_background_work(app, move |notification| {
+        // Shown by a window if one follows, else on the desktop
+        if clients.broadcast(&DaemonEvent::Reminder(notification.clone())) == 0 {
+            daemon::notify_desktop(&notification);
+        }
+    });
+    // Keeps the feed and snapshot up to date with changes made by other
+    // programs or a sync, and tells the windows following the daemon
+    let handle = app.clone();
+    watch_todo_file(move |list, previous| {
+        if let Ok(settings) = settings_path(&handle).and_then(|path| settings::load(&path)) {
+            write_outputs(&settings, list);
+        }
+        let event = DaemonEvent::Changed { previous };
+        handle.state::<daemon::Clients>().broadcast(&event);
+    });
+    tracing::info!("running as daemon");
+    Ok(())
+}
+
+/// Leaves the background work and writing todo.txt to the daemon if one
+/// runs, passing on what it sends. Returns whether one does.
+fn follow_daemon(app: &AppHandle) -> bool {
+    let Ok(path) = daemon::socket_path() else {
+        return false;
+    };
+    let handle = app.clone();
+    let following = daemon::follow(&path, move |event| match event {
+        DaemonEvent::Reminder(notification) => {
+            if let Err(e) = handle.emit(REMINDER_EVENT, notification) {
+                tracing::warn!("Failed to deliver reminder: {e}");
+            }
+        }
+        // The daemon can't tell the window's own saves from other changes
+        DaemonEvent::Changed { .. } => notify_external_change(&handle),
+    });
+    app.state::<Following>()
+        .0
+        .store(following, Ordering::Relaxed);
+    following
+}
+
+/// Starts serving the calendar subscription if it is enabled. Like sync,
+/// changes to its settings take effect on the next start.
+fn start_calendar_server(app: &AppHandle) -> Result<(), String> {
+    let settings = settings::load(&settings_path(app)?)?.calendar;
+    if !settings.enabled || !settings.has_tokens() {
+        return Ok(());
+    }
+    calendar::serve(settings, todo_path(), settings_path(app)?).map_err(|e| e.to_string())
+}
+
+/// Text handed to the app to capture as a task, e.g. by a desktop "send to"
+/// entry running `gui --share <text>`. Taken once by the frontend, which
+/// opens the add dialog with it.
+struct SharedText(Mutex<Option<String>>);
+
+fn share_arg() -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--share");
+    args.next()?;
+    args.next()
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(shared), err)]
+fn take_shared_text(shared: State<'_, SharedText>) -> Result<Option<String>, String> {
+    let mut text = shared.0.lock().map_err(|e| e.to_string())?;
+    Ok(text.take())
+}
+
+/// A link to a view the app was opened with, e.g. by the desktop's handler
+/// for `todotxt://` links running `gui <link>`, see [`todotxt::view`].
+/// Taken once by the frontend, which switches to that view.
+struct ViewLink(Mutex<Option<String>>);
+
+fn view_link_arg() -> Option<String> {
+    let prefix = todotxt::view::link_prefix();
+    std::env::args()
+        .skip(1)
+        .find(|arg| arg.starts_with(&prefix))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(link), err)]
+fn take_view_link(link: State<'_, ViewLink>) -> Result<Option<String>, String> {
+    let mut link = link.0.lock().map_err(|e| e.to_string())?;
+    Ok(link.take())
+}
+
+/// What the migrations at this start did, see [`migrate`]. Taken once by
+/// the frontend, which shows it as a changelog.
+struct MigrationReportState(Mutex<Option<MigrationReport>>);
+
+#[tauri::command]
+#[tracing::instrument(skip(report), err)]
+fn take_migration_report(
+    report: State<'_, MigrationReportState>,
+) -> Result<Option<MigrationReport>, String> {
+    let mut report = report.0.lock().map_err(|e| e.to_string())?;
+    Ok(report.take())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![get_todos, add_todo, toggle_todo, edit_todo, delete_todo])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .manage(Jobs::default())
+        .manage(Focus::default())
+        .manage(SharedText(Mutex::new(share_arg())))
+        .manage(ViewLink(Mutex::new(view_link_arg())))
+        .manage(MigrationReportState(Mutex::new(None)))
+        .manage(KnownContent(Mutex::new(None)))
+        .manage(Following(AtomicBool::new(false)))
+        .manage(daemon::Clients::default())
+        .invoke_handler(tauri::generate_handler![
+            get_todos,
+            query_todos,
+            render_filtered,
+            add_todo,
+            add_spoken_todo,
+            toggle_todo,
+            edit_todo,
+            split_todo,
+            assign_todo,
+            add_todo_tag,
+            remove_todo_tag,
+            plan_todos,
+            set_starred,
+            set_flag,
+            reorder_todo,
+            set_auto_done,
+            delete_todo,
+            get_activity,
+            get_links,
+            get_checklist,
+            set_checklist,
+            checklist_progress,
+            recently_changed,
+            restore_todo,
+            undo_activity,
+            undo_activities,
+            preview_replace,
+            preview_normalize,
+            replace_in_tasks,
+            preview_shift_dates,
+            shift_dates,
+            get_tag_usage,
+            rename_tag,
+            delete_tag,
+            get_raw,
+            save_raw,
+            open_in_editor,
+            reveal_in_editor,
+            open_url,
+            check_file,
+            repair_file,
+            get_streaks,
+            generate_report,
+            compact_archive,
+            archive_done,
+            get_inbox,
+            capture_todo,
+            refile_todo,
+            list_backups,
+            diff_backup,
+            restore_backup,
+            restore_lines,
+            get_counts,
+            get_due_summary,
+            pick_todo,
+            bulk_action,
+            find_duplicates,
+            merge_todos,
+            get_settings,
+            save_settings,
+            set_project_separator,
+            get_plugins,
+            run_plugin_command,
+            plugin_view,
+            get_custom_css,
+            get_completion_sound,
+            get_draft,
+            save_draft,
+            export_diagnostics,
+            export_config,
+            import_config,
+            sync_now,
+            find_sync_devices,
+            cancel_job,
+            start_focus,
+            stop_focus,
+            open_overlay,
+            close_overlay,
+            get_focus,
+            do_not_disturb_supported,
+            take_shared_text,
+            take_view_link,
+            take_migration_report
+        ])
+        .setup(|app| {
+            let level = settings_path(app.handle())
+                .and_then(|path| settings::load(&path))
+                .map(|settings| settings.log_level)
+                .unwrap_or_default();
+            if let Err(e) = logs_dir(app.handle()).and_then(|dir| logging::init(&dir, level)) {
+                eprintln!("Failed to set up logging: {e}");
+            }
+            // Before anything reads the settings or todo.txt
+            let report = settings_path(app.handle())
+                .and_then(|path| migrate::run(&path, &todo_path()))
+                .unwrap_or_else(|e| {
+                    tracing::error!("Failed to migrate settings: {e}");
+                    None
+                });
+            if let Ok(mut taken) = app.state::<MigrationReportState>().0.lock() {
+                *taken = report;
+            }
+            if let Ok(list) = load_todos() {
+                *app.state::<KnownContent>().lock() = Some(list.to_content());
+            }
+            if daemon::requested() {
+                if let Err(e) = start_daemon(app.handle()) {
+                    tracing::error!("Failed to start daemon: {e}");
+                    eprintln!("Failed to start daemon: {e}");
+                    std::process::exit(1);
+                }
+            } else if follow_daemon(app.handle()) {
+                tracing::info!("following the daemon");
+            } else {
+                let handle = app.handle().clone();
+                start_background_work(app.handle(), move |notification| {
+                    if let Err(e) = handle.emit(REMINDER_EVENT, notification) {
+                        tracing::warn!("Failed to deliver reminder: {e}");
+                    }
+                });
+                let handle = app.handle().clone();
+                let served = daemon::socket_path().and_then(|path| {
+                    daemon::serve(&path, None, move |request| handle_request(&handle, request))
+                });
+                if let Err(e) = served {
+                    tracing::warn!("The command line can't reach this window: {e}");
+                }
+                let handle = app.handle().clone();
+                watch_todo_file(move |_, _| notify_external_change(&handle));
+            }
+            let rotate = settings_path(app.handle())
+                .and_then(|path| settings::load(&path))
+                .is_ok_and(|settings| settings.rotate_done);
+            if rotate {
+                if let Err(e) = compact_archive() {
+                    tracing::error!("Failed to rotate done.txt: {e}");
+                }
+            }
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| match event {
+            // The daemon has no window to close, it runs until it is killed
+            RunEvent::ExitRequested {
+                api, code: None, ..
+            } if daemon::requested() => {
+                api.prevent_exit();
+            }
+            // Don't leave Do Not Disturb on behind a session cut short
+            RunEvent::Exit => {
+                if let Err(e) = focus::stop(app) {
+                    tracing::error!("Failed to end focus session: {e}");
+                }
+            }
+            _ => {}
+        });
 }
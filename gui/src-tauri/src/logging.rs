@@ -0,0 +1,167 @@
+//! The app's log file and the diagnostics bundle users attach to bug reports.
+//!
+//! Logs go to `logs/` in the app data dir, one file per day, keeping the
+//! last [`KEPT_LOG_FILES`]. The bundle is a zip of those files plus the
+//! settings with everything personal replaced, see [`anonymize`]. Commands
+//! log their errors but skip arguments holding task text, tag names or
+//! addresses, so the logs are fine to share too.
+
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+use todotxt::geo::Location;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use types::settings::{LogLevel, Settings};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+pub const LOGS_DIR: &str = "logs";
+pub const KEPT_LOG_FILES: usize = 7;
+
+const REDACTED: &str = "<redacted>";
+
+fn level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Error => LevelFilter::ERROR,
+        LogLevel::Warn => LevelFilter::WARN,
+        LogLevel::Info => LevelFilter::INFO,
+        LogLevel::Debug => LevelFilter::DEBUG,
+        LogLevel::Trace => LevelFilter::TRACE,
+    }
+}
+
+/// Sends everything logged from now on at `level` or above to the files in `dir`.
+pub fn init(dir: &Path, level: LogLevel) -> Result<(), String> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("todo")
+        .filename_suffix("log")
+        .max_log_files(KEPT_LOG_FILES)
+        .build(dir)
+        .map_err(|e| e.to_string())?;
+    tracing_subscriber::fmt()
+        .with_writer(appender)
+        .with_ansi(false)
+        .with_max_level(level_filter(level))
+        .try_init()
+        .map_err(|e| e.to_string())
+}
+
+/// A copy of `settings` that is safe to share: the pairing code, calendar
+/// tokens, device addresses, hook commands, snippet expansions, file paths,
+/// list and tag names, including those in the feed and snapshot filters,
+/// and the places of contexts are replaced.
+pub fn anonymize(settings: &Settings) -> Settings {
+    let mut settings = settings.clone();
+    if !settings.sync.pairing_code.is_empty() {
+        settings.sync.pairing_code = REDACTED.to_string();
+    }
+    if !settings.calendar.token.is_empty() {
+        settings.calendar.token = REDACTED.to_string();
+    }
+    for (i, token) in settings.calendar.tokens.iter_mut().enumerate() {
+        token.name = format!("token {}", i + 1);
+        token.token = REDACTED.to_string();
+    }
+    for (i, device) in settings.sync.devices.iter_mut().enumerate() {
+        device.name = format!("device {}", i + 1);
+        device.address = REDACTED.to_string();
+    }
+    for hook in &mut settings.hooks {
+        hook.command = REDACTED.to_string();
+    }
+    for snippet in &mut settings.snippets {
+        snippet.expansion = REDACTED.to_string();
+    }
+    for (i, list) in settings.watched_lists.iter_mut().enumerate() {
+        list.name = format!("list {}", i + 1);
+        list.path = REDACTED.to_string();
+    }
+    let defaults = &mut settings.task_defaults;
+    for tag in defaults
+        .projects
+        .iter_mut()
+        .chain(&mut defaults.contexts)
+        .chain(&mut settings.reminders.muted_projects)
+    {
+        *tag = REDACTED.to_string();
+    }
+    for reminder in &mut settings.reminders.standing {
+        reminder.title = REDACTED.to_string();
+    }
+    for (i, place) in settings.context_locations.iter_mut().enumerate() {
+        place.context = format!("context {}", i + 1);
+        place.location = Location::default();
+    }
+    for (path, filter) in [
+        (&mut settings.feed.path, &mut settings.feed.filter),
+        (&mut settings.snapshot.path, &mut settings.snapshot.filter),
+    ] {
+        if !path.is_empty() {
+            *path = REDACTED.to_string();
+        }
+        for tag in [
+            &mut filter.project,
+            &mut filter.context,
+            &mut filter.assignee,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(filter.any_context.iter_mut().flatten())
+        {
+            *tag = REDACTED.to_string();
+        }
+    }
+    if let Some(path) = &mut settings.custom_css {
+        *path = REDACTED.to_string();
+    }
+    settings.tag_colors = settings
+        .tag_colors
+        .into_values()
+        .enumerate()
+        .map(|(i, color)| (format!("tag {}", i + 1), color))
+        .collect();
+    settings
+}
+
+/// Writes the log files from `logs_dir` and the anonymized `settings` to the zip file `out`.
+pub fn export_diagnostics(logs_dir: &Path, settings: &Settings, out: &Path) -> Result<(), String> {
+    let file = File::create(out).map_err(|e| format!("{}: {e}", out.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("about.txt", options)
+        .map_err(|e| e.to_string())?;
+    writeln!(
+        zip,
+        "{} {}\n{} {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+    .map_err(|e| e.to_string())?;
+
+    zip.start_file("settings.json", options)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_writer_pretty(&mut zip, &anonymize(settings)).map_err(|e| e.to_string())?;
+
+    let entries = match fs::read_dir(logs_dir) {
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        result => Some(result.map_err(|e| e.to_string())?),
+    };
+    for entry in entries.into_iter().flatten().flatten() {
+        if !entry.path().is_file() {
+            continue;
+        }
+        let name = format!("{LOGS_DIR}/{}", entry.file_name().to_string_lossy());
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        let content = fs::read(entry.path()).map_err(|e| e.to_string())?;
+        zip.write_all(&content).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
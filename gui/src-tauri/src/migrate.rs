@@ -0,0 +1,206 @@
+//! Upgrading the settings file, and todo.txt if the user lets it, when a
+//! convention changes between versions. Each migration raises the settings
+//! version by one; at startup those above the file's version run in order,
+//! after a copy of each file they change is kept next to it. What they did
+//! is reported once to the frontend as a changelog.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use types::settings::{DEFAULT_PROJECT_SEPARATOR, SETTINGS_VERSION};
+use types::MigrationReport;
+
+use crate::settings::validate_project_separator;
+
+struct Migration {
+    /// The settings version it upgrades to
+    version: u32,
+    /// What it changes, for the changelog
+    description: &'static str,
+    /// Upgrades the settings, returning whether they changed
+    settings: fn(&mut Map<String, Value>) -> bool,
+    /// Rewrites a line of todo.txt, for migrations that touch it
+    todo_line: Option<fn(&str) -> String>,
+}
+
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] = [
+    Migration {
+        version: 1,
+        description: "The project separator was empty or had spaces and is back to \"---\".",
+        settings: reset_invalid_separator,
+        todo_line: None,
+    },
+    Migration {
+        version: 2,
+        description: "energy:medium tags in todo.txt are written energy:med now.",
+        settings: |_| false,
+        todo_line: Some(shorten_energy),
+    },
+    Migration {
+        version: 3,
+        description: "Server tokens without ticked endpoints could use all of them and have them all ticked now, as none ticked allows none.",
+        settings: tick_all_endpoints,
+        todo_line: None,
+    },
+];
+
+fn reset_invalid_separator(settings: &mut Map<String, Value>) -> bool {
+    let invalid = settings
+        .get("project_separator")
+        .and_then(Value::as_str)
+        .is_some_and(|separator| validate_project_separator(separator).is_err());
+    if invalid {
+        settings.insert(
+            "project_separator".to_string(),
+            DEFAULT_PROJECT_SEPARATOR.into(),
+        );
+    }
+    invalid
+}
+
+fn shorten_energy(line: &str) -> String {
+    let words: Vec<&str> = line
+        .split(' ')
+        .map(|word| {
+            if word == "energy:medium" {
+                "energy:med"
+            } else {
+                word
+            }
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn tick_all_endpoints(settings: &mut Map<String, Value>) -> bool {
+    let Some(tokens) = settings
+        .get_mut("calendar")
+        .and_then(|calendar| calendar.get_mut("tokens"))
+        .and_then(Value::as_array_mut)
+    else {
+        return false;
+    };
+    let mut changed = false;
+    for token in tokens.iter_mut().filter_map(Value::as_object_mut) {
+        let ticked = token
+            .get("endpoints")
+            .and_then(Value::as_array)
+            .is_some_and(|endpoints| !endpoints.is_empty());
+        if !ticked {
+            token.insert("endpoints".to_string(), json!(["calendar", "todos"]));
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Where the copy of `path` from before migrating `from` goes, e.g.
+/// `settings.json.v1.bak`.
+fn backup_path(path: &Path, from: u32) -> PathBuf {
+    let name = path
+        .file_name()
+        .map_or("backup".into(), |name| name.to_string_lossy());
+    path.with_file_name(format!("{name}.v{from}.bak"))
+}
+
+fn backup(path: &Path, from: u32, report: &mut MigrationReport) -> Result<(), String> {
+    let copy = backup_path(path, from);
+    fs::copy(path, &copy).map_err(|e| format!("{}: {e}", path.display()))?;
+    report.backups.push(copy.display().to_string());
+    Ok(())
+}
+
+/// The version of `settings`; 0 for those from before versions.
+fn version(settings: &Map<String, Value>) -> u32 {
+    settings
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Runs the settings part of the migrations `settings` haven't had yet,
+/// e.g. for settings imported from an older version. todo.txt is left as
+/// it is; the migrations at startup take care of it.
+pub fn upgrade(settings: &mut Map<String, Value>) {
+    let from = version(settings);
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from) {
+        (migration.settings)(settings);
+    }
+    settings.insert("version".to_string(), SETTINGS_VERSION.into());
+}
+
+/// Runs the migrations the settings at `settings_path` haven't had yet,
+/// including those of `todo_path` unless the settings say not to. `None`
+/// if there was nothing to do; a missing settings file is already current.
+pub fn run(settings_path: &Path, todo_path: &Path) -> Result<Option<MigrationReport>, String> {
+    let content = match fs::read_to_string(settings_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut value: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let Some(settings) = value.as_object_mut() else {
+        return Err("settings aren't a JSON object".to_string());
+    };
+    let from = version(settings);
+    if from >= SETTINGS_VERSION {
+        return Ok(None);
+    }
+
+    let migrate_todo = settings
+        .get("migrate_todo_file")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let todo = if migrate_todo && todo_path.exists() {
+        Some(fs::read_to_string(todo_path).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
+    let mut report = MigrationReport {
+        from,
+        to: SETTINGS_VERSION,
+        ..MigrationReport::default()
+    };
+    backup(settings_path, from, &mut report)?;
+    let mut lines: Vec<String> = todo
+        .iter()
+        .flat_map(|t| t.lines())
+        .map(String::from)
+        .collect();
+    let mut todo_changed = false;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from) {
+        let mut changed = (migration.settings)(settings);
+        if let Some(rewrite) = migration.todo_line {
+            if todo.is_none() {
+                let description = migration.description;
+                report
+                    .changes
+                    .push(format!("Left out for todo.txt: {description}"));
+            }
+            for line in &mut lines {
+                let new = rewrite(line);
+                changed |= new != *line;
+                todo_changed |= new != *line;
+                *line = new;
+            }
+        }
+        if changed {
+            report.changes.push(migration.description.to_string());
+        }
+    }
+    if todo_changed {
+        backup(todo_path, from, &mut report)?;
+        let mut new = lines.join("\n");
+        new.push('\n');
+        fs::write(todo_path, new).map_err(|e| e.to_string())?;
+    }
+
+    settings.insert("version".to_string(), SETTINGS_VERSION.into());
+    let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    fs::write(settings_path, content).map_err(|e| e.to_string())?;
+    tracing::info!(from, to = SETTINGS_VERSION, "migrated settings");
+    Ok(Some(report))
+}
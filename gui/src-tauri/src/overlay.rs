@@ -0,0 +1,45 @@
+//! The overlay: a small translucent window without borders that stays on
+//! top, showing the pending todos (see `gui/src/overlay.rs`). It can let
+//! clicks through to the windows below, so it sits over other work without
+//! getting in the way.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use types::settings::OverlaySettings;
+
+pub const LABEL: &str = "overlay";
+const WIDTH: f64 = 320.0;
+const HEIGHT: f64 = 420.0;
+
+/// Opens the overlay, or brings it to the front if it is open.
+pub fn open(app: &AppHandle, settings: &OverlaySettings) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.show()?;
+        return window.set_focus();
+    }
+    WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html#/overlay".into()))
+        .title("todo.txt")
+        .inner_size(WIDTH, HEIGHT)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build()?;
+    apply(app, settings)
+}
+
+/// Applies changed `settings` to the overlay if it is open. The opacity is
+/// up to its stylesheet.
+pub fn apply(app: &AppHandle, settings: &OverlaySettings) -> tauri::Result<()> {
+    match app.get_webview_window(LABEL) {
+        Some(window) => window.set_ignore_cursor_events(settings.click_through),
+        None => Ok(()),
+    }
+}
+
+pub fn close(app: &AppHandle) -> tauri::Result<()> {
+    match app.get_webview_window(LABEL) {
+        Some(window) => window.close(),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,264 @@
+//! Discovery and running of installed plugins, see [`todotxt::plugin`].
+//!
+//! Plugins live in the `plugins` directory of the app data dir, one
+//! directory per plugin. A plugin's module only runs once the user granted
+//! everything its manifest asks for. It runs in a sandbox with nothing
+//! linked in, so all it can do is compute on what the app hands it, and the
+//! app only hands it the list and takes changes back as its capabilities
+//! allow. Each call starts a fresh instance with [`FUEL`] to burn; a view
+//! gets one instance and one such budget for all the todos it renders.
+
+use std::fmt::Display;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use todotxt::plugin::{Capability, Manifest, MANIFEST_FILE};
+use types::PluginInfo;
+use wasmi::{Config, Engine, Linker, Memory, Module, Store, TypedFunc};
+
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// Roughly the instructions one call or one render of a view may run, so a
+/// plugin stuck in a loop fails instead of hanging the app.
+const FUEL: u64 = 1_000_000_000;
+const MAX_OUTPUT: usize = 16 * 1024 * 1024;
+
+/// Lists the plugins in `dir`, including ones whose manifest is broken.
+pub fn discover(
+    dir: &Path,
+    granted: impl Fn(&str) -> Vec<Capability>,
+) -> Result<Vec<PluginInfo>, String> {
+    let entries = match fs::read_dir(dir) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        result => result.map_err(|e| e.to_string())?,
+    };
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let dir = entry.file_name().to_string_lossy().into_owned();
+        let info = match fs::read_to_string(entry.path().join(MANIFEST_FILE))
+            .map_err(|e| e.to_string())
+            .and_then(|json| Manifest::parse(&json))
+        {
+            Ok(manifest) => PluginInfo {
+                dir,
+                missing: manifest.missing(&granted(&manifest.name)),
+                manifest: Some(manifest),
+                error: None,
+            },
+            Err(e) => PluginInfo {
+                dir,
+                manifest: None,
+                error: Some(e),
+                missing: Vec::new(),
+            },
+        };
+        plugins.push(info);
+    }
+    plugins.sort_by(|a, b| a.dir.cmp(&b.dir));
+    Ok(plugins)
+}
+
+/// A plugin with everything it asks for granted, ready to run.
+pub struct Plugin {
+    pub manifest: Manifest,
+    engine: Engine,
+    module: Module,
+}
+
+/// The plugins in `dir` that may run. Those that fail to load are logged
+/// and left out.
+pub fn load(dir: &Path, granted: impl Fn(&str) -> Vec<Capability>) -> Result<Vec<Plugin>, String> {
+    let mut plugins = Vec::new();
+    for info in discover(dir, &granted)? {
+        let Some(manifest) = info.manifest.filter(|_| info.missing.is_empty()) else {
+            continue;
+        };
+        match Plugin::new(&dir.join(&info.dir), manifest) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => tracing::warn!("plugin {}: {e}", info.dir),
+        }
+    }
+    Ok(plugins)
+}
+
+impl Plugin {
+    fn new(dir: &Path, manifest: Manifest) -> Result<Self, String> {
+        let wasm = fs::read(dir.join(&manifest.module))
+            .map_err(|e| format!("{}: {e}", manifest.module))?;
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &wasm).map_err(|e| e.to_string())?;
+        Ok(Self {
+            manifest,
+            engine,
+            module,
+        })
+    }
+
+    /// Whether the plugin may do what `capability` allows.
+    pub fn may(&self, capability: Capability) -> bool {
+        self.manifest.capabilities.contains(&capability)
+    }
+
+    /// A fresh instance of the module with [`FUEL`] to burn.
+    fn start(&self) -> Result<Instance<'_>, String> {
+        let fail = |e: &dyn Display| format!("Plugin {} failed to start: {e}", self.manifest.name);
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(FUEL).map_err(|e| fail(&e))?;
+        let instance = Linker::<()>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .map_err(|e| fail(&e))?
+            .start(&mut store)
+            .map_err(|e| fail(&e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| fail(&"it exports no memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| fail(&e))?;
+        Ok(Instance {
+            plugin: self,
+            store,
+            instance,
+            memory,
+            alloc,
+        })
+    }
+
+    /// Calls `export` with `input` in a fresh instance.
+    fn call(&self, export: &str, input: &str) -> Result<String, String> {
+        self.start()?.call(export, input)
+    }
+
+    /// A new todo's `line` rewritten by each of the plugin's transforms.
+    pub fn transform(&self, line: &str) -> Result<String, String> {
+        self.manifest
+            .transforms
+            .iter()
+            .try_fold(line.to_string(), |line, export| self.call(export, &line))
+    }
+
+    /// Runs the command `export` on the list's `content`, which it only
+    /// gets if it may read tasks.
+    pub fn command(&self, export: &str, content: &str) -> Result<String, String> {
+        if !self.manifest.commands.iter().any(|c| c.export == export) {
+            return Err(format!(
+                "Plugin {} has no command {export}",
+                self.manifest.name
+            ));
+        }
+        let content = if self.may(Capability::ReadTasks) {
+            content
+        } else {
+            ""
+        };
+        self.call(export, content)
+    }
+
+    /// Whether the sidebar view `export` shows each of the todo `lines`,
+    /// all asked of one instance.
+    pub fn view(&self, export: &str, lines: &[String]) -> Result<Vec<bool>, String> {
+        if !self.manifest.views.iter().any(|v| v.export == export) {
+            return Err(format!(
+                "Plugin {} has no view {export}",
+                self.manifest.name
+            ));
+        }
+        let mut instance = self.start()?;
+        lines
+            .iter()
+            .map(|line| {
+                let output = instance.call(export, line)?;
+                Ok(!output.is_empty() && output != "0")
+            })
+            .collect()
+    }
+}
+
+/// A running instance of a plugin's module. Calls share its memory and
+/// what is left of its fuel.
+struct Instance<'a> {
+    plugin: &'a Plugin,
+    store: Store<()>,
+    instance: wasmi::Instance,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+}
+
+impl Instance<'_> {
+    /// Calls `export` with `input`, following the ABI in [`todotxt::plugin`].
+    fn call(&mut self, export: &str, input: &str) -> Result<String, String> {
+        let name = &self.plugin.manifest.name;
+        let fail = |e: &dyn Display| format!("Plugin {name} failed in {export}: {e}");
+        let store = &mut self.store;
+        let func = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&*store, export)
+            .map_err(|e| fail(&e))?;
+
+        let len = i32::try_from(input.len()).map_err(|e| fail(&e))?;
+        let ptr = self.alloc.call(&mut *store, len).map_err(|e| fail(&e))?;
+        self.memory
+            .write(&mut *store, ptr as u32 as usize, input.as_bytes())
+            .map_err(|e| fail(&e))?;
+        let packed = func.call(&mut *store, (ptr, len)).map_err(|e| fail(&e))? as u64;
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if len > MAX_OUTPUT {
+            return Err(fail(&"its output is too large"));
+        }
+        let mut output = vec![0; len];
+        self.memory
+            .read(&*store, ptr, &mut output)
+            .map_err(|e| fail(&e))?;
+        String::from_utf8(output).map_err(|e| fail(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_keeps_one_instance() {
+        // Shows every todo but the first it is asked about
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "01")
+                (global $asked (mut i32) (i32.const 0))
+                (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+                (func (export "later") (param i32 i32) (result i64)
+                    (i64.or
+                        (i64.shl (i64.extend_i32_u (global.get $asked)) (i64.const 32))
+                        (i64.const 1))
+                    (global.set $asked (i32.const 1))))"#,
+        )
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!("plugins-{}", std::process::id()));
+        let plugin_dir = dir.join("later");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("later.wasm"), wasm).unwrap();
+        fs::write(
+            plugin_dir.join(MANIFEST_FILE),
+            r#"{"name": "later", "version": "1", "module": "later.wasm",
+                "capabilities": ["read-tasks", "sidebar-views"],
+                "views": [{"export": "later", "title": "Later"}]}"#,
+        )
+        .unwrap();
+
+        let granted = |_: &str| vec![Capability::ReadTasks, Capability::SidebarViews];
+        let plugins = load(&dir, granted).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let lines = ["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(
+            plugins[0].view("later", &lines).unwrap(),
+            [false, true, true]
+        );
+        assert!(plugins[0].view("sooner", &lines).is_err());
+    }
+}
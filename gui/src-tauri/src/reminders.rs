@@ -0,0 +1,112 @@
+//! The reminder scheduler, a background thread that looks for reminders that
+//! came due once a minute, see [`todotxt::reminders`]: those for due tasks
+//! and the standing ones from the settings.
+//!
+//! The time of the last look is kept on disk, so reminders that came due
+//! while the machine was suspended or the app was closed go out on the next
+//! look, merged into one notification. A look that comes much later than
+//! the minute it waited means the machine was suspended in between. Quiet
+//! hours work the same way: looks during them are skipped, so what came due
+//! goes out when they end.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use todotxt::activity;
+use todotxt::reminders::{self, Notification, Rules};
+use types::settings::Settings;
+
+use crate::cache;
+
+const TICK: Duration = Duration::from_secs(60);
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Looks for reminders in `todo_path` from a background thread, with the
+/// settings `load_settings` returns at each look, and hands what is due
+/// to `deliver`. `state_path` keeps the time of the last look.
+pub fn start(
+    todo_path: PathBuf,
+    state_path: PathBuf,
+    load_settings: impl Fn() -> Result<Settings, String> + Send + 'static,
+    deliver: impl Fn(Notification) + Send + 'static,
+) {
+    thread::spawn(move || {
+        let mut last = read_last_check(&state_path).unwrap_or_else(activity::now);
+        loop {
+            let now = activity::now();
+            if (now - last).to_std().unwrap_or_default() > TICK * 3 {
+                tracing::info!("reminders: resumed after {}", now - last);
+            }
+            match check(&todo_path, &load_settings, last, now) {
+                Ok(Check::Quiet) => {
+                    thread::sleep(TICK);
+                    continue;
+                }
+                Ok(Check::Due(notifications)) => notifications.into_iter().for_each(&deliver),
+                Err(e) => tracing::warn!("reminders: {e}"),
+            }
+            last = now;
+            if let Err(e) = fs::write(&state_path, now.format(TIME_FORMAT).to_string()) {
+                tracing::warn!("reminders: {e}");
+            }
+            thread::sleep(TICK);
+        }
+    });
+}
+
+enum Check {
+    Due(Vec<Notification>),
+    /// In quiet hours; the reminders since the last look wait until they end
+    Quiet,
+}
+
+fn check(
+    todo_path: &Path,
+    load_settings: &impl Fn() -> Result<Settings, String>,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Result<Check, String> {
+    let settings = load_settings()?;
+    let reminder_settings = &settings.reminders;
+    if reminder_settings.quiet_hours {
+        let start = parse_time(&reminder_settings.quiet_start)?;
+        let end = parse_time(&reminder_settings.quiet_end)?;
+        if reminders::in_quiet_hours(now.time(), start, end) {
+            return Ok(Check::Quiet);
+        }
+    }
+    let mut notifications: Vec<Notification> = reminder_settings
+        .standing
+        .iter()
+        .filter(|reminder| reminder.due_between(since, now))
+        .map(|reminder| reminder.notification())
+        .collect();
+    if reminder_settings.enabled {
+        let time = parse_time(&reminder_settings.time)?;
+        let rules = Rules {
+            muted_projects: reminder_settings.muted_projects.clone(),
+            min_priority: reminder_settings.min_priority,
+        };
+        let list = cache::load(todo_path).map_err(|e| e.to_string())?;
+        let items = list
+            .items()
+            .iter()
+            .filter(|item| rules.allows(item, &settings.project_separator));
+        let due = reminders::due_between(items, time, since, now);
+        notifications.extend(reminders::notification(&due));
+    }
+    Ok(Check::Due(notifications))
+}
+
+fn parse_time(text: &str) -> Result<NaiveTime, String> {
+    reminders::parse_time(text).ok_or_else(|| format!("invalid time {text}"))
+}
+
+/// `None` before the first look, and if the file is unreadable
+fn read_last_check(path: &Path) -> Option<NaiveDateTime> {
+    let text = fs::read_to_string(path).ok()?;
+    NaiveDateTime::parse_from_str(text.trim(), TIME_FORMAT).ok()
+}
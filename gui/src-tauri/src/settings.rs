@@ -0,0 +1,34 @@
+//! Loading and saving the settings defined in [`types::settings`].
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub use types::settings::Settings;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Project separators end up inside `+project` tags, so they can't be empty or contain whitespace.
+pub fn validate_project_separator(separator: &str) -> Result<(), String> {
+    if separator.is_empty() || separator.contains(char::is_whitespace) {
+        return Err("Project separator must be non-empty and contain no spaces".to_string());
+    }
+    Ok(())
+}
+
+/// Reads settings from `path`, falling back to defaults when the file doesn't exist yet.
+pub fn load(path: &Path) -> Result<Settings, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn save(settings: &Settings, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
@@ -0,0 +1,49 @@
+//! The chime played when a task is checked off, synthesized as a WAV file
+//! instead of shipping one.
+
+use std::f32::consts::TAU;
+
+const SAMPLE_RATE: u32 = 22_050;
+/// Two rising notes, E6 and A6, with how long each rings in seconds
+const NOTES: [(f32, f32); 2] = [(1318.5, 0.09), (1760.0, 0.22)];
+
+/// A short two-note chime as a 16-bit mono WAV file.
+pub fn chime() -> Vec<u8> {
+    let samples: Vec<i16> = NOTES
+        .iter()
+        .flat_map(|&(frequency, seconds)| {
+            let count = (SAMPLE_RATE as f32 * seconds) as usize;
+            (0..count).map(move |i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                // Fade out so the note doesn't end in a click
+                let envelope = (1.0 - i as f32 / count as f32).powi(2);
+                let value = (TAU * frequency * t).sin() * envelope * 0.3;
+                (value * f32::from(i16::MAX)) as i16
+            })
+        })
+        .collect();
+    wav(&samples)
+}
+
+fn wav(samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    out.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    // Bytes per frame and bits per sample
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+    out
+}
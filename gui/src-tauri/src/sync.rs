@@ -0,0 +1,464 @@
+//! Direct sync between two instances of the app on the same network.
+//!
+//! Each device keeps an [`OpLog`] of the changes to its todo.txt, recorded
+//! from the file whenever it syncs. One device serves over TCP; the other
+//! connects, and each sends the ops the other lacks, so both logs end up
+//! with the same ops and both lists with the same lines, whichever device
+//! synced with which before. Devices are added by address and must share a
+//! pairing code.
+//!
+//! Serving devices announce themselves over mDNS as [`SERVICE_TYPE`] and
+//! are found with [`discover`]. mDNS doesn't cross routers, so devices
+//! elsewhere still need to be added by address.
+//!
+//! The pairing code never goes over the network. Each side sends a random
+//! challenge and proves it knows the code with an HMAC-SHA256 of both, the
+//! connecting side first, so a device without the code gets nothing to
+//! guess the code from by connecting. Everything sent carries an HMAC keyed
+//! for that session, so a device without the code can neither read the
+//! ops nor slip in some of its own. The ops themselves aren't encrypted.
+//!
+//! Both sides write the merged list through a [`Store`], the way the app
+//! writes any change, and only if their list didn't change since they
+//! recorded it. The log is saved only once the list is written, so the two
+//! always agree.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use todotxt::oplog::{Op, OpLog, TaskId, Vector};
+
+/// File name of the op log, in the app's sync directory.
+pub const LOG_FILE: &str = "oplog.json";
+
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+/// Longer than any line of the handshake.
+const MAX_LINE: u64 = 1024;
+const TIMEOUT: Duration = Duration::from_secs(10);
+const NONCE_LEN: usize = 16;
+/// The mDNS service type serving devices announce.
+pub const SERVICE_TYPE: &str = "_todotxt-sync._tcp.local.";
+/// How long [`discover`] waits for answers.
+const DISCOVERY_TIME: Duration = Duration::from_secs(2);
+
+/// HMAC-SHA256 of `message` with `key`, see RFC 2104.
+fn hmac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Whether the hex `mac` is `expected`, taking as long whatever it holds.
+fn verify(mac: &str, expected: &[u8]) -> bool {
+    let expected = hex(expected);
+    mac.len() == expected.len()
+        && mac
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn nonce() -> io::Result<String> {
+    let mut bytes = [0u8; NONCE_LEN];
+    getrandom::fill(&mut bytes).map_err(io::Error::other)?;
+    Ok(hex(&bytes))
+}
+
+/// What both sides of a session derive from the pairing code and the two
+/// challenges.
+struct Session {
+    code: Vec<u8>,
+    client: String,
+    server: String,
+}
+
+impl Session {
+    /// The proof of knowing the code that `role` sends.
+    fn proof(&self, role: &str) -> [u8; 32] {
+        let message = format!("{role} {} {}", self.client, self.server);
+        hmac(&self.code, message.as_bytes())
+    }
+
+    /// The MAC of a frame sent during the session.
+    fn seal(&self, content: &str) -> [u8; 32] {
+        let key = hmac(
+            &self.code,
+            format!("frame {} {}", self.client, self.server).as_bytes(),
+        );
+        hmac(&key, content.as_bytes())
+    }
+}
+
+/// The list a sync reads and writes.
+pub trait Store {
+    /// The list's content, with a revision that changes whenever it does.
+    fn read(&self) -> io::Result<(String, u64)>;
+
+    /// Replaces the list with `content`, naming `peer` as the one who made
+    /// the changes, unless it changed since `revision`.
+    fn write(&self, content: &str, revision: u64, peer: &str) -> io::Result<()>;
+}
+
+/// The error of writing a list that changed since it was read.
+pub fn changed_error() -> io::Error {
+    io::Error::other("changed during sync, try again")
+}
+
+/// Held while a log is read, changed and saved with its list, as sessions
+/// run side by side.
+static LOG: Mutex<()> = Mutex::new(());
+
+/// The op log at `path`, a new one for a replica of a random name if there
+/// is none yet.
+fn load_log(path: &Path) -> io::Result<OpLog> {
+    match fs::read_to_string(path) {
+        Ok(json) => decode(&json),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(OpLog::new(&nonce()?)),
+        Err(e) => Err(e),
+    }
+}
+
+fn save_log(path: &Path, log: &OpLog) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Renamed into place, so an interruption leaves the old log
+    let partial = path.with_extension("json.partial");
+    fs::write(&partial, encode(log)?)?;
+    fs::rename(&partial, path)
+}
+
+/// Records what changed in the list of `store` into the log at `log_path`,
+/// merges `ops` into it and writes the list that results, naming `peer` for
+/// the changes. The todos of `order` come first, then the others in the
+/// order of the list. Returns the log as saved and the order of the list
+/// written.
+fn apply(
+    store: &impl Store,
+    log_path: &Path,
+    ops: Vec<Op>,
+    order: &[TaskId],
+    peer: &str,
+) -> io::Result<(OpLog, Vec<TaskId>)> {
+    let _log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let mut log = load_log(log_path)?;
+    let (content, revision) = store.read()?;
+    let own = log.record(&content);
+    log.merge(ops);
+    let order: Vec<TaskId> = order.iter().chain(&own).cloned().collect();
+    let merged = log.arrange(&order).to_content();
+    if merged != content {
+        store.write(&merged, revision, peer)?;
+    }
+    save_log(log_path, &log)?;
+    // Matches the log line for line, so this records nothing
+    let order = log.record(&merged);
+    Ok((log, order))
+}
+
+/// What the serving side answers the connecting one's [`Vector`] with.
+#[derive(Serialize, Deserialize)]
+struct Offer {
+    /// The ops the connecting side lacks
+    ops: Vec<Op>,
+    vector: Vector,
+}
+
+/// What the connecting side sends back once it merged the [`Offer`].
+#[derive(Serialize, Deserialize)]
+struct Push {
+    /// The ops the serving side lacks
+    ops: Vec<Op>,
+    /// The order of the merged list, for both lists to end up alike
+    order: Vec<TaskId>,
+}
+
+fn encode(value: &impl Serialize) -> io::Result<String> {
+    serde_json::to_string(value).map_err(io::Error::other)
+}
+
+fn decode<T: DeserializeOwned>(json: &str) -> io::Result<T> {
+    serde_json::from_str(json).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Serves the op log at `log_path` and the list of `store` to paired
+/// devices on `port`, each session on a background thread of its own, and
+/// announces it over mDNS. It listens on every interface, since the devices
+/// are elsewhere on the network, and only answers those proving they know
+/// `pairing_code`.
+pub fn serve(
+    port: u16,
+    pairing_code: String,
+    store: impl Store + Send + Sync + 'static,
+    log_path: PathBuf,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    let mdns = announce(port)?;
+    let pairing_code: Arc<str> = pairing_code.into();
+    let store = Arc::new(store);
+    let log_path = Arc::new(log_path);
+    thread::spawn(move || {
+        // Answers mDNS queries for as long as sessions are taken
+        let _mdns = mdns;
+        for stream in listener.incoming().flatten() {
+            let pairing_code = Arc::clone(&pairing_code);
+            let store = Arc::clone(&store);
+            let log_path = Arc::clone(&log_path);
+            thread::spawn(move || {
+                if let Err(e) = handle(stream, &pairing_code, &*store, &log_path) {
+                    tracing::warn!("sync: {e}");
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Announces sync on `port` as a [`SERVICE_TYPE`] instance with the
+/// addresses of every interface, for as long as the returned daemon lives.
+fn announce(port: u16) -> io::Result<ServiceDaemon> {
+    let mdns = ServiceDaemon::new().map_err(io::Error::other)?;
+    let name = format!("todotxt-{}", &nonce()?[..8]);
+    let info = ServiceInfo::new(
+        SERVICE_TYPE,
+        &name,
+        &format!("{name}.local."),
+        (),
+        port,
+        None::<HashMap<String, String>>,
+    )
+    .map_err(io::Error::other)?
+    .enable_addr_auto();
+    mdns.register(info).map_err(io::Error::other)?;
+    Ok(mdns)
+}
+
+/// Addresses of the devices on the local network serving sync, this one
+/// included if it does, as `host:port`.
+pub fn discover() -> io::Result<Vec<String>> {
+    let mdns = ServiceDaemon::new().map_err(io::Error::other)?;
+    let events = mdns.browse(SERVICE_TYPE).map_err(io::Error::other)?;
+    let deadline = Instant::now() + DISCOVERY_TIME;
+    let mut found = Vec::new();
+    while let Ok(event) = events.recv_deadline(deadline) {
+        let ServiceEvent::ServiceResolved(info) = event else {
+            continue;
+        };
+        for ip in info.get_addresses_v4() {
+            let address = format!("{ip}:{}", info.get_port());
+            if !found.contains(&address) {
+                found.push(address);
+            }
+        }
+    }
+    if let Err(e) = mdns.shutdown() {
+        tracing::warn!("sync: {e}");
+    }
+    Ok(found)
+}
+
+/// Handles one session.
+fn handle(
+    stream: TcpStream,
+    pairing_code: &str,
+    store: &impl Store,
+    log_path: &Path,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let peer = stream.peer_addr()?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let request = read_line(&mut reader)?;
+    let Some(client) = request.strip_prefix("HELLO ") else {
+        writer.write_all(b"ERR not paired\n")?;
+        return Ok(());
+    };
+    let session = Session {
+        code: pairing_code.as_bytes().to_vec(),
+        client: client.to_string(),
+        server: nonce()?,
+    };
+    // Nothing derived from the code goes out before the other side proved
+    // it knows it
+    writeln!(writer, "CHALLENGE {}", session.server)?;
+    let proof = read_line(&mut reader)?;
+    if !proof
+        .strip_prefix("PROOF ")
+        .is_some_and(|mac| verify(mac, &session.proof("client")))
+    {
+        writer.write_all(b"ERR not paired\n")?;
+        return Ok(());
+    }
+    writeln!(writer, "OK {}", hex(&session.proof("server")))?;
+    let vector: Vector = decode(&read_frame(&mut reader, &session)?)?;
+    let peer = peer.to_string();
+    // The changes made here since the last sync get recorded before they
+    // are offered
+    let (log, _) = apply(store, log_path, Vec::new(), &[], &peer)?;
+    let offer = Offer {
+        ops: log.ops_since(&vector),
+        vector: log.vector(),
+    };
+    write_frame(&mut writer, &encode(&offer)?, &session)?;
+
+    let push: Push = decode(&read_frame(&mut reader, &session)?)?;
+    // Edits made here while the other device was merging are recorded too
+    match apply(store, log_path, push.ops, &push.order, &peer) {
+        Ok(_) => writer.write_all(b"OK\n"),
+        Err(e) => writeln!(writer, "ERR {e}"),
+    }
+}
+
+/// The steps of [`sync_with`], in order.
+pub const STEPS: [&str; 3] = ["Connecting", "Merging", "Sending"];
+
+/// Syncs the list of `store` and the op log at `log_path` with the device
+/// at `address`. `on_step` runs with the index of each of the [`STEPS`] as
+/// it starts and stops the sync when it returns false. The merged list is
+/// written here before the ops are sent, so nothing is sent if the list
+/// changed meanwhile; ops the other device didn't get are sent next time.
+pub fn sync_with(
+    address: &str,
+    pairing_code: &str,
+    store: &impl Store,
+    log_path: &Path,
+    on_step: impl Fn(usize) -> bool,
+) -> io::Result<()> {
+    let step = |index| {
+        if on_step(index) {
+            Ok(())
+        } else {
+            Err(io::Error::new(ErrorKind::Interrupted, "cancelled"))
+        }
+    };
+    step(0)?;
+    let addr = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "address not found"))?;
+    let stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let mut session = Session {
+        code: pairing_code.as_bytes().to_vec(),
+        client: nonce()?,
+        server: String::new(),
+    };
+    writeln!(writer, "HELLO {}", session.client)?;
+    let challenge = read_line(&mut reader)?;
+    let Some(server) = challenge.strip_prefix("CHALLENGE ") else {
+        return Err(refused(&challenge));
+    };
+    session.server = server.to_string();
+    writeln!(writer, "PROOF {}", hex(&session.proof("client")))?;
+    let answer = read_line(&mut reader)?;
+    let Some(mac) = answer.strip_prefix("OK ") else {
+        return Err(refused(&answer));
+    };
+    // A device without the code doesn't get to see this list either
+    if !verify(mac, &session.proof("server")) {
+        return Err(io::Error::other("the device has another pairing code"));
+    }
+
+    step(1)?;
+    let vector = {
+        let _log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+        load_log(log_path)?.vector()
+    };
+    write_frame(&mut writer, &encode(&vector)?, &session)?;
+    let offer: Offer = decode(&read_frame(&mut reader, &session)?)?;
+    let (log, order) = apply(store, log_path, offer.ops, &[], address)?;
+
+    step(2)?;
+    let push = Push {
+        ops: log.ops_since(&offer.vector),
+        order,
+    };
+    write_frame(&mut writer, &encode(&push)?, &session)?;
+    expect_ok(&mut reader)
+}
+
+fn expect_ok(reader: &mut impl BufRead) -> io::Result<()> {
+    match read_line(reader)?.as_str() {
+        "OK" => Ok(()),
+        status => Err(refused(status)),
+    }
+}
+
+/// The error for the other side answering `status` where it should have
+/// gone on.
+fn refused(status: &str) -> io::Error {
+    let reason = match status.strip_prefix("ERR ") {
+        Some(reason) => reason,
+        None if status.is_empty() => "connection closed",
+        None => status,
+    };
+    io::Error::other(reason.to_string())
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    reader.take(MAX_LINE).read_line(&mut line)?;
+    Ok(line.trim_end().to_string())
+}
+
+/// Frames are the byte length on its own line followed by the content, JSON
+/// for all but the handshake, and its MAC on a line of its own, see
+/// [`Session::seal`].
+fn write_frame(writer: &mut impl Write, content: &str, session: &Session) -> io::Result<()> {
+    writeln!(writer, "{}", content.len())?;
+    writer.write_all(content.as_bytes())?;
+    writeln!(writer, "{}", hex(&session.seal(content)))?;
+    writer.flush()
+}
+
+fn read_frame(reader: &mut impl BufRead, session: &Session) -> io::Result<String> {
+    let len: usize = read_line(reader)?
+        .parse()
+        .map_err(|_| io::Error::new(ErrorKind::InvalidData, "invalid frame length"))?;
+    if len > MAX_FRAME {
+        return Err(io::Error::new(ErrorKind::InvalidData, "frame too large"));
+    }
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    let content = String::from_utf8(buf).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    if !verify(&read_line(reader)?, &session.seal(&content)) {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "list was altered on the way",
+        ));
+    }
+    Ok(content)
+}
@@ -1,48 +1,129 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Duration;
 
 use leptos::task::spawn_local;
 use leptos::{ev::SubmitEvent, prelude::*};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use todotxt::checklist::Progress;
+use todotxt::estimate;
+use todotxt::export::ExportFormat;
+use todotxt::filter::{Due, Filter, Flag, Status};
+use todotxt::geo;
+use todotxt::group::{self, GroupBy};
+use todotxt::reminders::Notification;
+use todotxt::rules::Overflow;
+use todotxt::share;
+use todotxt::sort::{self, SortBy};
+use todotxt::stats::{Counts, DueSummary, Streaks};
+use todotxt::view::ViewState;
+use todotxt::{Completion, Date, TodoItem};
+use types::{
+    AddTodoArgs, AssignTodoArgs, DeleteTodoArgs, Draft, EditDraft, EditTodoArgs, PluginExportArgs,
+    QueryTodosArgs, RenderFilteredArgs, ReorderTodoArgs, RestoreTodoArgs, Revised, SaveDraftArgs,
+    SaveSettingsArgs, SetAutoDoneArgs, SetFlagArgs, SetProjectSeparatorArgs, SetStarredArgs,
+    SyncNowArgs, Todo, TodoChange, TodoTagArgs, ToggleTodoArgs, INBOX_CHANGED_EVENT,
+    REMINDER_EVENT, TODO_ADDED_EVENT, TODO_REMOVED_EVENT, TODO_UPDATED_EVENT,
+};
 use wasm_bindgen::prelude::*;
 
-use crate::project_tree::{build_project_tree, render_project_tree, PROJECT_SEPARATOR};
+use crate::archive_dialog::ArchiveDialog;
+use crate::backend::{self, invoke, listen};
+use crate::backups::BackupDialog;
+use crate::captured::Captured;
+use crate::celebrate::{self, Celebration};
+use crate::changelog::ChangelogDialog;
+use crate::colors::{badge_style, flag_color, tag_color};
+use crate::custom_css::CustomCss;
+use crate::due_overview::{DueNav, WorkloadSummary};
+use crate::file_changes::FileChanges;
+use crate::filter_bar::{ActiveFilters, PriorityBands};
+use crate::filter_panel::FilterPanel;
+use crate::flag_menu::FlagMenu;
+use crate::focus::{self, FocusBar};
+use crate::geolocation;
+use crate::history::HistoryDialog;
+use crate::jobs::JobToasts;
+use crate::notify;
+use crate::palette::{Command, CommandPalette};
+use crate::picker::Picker;
+use crate::plugin_views::PluginViews;
+use crate::raw_editor::RawEditor;
+use crate::recent::Recent;
+use crate::replace_dialog::ReplaceDialog;
+use crate::review::{self, Review};
+use crate::rich_text::rich_text;
+use crate::settings::{
+    Density, DisplaySettings, GroupSettings, Settings, SettingsPage, ShortcutAction, SortSettings,
+};
+use crate::shift_dialog::ShiftDialog;
+use crate::shortcuts::{self, ShortcutsPage};
+use crate::snippets;
+use crate::sort_controls::SortControls;
+use crate::split_dialog::SplitDialog;
+use crate::streak::Streak;
+use crate::tag_manager::TagManager;
+use crate::tag_tree::{build_tag_tree, render_tag_tree, TagKind, TagNode, TagPath, TagTreeState};
+use crate::voice;
+use crate::week::WeekPlanner;
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+    #[wasm_bindgen(catch, js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+    pub(crate) async fn write_clipboard(text: &str) -> Result<JsValue, JsValue>;
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct TodoItem {
-    pub id: usize,
-    pub subject: String,
-    pub raw: String,
-    pub finished: bool,
-    pub priority: u8,
-    pub contexts: Vec<String>,
-    pub projects: Vec<String>,
+#[derive(Deserialize)]
+pub(crate) struct TauriEvent<T> {
+    pub(crate) payload: T,
 }
 
-#[derive(Serialize)]
-struct AddTodoArgs<'a> {
-    text: &'a str,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Todos,
+    Raw,
+    Tags,
+    Review,
+    Recent,
+    Week,
+    Captured,
+    Settings,
+    Shortcuts,
 }
 
-#[derive(Serialize)]
-struct ToggleTodoArgs {
-    id: usize,
-}
+/// How long a todo that needs confirming waits for the second click.
+const CONFIRM_SECS: u64 = 3;
 
-#[derive(Serialize)]
-struct EditTodoArgs<'a> {
-    id: usize,
-    text: &'a str,
+/// The header of a group of the list, see [`group::label`].
+fn group_header(by: GroupBy, label: Option<String>) -> String {
+    match (by, label) {
+        (GroupBy::Priority, Some(letter)) => format!("Priority {letter}"),
+        (_, Some(label)) => label,
+        (GroupBy::Priority, None) => "No priority".to_string(),
+        (GroupBy::Project, None) => "No project".to_string(),
+        (_, None) => "No context".to_string(),
+    }
 }
 
-#[derive(Serialize)]
-struct DeleteTodoArgs {
-    id: usize,
+/// What to tell the user about WIP limits a task just went over, if any.
+pub(crate) fn overflow_warning(overflows: &[Overflow]) -> Option<String> {
+    let limits: Vec<String> = overflows
+        .iter()
+        .map(|o| {
+            format!(
+                "{} has {} open tasks, the limit is {}",
+                o.limit.tag(),
+                o.count,
+                o.limit.max
+            )
+        })
+        .collect();
+    (!limits.is_empty()).then(|| {
+        format!(
+            "Over the WIP limit: {}. The task is tagged overflow:1.",
+            limits.join("; ")
+        )
+    })
 }
 
 fn priority_label(p: u8) -> Option<&'static str> {
@@ -54,51 +135,1158 @@ fn priority_label(p: u8) -> Option<&'static str> {
     }
 }
 
+/// Builds the sidebar tree for one kind of tag, only rebuilding when
+/// something it depends on (the tags, completion) changed.
+fn tag_tree_memo(
+    todos: Signal<Vec<Todo>>,
+    kind: TagKind,
+    separator: Memo<String>,
+) -> Memo<Vec<TagNode>> {
+    let key = Memo::new(move |_| {
+        let mut hasher = DefaultHasher::new();
+        todos.with(|items| {
+            for todo in items {
+                kind.tags(todo).hash(&mut hasher);
+                todo.finished.hash(&mut hasher);
+            }
+        });
+        hasher.finish()
+    });
+    Memo::new(move |_| {
+        key.track();
+        let separator = separator.get();
+        todos.with_untracked(|items| build_tag_tree(items, |todo| kind.tags(todo), &separator))
+    })
+}
+
+/// Count shown in the corner of a navigation icon; hidden while zero.
+fn count_badge(count: Signal<usize>) -> impl IntoView {
+    move || {
+        let count = count.get();
+        (count > 0).then(|| view! {
+            <span class="badge badge-xs badge-primary absolute -top-1 -right-1" aria-hidden="true">{count}</span>
+        })
+    }
+}
+
+/// A tag on a row of the list, with a button removing it from the todo.
+fn tag_chip(
+    tag: String,
+    style: Signal<String>,
+    read_only: Signal<bool>,
+    remove: Callback<String>,
+) -> impl IntoView {
+    let label = format!("Remove {tag}");
+    view! {
+        <span class="badge p-1 badge-outline badge-sm gap-0.5" style=move || style.get()>
+            {tag.clone()}
+            <button
+                type="button"
+                class="cursor-pointer opacity-60 hover:opacity-100 focus-visible:opacity-100"
+                class=("hidden", move || read_only.get())
+                aria-label=label
+                on:click=move |ev: leptos::ev::MouseEvent| {
+                    ev.stop_propagation();
+                    remove.run(tag.clone());
+                }
+            >
+                "×"
+            </button>
+        </span>" "
+    }
+}
+
+/// Navigation entry that shows the todo list with a preset filter.
+fn filter_nav_item(
+    label: &'static str,
+    icon_path: &'static str,
+    preset: Filter,
+    count: Signal<usize>,
+    is_active: impl Fn(Filter) -> bool + Copy + Send + Sync + 'static,
+    on_select: Callback<Filter>,
+) -> impl IntoView {
+    let active = {
+        let preset = preset.clone();
+        move || is_active(preset.clone())
+    };
+    let active_current = active.clone();
+    view! {
+        <li>
+            <button
+                type="button"
+                class="tooltip tooltip-right"
+                class=("menu-active", active)
+                data-tip=label
+                aria-label=move || format!("{label}, {} todos", count.get())
+                aria-current=move || active_current().then_some("page")
+                on:click=move |_| on_select.run(preset.clone())
+            >
+                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d=icon_path/>
+                </svg>
+                {count_badge(count)}
+            </button>
+        </li>
+    }
+}
+
 #[component]
 pub fn App() -> impl IntoView {
-    let (todos, set_todos) = signal(Vec::<TodoItem>::new());
+    let (todos, set_todos) = signal(Vec::<Todo>::new());
+    // The newest list revision seen; lists read before it are stale by the time they arrive
+    let (revision, set_revision) = signal(0_u64);
+    let is_current = move |seen: u64| {
+        if seen < revision.get_untracked() {
+            return false;
+        }
+        set_revision.set(seen);
+        true
+    };
     let (error, set_error) = signal(Option::<String>::None);
+    // Set after adding or editing a task went over a WIP limit
+    let (wip_warning, set_wip_warning) = signal(Option::<String>::None);
+    // Reminders that couldn't be shown as system notifications, and whether they lead to the review
+    let (reminder, set_reminder) = signal(Option::<(String, bool)>::None);
     let (dialog_open, set_dialog_open) = signal(false);
     let (new_todo, set_new_todo) = signal(String::new());
     let (editing_id, set_editing_id) = signal(Option::<usize>::None);
     let (edit_text, set_edit_text) = signal(String::new());
     let (projects_panel_open, set_projects_panel_open) = signal(false);
-    let (active_project_filter, set_active_project_filter) = signal(Option::<String>::None);
-    let (collapsed_nodes, set_collapsed_nodes) = signal(HashSet::<String>::new());
-
-    let project_tree = Memo::new(move |_| build_project_tree(&todos.get()));
-
-    let displayed_todos = Memo::new(move |_| {
-        let all = todos.get();
-        match active_project_filter.get() {
-            None => all,
-            Some(filter) => {
-                let prefix = format!("{}{}", filter, PROJECT_SEPARATOR);
-                all.into_iter()
-                    .filter(|todo| {
-                        todo.projects
+    let (filter, set_filter) = signal(Filter::default());
+    let (collapsed_nodes, set_collapsed_nodes) = signal(HashSet::<TagPath>::new());
+    let (view, set_view) = signal(View::Todos);
+    let (settings, set_settings) = signal(Settings::default());
+    let (streaks, set_streaks) = signal(Streaks::default());
+    let (due_summary, set_due_summary) = signal(DueSummary::default());
+    let (counts, set_counts) = signal(Counts::default());
+    // What inbox.txt holds, see [`Captured`]
+    let (inbox, set_inbox) = signal(Vec::<Todo>::new());
+    let (history_task, set_history_task) = signal(Option::<Todo>::None);
+    let (split_task, set_split_task) = signal(Option::<Todo>::None);
+    // Read out by screen readers after a change, e.g. "Completed: Buy milk"
+    let (announcement, set_announcement) = signal(String::new());
+    // The line and subject of the todo deleted last, until it's restored
+    let (last_deleted, set_last_deleted) = signal(Option::<(String, String)>::None);
+    // The todo whose flag menu is open, with where it was opened
+    let (flag_menu, set_flag_menu) = signal(Option::<(usize, i32, i32)>::None);
+    // How far along the checklist of each todo with one is, by id
+    let (checklists, set_checklists) = signal(HashMap::<usize, Progress>::new());
+    let (replace_open, set_replace_open) = signal(false);
+    let (shift_open, set_shift_open) = signal(false);
+    let (backups_open, set_backups_open) = signal(false);
+    let (picker_open, set_picker_open) = signal(false);
+    let (palette_open, set_palette_open) = signal(false);
+    let (archive_open, set_archive_open) = signal(false);
+    // Grouping from a view link, for this session only; picking another in
+    // the settings drops it
+    let (group_override, set_group_override) = signal(Option::<GroupSettings>::None);
+
+    let display = Memo::new(move |_| settings.get().display);
+
+    // Badge colors come from the palette's tokens in input.css
+    Effect::new(move |_| {
+        let palette = display.with(|d| d.palette);
+        if let Some(root) = document().document_element() {
+            let _ = root.set_attribute("data-palette", palette.name());
+        }
+    });
+    Effect::new(move |_| {
+        // Clamped in case the settings file was edited by hand
+        let zoom = display.with(|d| {
+            d.zoom
+                .clamp(DisplaySettings::ZOOM_MIN, DisplaySettings::ZOOM_MAX)
+        });
+        if let Some(root) = document().document_element() {
+            let _ = root.set_attribute("style", &format!("--zoom: {}", zoom as f64 / 100.0));
+        }
+    });
+
+    let add_button_ref = NodeRef::<leptos::html::Button>::new();
+    let new_todo_ref = NodeRef::<leptos::html::Input>::new();
+    let submit_ref = NodeRef::<leptos::html::Button>::new();
+
+    // A watched list is shown instead of the user's own while it's active.
+    // It's only read, so it's reloaded whenever it's opened instead of
+    // following events.
+    let (active_list, set_active_list) = signal(Option::<String>::None);
+    let watching = Memo::new(move |_| active_list.with(Option::is_some));
+    // Demo mode shows lorem ipsum, which must never be saved back
+    let demo_mode = Memo::new(move |_| settings.with(|s| s.display.demo_mode));
+    let read_only = Memo::new(move |_| backend::READ_ONLY || watching.get() || demo_mode.get());
+    let (watched_todos, set_watched_todos) = signal(Vec::<Todo>::new());
+    Effect::new(move |_| {
+        let Some(name) = active_list.get() else {
+            set_watched_todos.set(Vec::new());
+            return;
+        };
+        spawn_local(async move {
+            let filter = Filter::default();
+            let args = serde_wasm_bindgen::to_value(&QueryTodosArgs {
+                filter: &filter,
+                list: Some(&name),
+            })
+            .unwrap();
+            let result = invoke("query_todos", args).await;
+            match serde_wasm_bindgen::from_value::<Revised<Vec<Todo>>>(result) {
+                Ok(items) => set_watched_todos.set(items.value),
+                Err(e) => set_error.set(Some(format!("Failed to load {name}: {e}"))),
+            }
+        });
+    });
+    // Leave a watched list that was removed from the settings
+    Effect::new(move |_| {
+        let Some(name) = active_list.get() else {
+            return;
+        };
+        if settings.with(|s| !s.watched_lists.iter().any(|list| list.name == name)) {
+            set_active_list.set(None);
+        }
+    });
+    let shown_todos = Signal::derive(move || {
+        if demo_mode.get() {
+            todos.with(|items| items.iter().map(Todo::redacted).collect())
+        } else {
+            todos.get()
+        }
+    });
+    let list_todos = Signal::derive(move || match (watching.get(), demo_mode.get()) {
+        (true, true) => watched_todos.with(|items| items.iter().map(Todo::redacted).collect()),
+        (true, false) => watched_todos.get(),
+        (false, _) => shown_todos.get(),
+    });
+
+    // The plugin view picked in the sidebar, as (plugin, export), and the
+    // ids of the todos it shows, asked again whenever the list changes
+    let (plugin_view, set_plugin_view) = signal(Option::<(String, String)>::None);
+    let (plugin_view_ids, set_plugin_view_ids) = signal(Option::<Vec<usize>>::None);
+    Effect::new(move |_| {
+        list_todos.track();
+        let Some((plugin, export)) = plugin_view.get() else {
+            set_plugin_view_ids.set(None);
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PluginExportArgs {
+                plugin: &plugin,
+                export: &export,
+            })
+            .unwrap();
+            let result = invoke("plugin_view", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<usize>>(result) {
+                Ok(ids) => set_plugin_view_ids.set(Some(ids)),
+                Err(e) => {
+                    set_error.set(Some(format!("Failed to show the view of {plugin}: {e}")));
+                    set_plugin_view.set(None);
+                }
+            }
+        });
+    });
+    // Plugin views only look at the user's own list
+    Effect::new(move |_| {
+        if watching.get() {
+            set_plugin_view.set(None);
+        }
+    });
+
+    let separator = Memo::new(move |_| settings.with(|s| s.project_separator.clone()));
+    let project_tree = tag_tree_memo(list_todos, TagKind::Project, separator);
+    let context_tree = tag_tree_memo(list_todos, TagKind::Context, separator);
+
+    let tag_colors = Memo::new(move |_| settings.with(|s| s.tag_colors.clone()));
+    let snippets = Memo::new(move |_| settings.with(|s| s.snippets.clone()));
+    let tree_state = TagTreeState {
+        filter,
+        set_filter,
+        collapsed_nodes,
+        set_collapsed_nodes,
+        count_mode: Signal::derive(move || display.get().project_count),
+        tag_colors: tag_colors.into(),
+    };
+    // Everyone who has something assigned, for the filter and the assign menus
+    let assignees = Memo::new(move |_| {
+        let mut names: Vec<String> = list_todos.with(|items| {
+            items
+                .iter()
+                .filter_map(|todo| todo.assignee.clone())
+                .collect()
+        });
+        names.sort();
+        names.dedup();
+        names
+    });
+
+    // Every tag in the list, suggested when adding one to a todo
+    let tag_options = Memo::new(move |_| {
+        let mut tags: Vec<String> = list_todos.with(|items| {
+            items
+                .iter()
+                .flat_map(|todo| {
+                    let projects = todo.projects.iter().map(|p| format!("+{p}"));
+                    let contexts = todo.contexts.iter().map(|c| format!("@{c}"));
+                    projects
+                        .chain(contexts)
+                        .chain(TodoItem::new(&todo.raw).free_tags())
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        });
+        tags.sort();
+        tags.dedup();
+        tags
+    });
+
+    let add_tag = Callback::new(move |(id, tag): (usize, String)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&TodoTagArgs { id, tag: &tag }).unwrap();
+            let result = invoke("add_todo_tag", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<Overflow>>(result) {
+                Ok(overflows) => {
+                    set_error.set(None);
+                    set_wip_warning.set(overflow_warning(&overflows));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to add tag: {e}"))),
+            }
+        });
+    });
+
+    let remove_tag = Callback::new(move |(id, tag): (usize, String)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&TodoTagArgs { id, tag: &tag }).unwrap();
+            let result = invoke("remove_todo_tag", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to remove tag: {e}"))),
+            }
+        });
+    });
+
+    let assign = Callback::new(move |(id, assignee): (usize, Option<String>)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AssignTodoArgs {
+                id,
+                assignee: assignee.as_deref(),
+            })
+            .unwrap();
+            let result = invoke("assign_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to assign todo: {e}"))),
+            }
+        });
+    });
+
+    let set_flag = Callback::new(move |(id, flag): (usize, Option<Flag>)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SetFlagArgs { id, flag }).unwrap();
+            let result = invoke("set_flag", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to flag todo: {e}"))),
+            }
+        });
+    });
+
+    // Puts the todo deleted last back at the end of the list
+    let restore_deleted = move || {
+        let Some((line, subject)) = last_deleted.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RestoreTodoArgs { line: &line }).unwrap();
+            let result = invoke("restore_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    set_last_deleted.set(None);
+                    set_announcement.set(format!("Restored: {subject}"));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to restore todo: {e}"))),
+            }
+        });
+    };
+
+    // Whether the todo list is showing exactly `preset`, e.g. for highlighting nav entries
+    let is_list_active = move |preset: Filter| {
+        view.get() == View::Todos && !projects_panel_open.get() && filter.with(|f| *f == preset)
+    };
+    let show_list = Callback::new(move |preset: Filter| {
+        set_view.set(View::Todos);
+        set_projects_panel_open.set(false);
+        set_filter.set(preset);
+    });
+    let is_here_active = move || {
+        view.get() == View::Todos
+            && !projects_panel_open.get()
+            && filter.with(|f| f.any_context.is_some())
+    };
+    // Pending todos for the contexts placed near the current location
+    let show_here = move |_: ()| {
+        spawn_local(async move {
+            match geolocation::current_location().await {
+                Ok(here) => {
+                    let contexts =
+                        settings.with_untracked(|s| geo::contexts_near(&s.context_locations, here));
+                    show_list.run(Filter {
+                        status: Status::Pending,
+                        any_context: Some(contexts),
+                        ..Filter::default()
+                    });
+                }
+                Err(e) => set_error.set(Some(format!("Failed to get location: {e}"))),
+            }
+        });
+    };
+
+    // The todo waiting for a second click to be checked off, see `ConfirmSettings`
+    let (confirm_id, set_confirm_id) = signal(Option::<usize>::None);
+
+    // Feedback for checking off a todo, as the display settings ask for
+    let (celebrating, set_celebrating) = signal(false);
+    let celebrate = move || {
+        let display = settings.with_untracked(|s| s.display.clone());
+        if display.celebrate {
+            set_celebrating.set(true);
+            set_timeout(
+                move || set_celebrating.set(false),
+                Duration::from_millis(celebrate::DURATION_MS),
+            );
+        }
+        if display.completion_sound {
+            spawn_local(celebrate::play_chime());
+        }
+    };
+
+    // Re-run the query whenever the list or one of the filter criteria changes
+    let (displayed_todos, set_displayed_todos) = signal(Vec::<Todo>::new());
+    let hide_completed = Memo::new(move |_| settings.with(|s| s.completion == Completion::Hide));
+    let grouping = Memo::new(move |_| {
+        group_override
+            .get()
+            .unwrap_or_else(|| settings.with(|s| s.group.clone()))
+    });
+    let sorting = Memo::new(move |_| settings.with(|s| s.sort.clone()));
+    Effect::new(move |_| {
+        list_todos.track();
+        hide_completed.track();
+        grouping.track();
+        sorting.track();
+        let filter = filter.get();
+        let list = active_list.get();
+        let plugin_view_ids = plugin_view_ids.get();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&QueryTodosArgs {
+                filter: &filter,
+                list: list.as_deref(),
+            })
+            .unwrap();
+            let result = invoke("query_todos", args).await;
+            match serde_wasm_bindgen::from_value::<Revised<Vec<Todo>>>(result) {
+                Ok(Revised {
+                    revision,
+                    value: mut items,
+                }) => {
+                    if !is_current(revision) {
+                        return;
+                    }
+                    // Hidden completed todos still show when asked for
+                    if hide_completed.get_untracked() && filter.status == Status::All {
+                        items.retain(|todo| !todo.finished);
+                    }
+                    if let Some(ids) = &plugin_view_ids {
+                        items.retain(|todo| ids.contains(&todo.id));
+                    }
+                    let sorting = sorting.get_untracked();
+                    if sorting != SortSettings::default() {
+                        sort::sort(&mut items, &sorting.by, sorting.descending, |todo| {
+                            TodoItem::new(&todo.raw)
+                        });
+                        // Starred todos stay pinned on top
+                        items.sort_by_key(|todo| !todo.starred);
+                    }
+                    let grouping = grouping.get_untracked();
+                    if grouping.by != GroupBy::None {
+                        let order = grouping.order();
+                        group::sort(&mut items, order, |todo| {
+                            group::label(&TodoItem::new(&todo.raw), grouping.by, order)
+                        });
+                    }
+                    if demo_mode.get_untracked() {
+                        items = items.iter().map(Todo::redacted).collect();
+                    }
+                    set_displayed_todos.set(items);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to filter todos: {e}"))),
+            }
+        });
+    });
+
+    // Rows can be dragged into a manual order within a project, unless
+    // groups would put them elsewhere
+    let reorderable = Memo::new(move |_| {
+        !read_only.get()
+            && sorting.with(|s| s.by == SortBy::Order && !s.descending)
+            && grouping.with(|g| g.by == GroupBy::None)
+            && filter.with(|f| f.project.is_some())
+    });
+    // The todo being dragged to another place in the list
+    let (dragged, set_dragged) = signal(Option::<usize>::None);
+
+    // Drops the dragged todo onto the row of `target`, taking its place
+    let reorder = move |target: usize| {
+        let Some(moved) = dragged.get_untracked() else {
+            return;
+        };
+        set_dragged.set(None);
+        if moved == target {
+            return;
+        }
+        let mut ids: Vec<usize> =
+            displayed_todos.with_untracked(|items| items.iter().map(|todo| todo.id).collect());
+        let Some(to) = ids.iter().position(|&id| id == target) else {
+            return;
+        };
+        ids.retain(|&id| id != moved);
+        ids.insert(to, moved);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ReorderTodoArgs {
+                ids: &ids,
+                id: moved,
+            })
+            .unwrap();
+            let result = invoke("reorder_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => set_error.set(None),
+                Err(e) => set_error.set(Some(format!("Failed to reorder todo: {e}"))),
+            }
+        });
+    };
+
+    // The first shown todo of every group, with the group's header
+    let group_headers = Memo::new(move |_| {
+        let grouping = grouping.get();
+        let mut headers = HashMap::new();
+        if grouping.by == GroupBy::None {
+            return headers;
+        }
+        let mut last = None;
+        displayed_todos.with(|items| {
+            for todo in items {
+                let label = group::label(&TodoItem::new(&todo.raw), grouping.by, grouping.order());
+                if headers.is_empty() || label != last {
+                    headers.insert(todo.id, group_header(grouping.by, label.clone()));
+                    last = label;
+                }
+            }
+        });
+        headers
+    });
+
+    Effect::new(move |_| {
+        todos.track();
+        spawn_local(async move {
+            let result = invoke("checklist_progress", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<(usize, Progress)>>(result) {
+                Ok(found) => set_checklists.set(found.into_iter().collect()),
+                Err(e) => set_error.set(Some(format!("Failed to load checklists: {e}"))),
+            }
+        });
+    });
+
+    // For the age of todos; follows the end of day, though not the clock past midnight
+    let today = Memo::new(move |_| settings.with(|s| review::today(&s.day_ends)));
+
+    // Keep the backend's draft in step with the add dialog and the inline edit,
+    // once the previous draft had its chance to be restored
+    let (draft_restored, set_draft_restored) = signal(false);
+    Effect::new(move |_| {
+        let draft = Draft {
+            add: if dialog_open.get() {
+                new_todo.get()
+            } else {
+                String::new()
+            },
+            edit: editing_id.get().and_then(|id| {
+                let original = todos.with_untracked(|items| {
+                    items.iter().find(|t| t.id == id).map(|t| t.raw.clone())
+                })?;
+                Some(EditDraft {
+                    id,
+                    original,
+                    text: edit_text.get(),
+                })
+            }),
+        };
+        if !draft_restored.get() {
+            return;
+        }
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SaveDraftArgs { draft: &draft }).unwrap();
+            let result = invoke("save_draft", args).await;
+            if let Err(e) = serde_wasm_bindgen::from_value::<()>(result) {
+                set_error.set(Some(format!("Failed to save draft: {e}")));
+            }
+        });
+    });
+
+    let capture_to_inbox = move |text: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AddTodoArgs { text: &text }).unwrap();
+            let result = invoke("capture_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => set_error.set(None),
+                Err(e) => set_error.set(Some(format!("Failed to capture todo: {e}"))),
+            }
+        });
+    };
+    // Shared text from other apps opens the add dialog prefilled, with its link as a `url:` tag,
+    // or goes straight to inbox.txt
+    let capture = move |text: String| {
+        if text.trim().is_empty() {
+            return;
+        }
+        let task = share::shared_task(&text);
+        if settings.with_untracked(|s| s.capture_to_inbox) {
+            capture_to_inbox(task);
+        } else {
+            set_new_todo.set(task);
+            set_dialog_open.set(true);
+        }
+    };
+    // The capture route `#/capture?text=...`, which share targets open the app at
+    let capture_from_route = move || {
+        let location = window().location();
+        let hash = location.hash().unwrap_or_default();
+        let Some(query) = hash.strip_prefix("#/capture?") else {
+            return;
+        };
+        let text = web_sys::UrlSearchParams::new_with_str(query)
+            .ok()
+            .and_then(|params| params.get("text"));
+        let _ = location.set_hash("");
+        if let Some(text) = text {
+            capture(text);
+        }
+    };
+    // Links to a view, see [`todotxt::view`], come in through the deep-link
+    // scheme or as `#/view?<state>`
+    let open_view_link = move |link: &str| match ViewState::from_link(link) {
+        Some(Ok(state)) => {
+            let mut grouping = settings.with_untracked(|s| s.group.clone());
+            grouping.by = state.group;
+            if let Some(order) = grouping.order_mut().filter(|_| !state.order.is_empty()) {
+                *order = state.order;
+            }
+            set_error.set(None);
+            set_active_list.set(state.list);
+            set_filter.set(state.filter);
+            set_group_override.set(Some(grouping));
+            set_view.set(View::Todos);
+        }
+        Some(Err(e)) => set_error.set(Some(format!("Failed to open view link: {e}"))),
+        None => set_error.set(Some(format!("Not a link to a view: {link}"))),
+    };
+    let view_from_route = move || {
+        let location = window().location();
+        let hash = location.hash().unwrap_or_default();
+        let Some(query) = hash.strip_prefix("#/view?") else {
+            return;
+        };
+        let _ = location.set_hash("");
+        open_view_link(&format!("{}{query}", todotxt::view::link_prefix()));
+    };
+    let _ = window_event_listener(leptos::ev::hashchange, move |_| {
+        capture_from_route();
+        view_from_route();
+    });
+
+    // Load the list once, then follow the backend's events for every change,
+    // whichever window or sync peer made it
+    spawn_local(async move {
+        let result = invoke("get_todos", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Revised<Vec<Todo>>>(result) {
+            Ok(items) => {
+                set_error.set(None);
+                if is_current(items.revision) {
+                    set_todos.set(items.value);
+                }
+            }
+            Err(e) => set_error.set(Some(format!("Failed to load todos: {e}"))),
+        }
+
+        // Bring back whatever was typed but not saved when the app last closed
+        let result = invoke("get_draft", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Draft>(result) {
+            Ok(draft) => {
+                if !draft.add.is_empty() {
+                    set_new_todo.set(draft.add);
+                    set_dialog_open.set(true);
+                }
+                if let Some(edit) = draft.edit {
+                    let unchanged = todos.with_untracked(|items| {
+                        items
                             .iter()
-                            .any(|p| *p == filter || p.starts_with(&prefix))
-                    })
-                    .collect()
+                            .any(|t| t.id == edit.id && t.raw == edit.original)
+                    });
+                    if unchanged {
+                        set_edit_text.set(edit.text);
+                        set_editing_id.set(Some(edit.id));
+                    }
+                }
+            }
+            Err(e) => set_error.set(Some(format!("Failed to restore draft: {e}"))),
+        }
+        set_draft_restored.set(true);
+
+        // Shared content wins over a restored draft, it's what the app was opened for
+        let result = invoke("take_shared_text", JsValue::NULL).await;
+        if let Ok(Some(text)) = serde_wasm_bindgen::from_value::<Option<String>>(result) {
+            capture(text);
+        }
+        capture_from_route();
+        let result = invoke("take_view_link", JsValue::NULL).await;
+        if let Ok(Some(link)) = serde_wasm_bindgen::from_value::<Option<String>>(result) {
+            open_view_link(&link);
+        }
+        view_from_route();
+
+        // Events come in order, so they always apply; they only move the revision on
+        let apply = move |revision: u64, change: TodoChange| {
+            set_revision.update(|seen| *seen = (*seen).max(revision));
+            set_todos.update(|items| change.apply(items));
+        };
+        let on_added = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Revised<Todo>>>(event) {
+                apply(
+                    event.payload.revision,
+                    TodoChange::Added(event.payload.value),
+                );
+            }
+        });
+        let on_updated = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Revised<Todo>>>(event) {
+                apply(
+                    event.payload.revision,
+                    TodoChange::Updated(event.payload.value),
+                );
+            }
+        });
+        let on_removed = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Revised<usize>>>(event) {
+                apply(
+                    event.payload.revision,
+                    TodoChange::Removed(event.payload.value),
+                );
+            }
+        });
+        listen(TODO_ADDED_EVENT, &on_added).await;
+        listen(TODO_UPDATED_EVENT, &on_updated).await;
+        listen(TODO_REMOVED_EVENT, &on_removed).await;
+        // The listeners live as long as the app
+        on_added.forget();
+        on_updated.forget();
+        on_removed.forget();
+    });
+
+    // The backend pushes the captured todos after every write to inbox.txt
+    spawn_local(async move {
+        let result = invoke("get_inbox", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Vec<Todo>>(result) {
+            Ok(items) => set_inbox.set(items),
+            Err(e) => set_error.set(Some(format!("Failed to load inbox: {e}"))),
+        }
+        let on_inbox = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Vec<Todo>>>(event) {
+                set_inbox.set(event.payload);
+            }
+        });
+        listen(INBOX_CHANGED_EVENT, &on_inbox).await;
+        // The listener lives as long as the app
+        on_inbox.forget();
+    });
+
+    // The backend pushes fresh counts after every write
+    spawn_local(async move {
+        let result = invoke("get_counts", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Counts>(result) {
+            Ok(c) => set_counts.set(c),
+            Err(e) => set_error.set(Some(format!("Failed to load counts: {e}"))),
+        }
+        let on_counts = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Counts>>(event) {
+                set_counts.set(event.payload);
             }
+        });
+        listen("counts-changed", &on_counts).await;
+        // The listener lives as long as the app
+        on_counts.forget();
+    });
+
+    // Reminders come from the backend's scheduler; without permission for
+    // system notifications they show up in the app instead
+    Effect::new(move |_| {
+        if settings.with(|s| s.reminders.enabled) {
+            notify::request_permission();
         }
     });
+    spawn_local(async move {
+        let on_reminder = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Notification>>(event) {
+                let Notification {
+                    title,
+                    body,
+                    review,
+                } = event.payload;
+                let open_review = move || {
+                    if review {
+                        set_view.set(View::Review);
+                        set_projects_panel_open.set(false);
+                    }
+                };
+                if !notify::show(&title, &body, open_review) {
+                    set_reminder.set(Some((
+                        format!("{title}: {}", body.replace('\n', ", ")),
+                        review,
+                    )));
+                }
+            }
+        });
+        listen(REMINDER_EVENT, &on_reminder).await;
+        // The listener lives as long as the app
+        on_reminder.forget();
+    });
+
+    // Completions and due dates only change together with the list, so refresh
+    // streaks and the due summary whenever it does
+    Effect::new(move |_| {
+        todos.track();
+        spawn_local(async move {
+            let result = invoke("get_streaks", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Streaks>(result) {
+                Ok(s) => set_streaks.set(s),
+                Err(e) => set_error.set(Some(format!("Failed to load streaks: {e}"))),
+            }
+            let result = invoke("get_due_summary", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<DueSummary>(result) {
+                Ok(summary) => set_due_summary.set(summary),
+                Err(e) => set_error.set(Some(format!("Failed to load due dates: {e}"))),
+            }
+        });
+    });
+
+    spawn_local(async move {
+        let result = invoke("get_settings", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Settings>(result) {
+            Ok(loaded) => set_settings.set(loaded),
+            Err(e) => set_error.set(Some(format!("Failed to load settings: {e}"))),
+        }
+    });
+
+    let on_settings_change = Callback::new(move |next: Settings| {
+        if settings.with_untracked(|s| s.group != next.group) {
+            set_group_override.set(None);
+        }
+        set_settings.set(next.clone());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SaveSettingsArgs { settings: &next }).unwrap();
+            let result = invoke("save_settings", args).await;
+            match serde_wasm_bindgen::from_value::<Settings>(result) {
+                Ok(saved) => {
+                    set_error.set(None);
+                    set_settings.set(saved);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to save settings: {e}"))),
+            }
+        });
+    });
+
+    // Copies the todos currently shown to the clipboard
+    let (copy_status, set_copy_status) = signal(Option::<String>::None);
+    Effect::new(move |_| {
+        filter.track();
+        set_copy_status.set(None);
+    });
+    let copy_filtered = move |format: ExportFormat| {
+        let current = filter.get_untracked();
+        let list = active_list.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RenderFilteredArgs {
+                filter: &current,
+                format,
+                list: list.as_deref(),
+            })
+            .unwrap();
+            let result = invoke("render_filtered", args).await;
+            let text = match serde_wasm_bindgen::from_value::<String>(result) {
+                Ok(text) => text,
+                Err(e) => {
+                    set_error.set(Some(format!("Failed to render todos: {e}")));
+                    return;
+                }
+            };
+            match write_clipboard(&text).await {
+                Ok(_) => {
+                    set_copy_status.set(Some(format!("Copied {} todos", text.lines().count())))
+                }
+                Err(e) => set_error.set(Some(format!("Failed to copy to clipboard: {e:?}"))),
+            }
+        });
+    };
+
+    // The link to what's shown now, to bookmark or open on another device
+    let copy_view_link = move || {
+        let grouping = grouping.get_untracked();
+        let state = ViewState {
+            list: active_list.get_untracked(),
+            filter: filter.get_untracked(),
+            group: grouping.by,
+            order: grouping.order().to_vec(),
+        };
+        spawn_local(async move {
+            match write_clipboard(&state.to_link()).await {
+                Ok(_) => set_copy_status.set(Some("Copied view link".to_string())),
+                Err(e) => set_error.set(Some(format!("Failed to copy to clipboard: {e:?}"))),
+            }
+        });
+    };
+
+    let palette_commands = vec![
+        Command {
+            label: "Copy view link",
+            run: Callback::new(move |()| copy_view_link()),
+        },
+        Command {
+            label: "Clear filter",
+            run: Callback::new(move |()| set_filter.set(Filter::default())),
+        },
+        Command {
+            label: "What should I do now?",
+            run: Callback::new(move |()| set_picker_open.set(true)),
+        },
+        Command {
+            label: "Archive completed todos",
+            run: Callback::new(move |()| {
+                if !read_only.get_untracked() {
+                    set_archive_open.set(true);
+                }
+            }),
+        },
+        Command {
+            label: "Browse backups",
+            run: Callback::new(move |()| {
+                if !read_only.get_untracked() {
+                    set_backups_open.set(true);
+                }
+            }),
+        },
+        Command {
+            label: "Go to todos",
+            run: Callback::new(move |()| set_view.set(View::Todos)),
+        },
+        Command {
+            label: "Go to raw editor",
+            run: Callback::new(move |()| set_view.set(View::Raw)),
+        },
+        Command {
+            label: "Go to tags",
+            run: Callback::new(move |()| set_view.set(View::Tags)),
+        },
+        Command {
+            label: "Go to review",
+            run: Callback::new(move |()| set_view.set(View::Review)),
+        },
+        Command {
+            label: "Go to recent",
+            run: Callback::new(move |()| set_view.set(View::Recent)),
+        },
+        Command {
+            label: "Plan the week",
+            run: Callback::new(move |()| set_view.set(View::Week)),
+        },
+        Command {
+            label: "Go to captured",
+            run: Callback::new(move |()| set_view.set(View::Captured)),
+        },
+        Command {
+            label: "Go to settings",
+            run: Callback::new(move |()| set_view.set(View::Settings)),
+        },
+        Command {
+            label: "Edit keyboard shortcuts",
+            run: Callback::new(move |()| set_view.set(View::Shortcuts)),
+        },
+        Command {
+            label: "Open overlay",
+            run: Callback::new(move |()| {
+                spawn_local(async move {
+                    invoke("open_overlay", JsValue::NULL).await;
+                })
+            }),
+        },
+        Command {
+            label: "Toggle overlay click-through",
+            run: Callback::new(move |()| {
+                let mut next = settings.get_untracked();
+                next.overlay.click_through = !next.overlay.click_through;
+                on_settings_change.run(next);
+            }),
+        },
+    ];
+    let zoom = move |change: &dyn Fn(&mut DisplaySettings)| {
+        let mut next = settings.get_untracked();
+        change(&mut next.display);
+        on_settings_change.run(next);
+    };
+    shortcuts::dispatch(
+        Signal::derive(move || settings.with(|s| s.keymap.clone())),
+        move |action| match action {
+            ShortcutAction::OpenPalette => set_palette_open.update(|open| *open = !*open),
+            ShortcutAction::AddTodo => {
+                if !read_only.get_untracked() {
+                    set_dialog_open.set(true);
+                }
+            }
+            ShortcutAction::FindReplace => {
+                if !read_only.get_untracked() {
+                    set_replace_open.set(true);
+                }
+            }
+            ShortcutAction::PickTodo => set_picker_open.set(true),
+            ShortcutAction::ZoomIn => zoom(&|d| d.zoom_by(1)),
+            ShortcutAction::ZoomOut => zoom(&|d| d.zoom_by(-1)),
+            ShortcutAction::ResetZoom => zoom(&|d| d.zoom = 100),
+            ShortcutAction::GoToTodos => set_view.set(View::Todos),
+            ShortcutAction::GoToRaw => set_view.set(View::Raw),
+            ShortcutAction::GoToWeek => set_view.set(View::Week),
+            ShortcutAction::GoToSettings => set_view.set(View::Settings),
+            ShortcutAction::RestoreDeleted => {
+                if !read_only.get_untracked() {
+                    restore_deleted();
+                }
+            }
+        },
+    );
+
+    let on_sync = Callback::new(move |address: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SyncNowArgs { address: &address }).unwrap();
+            let result = invoke("sync_now", args).await;
+            // The sync runs as a job, which reports how it went on its own
+            match serde_wasm_bindgen::from_value::<u64>(result) {
+                Ok(_) => {
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to sync: {e}"))),
+            }
+        });
+    });
+
+    let on_separator_change = Callback::new(move |(separator, migrate): (String, bool)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SetProjectSeparatorArgs {
+                separator: &separator,
+                migrate,
+            })
+            .unwrap();
+            let result = invoke("set_project_separator", args).await;
+            match serde_wasm_bindgen::from_value::<Settings>(result) {
+                Ok(saved) => {
+                    set_error.set(None);
+                    set_settings.set(saved);
+                    // Tag paths are spelled differently now
+                    set_filter.set(Filter::default());
+                }
+                Err(e) => set_error.set(Some(format!("Failed to change project separator: {e}"))),
+            }
+        });
+    });
 
-    let load_todos = move || {
-        spawn_local(async move {
-            let result = invoke("get_todos", JsValue::NULL).await;
-            match serde_wasm_bindgen::from_value::<Vec<TodoItem>>(result) {
-                Ok(items) => {
-                    set_error.set(None);
-                    set_todos.set(items);
+    // Focus the text field whenever the add dialog opens
+    Effect::new(move |_| {
+        if dialog_open.get() {
+            request_animation_frame(move || {
+                if let Some(input) = new_todo_ref.get_untracked() {
+                    let _ = input.focus();
                 }
-                Err(e) => set_error.set(Some(format!("Failed to load todos: {e}"))),
+            });
+        }
+    });
+
+    // Close the add dialog and hand focus back to the button that opened it
+    let close_dialog = Callback::new(move |_: ()| {
+        set_new_todo.set(String::new());
+        set_dialog_open.set(false);
+        if let Some(button) = add_button_ref.get_untracked() {
+            let _ = button.focus();
+        }
+    });
+
+    // Keep Tab cycling inside the dialog and let Escape dismiss it
+    let on_dialog_keydown = move |ev: leptos::ev::KeyboardEvent| match ev.key().as_str() {
+        "Escape" => {
+            ev.prevent_default();
+            close_dialog.run(());
+        }
+        "Tab" => {
+            let (Some(first), Some(last)) =
+                (new_todo_ref.get_untracked(), submit_ref.get_untracked())
+            else {
+                return;
+            };
+            let active = document().active_element();
+            if ev.shift_key() && active.as_ref() == Some(first.as_ref()) {
+                ev.prevent_default();
+                let _ = last.focus();
+            } else if !ev.shift_key() && active.as_ref() == Some(last.as_ref()) {
+                ev.prevent_default();
+                let _ = first.focus();
             }
-        });
+        }
+        _ => {}
+    };
+
+    // Dictation adds the task right away, with spoken dates like "tomorrow" as due dates
+    let voice_available = voice::is_available();
+    let (listening, set_listening) = signal(false);
+    let dictate = move |_: ()| {
+        set_listening.set(true);
+        voice::listen(
+            move |text| {
+                spawn_local(async move {
+                    let args = serde_wasm_bindgen::to_value(&AddTodoArgs { text: &text }).unwrap();
+                    let result = invoke("add_spoken_todo", args).await;
+                    match serde_wasm_bindgen::from_value::<Vec<Overflow>>(result) {
+                        Ok(overflows) => {
+                            set_error.set(None);
+                            set_wip_warning.set(overflow_warning(&overflows));
+                        }
+                        Err(e) => set_error.set(Some(format!("Failed to add todo: {e}"))),
+                    }
+                });
+            },
+            move |e| {
+                // Nothing said or cancelled isn't worth an error
+                if !matches!(e.as_str(), "no-speech" | "aborted") {
+                    set_error.set(Some(format!("Failed to recognize speech: {e}")));
+                }
+            },
+            move || set_listening.set(false),
+        );
     };
 
-    load_todos();
+    let on_capture = move |_| {
+        let text = new_todo.get_untracked();
+        if text.trim().is_empty() {
+            return;
+        }
+        capture_to_inbox(text);
+        close_dialog.run(());
+    };
 
     let on_add_submit = move |ev: SubmitEvent| {
         ev.prevent_default();
@@ -109,12 +1297,12 @@ pub fn App() -> impl IntoView {
         spawn_local(async move {
             let args = serde_wasm_bindgen::to_value(&AddTodoArgs { text: &text }).unwrap();
             let result = invoke("add_todo", args).await;
-            match serde_wasm_bindgen::from_value::<Vec<TodoItem>>(result) {
-                Ok(items) => {
+            match serde_wasm_bindgen::from_value::<Vec<Overflow>>(result) {
+                Ok(overflows) => {
                     set_error.set(None);
-                    set_todos.set(items);
-                    set_new_todo.set(String::new());
-                    set_dialog_open.set(false);
+                    set_wip_warning.set(overflow_warning(&overflows));
+                    set_announcement.set(format!("Added: {}", text.trim()));
+                    close_dialog.run(());
                 }
                 Err(e) => set_error.set(Some(format!("Failed to add todo: {e}"))),
             }
@@ -124,16 +1312,23 @@ pub fn App() -> impl IntoView {
     view! {
         <div class="flex h-screen">
             // Sidebar navigation
-            <nav class="fixed left-0 top-0 h-full w-16 bg-base-300 flex flex-col items-center py-4 z-50">
+            <nav
+                class="fixed left-0 top-0 h-full w-16 bg-base-300 flex flex-col items-center py-4 z-50"
+                aria-label="Main navigation"
+            >
                 <ul class="menu menu-vertical gap-2">
                     <li>
-                        <a
+                        <button
+                            type="button"
                             class="tooltip tooltip-right"
-                            class=("menu-active", move || !projects_panel_open.get())
-                            data-tip="Todos"
+                            class=("menu-active", move || is_list_active(Filter::default()))
+                            data-tip="Inbox"
+                            aria-label=move || format!("Inbox, {} pending", counts.get().inbox)
+                            aria-current=move || is_list_active(Filter::default()).then_some("page")
                             on:click=move |_| {
+                                set_view.set(View::Todos);
                                 set_projects_panel_open.set(false);
-                                set_active_project_filter.set(None);
+                                set_filter.set(Filter::default());
                             }
                         >
                             // <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
@@ -143,53 +1338,288 @@ pub fn App() -> impl IntoView {
                         <svg class="w-28px h-28px text-gray-800 dark:text-white" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="24" height="24" fill="none" viewBox="0 0 24 24">
                           <path stroke="currentColor" stroke-linecap="round" stroke-linejoin="round" stroke-width="1.5" d="M4 13h3.439a.991.991 0 0 1 .908.6 3.978 3.978 0 0 0 7.306 0 .99.99 0 0 1 .908-.6H20M4 13v6a1 1 0 0 0 1 1h14a1 1 0 0 0 1-1v-6M4 13l2-9h12l2 9M9 7h6m-7 3h8"/>
                         </svg>
+                        {count_badge(Signal::derive(move || counts.get().inbox))}
 
-
-                        </a>
+                        </button>
+                    </li>
+                    {filter_nav_item(
+                        "Today",
+                        "M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z",
+                        Filter { status: Status::Pending, due: Some(Due::Today), ..Filter::default() },
+                        Signal::derive(move || counts.get().today),
+                        is_list_active,
+                        show_list,
+                    )}
+                    {filter_nav_item(
+                        "Upcoming",
+                        "M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z",
+                        Filter { status: Status::Pending, due: Some(Due::Upcoming), ..Filter::default() },
+                        Signal::derive(move || counts.get().upcoming),
+                        is_list_active,
+                        show_list,
+                    )}
+                    <li class=("hidden", move || settings.with(|s| s.context_locations.is_empty()))>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", is_here_active)
+                            data-tip="Here"
+                            aria-label="Todos for contexts near here"
+                            aria-current=move || is_here_active().then_some("page")
+                            on:click=move |_| show_here(())
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M17.657 16.657L13.414 20.9a2 2 0 01-2.827 0l-4.244-4.243a8 8 0 1111.314 0z"/>
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 11a3 3 0 11-6 0 3 3 0 016 0z"/>
+                            </svg>
+                        </button>
                     </li>
+                    {filter_nav_item(
+                        "Completed",
+                        "M9 12l2 2 4-4m6 2a9 9 0 11-18 0 9 9 0 0118 0z",
+                        Filter { status: Status::Done, ..Filter::default() },
+                        Signal::derive(move || counts.get().completed),
+                        is_list_active,
+                        show_list,
+                    )}
                     <li>
-                        <a
+                        <button
+                            type="button"
                             class="tooltip tooltip-right"
                             class=("menu-active", move || projects_panel_open.get())
                             data-tip="Projects"
-                            on:click=move |_| set_projects_panel_open.update(|v| *v = !*v)
+                            aria-label="Projects"
+                            aria-controls="projects-panel"
+                            aria-expanded=move || if projects_panel_open.get() { "true" } else { "false" }
+                            on:click=move |_| {
+                                set_view.set(View::Todos);
+                                set_projects_panel_open.update(|v| *v = !*v);
+                            }
                         >
-                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
                                 <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M3 7v10a2 2 0 002 2h14a2 2 0 002-2V9a2 2 0 00-2-2h-6l-2-2H5a2 2 0 00-2 2z"/>
                             </svg>
-                        </a>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Raw)
+                            data-tip="Raw"
+                            aria-label="Raw file editor"
+                            aria-current=move || (view.get() == View::Raw).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Raw);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4"/>
+                            </svg>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Tags)
+                            data-tip="Tags"
+                            aria-label="Manage tags"
+                            aria-current=move || (view.get() == View::Tags).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Tags);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M7 7h.01M7 3h5c.512 0 1.024.195 1.414.586l7 7a2 2 0 010 2.828l-7 7a2 2 0 01-2.828 0l-7-7A1.994 1.994 0 013 12V7a4 4 0 014-4z"/>
+                            </svg>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Review)
+                            data-tip="Review"
+                            aria-label="Review"
+                            aria-current=move || (view.get() == View::Review).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Review);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 5H7a2 2 0 00-2 2v12a2 2 0 002 2h10a2 2 0 002-2V7a2 2 0 00-2-2h-2M9 5a2 2 0 002 2h2a2 2 0 002-2M9 5a2 2 0 012-2h2a2 2 0 012 2m-6 9l2 2 4-4"/>
+                            </svg>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Recent)
+                            data-tip="Recently changed"
+                            aria-label="Recently changed"
+                            aria-current=move || (view.get() == View::Recent).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Recent);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z"/>
+                            </svg>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Week)
+                            data-tip="Plan the week"
+                            aria-label="Plan the week"
+                            aria-current=move || (view.get() == View::Week).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Week);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z"/>
+                            </svg>
+                        </button>
+                    </li>
+                    <li>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Captured)
+                            data-tip="Captured"
+                            aria-label=move || format!("Captured, {} to refile", inbox.with(Vec::len))
+                            aria-current=move || (view.get() == View::Captured).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Captured);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 16v1a3 3 0 003 3h10a3 3 0 003-3v-1m-4-8l-4-4m0 0L8 8m4-4v12"/>
+                            </svg>
+                            {count_badge(Signal::derive(move || inbox.with(Vec::len)))}
+                        </button>
                     </li>
+                    {move || settings.get().watched_lists.into_iter().map(|list| {
+                        let name = list.name;
+                        let is_active = {
+                            let name = name.clone();
+                            Memo::new(move |_| active_list.with(|active| active.as_deref() == Some(name.as_str())))
+                        };
+                        let on_click = {
+                            let name = name.clone();
+                            move |_| {
+                                // Clicking the open list again goes back to the user's own
+                                let next = (!is_active.get_untracked()).then(|| name.clone());
+                                set_active_list.set(next);
+                                set_view.set(View::Todos);
+                            }
+                        };
+                        view! {
+                            <li>
+                                <button
+                                    type="button"
+                                    class="tooltip tooltip-right"
+                                    class=("menu-active", move || is_active.get())
+                                    data-tip=format!("{name} (read-only)")
+                                    aria-label=format!("{name}, read-only")
+                                    aria-pressed=move || is_active.get().to_string()
+                                    on:click=on_click
+                                >
+                                    <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 12a3 3 0 11-6 0 3 3 0 016 0z"/>
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M2.458 12C3.732 7.943 7.523 5 12 5c4.478 0 8.268 2.943 9.542 7-1.274 4.057-5.064 7-9.542 7-4.477 0-8.268-2.943-9.542-7z"/>
+                                    </svg>
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
                     <li>
-                        <a class="tooltip tooltip-right" data-tip="Add Todo"
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            data-tip="Add Todo"
+                            aria-label="Add Todo"
+                            aria-haspopup="dialog"
+                            node_ref=add_button_ref
+                            disabled=move || read_only.get()
                             on:click=move |_| set_dialog_open.set(true)
                         >
-                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
                                 <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
                             </svg>
-                        </a>
+                        </button>
+                    </li>
+                    <li class=("hidden", !voice_available)>
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || listening.get())
+                            data-tip=move || if listening.get() { "Listening…" } else { "Dictate a todo" }
+                            aria-label="Dictate a todo"
+                            aria-pressed=move || listening.get().to_string()
+                            disabled=move || read_only.get() || listening.get()
+                            on:click=move |_| dictate(())
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 11a7 7 0 01-7 7m0 0a7 7 0 01-7-7m7 7v4m0 0H8m4 0h4m-4-8a3 3 0 01-3-3V5a3 3 0 116 0v6a3 3 0 01-3 3z"/>
+                            </svg>
+                        </button>
                     </li>
                     <li>
-                        <a class="tooltip tooltip-right" data-tip="Settings">
-                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <button
+                            type="button"
+                            class="tooltip tooltip-right"
+                            class=("menu-active", move || view.get() == View::Settings)
+                            data-tip="Settings"
+                            aria-label="Settings"
+                            aria-current=move || (view.get() == View::Settings).then_some("page")
+                            on:click=move |_| {
+                                set_view.set(View::Settings);
+                                set_projects_panel_open.set(false);
+                            }
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
                                 <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10.325 4.317c.426-1.756 2.924-1.756 3.35 0a1.724 1.724 0 002.573 1.066c1.543-.94 3.31.826 2.37 2.37a1.724 1.724 0 001.066 2.573c1.756.426 1.756 2.924 0 3.35a1.724 1.724 0 00-1.066 2.573c.94 1.543-.826 3.31-2.37 2.37a1.724 1.724 0 00-2.573 1.066c-.426 1.756-2.924 1.756-3.35 0a1.724 1.724 0 00-2.573-1.066c-1.543.94-3.31-.826-2.37-2.37a1.724 1.724 0 00-1.066-2.573c-1.756-.426-1.756-2.924 0-3.35a1.724 1.724 0 001.066-2.573c-.94-1.543.826-3.31 2.37-2.37.996.608 2.296.07 2.572-1.065z"/>
                                 <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 12a3 3 0 11-6 0 3 3 0 016 0z"/>
                             </svg>
-                        </a>
+                        </button>
                     </li>
                 </ul>
             </nav>
 
             // Projects panel
             <aside
+                id="projects-panel"
                 class="fixed left-16 top-0 w-64 h-full bg-base-300 z-40 overflow-y-auto border-r border-base-content/10"
                 class=("hidden", move || !projects_panel_open.get())
+                aria-labelledby="projects-panel-title"
             >
                 <div class="p-3">
-                    <h2 class="text-sm font-semibold tracking-wide opacity-60 mb-2">"Projects"</h2>
+                    <h2 id="projects-panel-title" class="text-sm font-semibold tracking-wide opacity-60 mb-2">"Projects"</h2>
                     <div
-                        class="flex items-center gap-1 px-2 py-1 cursor-pointer rounded hover:bg-base-200"
-                        class=("bg-primary/20", move || active_project_filter.get().is_none())
-                        on:click=move |_| set_active_project_filter.set(None)
+                        role="button"
+                        tabindex="0"
+                        class="flex items-center gap-1 px-2 py-1 cursor-pointer rounded hover:bg-base-200 focus:outline-none focus-visible:ring-2 focus-visible:ring-primary"
+                        class=("bg-primary/20", move || filter.with(|f| f.project.is_none()))
+                        aria-pressed=move || if filter.with(|f| f.project.is_none()) { "true" } else { "false" }
+                        on:click=move |_| set_filter.update(|f| f.project = None)
+                        on:keydown=move |ev: leptos::ev::KeyboardEvent| {
+                            if matches!(ev.key().as_str(), "Enter" | " ") {
+                                ev.prevent_default();
+                                set_filter.update(|f| f.project = None);
+                            }
+                        }
                     >
 
                         <svg class="w-24px h-24px text-gray-800 dark:text-white" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="24" height="24" fill="none" viewBox="0 0 24 24">
@@ -198,16 +1628,20 @@ pub fn App() -> impl IntoView {
 
                         <span class="text-sm">"All"</span>
                     </div>
-                    <div class="mt-1">
-                        {move || render_project_tree(
-                            project_tree.get(),
-                            0,
-                            active_project_filter,
-                            set_active_project_filter,
-                            collapsed_nodes,
-                            set_collapsed_nodes,
-                        )}
+                    <div class="mt-1" role="tree" aria-label="Project hierarchy">
+                        {render_tag_tree(project_tree.into(), TagKind::Project, 0, tree_state)}
                     </div>
+                    <h2 id="contexts-title" class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Contexts"</h2>
+                    <div role="tree" aria-labelledby="contexts-title">
+                        {render_tag_tree(context_tree.into(), TagKind::Context, 0, tree_state)}
+                    </div>
+                    <FilterPanel filter=filter set_filter=set_filter assignees=assignees />
+                    <PluginViews
+                        settings=settings
+                        watching=watching.into()
+                        plugin_view=plugin_view
+                        set_plugin_view=set_plugin_view
+                    />
                 </div>
             </aside>
 
@@ -218,198 +1652,982 @@ pub fn App() -> impl IntoView {
                 class=("ml-80", move || projects_panel_open.get())
             >
                 <div class="max-w-5xl mx-auto">
-                    <h1 class="text-3xl font-bold mb-6">
-                        {move || match active_project_filter.get() {
-                            None => "Inbox".to_string(),
-                            Some(p) => {
-                                // Show just the last segment of the project path
-                                p.rsplit(PROJECT_SEPARATOR).next().unwrap_or(&p).to_string()
-                            }
-                        }}
-                    </h1>
+                    <div class="flex items-center justify-between gap-4 mb-6">
+                        <h1 class="text-3xl font-bold">
+                            {move || {
+                                let filter = filter.get();
+                                let tag = [TagKind::Project, TagKind::Context]
+                                    .into_iter()
+                                    .find_map(|kind| kind.criterion(&filter).map(|path| (kind, path)));
+                                match tag {
+                                    _ if view.get() == View::Settings => "Settings".to_string(),
+                                    _ if view.get() == View::Shortcuts => "Keyboard shortcuts".to_string(),
+                                    _ if view.get() == View::Tags => "Tags".to_string(),
+                                    _ if view.get() == View::Review => "Review".to_string(),
+                                    _ if view.get() == View::Recent => "Recently changed".to_string(),
+                                    _ if view.get() == View::Week => "Week".to_string(),
+                                    _ if view.get() == View::Captured => "Captured".to_string(),
+                                    _ if view.get() == View::Raw => "todo.txt".to_string(),
+                                    None if filter.any_context.is_some() => "Here".to_string(),
+                                    None => match (filter.due, filter.status) {
+                                        (Some(Due::Today | Due::TodayOnly), _) => "Today",
+                                        (Some(Due::Upcoming), _) => "Upcoming",
+                                        (Some(Due::Overdue), _) => "Overdue",
+                                        (Some(Due::ThisWeek), _) => "This week",
+                                        (None, Status::Done) => "Completed",
+                                        _ => "Inbox",
+                                    }
+                                    .to_string(),
+                                    Some((kind, path)) => {
+                                        // Show just the last segment of the tag path
+                                        let separator = separator.get();
+                                        let name = if separator.is_empty() {
+                                            path
+                                        } else {
+                                            path.rsplit(separator.as_str()).next().unwrap_or(path)
+                                        };
+                                        format!("{}{}", kind.sigil(), name)
+                                    }
+                                }
+                            }}
+                        </h1>
+                        {move || active_list.get().map(|name| view! {
+                            <span class="badge badge-info gap-1">
+                                {format!("Read-only: {name}")}
+                                <button
+                                    type="button"
+                                    class="btn btn-ghost btn-xs"
+                                    aria-label="Back to my list"
+                                    on:click=move |_| set_active_list.set(None)
+                                >
+                                    "×"
+                                </button>
+                            </span>
+                        })}
+                        <div class="flex items-center gap-3" class=("hidden", move || view.get() != View::Todos)>
+                            <span class="text-sm opacity-70" role="status">{move || copy_status.get()}</span>
+                            <SortControls
+                                todos=list_todos
+                                settings=settings
+                                sorting=sorting
+                                on_settings_change=on_settings_change
+                            />
+                            <div class="dropdown dropdown-end">
+                                <button type="button" class="btn btn-ghost btn-sm" aria-haspopup="menu">"Copy as text"</button>
+                                <ul class="dropdown-content menu bg-base-100 rounded-box shadow z-10 w-48" role="menu">
+                                    <li role="none">
+                                        <button type="button" role="menuitem" on:click=move |_| copy_filtered(ExportFormat::Raw)>
+                                            "todo.txt lines"
+                                        </button>
+                                    </li>
+                                    <li role="none">
+                                        <button type="button" role="menuitem" on:click=move |_| copy_filtered(ExportFormat::Checklist)>
+                                            "Checklist"
+                                        </button>
+                                    </li>
+                                </ul>
+                            </div>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-sm"
+                                class=("hidden", move || read_only.get())
+                                on:click=move |_| set_picker_open.set(true)
+                            >
+                                "What now?"
+                            </button>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-sm"
+                                class=("hidden", move || read_only.get())
+                                on:click=move |_| set_archive_open.set(true)
+                            >
+                                "Archive…"
+                            </button>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-sm"
+                                class=("hidden", move || read_only.get())
+                                on:click=move |_| set_replace_open.set(true)
+                            >
+                                "Replace…"
+                            </button>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-sm"
+                                class=("hidden", move || read_only.get())
+                                on:click=move |_| set_shift_open.set(true)
+                            >
+                                "Shift dates…"
+                            </button>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-sm"
+                                class=("hidden", move || read_only.get())
+                                on:click=move |_| set_backups_open.set(true)
+                            >
+                                "Backups…"
+                            </button>
+                            <Streak streaks=streaks settings=settings celebrating=celebrating />
+                        </div>
+                    </div>
 
+                    <FocusBar set_error=set_error />
                     {move || error.get().map(|e| view! {
-                        <div class="alert alert-error mb-4">
+                        <div class="alert alert-error mb-4" role="alert">
                             <span>{e}</span>
                         </div>
                     })}
+                    {move || wip_warning.get().map(|text| view! {
+                        <div class="alert alert-warning mb-4" role="status">
+                            <span>{text}</span>
+                            <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_wip_warning.set(None)>
+                                "Dismiss"
+                            </button>
+                        </div>
+                    })}
+                    {move || reminder.get().map(|(text, review)| view! {
+                        <div class="alert alert-info mb-4" role="status">
+                            <span>{text}</span>
+                            <button
+                                type="button"
+                                class="btn btn-primary btn-xs"
+                                class=("hidden", !review)
+                                on:click=move |_| {
+                                    set_reminder.set(None);
+                                    set_view.set(View::Review);
+                                    set_projects_panel_open.set(false);
+                                }
+                            >
+                                "Start review"
+                            </button>
+                            <button type="button" class="btn btn-ghost btn-xs" aria-label="Dismiss reminder" on:click=move |_| set_reminder.set(None)>
+                                "×"
+                            </button>
+                        </div>
+                    })}
 
-                    <div class="card bg-base-100 shadow-xl">
-                        <div class="card-body p-0">
-                            <ul class="list">
-                                <For
-                                    each=move || displayed_todos.get()
-                                    key=|item| (item.id, item.raw.clone(), item.finished)
-                                    children=move |item| {
-                                        let id = item.id;
-                                        let finished = item.finished;
-                                        let subject = item.subject.clone();
-                                        let priority = item.priority;
-                                        let contexts = item.contexts.clone();
-                                        let projects = item.projects.clone();
-
-                                        let on_toggle = move |_| {
-                                            spawn_local(async move {
-                                                let args = serde_wasm_bindgen::to_value(&ToggleTodoArgs { id }).unwrap();
-                                                let result = invoke("toggle_todo", args).await;
-                                                match serde_wasm_bindgen::from_value::<Vec<TodoItem>>(result) {
-                                                    Ok(items) => {
-                                                        set_error.set(None);
-                                                        set_todos.set(items);
-                                                    }
-                                                    Err(e) => set_error.set(Some(format!("Failed to toggle todo: {e}"))),
-                                                }
+                    <div class=("hidden", move || view.get() != View::Raw)>
+                        <RawEditor
+                            active=Signal::derive(move || view.get() == View::Raw)
+                            demo_mode=demo_mode.into()
+                            set_error=set_error
+                        />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Tags)>
+                        <TagManager
+                            active=Signal::derive(move || view.get() == View::Tags)
+                            todos=todos
+                            settings=settings
+                            set_settings=set_settings
+                            on_settings_change=on_settings_change
+                            set_error=set_error
+                        />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Review)>
+                        <Review todos=shown_todos settings=settings set_error=set_error />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Recent)>
+                        <Recent
+                            active=Signal::derive(move || view.get() == View::Recent)
+                            todos=todos
+                            demo_mode=demo_mode.into()
+                            set_error=set_error
+                        />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Week)>
+                        <WeekPlanner todos=list_todos today=today read_only=read_only.into() set_error=set_error />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Captured)>
+                        <Captured inbox=inbox read_only=read_only.into() set_error=set_error set_wip_warning=set_wip_warning />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Settings)>
+                        <SettingsPage
+                            settings=settings
+                            on_change=on_settings_change
+                            on_separator_change=on_separator_change
+                            on_sync=on_sync
+                            on_edit_shortcuts=Callback::new(move |()| set_view.set(View::Shortcuts))
+                        />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Shortcuts)>
+                        <ShortcutsPage settings=settings on_change=on_settings_change />
+                    </div>
+
+                    <div class=("hidden", move || view.get() != View::Todos)>
+                        <PriorityBands filter=filter set_filter=set_filter />
+                        <ActiveFilters filter=filter set_filter=set_filter />
+                        <DueNav summary=due_summary filter=filter show_list=show_list />
+                        <WorkloadSummary todos=displayed_todos filter=filter settings=settings />
+                        <div class="card bg-base-100 shadow-xl">
+                            <div class="card-body p-0">
+                                <ul class="list" aria-label="Todos">
+                                    <For
+                                        each=move || displayed_todos.get()
+                                        key=|item| (item.id, item.raw.clone(), item.finished)
+                                        children=move |item| {
+                                            let id = item.id;
+                                            let finished = item.finished;
+                                            let subject = item.subject.clone();
+                                            let priority = item.priority;
+                                            let contexts = item.contexts.clone();
+                                            let projects = item.projects.clone();
+                                            let creation_date = item.creation_date.clone();
+                                            let due_date = item.due_date.clone().map(|date| match &item.due_time {
+                                                Some(time) => format!("{date} {time}"),
+                                                None => date,
                                             });
-                                        };
 
-                                        let on_delete = move |ev: leptos::ev::MouseEvent| {
-                                            ev.stop_propagation();
-                                            spawn_local(async move {
-                                                let args = serde_wasm_bindgen::to_value(&DeleteTodoArgs { id }).unwrap();
-                                                let result = invoke("delete_todo", args).await;
-                                                match serde_wasm_bindgen::from_value::<Vec<TodoItem>>(result) {
-                                                    Ok(items) => {
-                                                        set_error.set(None);
-                                                        set_todos.set(items);
-                                                    }
-                                                    Err(e) => set_error.set(Some(format!("Failed to delete todo: {e}"))),
+                                            let needs_confirm = {
+                                                let item = TodoItem::new(&item.raw);
+                                                move || {
+                                                    let Ok(today) = Date::parse_from_str(&today.get_untracked(), "%Y-%m-%d") else {
+                                                        return false;
+                                                    };
+                                                    settings.with_untracked(|s| s.confirm.applies(&item, &s.project_separator, today))
                                                 }
-                                            });
-                                        };
+                                            };
+                                            let toggled = subject.clone();
+                                            let on_toggle = move |ev: leptos::ev::MouseEvent| {
+                                                // Critical todos take a second click within a few seconds
+                                                if !finished && confirm_id.get_untracked() != Some(id) && needs_confirm() {
+                                                    ev.prevent_default();
+                                                    set_confirm_id.set(Some(id));
+                                                    set_timeout(
+                                                        move || set_confirm_id.update(|c| if *c == Some(id) { *c = None }),
+                                                        Duration::from_secs(CONFIRM_SECS),
+                                                    );
+                                                    return;
+                                                }
+                                                set_confirm_id.set(None);
+                                                let subject = toggled.clone();
+                                                spawn_local(async move {
+                                                    let args = serde_wasm_bindgen::to_value(&ToggleTodoArgs { id }).unwrap();
+                                                    let result = invoke("toggle_todo", args).await;
+                                                    match serde_wasm_bindgen::from_value::<()>(result) {
+                                                        Ok(()) => {
+                                                            set_error.set(None);
+                                                            if finished {
+                                                                set_announcement.set(format!("Reopened: {subject}"));
+                                                            } else {
+                                                                set_announcement.set(format!("Completed: {subject}"));
+                                                                celebrate();
+                                                            }
+                                                        }
+                                                        Err(e) => set_error.set(Some(format!("Failed to toggle todo: {e}"))),
+                                                    }
+                                                });
+                                            };
 
-                                        let raw = item.raw.clone();
+                                            let deleted = (item.raw.clone(), item.subject.clone());
+                                            let on_delete = move |ev: leptos::ev::MouseEvent| {
+                                                ev.stop_propagation();
+                                                let (raw, subject) = deleted.clone();
+                                                spawn_local(async move {
+                                                    let args = serde_wasm_bindgen::to_value(&DeleteTodoArgs { id }).unwrap();
+                                                    let result = invoke("delete_todo", args).await;
+                                                    match serde_wasm_bindgen::from_value::<()>(result) {
+                                                        Ok(()) => {
+                                                            set_error.set(None);
+                                                            let keys = settings
+                                                                .with_untracked(|s| s.keymap.keys(ShortcutAction::RestoreDeleted).to_string());
+                                                            set_announcement.set(if keys.is_empty() {
+                                                                format!("Deleted: {subject}")
+                                                            } else {
+                                                                format!("Deleted: {subject} — press {keys} to undo")
+                                                            });
+                                                            set_last_deleted.set(Some((raw, subject)));
+                                                        }
+                                                        Err(e) => set_error.set(Some(format!("Failed to delete todo: {e}"))),
+                                                    }
+                                                });
+                                            };
 
-                                        let on_text_click = move |ev: leptos::ev::MouseEvent| {
-                                            ev.stop_propagation();
-                                            set_editing_id.set(Some(id));
-                                            set_edit_text.set(raw.clone());
-                                        };
+                                            let starred = item.starred;
+                                            let flag = item.flag;
+                                            let on_star = move |ev: leptos::ev::MouseEvent| {
+                                                ev.stop_propagation();
+                                                spawn_local(async move {
+                                                    let args = serde_wasm_bindgen::to_value(&SetStarredArgs { id, starred: !starred }).unwrap();
+                                                    let result = invoke("set_starred", args).await;
+                                                    match serde_wasm_bindgen::from_value::<()>(result) {
+                                                        Ok(()) => {
+                                                            set_error.set(None);
+                                                        }
+                                                        Err(e) => set_error.set(Some(format!("Failed to star todo: {e}"))),
+                                                    }
+                                                });
+                                            };
 
-                                        let on_edit_keydown = move |ev: leptos::ev::KeyboardEvent| {
-                                            if ev.key() == "Enter" {
-                                                ev.prevent_default();
-                                                let text = edit_text.get_untracked();
-                                                set_editing_id.set(None);
+                                            let auto_done = item.auto_done;
+                                            let has_due_date = item.due_date.is_some();
+                                            let on_auto_done = move |ev: leptos::ev::Event| {
+                                                let auto_done = event_target_checked(&ev);
                                                 spawn_local(async move {
-                                                    let args = serde_wasm_bindgen::to_value(&EditTodoArgs { id, text: &text }).unwrap();
-                                                    let result = invoke("edit_todo", args).await;
-                                                    match serde_wasm_bindgen::from_value::<Vec<TodoItem>>(result) {
-                                                        Ok(items) => {
+                                                    let args = serde_wasm_bindgen::to_value(&SetAutoDoneArgs { id, auto_done }).unwrap();
+                                                    let result = invoke("set_auto_done", args).await;
+                                                    match serde_wasm_bindgen::from_value::<()>(result) {
+                                                        Ok(()) => {
                                                             set_error.set(None);
-                                                            set_todos.set(items);
                                                         }
-                                                        Err(e) => set_error.set(Some(format!("Failed to edit todo: {e}"))),
+                                                        Err(e) => set_error.set(Some(format!("Failed to update todo: {e}"))),
+                                                    }
+                                                });
+                                            };
+
+                                            let assignee = item.assignee.clone();
+                                            let has_assignee = assignee.is_some();
+                                            let (assignee_input, set_assignee_input) = signal(assignee.clone().unwrap_or_default());
+                                            let (new_tag, set_new_tag) = signal(String::new());
+
+                                            let history_item = item.clone();
+                                            let split_item = item.clone();
+                                            let raw = item.raw.clone();
+                                            let subject_ref = NodeRef::<leptos::html::Span>::new();
+                                            let edit_ref = NodeRef::<leptos::html::Input>::new();
+
+                                            // Move keyboard focus into the edit field once it is no longer hidden
+                                            let start_edit = Callback::new(move |_: ()| {
+                                                if read_only.get_untracked() {
+                                                    return;
+                                                }
+                                                set_editing_id.set(Some(id));
+                                                set_edit_text.set(raw.clone());
+                                                request_animation_frame(move || {
+                                                    if let Some(input) = edit_ref.get_untracked() {
+                                                        let _ = input.focus();
                                                     }
                                                 });
-                                            } else if ev.key() == "Escape" {
+                                            });
+
+                                            let stop_edit = move || {
                                                 set_editing_id.set(None);
-                                            }
-                                        };
+                                                request_animation_frame(move || {
+                                                    if let Some(span) = subject_ref.get_untracked() {
+                                                        let _ = span.focus();
+                                                    }
+                                                });
+                                            };
 
-                                        view! {
-                                            <li class="list-row p-2 group cursor-pointer hover:bg-base-300 transition-colors" >
-                                                    <input
-                                                        type="checkbox"
-                                                        class="checkbox checkbox-accent"
-                                                        prop:checked=finished
-                                                        on:click=on_toggle
-                                                    />
-                                                    <div class="">
-                                                        <span
-                                                            class=("line-through", finished)
-                                                            class=("opacity-50", finished)
-                                                            class=("hidden", move || editing_id.get() == Some(id))
-                                                            on:click=on_text_click
-                                                        >
-                                                            {subject.clone()}
-                                                        </span>
+                                            let on_text_click = move |ev: leptos::ev::MouseEvent| {
+                                                ev.stop_propagation();
+                                                start_edit.run(());
+                                            };
+
+                                            let on_text_keydown = move |ev: leptos::ev::KeyboardEvent| {
+                                                if matches!(ev.key().as_str(), "Enter" | " " | "F2") {
+                                                    ev.prevent_default();
+                                                    start_edit.run(());
+                                                }
+                                            };
+
+                                            let on_edit_keydown = move |ev: leptos::ev::KeyboardEvent| {
+                                                if ev.key() == "Enter" {
+                                                    ev.prevent_default();
+                                                    let text = edit_text.get_untracked();
+                                                    stop_edit();
+                                                    spawn_local(async move {
+                                                        let args = serde_wasm_bindgen::to_value(&EditTodoArgs { id, text: &text }).unwrap();
+                                                        let result = invoke("edit_todo", args).await;
+                                                        match serde_wasm_bindgen::from_value::<Vec<Overflow>>(result) {
+                                                            Ok(overflows) => {
+                                                                set_error.set(None);
+                                                                set_wip_warning.set(overflow_warning(&overflows));
+                                                            }
+                                                            Err(e) => set_error.set(Some(format!("Failed to edit todo: {e}"))),
+                                                        }
+                                                    });
+                                                } else if ev.key() == "Escape" {
+                                                    stop_edit();
+                                                }
+                                            };
+
+                                            view! {
+                                                {move || group_headers.with(|headers| headers.get(&id).cloned()).map(|header| view! {
+                                                    <li class="px-4 pt-3 pb-1 text-xs font-semibold uppercase opacity-60" role="presentation">
+                                                        {header}
+                                                    </li>
+                                                })}
+                                                <li
+                                                    class="list-row group cursor-pointer hover:bg-base-300 focus-within:bg-base-300 transition-colors"
+                                                    class=("p-2", move || display.get().density == Density::Comfortable)
+                                                    class=("px-2", move || display.get().density == Density::Compact)
+                                                    class=("py-0.5", move || display.get().density == Density::Compact)
+                                                    class=("text-sm", move || display.get().density == Density::Compact)
+                                                    class=("bg-warning/10", starred)
+                                                    style=flag.map(|flag| format!("box-shadow: inset 4px 0 0 {}", flag_color(flag)))
+                                                    on:contextmenu=move |ev: leptos::ev::MouseEvent| {
+                                                        if read_only.get_untracked() {
+                                                            return;
+                                                        }
+                                                        ev.prevent_default();
+                                                        set_flag_menu.set(Some((id, ev.client_x(), ev.client_y())));
+                                                    }
+                                                    class=("opacity-50", move || dragged.get() == Some(id))
+                                                    draggable=move || reorderable.get().to_string()
+                                                    on:dragstart=move |ev: leptos::ev::DragEvent| {
+                                                        if !reorderable.get_untracked() {
+                                                            return;
+                                                        }
+                                                        // Some browsers only start a drag that carries data
+                                                        if let Some(transfer) = ev.data_transfer() {
+                                                            let _ = transfer.set_data("text/plain", &id.to_string());
+                                                        }
+                                                        set_dragged.set(Some(id));
+                                                    }
+                                                    on:dragover=move |ev: leptos::ev::DragEvent| {
+                                                        if dragged.get_untracked().is_some() {
+                                                            ev.prevent_default();
+                                                        }
+                                                    }
+                                                    on:drop=move |ev: leptos::ev::DragEvent| {
+                                                        ev.prevent_default();
+                                                        reorder(id);
+                                                    }
+                                                    on:dragend=move |_| set_dragged.set(None)
+                                                >
+                                                        {flag.map(|flag| view! {
+                                                            <span class="sr-only">{format!("Flagged {}", flag.name())}</span>
+                                                        })}
                                                         <input
-                                                            type="text"
-                                                            class="input input-bordered input-sm w-full"
-                                                            class=("hidden", move || editing_id.get() != Some(id))
-                                                            prop:value=move || edit_text.get()
-                                                            on:input=move |ev| set_edit_text.set(event_target_value(&ev))
-                                                            on:keydown=on_edit_keydown
-                                                            on:blur=move |_| set_editing_id.set(None)
+                                                            type="checkbox"
+                                                            class="checkbox checkbox-accent"
+                                                            aria-label=format!("Mark \"{subject}\" as {}", if finished { "not done" } else { "done" })
+                                                            prop:checked=finished
+                                                            disabled=move || read_only.get()
+                                                            on:click=on_toggle
                                                         />
-                                                        <span>" "</span>
-                                                    </div>
-                                                    <div class="">
-                                                            {priority_label(priority).map(|p| view! {
-                                                                <span class="badge p-1 badge-primary badge-sm">{p}</span>" "
+                                                        <div class="">
+                                                            <span
+                                                                role="button"
+                                                                tabindex="0"
+                                                                class="rounded focus:outline-none focus-visible:ring-2 focus-visible:ring-primary"
+                                                                class=("line-through", finished)
+                                                                class=("opacity-50", finished)
+                                                                class=("hidden", move || editing_id.get() == Some(id))
+                                                                aria-label=format!("Edit \"{subject}\"")
+                                                                node_ref=subject_ref
+                                                                on:click=on_text_click
+                                                                on:keydown=on_text_keydown
+                                                            >
+                                                                {
+                                                                    let subject = subject.clone();
+                                                                    move || if display.get().render_markdown {
+                                                                        rich_text(&subject, set_error).into_any()
+                                                                    } else {
+                                                                        subject.clone().into_any()
+                                                                    }
+                                                                }
+                                                            </span>
+                                                            <input
+                                                                type="text"
+                                                                class="input input-bordered input-sm w-full"
+                                                                class=("hidden", move || editing_id.get() != Some(id))
+                                                                aria-label="Todo text"
+                                                                node_ref=edit_ref
+                                                                spellcheck="true"
+                                                                prop:value=move || edit_text.get()
+                                                                on:input=snippets::on_input(snippets, set_edit_text)
+                                                                on:keydown=on_edit_keydown
+                                                                on:blur=move |_| set_editing_id.set(None)
+                                                            />
+                                                            <span>" "</span>
+                                                            {move || display.get().show_id.then(|| view! {
+                                                                <span class="text-xs opacity-50">{format!("#{id}")}</span>
                                                             })}
-                                                    </div>
-                                                    <div class="">
-                                                            {projects.into_iter().map(|p| view! {
-                                                                <span class="badge p-1 badge-secondary badge-sm">{"+"}{p}</span>" "
-                                                            }).collect::<Vec<_>>()}
-                                                    </div>
-                                                    <div class="">
-                                                            {contexts.into_iter().map(|c| view! {
-                                                                <span class="badge p-1 badge-accent badge-sm">{"@"}{c}</span>" "
-                                                            }).collect::<Vec<_>>()}
-                                                    </div>
-
-
-                                                    <button
-                                                        class="btn btn-ghost btn-sm opacity-0 group-hover:opacity-80 transition-opacity"
-                                                        on:click=on_delete
-                                                    >
-                                                        <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
-                                                        </svg>
-                                                    </button>
-                                            </li>
+                                                            {
+                                                                let raw = item.raw.clone();
+                                                                move || display.get().show_raw.then(|| view! {
+                                                                    <div class="font-mono text-xs opacity-60">{raw.clone()}</div>
+                                                                })
+                                                            }
+                                                            {move || (confirm_id.get() == Some(id)).then(|| view! {
+                                                                <span class="badge badge-warning badge-sm" role="status">"Click again to complete"</span>
+                                                            })}
+                                                        </div>
+                                                        <div class="text-xs opacity-60 whitespace-nowrap">
+                                                            {
+                                                                let creation_date = creation_date.clone();
+                                                                move || {
+                                                                    let age = creation_date.as_deref().and_then(|created| review::age_days(created, &today.get()))?;
+                                                                    let stale = age > i64::from(settings.with(|s| s.stale_after_days));
+                                                                    (age > 0).then(|| view! {
+                                                                        <span
+                                                                            class=if stale { "text-warning" } else { "" }
+                                                                            title=format!("Created {age} days ago")
+                                                                            aria-label=format!("{age} days old")
+                                                                        >
+                                                                            {format!("{age}d")}
+                                                                        </span>" "
+                                                                    })
+                                                                }
+                                                            }
+                                                            {
+                                                                let creation_date = creation_date.clone();
+                                                                move || display.get().show_creation_date.then(|| creation_date.clone()).flatten().map(|d| view! {
+                                                                    <span aria-label=format!("Created {d}")>{d.clone()}</span>" "
+                                                                })
+                                                            }
+                                                            {
+                                                                let due_date = due_date.clone();
+                                                                move || display.get().show_due_date.then(|| due_date.clone()).flatten().map(|d| view! {
+                                                                    <span aria-label=format!("Due {d}")>{"due "}{d.clone()}</span>
+                                                                })
+                                                            }
+                                                        </div>
+                                                        <div class="">
+                                                                {item.estimate.map(|minutes| view! {
+                                                                    <span class="badge badge-ghost badge-sm" aria-label=format!("Estimated {}", estimate::format(minutes))>
+                                                                        {format!("~{}", estimate::format(minutes))}
+                                                                    </span>" "
+                                                                })}
+                                                                {move || checklists.with(|c| c.get(&id).copied()).map(|p| view! {
+                                                                    <span class="badge badge-ghost badge-sm" aria-label=format!("{} of {} checklist items done", p.done, p.total)>
+                                                                        {format!("{}/{}", p.done, p.total)}
+                                                                    </span>" "
+                                                                })}
+                                                                {assignee.clone().map(|a| view! {
+                                                                    <span class="badge badge-ghost badge-sm" aria-label=format!("Assigned to {a}")>{a.clone()}</span>" "
+                                                                })}
+                                                                {priority_label(priority).map(|p| view! {
+                                                                    <span class="badge p-1 badge-priority badge-sm" aria-label=format!("Priority {p}")>{p}</span>" "
+                                                                })}
+                                                        </div>
+                                                        <div class="">
+                                                                {projects.into_iter().map(|p| {
+                                                                    let key = format!("+{p}");
+                                                                    let style = Signal::derive({
+                                                                        let key = key.clone();
+                                                                        move || tag_colors.with(|colors| badge_style(&tag_color(&key, colors)))
+                                                                    });
+                                                                    tag_chip(key, style, read_only.into(), Callback::new(move |tag| remove_tag.run((id, tag))))
+                                                                }).collect::<Vec<_>>()}
+                                                        </div>
+                                                        <div class="">
+                                                                {contexts.into_iter().map(|c| {
+                                                                    let key = format!("@{c}");
+                                                                    let style = Signal::derive({
+                                                                        let key = key.clone();
+                                                                        move || tag_colors.with(|colors| badge_style(&tag_color(&key, colors)))
+                                                                    });
+                                                                    tag_chip(key, style, read_only.into(), Callback::new(move |tag| remove_tag.run((id, tag))))
+                                                                }).collect::<Vec<_>>()}
+                                                        </div>
+                                                        <div class="flex flex-wrap items-center">
+                                                                {TodoItem::new(&item.raw).free_tags().into_iter().map(|tag| {
+                                                                    tag_chip(tag, Signal::stored(String::new()), read_only.into(), Callback::new(move |tag| remove_tag.run((id, tag))))
+                                                                }).collect::<Vec<_>>()}
+                                                                <div class="dropdown" class=("hidden", move || read_only.get())>
+                                                                    <button
+                                                                        type="button"
+                                                                        class="badge p-1 badge-ghost badge-sm cursor-pointer opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 transition-opacity"
+                                                                        aria-haspopup="true"
+                                                                        aria-label=format!("Add a tag to \"{subject}\"")
+                                                                    >
+                                                                        "+"
+                                                                    </button>
+                                                                    <form
+                                                                        class="dropdown-content bg-base-100 rounded-box shadow z-10 p-2 flex gap-1 w-64"
+                                                                        on:submit=move |ev: SubmitEvent| {
+                                                                            ev.prevent_default();
+                                                                            let tag = new_tag.get_untracked().trim().to_string();
+                                                                            if !tag.is_empty() {
+                                                                                add_tag.run((id, tag));
+                                                                                set_new_tag.set(String::new());
+                                                                            }
+                                                                        }
+                                                                    >
+                                                                        <input
+                                                                            type="text"
+                                                                            class="input input-bordered input-xs flex-1 font-mono"
+                                                                            list="tag-options"
+                                                                            placeholder="+project @context key:value"
+                                                                            aria-label="Tag"
+                                                                            prop:value=move || new_tag.get()
+                                                                            on:input=move |ev| set_new_tag.set(event_target_value(&ev))
+                                                                        />
+                                                                        <button type="submit" class="btn btn-primary btn-xs">"Add"</button>
+                                                                    </form>
+                                                                </div>
+                                                        </div>
+
+
+                                                        <div
+                                                            class="dropdown dropdown-end opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 transition-opacity"
+                                                            class=("hidden", move || read_only.get())
+                                                        >
+                                                            <button
+                                                                type="button"
+                                                                class="btn btn-ghost btn-sm"
+                                                                aria-haspopup="true"
+                                                                aria-label=format!("Assign \"{subject}\" to…")
+                                                            >
+                                                                <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z"/>
+                                                                </svg>
+                                                            </button>
+                                                            <form
+                                                                class="dropdown-content bg-base-100 rounded-box shadow z-10 p-2 flex flex-wrap gap-1 w-64"
+                                                                on:submit=move |ev: SubmitEvent| {
+                                                                    ev.prevent_default();
+                                                                    let name = assignee_input.get_untracked();
+                                                                    assign.run((id, (!name.trim().is_empty()).then_some(name)));
+                                                                }
+                                                            >
+                                                                <input
+                                                                    type="text"
+                                                                    class="input input-bordered input-xs flex-1"
+                                                                    list="assignee-options"
+                                                                    placeholder="Name"
+                                                                    aria-label="Assignee"
+                                                                    prop:value=move || assignee_input.get()
+                                                                    on:input=move |ev| set_assignee_input.set(event_target_value(&ev))
+                                                                />
+                                                                <button type="submit" class="btn btn-primary btn-xs">"Assign"</button>
+                                                                <button
+                                                                    type="button"
+                                                                    class="btn btn-ghost btn-xs"
+                                                                    prop:disabled=!has_assignee
+                                                                    on:click=move |_| assign.run((id, None))
+                                                                >
+                                                                    "Unassign"
+                                                                </button>
+                                                                <label
+                                                                    class="label cursor-pointer gap-2 w-full text-xs"
+                                                                    title=(!has_due_date).then_some("Needs a due date")
+                                                                >
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="checkbox checkbox-xs"
+                                                                        prop:checked=auto_done
+                                                                        prop:disabled=!has_due_date && !auto_done
+                                                                        on:change=on_auto_done
+                                                                    />
+                                                                    "Complete once the due date passes (auto:done)"
+                                                                </label>
+                                                            </form>
+                                                        </div>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-ghost btn-sm opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 focus-visible:opacity-100 transition-opacity"
+                                                            class=("hidden", move || read_only.get())
+                                                            aria-haspopup="dialog"
+                                                            aria-label=format!("History of \"{subject}\"")
+                                                            on:click=move |ev: leptos::ev::MouseEvent| {
+                                                                ev.stop_propagation();
+                                                                set_history_task.set(Some(history_item.clone()));
+                                                            }
+                                                        >
+                                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z"/>
+                                                            </svg>
+                                                        </button>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-ghost btn-sm opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 focus-visible:opacity-100 transition-opacity"
+                                                            class=("hidden", move || read_only.get())
+                                                            aria-haspopup="dialog"
+                                                            aria-label=format!("Split \"{subject}\"")
+                                                            on:click=move |ev: leptos::ev::MouseEvent| {
+                                                                ev.stop_propagation();
+                                                                set_split_task.set(Some(split_item.clone()));
+                                                            }
+                                                        >
+                                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 7h12M8 12h12M8 17h12M4 7h.01M4 12h.01M4 17h.01"/>
+                                                            </svg>
+                                                        </button>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-ghost btn-sm opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 focus-visible:opacity-100 transition-opacity"
+                                                            class=("hidden", move || read_only.get() || finished)
+                                                            aria-label=format!("Focus on \"{subject}\"")
+                                                            on:click=move |ev: leptos::ev::MouseEvent| {
+                                                                ev.stop_propagation();
+                                                                focus::start(id, set_error);
+                                                            }
+                                                        >
+                                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                                <circle cx="12" cy="13" r="8" stroke-width="2"/>
+                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 9v4l2 2M10 2h4"/>
+                                                            </svg>
+                                                        </button>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-ghost btn-sm group-hover:opacity-80 group-focus-within:opacity-80 focus-visible:opacity-100 transition-opacity"
+                                                            class=("opacity-0", !starred)
+                                                            class=("text-warning", starred)
+                                                            class=("hidden", move || read_only.get())
+                                                            aria-label=format!("Star \"{subject}\"")
+                                                            aria-pressed=starred.to_string()
+                                                            on:click=on_star
+                                                        >
+                                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill=if starred { "currentColor" } else { "none" } viewBox="0 0 24 24" stroke="currentColor">
+                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11.48 3.5a.56.56 0 011.04 0l2.13 5.11a.56.56 0 00.47.34l5.52.44c.5.04.7.66.32.99l-4.2 3.6a.56.56 0 00-.18.55l1.28 5.38a.56.56 0 01-.84.61l-4.72-2.88a.56.56 0 00-.58 0l-4.72 2.88a.56.56 0 01-.84-.61l1.28-5.38a.56.56 0 00-.18-.55l-4.2-3.6a.56.56 0 01.32-.99l5.52-.44a.56.56 0 00.47-.34z"/>
+                                                            </svg>
+                                                        </button>
+                                                        <button
+                                                            type="button"
+                                                            class="btn btn-ghost btn-sm opacity-0 group-hover:opacity-80 group-focus-within:opacity-80 focus-visible:opacity-100 transition-opacity"
+                                                            class=("hidden", move || read_only.get())
+                                                            aria-label=format!("Delete \"{subject}\"")
+                                                            on:click=on_delete
+                                                        >
+                                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
+                                                            </svg>
+                                                        </button>
+                                                </li>
+                                            }
                                         }
-                                    }
-                                />
-                            </ul>
+                                    />
+                                </ul>
+                                <datalist id="tag-options">
+                                    <For
+                                        each=move || tag_options.get()
+                                        key=|tag| tag.clone()
+                                        children=move |tag| view! { <option value=tag></option> }
+                                    />
+                                </datalist>
+                                <datalist id="assignee-options">
+                                    <For
+                                        each=move || assignees.get()
+                                        key=|name| name.clone()
+                                        children=move |name| view! { <option value=name></option> }
+                                    />
+                                </datalist>
+                            </div>
                         </div>
                     </div>
                 </div>
             </main>
         </div>
 
-        <dialog class="modal" class:modal-open=move || dialog_open.get()>
-            <div class="modal-box">
-                <h3 class="text-lg font-bold">"Add Todo"</h3>
+        <dialog
+            class="modal"
+            class:modal-open=move || dialog_open.get()
+            aria-modal="true"
+            aria-labelledby="add-todo-title"
+        >
+            <div class="modal-box" on:keydown=on_dialog_keydown>
+                <h3 id="add-todo-title" class="text-lg font-bold">"Add Todo"</h3>
                 <form on:submit=on_add_submit>
                     <div class="form-control mt-4">
                         <input
                             type="text"
                             placeholder="e.g. (A) Buy milk @errands +shopping"
                             class="input input-bordered w-full"
+                            aria-label="Todo text"
+                            aria-describedby="add-todo-hint"
+                            node_ref=new_todo_ref
+                            spellcheck="true"
                             prop:value=move || new_todo.get()
-                            on:input=move |ev| set_new_todo.set(event_target_value(&ev))
+                            on:input=snippets::on_input(snippets, set_new_todo)
                         />
-                        <p class="label text-xs opacity-60">
+                        <p id="add-todo-hint" class="label text-xs opacity-60">
                             "Use todo.txt format: (A) priority, @context, +project"
                         </p>
                     </div>
                     <div class="modal-action">
-                        <button
-                            type="button"
-                            class="btn"
-                            on:click=move |_| {
-                                set_new_todo.set(String::new());
-                                set_dialog_open.set(false);
-                            }
-                        >
+                        <button type="button" class="btn" on:click=move |_| close_dialog.run(())>
                             "Cancel"
                         </button>
-                        <button type="submit" class="btn btn-primary">"Add"</button>
+                        <button type="button" class="btn" title="Add to inbox.txt, to refile later" on:click=on_capture>
+                            "Capture"
+                        </button>
+                        <button type="submit" class="btn btn-primary" node_ref=submit_ref>"Add"</button>
                     </div>
                 </form>
             </div>
             <form method="dialog" class="modal-backdrop">
                 <button
                     type="button"
-                    on:click=move |_| {
-                        set_new_todo.set(String::new());
-                        set_dialog_open.set(false);
-                    }
+                    tabindex="-1"
+                    aria-label="Close"
+                    on:click=move |_| close_dialog.run(())
                 />
             </form>
         </dialog>
+
+        <CustomCss
+            path=Signal::derive(move || settings.with(|s| s.custom_css.clone()))
+            watch=Signal::derive(move || view.get() == View::Settings)
+            set_error=set_error
+        />
+        <HistoryDialog
+            task=history_task
+            set_task=set_history_task
+            demo_mode=demo_mode.into()
+            on_checklist=Callback::new(move |(id, progress): (usize, Option<Progress>)| {
+                set_checklists.update(|c| match progress {
+                    Some(progress) => {
+                        c.insert(id, progress);
+                    }
+                    None => {
+                        c.remove(&id);
+                    }
+                });
+            })
+            on_follow=Callback::new(move |id: usize| {
+                set_filter.set(Filter { id: Some(id), ..Filter::default() });
+                set_view.set(View::Todos);
+            })
+            set_error=set_error
+        />
+        <ArchiveDialog open=archive_open set_open=set_archive_open demo_mode=demo_mode.into() set_error=set_error />
+        <SplitDialog task=split_task set_task=set_split_task set_error=set_error />
+        <ReplaceDialog open=replace_open set_open=set_replace_open filter=filter set_error=set_error />
+        <ShiftDialog open=shift_open set_open=set_shift_open filter=filter set_error=set_error />
+        <BackupDialog open=backups_open set_open=set_backups_open set_error=set_error />
+        <ChangelogDialog />
+        <CommandPalette open=palette_open set_open=set_palette_open commands=palette_commands />
+        <Picker open=picker_open set_open=set_picker_open filter=filter demo_mode=demo_mode.into() set_error=set_error />
+        <FlagMenu menu=flag_menu set_menu=set_flag_menu set_flag=set_flag />
+        <div class="sr-only" role="status" aria-live="polite" aria-atomic="true">
+            {move || announcement.get()}
+        </div>
+        <JobToasts set_error=set_error />
+        <FileChanges />
+        <Celebration active=celebrating />
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use std::rc::Rc;
+
+    use leptos::mount::mount_to;
+    use leptos::prelude::document;
+    use leptos::task::tick;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::{Event, HtmlElement, HtmlInputElement, KeyboardEvent, KeyboardEventInit};
+
+    use super::App;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::set_backend;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Mounts the app against `backend` and waits for the initial loads.
+    async fn mount(backend: &Rc<MockBackend>) -> HtmlElement {
+        set_backend(backend.clone());
+        let root: HtmlElement = document().create_element("div").unwrap().unchecked_into();
+        document().body().unwrap().append_child(&root).unwrap();
+        mount_to(root.clone(), App).forget();
+        settle().await;
+        root
+    }
+
+    /// Lets pending backend calls resolve and their effects run.
+    async fn settle() {
+        for _ in 0..10 {
+            tick().await;
+        }
+    }
+
+    fn find(root: &HtmlElement, selector: &str) -> HtmlElement {
+        root.query_selector(selector)
+            .unwrap()
+            .unwrap_or_else(|| panic!("nothing matches {selector}"))
+            .unchecked_into()
+    }
+
+    fn subjects(root: &HtmlElement) -> Vec<String> {
+        let nodes = root
+            .query_selector_all("ul[aria-label='Todos'] li span[role='button']")
+            .unwrap();
+        (0..nodes.length())
+            .filter_map(|i| nodes.item(i)?.text_content())
+            .collect()
+    }
+
+    async fn add_todo(root: &HtmlElement, text: &str) {
+        let input: HtmlInputElement =
+            find(root, "dialog input[aria-label='Todo text']").unchecked_into();
+        input.set_value(text);
+        input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+        find(root, "dialog button[type='submit']").click();
+        settle().await;
+    }
+
+    #[wasm_bindgen_test]
+    async fn shows_todos_from_backend() {
+        let backend = Rc::new(MockBackend::new("Call Bob\nBuy milk @store"));
+        let root = mount(&backend).await;
+        assert_eq!(subjects(&root), vec!["Call Bob", "Buy milk"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn adds_todo() {
+        let backend = Rc::new(MockBackend::new("Call Bob"));
+        let root = mount(&backend).await;
+        add_todo(&root, "Water plants +garden").await;
+        assert_eq!(backend.content(), "Call Bob\nWater plants +garden");
+        assert_eq!(subjects(&root), vec!["Call Bob", "Water plants"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn toggles_todo() {
+        let backend = Rc::new(MockBackend::new("Call Bob"));
+        let root = mount(&backend).await;
+        find(&root, "input[aria-label='Mark \"Call Bob\" as done']").click();
+        settle().await;
+        assert_eq!(backend.content(), "x Call Bob");
+        assert!(
+            find(&root, "input[aria-label='Mark \"Call Bob\" as not done']")
+                .unchecked_into::<HtmlInputElement>()
+                .checked()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn deletes_todo() {
+        let backend = Rc::new(MockBackend::new("Call Bob\nBuy milk"));
+        let root = mount(&backend).await;
+        find(&root, "button[aria-label='Delete \"Call Bob\"']").click();
+        settle().await;
+        assert_eq!(backend.content(), "Buy milk");
+        assert_eq!(subjects(&root), vec!["Buy milk"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn announces_and_restores_deletions() {
+        let backend = Rc::new(MockBackend::new("Call Bob\nBuy milk"));
+        let root = mount(&backend).await;
+        let announcement = || {
+            find(&root, "div.sr-only[role='status']")
+                .text_content()
+                .unwrap_or_default()
+        };
+        find(&root, "button[aria-label='Delete \"Call Bob\"']").click();
+        settle().await;
+        assert_eq!(announcement(), "Deleted: Call Bob — press U to undo");
+        let init = KeyboardEventInit::new();
+        init.set_key("u");
+        let press = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+        web_sys::window().unwrap().dispatch_event(&press).unwrap();
+        settle().await;
+        assert_eq!(backend.content(), "Buy milk\nCall Bob");
+        assert_eq!(announcement(), "Restored: Call Bob");
+    }
+
+    #[wasm_bindgen_test]
+    async fn shows_backend_errors() {
+        let backend = Rc::new(MockBackend::new("Call Bob"));
+        backend.fail("add_todo");
+        let root = mount(&backend).await;
+        add_todo(&root, "Water plants").await;
+        let alert = find(&root, "[role='alert']")
+            .text_content()
+            .unwrap_or_default();
+        assert!(alert.contains("Failed to add todo"), "{alert}");
+        assert_eq!(subjects(&root), vec!["Call Bob"]);
     }
 }
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::archive::{ArchiveLine, ArchivePreview};
+use todotxt::redact;
+use types::ArchiveDoneArgs;
+
+use crate::backend::invoke;
+
+/// Archives the finished todos to done.txt after showing which lines move
+/// and which stay, from a dry run. Unticking a line keeps it in todo.txt.
+#[component]
+pub fn ArchiveDialog(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    /// Show lorem ipsum instead of the lines
+    demo_mode: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (preview, set_preview) = signal(ArchivePreview::default());
+    // Finished todos the user opted out of
+    let (skipped, set_skipped) = signal(HashSet::<usize>::new());
+
+    // A fresh dry run whenever the dialog opens, with everything finished ticked
+    Effect::new(move |_| {
+        if !open.get() {
+            return;
+        }
+        set_skipped.set(HashSet::new());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ArchiveDoneArgs {
+                skip: &[],
+                dry_run: true,
+            })
+            .unwrap();
+            let result = invoke("archive_done", args).await;
+            match serde_wasm_bindgen::from_value::<ArchivePreview>(result) {
+                Ok(preview) => set_preview.set(preview),
+                Err(e) => set_error.set(Some(format!("Failed to preview archiving: {e}"))),
+            }
+        });
+    });
+
+    let close = move || set_open.set(false);
+
+    let archive = move |_| {
+        let skip: Vec<usize> = skipped.with_untracked(|s| s.iter().copied().collect());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ArchiveDoneArgs {
+                skip: &skip,
+                dry_run: false,
+            })
+            .unwrap();
+            let result = invoke("archive_done", args).await;
+            match serde_wasm_bindgen::from_value::<ArchivePreview>(result) {
+                Ok(_) => {
+                    set_error.set(None);
+                    close();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to archive: {e}"))),
+            }
+        });
+    };
+
+    let shown = move |line: &ArchiveLine| {
+        if demo_mode.get() {
+            redact::line(&line.raw)
+        } else {
+            line.raw.clone()
+        }
+    };
+    let moving = move || {
+        preview.with(|p| {
+            p.moved
+                .iter()
+                .filter(|line| !skipped.with(|s| s.contains(&line.id)))
+                .count()
+        })
+    };
+    let staying = move || preview.with(|p| p.kept.len()) + skipped.with(HashSet::len);
+
+    view! {
+        <dialog class="modal" class:modal-open=move || open.get() aria-modal="true" aria-labelledby="archive-title">
+            <div class="modal-box max-w-2xl" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="archive-title" class="text-lg font-bold">"Archive completed todos"</h3>
+                {move || preview.with(|p| p.moved.is_empty()).then(|| view! {
+                    <p class="mt-4 opacity-60">"Nothing finished to archive."</p>
+                })}
+                <ul class="mt-4 max-h-80 overflow-y-auto" aria-label="Moving to done.txt">
+                    {move || preview.get().moved.into_iter().map(|line| {
+                        let id = line.id;
+                        let text = shown(&line);
+                        view! {
+                            <li>
+                                <label class="label cursor-pointer justify-start gap-2 py-1">
+                                    <input
+                                        type="checkbox"
+                                        class="checkbox checkbox-sm"
+                                        prop:checked=move || !skipped.with(|s| s.contains(&id))
+                                        on:change=move |ev| {
+                                            let archived = event_target_checked(&ev);
+                                            set_skipped.update(|s| {
+                                                if archived {
+                                                    s.remove(&id);
+                                                } else {
+                                                    s.insert(id);
+                                                }
+                                            });
+                                        }
+                                    />
+                                    <span class="font-mono text-sm">{text}</span>
+                                </label>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                <details class="mt-2">
+                    <summary class="cursor-pointer text-sm">{move || format!("{} lines stay in todo.txt", staying())}</summary>
+                    <ul class="mt-1 max-h-48 overflow-y-auto" aria-label="Staying in todo.txt">
+                        {move || {
+                            let skipped = skipped.get();
+                            preview.with(|p| {
+                                p.moved
+                                    .iter()
+                                    .filter(|line| skipped.contains(&line.id))
+                                    .chain(&p.kept)
+                                    .map(|line| view! { <li class="font-mono text-sm opacity-70 py-0.5">{shown(line)}</li> })
+                                    .collect_view()
+                            })
+                        }}
+                    </ul>
+                </details>
+                <div class="modal-action">
+                    <button type="button" class="btn" on:click=move |_| close()>"Cancel"</button>
+                    <button type="button" class="btn btn-primary" disabled=move || moving() == 0 on:click=archive>
+                        {move || format!("Move {} to done.txt", moving())}
+                    </button>
+                </div>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
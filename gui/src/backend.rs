@@ -0,0 +1,537 @@
+//! The bridge to the Tauri backend. Components call [`invoke`] and [`listen`],
+//! which go to the real backend in the app and to [`mock::MockBackend`] in tests.
+//! Built with the `web` feature they go to [`web::HttpBackend`] instead, which
+//! reads the list from the app's server and changes nothing.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+
+#[cfg(not(feature = "web"))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    async fn tauri_invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+    async fn tauri_listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
+}
+
+pub type LocalBoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+pub trait Backend {
+    /// Runs the command `cmd` with `args`, resolving to its serialized result.
+    fn invoke<'a>(&'a self, cmd: &'a str, args: JsValue) -> LocalBoxFuture<'a, JsValue>;
+
+    /// Calls `handler` with every `event` the backend emits.
+    fn listen<'a>(
+        &'a self,
+        event: &'a str,
+        handler: &'a Closure<dyn FnMut(JsValue)>,
+    ) -> LocalBoxFuture<'a, ()>;
+}
+
+#[cfg(not(feature = "web"))]
+pub struct TauriBackend;
+
+#[cfg(not(feature = "web"))]
+impl Backend for TauriBackend {
+    fn invoke<'a>(&'a self, cmd: &'a str, args: JsValue) -> LocalBoxFuture<'a, JsValue> {
+        Box::pin(tauri_invoke(cmd, args))
+    }
+
+    fn listen<'a>(
+        &'a self,
+        event: &'a str,
+        handler: &'a Closure<dyn FnMut(JsValue)>,
+    ) -> LocalBoxFuture<'a, ()> {
+        Box::pin(async move {
+            tauri_listen(event, handler).await;
+        })
+    }
+}
+
+/// Whether nothing can be changed, as in the web viewer.
+pub const READ_ONLY: bool = cfg!(feature = "web");
+
+#[cfg(not(feature = "web"))]
+thread_local! {
+    static BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(Rc::new(TauriBackend));
+}
+
+#[cfg(feature = "web")]
+thread_local! {
+    static BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(Rc::new(web::HttpBackend::from_location()));
+}
+
+/// Routes all following calls to `backend`.
+#[cfg(all(test, target_arch = "wasm32"))]
+pub fn set_backend(backend: Rc<dyn Backend>) {
+    BACKEND.with(|current| *current.borrow_mut() = backend);
+}
+
+fn backend() -> Rc<dyn Backend> {
+    BACKEND.with(|current| current.borrow().clone())
+}
+
+pub async fn invoke(cmd: &str, args: JsValue) -> JsValue {
+    backend().invoke(cmd, args).await
+}
+
+pub async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) {
+    backend().listen(event, handler).await
+}
+
+#[cfg(feature = "web")]
+pub mod web {
+    use leptos::prelude::window;
+    use serde::Serialize;
+    use todotxt::filter::Filter;
+    use todotxt::{stats, Date, TodoItem};
+    use types::{Revised, Todo, WebList};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+
+    use super::{Backend, LocalBoxFuture};
+    use crate::settings::Settings;
+
+    /// Reads the list from `GET /todos.json` of the app's server, see
+    /// `src-tauri/src/calendar.rs`, and answers the commands that only read
+    /// from it. The page is opened as `/?token=<token>`, with `&api=<url>`
+    /// if the server isn't where the page comes from. Today's date comes
+    /// from the server too, as the browser's clock can't be read here.
+    pub struct HttpBackend {
+        api: String,
+        token: String,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct FilterArgs {
+        filter: Filter,
+    }
+
+    impl HttpBackend {
+        pub fn from_location() -> Self {
+            let location = window().location();
+            let params = location
+                .search()
+                .ok()
+                .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok());
+            let param = |name: &str| params.as_ref().and_then(|params| params.get(name));
+            Self {
+                api: param("api").unwrap_or_else(|| location.origin().unwrap_or_default()),
+                token: param("token").unwrap_or_default(),
+            }
+        }
+
+        async fn list(&self) -> Result<WebList, String> {
+            let token = String::from(js_sys::encode_uri_component(&self.token));
+            let url = format!(
+                "{}/todos.json?token={token}",
+                self.api.trim_end_matches('/')
+            );
+            let response: web_sys::Response = JsFuture::from(window().fetch_with_str(&url))
+                .await
+                .and_then(|response| response.dyn_into())
+                .map_err(|_| "The app can't be reached".to_string())?;
+            if !response.ok() {
+                return Err(format!(
+                    "The app refused the list: {}",
+                    response.status_text()
+                ));
+            }
+            let json = response.json().map_err(|_| "Not a list".to_string())?;
+            let json = JsFuture::from(json)
+                .await
+                .map_err(|_| "Not a list".to_string())?;
+            serde_wasm_bindgen::from_value(json).map_err(|e| e.to_string())
+        }
+
+        async fn handle(&self, cmd: &str, args: JsValue) -> Result<JsValue, String> {
+            fn to_js(value: &impl Serialize) -> Result<JsValue, String> {
+                serde_wasm_bindgen::to_value(value).map_err(|e| e.to_string())
+            }
+            fn today(list: &WebList) -> Result<Date, String> {
+                Date::parse_from_str(&list.today, "%Y-%m-%d").map_err(|e| e.to_string())
+            }
+            let items = |todos: &[Todo]| -> Vec<TodoItem> {
+                todos.iter().map(|todo| TodoItem::new(&todo.raw)).collect()
+            };
+
+            match cmd {
+                "get_todos" => to_js(&self.list().await?.todos),
+                "query_todos" => {
+                    let filter = serde_wasm_bindgen::from_value::<FilterArgs>(args)
+                        .map_err(|e| e.to_string())?
+                        .filter;
+                    let list = self.list().await?;
+                    let today = today(&list)?;
+                    let Revised { revision, value } = list.todos;
+                    let mut todos: Vec<Todo> = value
+                        .into_iter()
+                        .filter(|todo| {
+                            filter.matches(
+                                &TodoItem::new(&todo.raw),
+                                &list.project_separator,
+                                today,
+                            )
+                        })
+                        .collect();
+                    todos.sort_by_key(|todo| !todo.starred);
+                    to_js(&Revised {
+                        revision,
+                        value: todos,
+                    })
+                }
+                "get_counts" => {
+                    let list = self.list().await?;
+                    to_js(&stats::counts(&items(&list.todos.value), today(&list)?))
+                }
+                "get_due_summary" => {
+                    let list = self.list().await?;
+                    to_js(&stats::due_summary(
+                        &items(&list.todos.value),
+                        today(&list)?,
+                    ))
+                }
+                "get_streaks" => to_js(&stats::Streaks::default()),
+                "get_settings" => {
+                    let list = self.list().await?;
+                    to_js(&Settings {
+                        project_separator: list.project_separator,
+                        ..Settings::default()
+                    })
+                }
+                "get_custom_css" => to_js(&""),
+                "get_draft" => to_js(&types::Draft::default()),
+                "get_inbox" | "get_plugins" | "checklist_progress" => to_js(&Vec::<()>::new()),
+                _ => Ok(JsValue::NULL),
+            }
+        }
+    }
+
+    impl Backend for HttpBackend {
+        fn invoke<'a>(&'a self, cmd: &'a str, args: JsValue) -> LocalBoxFuture<'a, JsValue> {
+            Box::pin(async move {
+                self.handle(cmd, args)
+                    .await
+                    .unwrap_or_else(|e| JsValue::from_str(&e))
+            })
+        }
+
+        /// Nothing is pushed over HTTP; reloading the page shows changes.
+        fn listen<'a>(
+            &'a self,
+            _event: &'a str,
+            _handler: &'a Closure<dyn FnMut(JsValue)>,
+        ) -> LocalBoxFuture<'a, ()> {
+            Box::pin(async {})
+        }
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+pub mod mock {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use todotxt::filter::{Filter, Flag};
+    use todotxt::rules::Overflow;
+    use todotxt::{order, stats, Date, TodoList};
+    use types::{Revised, Todo, TodoChange};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use super::{Backend, LocalBoxFuture};
+    use crate::settings::{Settings, DEFAULT_PROJECT_SEPARATOR};
+
+    /// Answers the commands the todo list needs from an in-memory list and
+    /// emits the same todo events as the real backend. Commands it doesn't
+    /// know resolve to `null`. Its day is a fixed one, since the clock
+    /// can't be read in the browser.
+    pub struct MockBackend {
+        list: RefCell<TodoList>,
+        today: Date,
+        failing: RefCell<HashSet<String>>,
+        listeners: RefCell<Vec<(String, js_sys::Function)>>,
+    }
+
+    #[derive(Serialize)]
+    struct Event<T> {
+        payload: T,
+    }
+
+    #[derive(Deserialize)]
+    struct TextArgs {
+        text: String,
+    }
+
+    #[derive(Deserialize)]
+    struct IdArgs {
+        id: usize,
+    }
+
+    #[derive(Deserialize)]
+    struct EditArgs {
+        id: usize,
+        text: String,
+    }
+
+    #[derive(Deserialize)]
+    struct TagArgs {
+        id: usize,
+        tag: String,
+    }
+
+    #[derive(Deserialize)]
+    struct StarArgs {
+        id: usize,
+        starred: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct FlagArgs {
+        id: usize,
+        flag: Option<Flag>,
+    }
+
+    #[derive(Deserialize)]
+    struct ReorderArgs {
+        ids: Vec<usize>,
+        id: usize,
+    }
+
+    #[derive(Deserialize)]
+    struct LineArgs {
+        line: String,
+    }
+
+    #[derive(Deserialize)]
+    struct FilterArgs {
+        filter: Filter,
+    }
+
+    impl MockBackend {
+        pub fn new(content: &str) -> Self {
+            Self {
+                list: RefCell::new(TodoList::from_content(content)),
+                today: Date::from_ymd_opt(2025, 3, 1).unwrap(),
+                failing: RefCell::new(HashSet::new()),
+                listeners: RefCell::new(Vec::new()),
+            }
+        }
+
+        /// Makes `cmd` fail from now on, resolving to an error message like
+        /// the real backend does.
+        pub fn fail(&self, cmd: &str) {
+            self.failing.borrow_mut().insert(cmd.to_string());
+        }
+
+        pub fn content(&self) -> String {
+            self.list.borrow().to_content()
+        }
+
+        fn todos(&self, filter: &Filter) -> Vec<Todo> {
+            self.list
+                .borrow()
+                .items()
+                .iter()
+                .filter(|item| filter.matches(item, DEFAULT_PROJECT_SEPARATOR, self.today))
+                .map(|item| Todo {
+                    id: item.id,
+                    line: item.line(),
+                    subject: item
+                        .subject()
+                        .split_whitespace()
+                        .filter(|w| !w.starts_with('@') && !w.starts_with('+'))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    raw: item.raw(),
+                    finished: item.finished(),
+                    priority: item.priority(),
+                    creation_date: item.creation_date().map(|d| d.to_string()),
+                    due_date: item.due_date().map(|d| d.to_string()),
+                    due_time: item.due_time().map(|t| t.format("%H:%M").to_string()),
+                    threshold_date: item.threshold_date().map(|d| d.to_string()),
+                    assignee: item.assignee().map(str::to_string),
+                    estimate: item.estimate(),
+                    starred: item.starred(),
+                    auto_done: item.auto_done(),
+                    flag: item.flag(),
+                    contexts: item.unique_contexts(),
+                    projects: item.unique_projects(),
+                })
+                .collect()
+        }
+
+        fn revised<T>(&self, value: T) -> Revised<T> {
+            Revised {
+                revision: self.list.borrow().revision(),
+                value,
+            }
+        }
+
+        /// Calls the listeners with the changes from `before` to the current list.
+        fn emit_changes(&self, before: &[Todo]) {
+            for change in TodoChange::between(before, &self.todos(&Filter::default())) {
+                let event = change.event();
+                let payload = match change {
+                    TodoChange::Added(todo) | TodoChange::Updated(todo) => {
+                        serde_wasm_bindgen::to_value(&Event {
+                            payload: self.revised(todo),
+                        })
+                    }
+                    TodoChange::Removed(id) => serde_wasm_bindgen::to_value(&Event {
+                        payload: self.revised(id),
+                    }),
+                }
+                .unwrap();
+                let handlers: Vec<js_sys::Function> = self
+                    .listeners
+                    .borrow()
+                    .iter()
+                    .filter(|(name, _)| name == event)
+                    .map(|(_, handler)| handler.clone())
+                    .collect();
+                for handler in handlers {
+                    handler.call1(&JsValue::NULL, &payload).unwrap();
+                }
+            }
+        }
+
+        fn handle(&self, cmd: &str, args: JsValue) -> Result<JsValue, String> {
+            fn args_as<T: DeserializeOwned>(args: JsValue) -> Result<T, String> {
+                serde_wasm_bindgen::from_value(args).map_err(|e| e.to_string())
+            }
+            fn to_js(value: &impl Serialize) -> Result<JsValue, String> {
+                serde_wasm_bindgen::to_value(value).map_err(|e| e.to_string())
+            }
+
+            if self.failing.borrow().contains(cmd) {
+                return Err(format!("{cmd} failed"));
+            }
+            let all = Filter::default();
+            let before = self.todos(&all);
+            let result = match cmd {
+                "get_todos" => to_js(&self.revised(self.todos(&all))),
+                "query_todos" => {
+                    let mut todos = self.todos(&args_as::<FilterArgs>(args)?.filter);
+                    todos.sort_by_key(|todo| !todo.starred);
+                    to_js(&self.revised(todos))
+                }
+                "add_todo" => {
+                    self.list.borrow_mut().add(&args_as::<TextArgs>(args)?.text);
+                    to_js(&Vec::<Overflow>::new())
+                }
+                "toggle_todo" => {
+                    let id = args_as::<IdArgs>(args)?.id;
+                    let mut list = self.list.borrow_mut();
+                    let item = list.get_mut(id).ok_or("Todo not found")?;
+                    if item.finished() {
+                        item.uncomplete();
+                    } else {
+                        item.complete_on(self.today);
+                    }
+                    drop(list);
+                    Ok(JsValue::NULL)
+                }
+                "edit_todo" => {
+                    let EditArgs { id, text } = args_as(args)?;
+                    let mut list = self.list.borrow_mut();
+                    list.get_mut(id).ok_or("Todo not found")?.set_raw(&text);
+                    drop(list);
+                    to_js(&Vec::<Overflow>::new())
+                }
+                "add_todo_tag" => {
+                    let TagArgs { id, tag } = args_as(args)?;
+                    self.list
+                        .borrow_mut()
+                        .get_mut(id)
+                        .ok_or("Todo not found")?
+                        .add_tag(&tag);
+                    to_js(&Vec::<Overflow>::new())
+                }
+                "remove_todo_tag" => {
+                    let TagArgs { id, tag } = args_as(args)?;
+                    self.list
+                        .borrow_mut()
+                        .get_mut(id)
+                        .ok_or("Todo not found")?
+                        .remove_tag(&tag);
+                    Ok(JsValue::NULL)
+                }
+                "set_starred" => {
+                    let StarArgs { id, starred } = args_as(args)?;
+                    let mut list = self.list.borrow_mut();
+                    list.get_mut(id)
+                        .ok_or("Todo not found")?
+                        .set_starred(starred);
+                    drop(list);
+                    Ok(JsValue::NULL)
+                }
+                "set_flag" => {
+                    let FlagArgs { id, flag } = args_as(args)?;
+                    let mut list = self.list.borrow_mut();
+                    list.get_mut(id).ok_or("Todo not found")?.set_flag(flag);
+                    drop(list);
+                    Ok(JsValue::NULL)
+                }
+                "delete_todo" => {
+                    let id = args_as::<IdArgs>(args)?.id;
+                    self.list.borrow_mut().remove(id).ok_or("Todo not found")?;
+                    Ok(JsValue::NULL)
+                }
+                "reorder_todo" => {
+                    let ReorderArgs { ids, id } = args_as(args)?;
+                    order::place(&mut self.list.borrow_mut(), &ids, id);
+                    Ok(JsValue::NULL)
+                }
+                "restore_todo" => {
+                    self.list.borrow_mut().add(&args_as::<LineArgs>(args)?.line);
+                    Ok(JsValue::NULL)
+                }
+                "get_counts" => to_js(&stats::counts(self.list.borrow().items(), self.today)),
+                "get_streaks" => to_js(&stats::Streaks::default()),
+                "get_inbox" => to_js(&Vec::<Todo>::new()),
+                "get_due_summary" => {
+                    to_js(&stats::due_summary(self.list.borrow().items(), self.today))
+                }
+                "get_settings" => to_js(&Settings::default()),
+                "get_custom_css" => to_js(&""),
+                "get_draft" => to_js(&types::Draft::default()),
+                "get_plugins" => to_js(&Vec::<()>::new()),
+                "checklist_progress" => to_js(&Vec::<()>::new()),
+                _ => Ok(JsValue::NULL),
+            };
+            self.emit_changes(&before);
+            result
+        }
+    }
+
+    impl Backend for MockBackend {
+        fn invoke<'a>(&'a self, cmd: &'a str, args: JsValue) -> LocalBoxFuture<'a, JsValue> {
+            let result = self
+                .handle(cmd, args)
+                .unwrap_or_else(|e| JsValue::from_str(&e));
+            Box::pin(async move { result })
+        }
+
+        fn listen<'a>(
+            &'a self,
+            event: &'a str,
+            handler: &'a Closure<dyn FnMut(JsValue)>,
+        ) -> LocalBoxFuture<'a, ()> {
+            // The app forgets its closures, so the function stays valid
+            let handler = handler.as_ref().unchecked_ref::<js_sys::Function>().clone();
+            self.listeners
+                .borrow_mut()
+                .push((event.to_string(), handler));
+            Box::pin(async {})
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::backup::Backup;
+use todotxt::diff::DiffLine;
+use types::{BackupArgs, RestoreLinesArgs};
+use wasm_bindgen::JsValue;
+
+use crate::backend::invoke;
+use crate::diff_view::DiffView;
+
+/// The backups of todo.txt, see `todotxt::backup`, each compared with the
+/// file as it is. Either the whole backup is restored, or lines only it has
+/// are picked and added back.
+#[component]
+pub fn BackupDialog(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (backups, set_backups) = signal(Vec::<Backup>::new());
+    let (selected, set_selected) = signal(Option::<String>::None);
+    let (diff, set_diff) = signal(Vec::<DiffLine>::new());
+    let picked = RwSignal::new(HashSet::<usize>::new());
+
+    Effect::new(move |_| {
+        if !open.get() {
+            return;
+        }
+        spawn_local(async move {
+            let result = invoke("list_backups", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<Backup>>(result) {
+                Ok(found) => {
+                    set_selected.set(found.first().map(|backup| backup.name.clone()));
+                    set_backups.set(found);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to list backups: {e}"))),
+            }
+        });
+    });
+
+    let load_diff = move |name: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&BackupArgs { name: &name }).unwrap();
+            let result = invoke("diff_backup", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<DiffLine>>(result) {
+                Ok(lines) => set_diff.set(lines),
+                Err(e) => set_error.set(Some(format!("Failed to compare backup: {e}"))),
+            }
+        });
+    };
+    Effect::new(move |_| {
+        picked.set(HashSet::new());
+        match selected.get() {
+            Some(name) => load_diff(name),
+            None => set_diff.set(Vec::new()),
+        }
+    });
+
+    let close = move || set_open.set(false);
+    // Restoring changes the file, so the comparison is made again
+    let refresh = move || {
+        picked.set(HashSet::new());
+        if let Some(name) = selected.get_untracked() {
+            load_diff(name);
+        }
+    };
+
+    let restore_all = move |_| {
+        let Some(name) = selected.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&BackupArgs { name: &name }).unwrap();
+            let result = invoke("restore_backup", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    close();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to restore backup: {e}"))),
+            }
+        });
+    };
+
+    let restore_picked = move |_| {
+        let lines: Vec<String> = diff.with_untracked(|diff| {
+            picked.with_untracked(|p| {
+                diff.iter()
+                    .enumerate()
+                    .filter(|(index, _)| p.contains(index))
+                    .filter_map(|(_, line)| match line {
+                        DiffLine::Added(text) => Some(text.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+        });
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RestoreLinesArgs { lines: &lines }).unwrap();
+            let result = invoke("restore_lines", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    refresh();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to restore lines: {e}"))),
+            }
+        });
+    };
+
+    let unchanged =
+        move || diff.with(|lines| lines.iter().all(|line| matches!(line, DiffLine::Same(_))));
+
+    view! {
+        <dialog class="modal" class:modal-open=move || open.get() aria-modal="true" aria-labelledby="backups-title">
+            <div class="modal-box max-w-4xl" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="backups-title" class="text-lg font-bold">"Backups"</h3>
+                {move || backups.with(Vec::is_empty).then(|| view! {
+                    <p class="mt-4 opacity-60">"No backups yet. One is taken before todo.txt is written, at most once an hour."</p>
+                })}
+                <div class="flex gap-4 mt-4">
+                    <ul class="menu w-56 shrink-0 max-h-80 overflow-y-auto flex-nowrap" aria-label="Backups">
+                        {move || backups.get().into_iter().map(|backup| {
+                            let name = backup.name.clone();
+                            let is_selected = {
+                                let name = name.clone();
+                                move || selected.with(|s| s.as_deref() == Some(name.as_str()))
+                            };
+                            view! {
+                                <li>
+                                    <button
+                                        type="button"
+                                        class=("menu-active", is_selected.clone())
+                                        aria-current=move || is_selected().then_some("true")
+                                        on:click=move |_| set_selected.set(Some(name.clone()))
+                                    >
+                                        <span>{backup.taken.format("%Y-%m-%d %H:%M").to_string()}</span>
+                                        <span class="opacity-60">{format!("{} lines", backup.lines)}</span>
+                                    </button>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                    <div class="flex-1 min-w-0">
+                        {move || (selected.with(Option::is_some) && unchanged()).then(|| view! {
+                            <p class="opacity-60">"Same as todo.txt."</p>
+                        })}
+                        <DiffView
+                            lines=diff.into()
+                            added_label="Only in the backup"
+                            removed_label="Only in todo.txt"
+                            picked=picked
+                        />
+                    </div>
+                </div>
+                <div class="modal-action">
+                    <button type="button" class="btn" on:click=move |_| close()>"Close"</button>
+                    <button type="button" class="btn" disabled=move || picked.with(HashSet::is_empty) on:click=restore_picked>
+                        {move || format!("Add {} picked lines", picked.with(HashSet::len))}
+                    </button>
+                    <button type="button" class="btn btn-warning" disabled=move || selected.with(Option::is_none) || unchanged() on:click=restore_all>
+                        "Restore whole file"
+                    </button>
+                </div>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,74 @@
+use leptos::ev::SubmitEvent;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::rules::Overflow;
+use types::{RefileTodoArgs, Todo};
+
+use crate::app::overflow_warning;
+use crate::backend::invoke;
+
+/// The todos captured to inbox.txt, see `todotxt::inbox`, each with a
+/// field for the projects, contexts and tags to refile it under. Enter in
+/// the field moves it into the list.
+#[component]
+pub fn Captured(
+    inbox: ReadSignal<Vec<Todo>>,
+    read_only: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+    set_wip_warning: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let refile = move |id: usize, tags: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RefileTodoArgs { id, tags: &tags }).unwrap();
+            let result = invoke("refile_todo", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<Overflow>>(result) {
+                Ok(overflows) => {
+                    set_error.set(None);
+                    set_wip_warning.set(overflow_warning(&overflows));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to refile todo: {e}"))),
+            }
+        });
+    };
+
+    let row = move |todo: Todo| {
+        let id = todo.id;
+        let (tags, set_tags) = signal(String::new());
+        let on_submit = move |ev: SubmitEvent| {
+            ev.prevent_default();
+            refile(id, tags.get_untracked());
+        };
+        view! {
+            <li class="list-row p-2 block">
+                <form class="flex flex-wrap items-center gap-2" on:submit=on_submit>
+                    <span class="font-mono text-sm flex-1 min-w-48">{todo.raw.clone()}</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-56"
+                        placeholder="+project @context"
+                        aria-label=format!("Tags to refile \"{}\" with", todo.subject)
+                        prop:disabled=move || read_only.get()
+                        prop:value=move || tags.get()
+                        on:input=move |ev| set_tags.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm" prop:disabled=move || read_only.get()>
+                        "Refile"
+                    </button>
+                </form>
+            </li>
+        }
+    };
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body">
+                {move || inbox.with(Vec::is_empty).then(|| view! {
+                    <p class="opacity-60">"Nothing captured. Captures from the add dialog, shared text and "<code>"gui capture"</code>" land here."</p>
+                })}
+                <ul class="list" aria-label="Captured todos">
+                    {move || inbox.get().into_iter().map(row).collect_view()}
+                </ul>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,90 @@
+//! Feedback for checking off a task: a checkmark drawn over the app with a
+//! "+1 today", and a chime from the backend.
+
+use std::cell::RefCell;
+
+use js_sys::{Array, Function, Object, Reflect, Uint8Array};
+use leptos::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::backend::invoke;
+
+/// How long the checkmark stays up.
+pub const DURATION_MS: u64 = 900;
+
+thread_local! {
+    /// Object URL of the chime, fetched on first use
+    static CHIME: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn construct(class: &str, args: &Array) -> Option<JsValue> {
+    let window = web_sys::window()?;
+    let class = Reflect::get(&window, &class.into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    Reflect::construct(&class, args).ok()
+}
+
+/// An object URL for the WAV file in `bytes`.
+fn object_url(bytes: &[u8]) -> Option<String> {
+    let options = Object::new();
+    Reflect::set(&options, &"type".into(), &"audio/wav".into()).ok()?;
+    let blob = construct(
+        "Blob",
+        &Array::of2(&Array::of1(&Uint8Array::from(bytes)), &options),
+    )?;
+    let window = web_sys::window()?;
+    let url = Reflect::get(&window, &"URL".into()).ok()?;
+    let create = Reflect::get(&url, &"createObjectURL".into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    create.call1(&url, &blob).ok()?.as_string()
+}
+
+/// Plays the chime, fetching it from the backend the first time.
+pub async fn play_chime() {
+    let url = match CHIME.with_borrow(Clone::clone) {
+        Some(url) => url,
+        None => {
+            let result = invoke("get_completion_sound", JsValue::NULL).await;
+            let Some(url) = serde_wasm_bindgen::from_value::<Vec<u8>>(result)
+                .ok()
+                .and_then(|bytes| object_url(&bytes))
+            else {
+                return;
+            };
+            CHIME.set(Some(url.clone()));
+            url
+        }
+    };
+    let Some(audio) = construct("Audio", &Array::of1(&url.into())) else {
+        return;
+    };
+    if let Ok(play) = Reflect::get(&audio, &"play".into()).and_then(|f| f.dyn_into::<Function>()) {
+        // Autoplay rules may refuse; the checkmark is feedback enough then
+        let _ = play.call0(&audio);
+    }
+}
+
+/// The checkmark, shown while `active`.
+#[component]
+pub fn Celebration(active: ReadSignal<bool>) -> impl IntoView {
+    view! {
+        <div
+            class="fixed inset-0 z-50 flex flex-col items-center justify-center pointer-events-none"
+            class=("hidden", move || !active.get())
+            aria-hidden="true"
+        >
+            {move || active.get().then(|| view! {
+                <svg class="celebrate-check w-32 h-32 text-success drop-shadow-lg" viewBox="0 0 24 24" fill="none">
+                    <circle cx="12" cy="12" r="11" class="fill-base-100 opacity-90" />
+                    <path d="M6.5 12.5l3.5 3.5 7.5-8" stroke="currentColor" stroke-width="2.5" stroke-linecap="round" stroke-linejoin="round" />
+                </svg>
+                <span class="celebrate-plus badge badge-success badge-lg mt-2">"+1 today"</span>
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,49 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use types::MigrationReport;
+use wasm_bindgen::JsValue;
+
+use crate::backend::invoke;
+
+/// What the backend upgraded at this start, if anything, shown once.
+#[component]
+pub fn ChangelogDialog() -> impl IntoView {
+    let (report, set_report) = signal(Option::<MigrationReport>::None);
+
+    spawn_local(async move {
+        let result = invoke("take_migration_report", JsValue::NULL).await;
+        if let Ok(taken) = serde_wasm_bindgen::from_value::<Option<MigrationReport>>(result) {
+            set_report.set(taken);
+        }
+    });
+
+    let close = move || set_report.set(None);
+
+    view! {
+        <dialog class="modal" class:modal-open=move || report.with(Option::is_some) aria-modal="true" aria-labelledby="changelog-title">
+            <div class="modal-box" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="changelog-title" class="text-lg font-bold">"Upgraded to the new version"</h3>
+                {move || report.get().map(|report| view! {
+                    {report.changes.is_empty().then(|| view! {
+                        <p class="mt-4 opacity-60">"Nothing in your files needed changing."</p>
+                    })}
+                    <ul class="list-disc pl-6 mt-4" aria-label="Changes">
+                        {report.changes.into_iter().map(|change| view! { <li>{change}</li> }).collect_view()}
+                    </ul>
+                    {(!report.backups.is_empty()).then(|| view! {
+                        <p class="text-sm opacity-60 mt-4">"The files as they were before are kept in"</p>
+                        <ul class="text-sm font-mono mt-1" aria-label="Backups">
+                            {report.backups.into_iter().map(|path| view! { <li class="select-all">{path}</li> }).collect_view()}
+                        </ul>
+                    })}
+                })}
+                <div class="modal-action">
+                    <button type="button" class="btn btn-primary" on:click=move |_| close()>"OK"</button>
+                </div>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
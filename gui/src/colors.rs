@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use leptos::prelude::{document, window};
+use todotxt::filter::Flag;
+
+/// Colors handed out to tags that have none picked, defined per palette in
+/// `input.css` so a color-blind safe or high contrast palette can swap them.
+const PALETTE: [&str; 10] = [
+    "var(--tag-0)",
+    "var(--tag-1)",
+    "var(--tag-2)",
+    "var(--tag-3)",
+    "var(--tag-4)",
+    "var(--tag-5)",
+    "var(--tag-6)",
+    "var(--tag-7)",
+    "var(--tag-8)",
+    "var(--tag-9)",
+];
+
+/// Stable palette color for `key`. Uses FNV-1a rather than the std hasher so
+/// the choice doesn't change between builds.
+pub fn auto_color(key: &str) -> &'static str {
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+/// Color for a tag key such as `+work` or `@phone`, preferring the user's pick.
+pub fn tag_color(key: &str, colors: &BTreeMap<String, String>) -> String {
+    colors
+        .get(key)
+        .cloned()
+        .unwrap_or_else(|| auto_color(key).to_string())
+}
+
+/// `color` as the hex value it stands for in the current palette, for a
+/// color picker, which can't show a `var(...)`.
+pub fn resolve(color: &str) -> String {
+    let Some(name) = color
+        .strip_prefix("var(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    else {
+        return color.to_string();
+    };
+    document()
+        .document_element()
+        .and_then(|root| window().get_computed_style(&root).ok().flatten())
+        .and_then(|style| style.get_property_value(name).ok())
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// The palette's color for `flag`, defined in `input.css`.
+pub fn flag_color(flag: Flag) -> String {
+    format!("var(--flag-{})", flag.name())
+}
+
+/// Inline style for a tag badge in `color`.
+pub fn badge_style(color: &str) -> String {
+    format!("color: {color}; border-color: {color}")
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+
+use crate::backend::invoke;
+
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Injects the user's custom CSS file. The file is loaded when its path
+/// changes and reloaded every second while `watch` is true, so edits show up
+/// while the Settings page is open.
+#[component]
+pub fn CustomCss(
+    path: Signal<Option<String>>,
+    watch: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (css, set_css) = signal(String::new());
+
+    let load = move || {
+        spawn_local(async move {
+            let result = invoke("get_custom_css", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<String>(result) {
+                Ok(loaded) => {
+                    if css.with_untracked(|current| *current != loaded) {
+                        set_css.set(loaded);
+                    }
+                }
+                Err(e) => set_error.set(Some(format!("Failed to load custom CSS: {e}"))),
+            }
+        });
+    };
+
+    Effect::new(move |_| {
+        path.track();
+        load();
+        if watch.get() {
+            if let Ok(handle) = set_interval_with_handle(load, RELOAD_INTERVAL) {
+                on_cleanup(move || handle.clear());
+            }
+        }
+    });
+
+    view! { <style id="custom-css">{move || css.get()}</style> }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use todotxt::diff::DiffLine;
+
+/// Lines of a diff, those only in the new text marked `+` and those only in
+/// the old one `-`. With `picked` the added lines can be ticked, e.g. to
+/// take them over; it holds their indices in `lines`.
+#[component]
+pub fn DiffView(
+    lines: Signal<Vec<DiffLine>>,
+    /// What the added and removed lines are, e.g. "Only in the backup"
+    added_label: &'static str,
+    removed_label: &'static str,
+    #[prop(optional)] picked: Option<RwSignal<HashSet<usize>>>,
+) -> impl IntoView {
+    view! {
+        <ul class="font-mono text-xs max-h-80 overflow-y-auto" aria-label="Differences">
+            {move || lines.get().into_iter().enumerate().map(|(index, line)| match line {
+                DiffLine::Same(text) => view! {
+                    <li class="px-2 py-0.5 opacity-60">{format!("  {text}")}</li>
+                }.into_any(),
+                DiffLine::Removed(text) => view! {
+                    <li class="px-2 py-0.5 bg-error/15" title=removed_label>
+                        <span class="sr-only">{format!("{removed_label}: ")}</span>
+                        {format!("- {text}")}
+                    </li>
+                }.into_any(),
+                DiffLine::Added(text) => view! {
+                    <li class="px-2 py-0.5 bg-success/15 flex items-center gap-2" title=added_label>
+                        {picked.map(|picked| view! {
+                            <input
+                                type="checkbox"
+                                class="checkbox checkbox-xs"
+                                aria-label=format!("Pick \"{text}\"")
+                                prop:checked=move || picked.with(|p| p.contains(&index))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    picked.update(|p| {
+                                        if checked {
+                                            p.insert(index);
+                                        } else {
+                                            p.remove(&index);
+                                        }
+                                    });
+                                }
+                            />
+                        })}
+                        <span class="sr-only">{format!("{added_label}: ")}</span>
+                        {format!("+ {text}")}
+                    </li>
+                }.into_any(),
+            }).collect_view()}
+        </ul>
+    }
+}
@@ -0,0 +1,112 @@
+use leptos::prelude::*;
+use todotxt::estimate::{self, Workload};
+use todotxt::filter::{Due, Filter, Status};
+use todotxt::stats::DueSummary;
+use types::Todo;
+
+use crate::settings::Settings;
+
+/// How many pending todos are overdue, due today and due this week, each
+/// showing them when picked.
+#[component]
+pub fn DueNav(
+    summary: ReadSignal<DueSummary>,
+    filter: ReadSignal<Filter>,
+    show_list: Callback<Filter>,
+) -> impl IntoView {
+    view! {
+        <nav class="mb-2 text-sm" aria-label="Due dates">
+            {move || {
+                let summary = summary.get();
+                let segments = [
+                    (summary.overdue, "overdue", Due::Overdue),
+                    (summary.today, "today", Due::TodayOnly),
+                    (summary.this_week, "this week", Due::ThisWeek),
+                ];
+                segments.into_iter().filter(|(count, ..)| *count > 0).enumerate().map(|(i, (count, label, due))| {
+                    let preset = Filter { status: Status::Pending, due: Some(due), ..Filter::default() };
+                    view! {
+                        {(i > 0).then_some(" · ")}
+                        <button
+                            type="button"
+                            class="link link-hover"
+                            class=("text-error", due == Due::Overdue)
+                            class=("font-bold", move || filter.with(|f| f.due == Some(due)))
+                            on:click=move |_| show_list.run(preset.clone())
+                        >
+                            {format!("{count} {label}")}
+                        </button>
+                    }
+                }).collect_view()
+            }}
+        </nav>
+    }
+}
+
+/// The estimated work per due date among the pending `todos` shown, in the
+/// Today and Upcoming views, against the workday in the settings.
+#[component]
+pub fn WorkloadSummary(
+    todos: ReadSignal<Vec<Todo>>,
+    filter: ReadSignal<Filter>,
+    settings: ReadSignal<Settings>,
+) -> impl IntoView {
+    // Estimated work per due date among the pending todos
+    let workload = Memo::new(move |_| {
+        let due = filter.with(|f| f.due)?;
+        let days = todos.with(|items| {
+            estimate::per_day(
+                items
+                    .iter()
+                    .filter(|t| !t.finished)
+                    .filter_map(|t| Some((t.due_date.clone()?, t.estimate))),
+            )
+        });
+        Some((due, days))
+    });
+    let workload_text = |label: &str, workload: Workload| match workload.minutes {
+        0 => format!("{label}: {} tasks", workload.tasks),
+        minutes => format!(
+            "{label}: {} tasks, ~{}",
+            workload.tasks,
+            estimate::format(minutes)
+        ),
+    };
+
+    view! {
+        <div class="mb-4 text-sm" role="status">
+            {move || {
+                let (due, days) = workload.get()?;
+                let capacity = settings.with(|s| s.workday_minutes);
+                let overbooked = move |workload: &Workload| capacity.is_some_and(|c| workload.overbooked(c));
+                let view = match due {
+                    Due::Today | Due::TodayOnly => {
+                        // Overdue days count towards today
+                        let today: Workload = days.into_values().sum();
+                        view! {
+                            <p>{workload_text("Today", today)}</p>
+                            {overbooked(&today).then(|| view! {
+                                <p class="text-warning">
+                                    {format!("More than fits into a day of {}", estimate::format(capacity.unwrap_or(0)))}
+                                </p>
+                            })}
+                        }
+                        .into_any()
+                    }
+                    Due::Upcoming | Due::Overdue | Due::ThisWeek => view! {
+                        <ul aria-label="Workload per day">
+                            {days.into_iter().map(|(day, workload)| view! {
+                                <li class=("text-warning", overbooked(&workload))>
+                                    {workload_text(&day, workload)}
+                                    {overbooked(&workload).then_some(" (overbooked)")}
+                                </li>
+                            }).collect_view()}
+                        </ul>
+                    }
+                    .into_any(),
+                };
+                Some(view)
+            }}
+        </div>
+    }
+}
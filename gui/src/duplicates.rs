@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use types::{MergeTodosArgs, Todo};
+use wasm_bindgen::JsValue;
+
+use crate::backend::invoke;
+
+/// Groups of pending todos that read alike, each of which can be merged into
+/// one todo, the first ticked one, keeping the others' tags.
+#[component]
+pub fn Duplicates(
+    todos: Signal<Vec<Todo>>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (clusters, set_clusters) = signal(Vec::<Vec<Todo>>::new());
+    // Todos left out of their group's merge
+    let (unticked, set_unticked) = signal(HashSet::<usize>::new());
+
+    Effect::new(move |_| {
+        todos.track();
+        spawn_local(async move {
+            let result = invoke("find_duplicates", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<Vec<Todo>>>(result) {
+                Ok(found) => {
+                    set_clusters.set(found);
+                    set_unticked.set(HashSet::new());
+                }
+                Err(e) => set_error.set(Some(format!("Failed to find duplicates: {e}"))),
+            }
+        });
+    });
+
+    let merge = move |ids: Vec<usize>| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&MergeTodosArgs { ids: &ids }).unwrap();
+            let result = invoke("merge_todos", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => set_error.set(None),
+                Err(e) => set_error.set(Some(format!("Failed to merge todos: {e}"))),
+            }
+        });
+    };
+
+    view! {
+        <section class="mb-6" aria-label="Possible duplicates">
+            <h2 class="text-lg font-semibold mb-2">"Possible duplicates"</h2>
+            {move || clusters.with(Vec::is_empty).then(|| view! {
+                <p class="text-sm opacity-60">"No todos read alike."</p>
+            })}
+            {move || clusters.get().into_iter().map(|cluster| {
+                let ids: Vec<usize> = cluster.iter().map(|todo| todo.id).collect();
+                let ticked = move || {
+                    let unticked = unticked.get();
+                    ids.iter().copied().filter(|id| !unticked.contains(id)).collect::<Vec<usize>>()
+                };
+                let ticked_for_merge = ticked.clone();
+                view! {
+                    <div class="mb-3">
+                        <ul class="list bg-base-100 rounded-box">
+                            {cluster.into_iter().map(|todo| {
+                                let id = todo.id;
+                                view! {
+                                    <li class="list-row font-mono text-sm">
+                                        <input
+                                            type="checkbox"
+                                            class="checkbox checkbox-sm"
+                                            aria-label=format!("Merge \"{}\"", todo.subject)
+                                            prop:checked=move || !unticked.with(|u| u.contains(&id))
+                                            on:change=move |ev| {
+                                                let checked = event_target_checked(&ev);
+                                                set_unticked.update(|u| {
+                                                    if checked {
+                                                        u.remove(&id);
+                                                    } else {
+                                                        u.insert(id);
+                                                    }
+                                                });
+                                            }
+                                        />
+                                        <span>{todo.raw}</span>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                        <button
+                            type="button"
+                            class="btn btn-sm mt-2"
+                            prop:disabled=move || ticked().len() < 2
+                            on:click=move |_| merge(ticked_for_merge())
+                        >
+                            "Merge selected"
+                        </button>
+                    </div>
+                }
+            }).collect_view()}
+        </section>
+    }
+}
@@ -0,0 +1,105 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::diff::{DiffLine, TaskChange};
+use types::FILE_CHANGED_EVENT;
+use wasm_bindgen::prelude::*;
+
+use crate::app::TauriEvent;
+use crate::backend::listen;
+use crate::diff_view::DiffView;
+
+/// How many tasks `changes` added, removed and modified, e.g. "2 added, 1 modified".
+fn summary(changes: &[TaskChange]) -> String {
+    let count =
+        |kind: fn(&TaskChange) -> bool| changes.iter().filter(|change| kind(change)).count();
+    [
+        (count(|c| matches!(c, TaskChange::Added { .. })), "added"),
+        (
+            count(|c| matches!(c, TaskChange::Removed { .. })),
+            "removed",
+        ),
+        (
+            count(|c| matches!(c, TaskChange::Modified { .. })),
+            "modified",
+        ),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, what)| format!("{n} {what}"))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// `changes` as diff lines, each modified task as its old line and its new one.
+fn diff_lines(changes: &[TaskChange]) -> Vec<DiffLine> {
+    changes
+        .iter()
+        .flat_map(|change| match change {
+            TaskChange::Added { line } => vec![DiffLine::Added(line.clone())],
+            TaskChange::Removed { line } => vec![DiffLine::Removed(line.clone())],
+            TaskChange::Modified { old, new } => {
+                vec![DiffLine::Removed(old.clone()), DiffLine::Added(new.clone())]
+            }
+        })
+        .collect()
+}
+
+/// A toast when another program, e.g. a sync, changed todo.txt, which the
+/// list already shows. "View changes" lists what it did. Changes that come
+/// before the toast is dismissed add up.
+#[component]
+pub fn FileChanges() -> impl IntoView {
+    let (changes, set_changes) = signal(Vec::<TaskChange>::new());
+    let (open, set_open) = signal(false);
+
+    spawn_local(async move {
+        let on_changed = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Vec<TaskChange>>>(event)
+            {
+                if !event.payload.is_empty() {
+                    set_changes.update(|changes| changes.extend(event.payload));
+                }
+            }
+        });
+        listen(FILE_CHANGED_EVENT, &on_changed).await;
+        // The listener lives as long as the app
+        on_changed.forget();
+    });
+
+    let dismiss = move || {
+        set_open.set(false);
+        set_changes.set(Vec::new());
+    };
+    let lines = Signal::derive(move || changes.with(|changes| diff_lines(changes)));
+
+    view! {
+        <div class="toast toast-start z-40" role="status" aria-live="polite">
+            {move || (!open.get() && changes.with(|c| !c.is_empty())).then(|| view! {
+                <div class="alert alert-info">
+                    <span>{move || format!("todo.txt changed: {}", changes.with(|c| summary(c)))}</span>
+                    <button type="button" class="btn btn-primary btn-xs" aria-haspopup="dialog" on:click=move |_| set_open.set(true)>
+                        "View changes"
+                    </button>
+                    <button type="button" class="btn btn-ghost btn-xs" aria-label="Dismiss" on:click=move |_| dismiss()>
+                        "×"
+                    </button>
+                </div>
+            })}
+        </div>
+        <dialog class="modal" class:modal-open=move || open.get() aria-modal="true" aria-labelledby="file-changes-title">
+            <div class="modal-box max-w-3xl" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { dismiss() }>
+                <h3 id="file-changes-title" class="text-lg font-bold">"Changed outside the app"</h3>
+                <p class="mt-2 text-sm opacity-70">{move || changes.with(|c| summary(c))}</p>
+                <div class="mt-4">
+                    <DiffView lines=lines added_label="Now in todo.txt" removed_label="No longer in todo.txt" />
+                </div>
+                <div class="modal-action">
+                    <button type="button" class="btn" on:click=move |_| dismiss()>"Close"</button>
+                </div>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| dismiss() />
+            </form>
+        </dialog>
+    }
+}
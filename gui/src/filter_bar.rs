@@ -0,0 +1,130 @@
+use leptos::prelude::*;
+use todotxt::filter::{Due, Filter, Status};
+
+use crate::tag_tree::TagKind;
+
+/// Priority bands above the list, any number of them at once.
+#[component]
+pub fn PriorityBands(filter: ReadSignal<Filter>, set_filter: WriteSignal<Filter>) -> impl IntoView {
+    view! {
+        <div class="join mb-4" role="group" aria-label="Priority">
+            {[(Some('A'), "A"), (Some('B'), "B"), (Some('C'), "C"), (None, "No priority")]
+                .into_iter()
+                .map(|(priority, label)| {
+                    let selected = move || filter.with(|f| f.priorities.contains(&priority));
+                    view! {
+                        <button
+                            type="button"
+                            class="btn btn-xs join-item"
+                            class=("btn-primary", selected)
+                            aria-pressed=move || selected().to_string()
+                            on:click=move |_| set_filter.update(|f| {
+                                if let Some(pos) = f.priorities.iter().position(|p| *p == priority) {
+                                    f.priorities.remove(pos);
+                                } else {
+                                    f.priorities.push(priority);
+                                }
+                            })
+                        >
+                            {label}
+                        </button>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+/// The criteria the list is filtered by, each removable on its own.
+#[component]
+pub fn ActiveFilters(filter: ReadSignal<Filter>, set_filter: WriteSignal<Filter>) -> impl IntoView {
+    view! {
+        <div
+            class="flex flex-wrap items-center gap-2 mb-4"
+            class=("hidden", move || filter.with(Filter::is_empty))
+            role="group"
+            aria-label="Active filters"
+        >
+            {move || {
+                let current = filter.get();
+                let mut chips: Vec<(String, Callback<()>)> = [TagKind::Project, TagKind::Context]
+                    .into_iter()
+                    .filter_map(|kind| {
+                        kind.criterion(&current).map(|path| {
+                            let remove = Callback::new(move |_: ()| {
+                                set_filter.update(|f| *kind.criterion_mut(f) = None)
+                            });
+                            (format!("{}{}", kind.sigil(), path), remove)
+                        })
+                    })
+                    .collect();
+                let status_label = match current.status {
+                    Status::All => None,
+                    Status::Pending => Some("Pending"),
+                    Status::Done => Some("Done"),
+                };
+                if let Some(assignee) = &current.assignee {
+                    chips.push((format!("assignee:{assignee}"), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.assignee = None)
+                    })));
+                }
+                if let Some(due) = current.due {
+                    let label = match due {
+                        Due::Today => "Due today",
+                        Due::Upcoming => "Due later",
+                        Due::Overdue => "Overdue",
+                        Due::TodayOnly => "Due today only",
+                        Due::ThisWeek => "Due this week",
+                    };
+                    chips.push((label.to_string(), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.due = None)
+                    })));
+                }
+                if let Some(id) = current.id {
+                    chips.push((format!("#{id}"), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.id = None)
+                    })));
+                }
+                if current.starred {
+                    chips.push(("starred".to_string(), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.starred = false)
+                    })));
+                }
+                if let Some(energy) = current.energy {
+                    chips.push((format!("energy:{}", energy.name()), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.energy = None)
+                    })));
+                }
+                if let Some(flag) = current.flag {
+                    chips.push((format!("flag:{}", flag.name()), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.flag = None)
+                    })));
+                }
+                if let Some(label) = status_label {
+                    chips.push((label.to_string(), Callback::new(move |_: ()| {
+                        set_filter.update(|f| f.status = Status::All)
+                    })));
+                }
+                chips.into_iter().map(|(label, remove)| {
+                    let remove_label = format!("Remove filter {label}");
+                    view! {
+                        <span class="badge badge-primary badge-outline gap-1">
+                            {label}
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs p-0 min-h-0 h-4 w-4"
+                                aria-label=remove_label
+                                on:click=move |_| remove.run(())
+                            >
+                                "✕"
+                            </button>
+                        </span>
+                    }
+                }).collect_view()
+            }}
+            <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_filter.set(Filter::default())>
+                "Clear all"
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,116 @@
+use leptos::prelude::*;
+use todotxt::filter::{Energy, Filter, Flag, Status};
+
+use crate::colors::flag_color;
+
+/// The criteria of the sidebar besides the tag trees: who the todos are
+/// assigned to, their status, energy and flag, and whether they're starred.
+#[component]
+pub fn FilterPanel(
+    filter: ReadSignal<Filter>,
+    set_filter: WriteSignal<Filter>,
+    /// Everyone who has something assigned
+    assignees: Memo<Vec<String>>,
+) -> impl IntoView {
+    view! {
+        <div class=("hidden", move || assignees.with(Vec::is_empty))>
+            <h2 class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Assignees"</h2>
+            <div class="flex flex-wrap gap-1" role="group" aria-label="Assignees">
+                <For
+                    each=move || assignees.get()
+                    key=|name| name.clone()
+                    children=move |name| {
+                        let name_active = name.clone();
+                        let is_active =
+                            Signal::derive(move || filter.with(|f| f.assignee.as_deref() == Some(name_active.as_str())));
+                        let name_toggle = name.clone();
+                        view! {
+                            <button
+                                type="button"
+                                class="btn btn-xs"
+                                class=("btn-primary", is_active)
+                                aria-pressed=move || is_active.get().to_string()
+                                on:click=move |_| set_filter.update(|f| {
+                                    f.assignee = (f.assignee.as_ref() != Some(&name_toggle)).then(|| name_toggle.clone());
+                                })
+                            >
+                                {name}
+                            </button>
+                        }
+                    }
+                />
+            </div>
+        </div>
+        <h2 class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Status"</h2>
+        <div class="join w-full" role="group" aria-label="Status">
+            {[(Status::All, "All"), (Status::Pending, "Pending"), (Status::Done, "Done")]
+                .into_iter()
+                .map(|(status, label)| view! {
+                    <button
+                        type="button"
+                        class="btn btn-xs join-item flex-1"
+                        class=("btn-primary", move || filter.with(|f| f.status == status))
+                        aria-pressed=move || (filter.with(|f| f.status == status)).to_string()
+                        on:click=move |_| set_filter.update(|f| f.status = status)
+                    >
+                        {label}
+                    </button>
+                })
+                .collect_view()}
+        </div>
+        <h2 class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Energy"</h2>
+        <div class="join w-full" role="group" aria-label="Energy">
+            {[(None, "Any"), (Some(Energy::Low), "Low"), (Some(Energy::Med), "Medium"), (Some(Energy::High), "High")]
+                .into_iter()
+                .map(|(energy, label)| view! {
+                    <button
+                        type="button"
+                        class="btn btn-xs join-item flex-1"
+                        class=("btn-primary", move || filter.with(|f| f.energy == energy))
+                        aria-pressed=move || (filter.with(|f| f.energy == energy)).to_string()
+                        on:click=move |_| set_filter.update(|f| f.energy = energy)
+                    >
+                        {label}
+                    </button>
+                })
+                .collect_view()}
+        </div>
+        <h2 class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Flag"</h2>
+        <div class="flex flex-wrap gap-1" role="group" aria-label="Flag">
+            <button
+                type="button"
+                class="btn btn-xs"
+                class=("btn-primary", move || filter.with(|f| f.flag.is_none()))
+                aria-pressed=move || filter.with(|f| f.flag.is_none()).to_string()
+                on:click=move |_| set_filter.update(|f| f.flag = None)
+            >
+                "Any"
+            </button>
+            {Flag::ALL
+                .into_iter()
+                .map(|flag| view! {
+                    <button
+                        type="button"
+                        class="btn btn-xs btn-square"
+                        class=("btn-active", move || filter.with(|f| f.flag == Some(flag)))
+                        aria-pressed=move || (filter.with(|f| f.flag == Some(flag))).to_string()
+                        aria-label=flag.label()
+                        title=flag.label()
+                        on:click=move |_| set_filter.update(|f| f.flag = Some(flag))
+                    >
+                        <span class="inline-block w-3 h-3 rounded-full" style=format!("background: {}", flag_color(flag))></span>
+                    </button>
+                })
+                .collect_view()}
+        </div>
+        <label class="label cursor-pointer justify-between w-full mt-4">
+            <span class="text-sm font-semibold tracking-wide opacity-60">"Starred only"</span>
+            <input
+                type="checkbox"
+                class="toggle toggle-primary toggle-sm"
+                prop:checked=move || filter.with(|f| f.starred)
+                on:change=move |ev| set_filter.update(|f| f.starred = event_target_checked(&ev))
+            />
+        </label>
+    }
+}
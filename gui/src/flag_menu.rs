@@ -0,0 +1,61 @@
+use leptos::prelude::*;
+use todotxt::filter::Flag;
+
+use crate::colors::flag_color;
+
+/// The menu flagging a todo, opened from its row at a point of the window,
+/// as (id, x, y) in `menu`.
+#[component]
+pub fn FlagMenu(
+    menu: ReadSignal<Option<(usize, i32, i32)>>,
+    set_menu: WriteSignal<Option<(usize, i32, i32)>>,
+    set_flag: Callback<(usize, Option<Flag>)>,
+) -> impl IntoView {
+    view! {
+        {move || menu.get().map(|(id, x, y)| view! {
+            <div
+                class="fixed inset-0 z-40"
+                on:click=move |_| set_menu.set(None)
+                on:contextmenu=move |ev: leptos::ev::MouseEvent| {
+                    ev.prevent_default();
+                    set_menu.set(None);
+                }
+            ></div>
+            <ul
+                class="menu menu-sm bg-base-100 rounded-box shadow fixed z-50 w-36"
+                role="menu"
+                aria-label="Flag"
+                style=format!("left: {x}px; top: {y}px")
+                on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { set_menu.set(None) }
+            >
+                {Flag::ALL.into_iter().map(|flag| view! {
+                    <li role="none">
+                        <button
+                            type="button"
+                            role="menuitem"
+                            on:click=move |_| {
+                                set_menu.set(None);
+                                set_flag.run((id, Some(flag)));
+                            }
+                        >
+                            <span class="inline-block w-3 h-3 rounded-full" style=format!("background: {}", flag_color(flag))></span>
+                            {flag.label()}
+                        </button>
+                    </li>
+                }).collect_view()}
+                <li role="none">
+                    <button
+                        type="button"
+                        role="menuitem"
+                        on:click=move |_| {
+                            set_menu.set(None);
+                            set_flag.run((id, None));
+                        }
+                    >
+                        "No flag"
+                    </button>
+                </li>
+            </ul>
+        })}
+    }
+}
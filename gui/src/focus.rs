@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use types::{FocusSession, StartFocusArgs, FOCUS_EVENT};
+use wasm_bindgen::prelude::*;
+
+use crate::app::TauriEvent;
+use crate::backend::{invoke, listen};
+
+/// Starts a focus session on the todo `id`, ending the one running.
+pub fn start(id: usize, set_error: WriteSignal<Option<String>>) {
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&StartFocusArgs { id }).unwrap();
+        let result = invoke("start_focus", args).await;
+        match serde_wasm_bindgen::from_value::<FocusSession>(result) {
+            Ok(_) => set_error.set(None),
+            Err(e) => set_error.set(Some(format!("Failed to start focus session: {e}"))),
+        }
+    });
+}
+
+/// `ms` as a countdown, e.g. `24:59`.
+fn countdown(ms: f64) -> String {
+    let seconds = (ms / 1000.0).ceil().max(0.0) as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// The running focus session, counting down, with a button to end it early.
+/// The backend ends it when time is up and reports either through
+/// [`FOCUS_EVENT`].
+#[component]
+pub fn FocusBar(set_error: WriteSignal<Option<String>>) -> impl IntoView {
+    let (session, set_session) = signal(Option::<FocusSession>::None);
+    let (now, set_now) = signal(js_sys::Date::now());
+
+    spawn_local(async move {
+        let result = invoke("get_focus", JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<Option<FocusSession>>(result) {
+            Ok(current) => set_session.set(current),
+            Err(e) => set_error.set(Some(format!("Failed to load focus session: {e}"))),
+        }
+        let on_focus = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) =
+                serde_wasm_bindgen::from_value::<TauriEvent<Option<FocusSession>>>(event)
+            {
+                set_session.set(event.payload);
+            }
+        });
+        listen(FOCUS_EVENT, &on_focus).await;
+        // The listener lives as long as the app
+        on_focus.forget();
+    });
+
+    // Tick only while a session runs
+    Effect::new(move |_| {
+        if session.with(Option::is_some) {
+            set_now.set(js_sys::Date::now());
+            if let Ok(handle) = set_interval_with_handle(
+                move || set_now.set(js_sys::Date::now()),
+                Duration::from_secs(1),
+            ) {
+                on_cleanup(move || handle.clear());
+            }
+        }
+    });
+
+    let stop = move |_| {
+        spawn_local(async move {
+            let result = invoke("stop_focus", JsValue::NULL).await;
+            if let Err(e) = serde_wasm_bindgen::from_value::<()>(result) {
+                set_error.set(Some(format!("Failed to end focus session: {e}")));
+            }
+        });
+    };
+
+    move || {
+        session.get().map(|session| {
+            let ends_at = session.ends_at as f64;
+            view! {
+                <div role="timer" aria-label="Focus session" class="alert alert-info mb-4">
+                    <span class="font-mono text-lg">{move || countdown(ends_at - now.get())}</span>
+                    <span class="flex-1">
+                        "Focusing on "<span class="font-semibold">{session.subject.clone()}</span>
+                        {session.do_not_disturb.then_some(" · Do Not Disturb is on")}
+                    </span>
+                    <button type="button" class="btn btn-sm" on:click=stop>"Stop"</button>
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,35 @@
+//! The device's position from the webview's geolocation API, which asks
+//! the user for permission the first time.
+
+use todotxt::geo::Location;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+pub async fn current_location() -> Result<Location, String> {
+    let geolocation = web_sys::window()
+        .ok_or("No window")?
+        .navigator()
+        .geolocation()
+        .map_err(|_| "Location is not available")?;
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        if let Err(e) =
+            geolocation.get_current_position_with_error_callback(&resolve, Some(&reject))
+        {
+            let _ = reject.call1(&wasm_bindgen::JsValue::NULL, &e);
+        }
+    });
+    let position: web_sys::Position = JsFuture::from(promise)
+        .await
+        .map_err(|e| {
+            js_sys::Reflect::get(&e, &"message".into())
+                .ok()
+                .and_then(|message| message.as_string())
+                .unwrap_or_else(|| "Location is not available".to_string())
+        })?
+        .unchecked_into();
+    let coords = position.coords();
+    Ok(Location {
+        latitude: coords.latitude(),
+        longitude: coords.longitude(),
+    })
+}
@@ -0,0 +1,33 @@
+use todotxt::parse::{tokenize, Token};
+
+fn token_class(token: &Token) -> &'static str {
+    match token {
+        Token::CompletionMark => "text-success",
+        Token::Priority(_) => "text-primary font-bold",
+        Token::Date(_) => "text-info",
+        Token::Context(_) => "text-accent",
+        Token::Project(_) => "text-secondary",
+        Token::Tag { .. } => "text-warning",
+        Token::Word(_) => "",
+    }
+}
+
+/// Splits a todo.txt line into `(class, text)` segments for highlighting.
+/// Gaps between tokens are emitted unstyled so the concatenated text matches
+/// the input exactly.
+pub fn highlight_line(line: &str) -> Vec<(&'static str, &str)> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for spanned in tokenize(line) {
+        let span = spanned.span;
+        if span.start > pos {
+            segments.push(("", &line[pos..span.start]));
+        }
+        segments.push((token_class(&spanned.token), &line[span.range()]));
+        pos = span.end;
+    }
+    if pos < line.len() {
+        segments.push(("", &line[pos..]));
+    }
+    segments
+}
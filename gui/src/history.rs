@@ -0,0 +1,296 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::activity::{Action, Entry};
+use todotxt::checklist::{CheckItem, Progress};
+use todotxt::redact;
+use types::{
+    GetActivityArgs, GetChecklistArgs, GetLinksArgs, SetChecklistArgs, TaskLinks, Todo,
+    UndoActivityArgs,
+};
+
+use crate::backend::invoke;
+use crate::raw_editor::{open_in_editor, reveal_in_editor};
+
+pub(crate) fn action_label(action: Action) -> &'static str {
+    match action {
+        Action::Add => "Added",
+        Action::Edit => "Edited",
+        Action::Complete => "Completed",
+        Action::Uncomplete => "Reopened",
+        Action::Delete => "Deleted",
+    }
+}
+
+/// Logged changes of one todo, newest first, each of which can be undone,
+/// the todos it links to or is linked from, and its checklist.
+#[component]
+pub fn HistoryDialog(
+    task: ReadSignal<Option<Todo>>,
+    set_task: WriteSignal<Option<Todo>>,
+    /// Show lorem ipsum instead of the changed lines
+    demo_mode: Signal<bool>,
+    /// Called with the id of a linked todo to show
+    on_follow: Callback<usize>,
+    /// Called with the todo's id and the checklist's progress after it changed
+    on_checklist: Callback<(usize, Option<Progress>)>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (entries, set_entries) = signal(Vec::<Entry>::new());
+    let (links, set_links) = signal(TaskLinks::default());
+    let (checklist, set_checklist) = signal(Vec::<CheckItem>::new());
+    let (new_check, set_new_check) = signal(String::new());
+
+    Effect::new(move |_| {
+        let Some(id) = task.with(|t| t.as_ref().map(|t| t.id)) else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&GetActivityArgs { id }).unwrap();
+            let result = invoke("get_activity", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<Entry>>(result) {
+                Ok(found) => set_entries.set(found),
+                Err(e) => set_error.set(Some(format!("Failed to load history: {e}"))),
+            }
+            let args = serde_wasm_bindgen::to_value(&GetLinksArgs { id }).unwrap();
+            let result = invoke("get_links", args).await;
+            match serde_wasm_bindgen::from_value::<TaskLinks>(result) {
+                Ok(found) => set_links.set(found),
+                Err(e) => set_error.set(Some(format!("Failed to load links: {e}"))),
+            }
+            let args = serde_wasm_bindgen::to_value(&GetChecklistArgs { id }).unwrap();
+            let result = invoke("get_checklist", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<CheckItem>>(result) {
+                Ok(found) => set_checklist.set(found),
+                Err(e) => set_error.set(Some(format!("Failed to load checklist: {e}"))),
+            }
+        });
+    });
+
+    let close = move || {
+        set_task.set(None);
+        set_entries.set(Vec::new());
+        set_links.set(TaskLinks::default());
+        set_checklist.set(Vec::new());
+        set_new_check.set(String::new());
+    };
+
+    // Shows `items` right away and stores them in the background
+    let save_checklist = move |items: Vec<CheckItem>| {
+        let Some(id) = task.with_untracked(|t| t.as_ref().map(|t| t.id)) else {
+            return;
+        };
+        set_checklist.set(items.clone());
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&SetChecklistArgs { id, items: &items }).unwrap();
+            let result = invoke("set_checklist", args).await;
+            match serde_wasm_bindgen::from_value::<Option<Progress>>(result) {
+                Ok(progress) => {
+                    set_error.set(None);
+                    on_checklist.run((id, progress));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to save checklist: {e}"))),
+            }
+        });
+    };
+    let progress = move || {
+        checklist.with(|items| {
+            let done = items.iter().filter(|item| item.done).count();
+            (!items.is_empty()).then(|| format!("{done}/{}", items.len()))
+        })
+    };
+
+    let link_list = move |label: &'static str, todos: fn(&TaskLinks) -> &Vec<Todo>| {
+        view! {
+            <div class=("hidden", move || links.with(|l| todos(l).is_empty()))>
+                <h4 class="font-semibold mt-4">{label}</h4>
+                <ul aria-label=label>
+                    {move || links.with(|l| todos(l).clone()).into_iter().map(|todo| {
+                        let id = todo.id;
+                        let subject = if demo_mode.get() { redact::line(&todo.subject) } else { todo.subject };
+                        view! {
+                            <li>
+                                <button
+                                    type="button"
+                                    class="link link-hover text-sm"
+                                    on:click=move |_| {
+                                        close();
+                                        on_follow.run(id);
+                                    }
+                                >
+                                    {format!("#{id} {subject}")}
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </div>
+        }
+    };
+
+    let undo = Callback::new(move |entry: Entry| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&UndoActivityArgs { entry: &entry }).unwrap();
+            let result = invoke("undo_activity", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    close();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to undo change: {e}"))),
+            }
+        });
+    });
+
+    view! {
+        <dialog
+            class="modal"
+            class:modal-open=move || task.with(Option::is_some)
+            aria-modal="true"
+            aria-labelledby="history-title"
+        >
+            <div class="modal-box" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="history-title" class="text-lg font-bold">
+                    "History of "{move || task.with(|t| t.as_ref().map(|t| format!("\"{}\"", t.subject)))}
+                </h3>
+                {move || entries.with(Vec::is_empty).then(|| view! {
+                    <p class="mt-4 opacity-60">"No changes recorded yet."</p>
+                })}
+                <ul class="list mt-2" aria-label="Changes">
+                    {move || entries.get().into_iter().enumerate().map(|(i, entry)| {
+                        let label = action_label(entry.action);
+                        let at = entry.at.format("%Y-%m-%d %H:%M").to_string();
+                        let shown = |line: &Option<String>| {
+                            line.as_deref().map(|line| if demo_mode.get() { redact::line(line) } else { line.to_string() })
+                        };
+                        let before = shown(&entry.before);
+                        let after = shown(&entry.after);
+                        view! {
+                            <li class="list-row p-2">
+                                <div>
+                                    <div class="text-sm">
+                                        <span class="font-semibold">{label}</span>" "
+                                        <span class="opacity-60">{format!("{at} by {}", entry.actor)}</span>
+                                    </div>
+                                    {before.map(|line| view! {
+                                        <div class="font-mono text-xs opacity-60 line-through">{line}</div>
+                                    })}
+                                    {after.map(|line| view! {
+                                        <div class="font-mono text-xs">{line}</div>
+                                    })}
+                                </div>
+                                // Only the latest change applies to the todo as it is now
+                                <button
+                                    type="button"
+                                    class="btn btn-ghost btn-xs"
+                                    class=("hidden", i > 0)
+                                    aria-label=format!("Undo {}", label.to_lowercase())
+                                    on:click=move |_| undo.run(entry.clone())
+                                >
+                                    "Undo"
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                <details class="mt-4" open=move || checklist.with(|items| !items.is_empty())>
+                    <summary class="font-semibold cursor-pointer">
+                        "Checklist "<span class="font-normal opacity-60">{progress}</span>
+                    </summary>
+                    <ul class="mt-2" aria-label="Checklist">
+                        {move || checklist.get().into_iter().enumerate().map(|(i, item)| {
+                            let text = if demo_mode.get() { redact::line(&item.text) } else { item.text };
+                            view! {
+                                <li class="flex items-center gap-2">
+                                    <label class="label cursor-pointer gap-2 flex-1">
+                                        <input
+                                            type="checkbox"
+                                            class="checkbox checkbox-sm"
+                                            prop:checked=item.done
+                                            on:change=move |ev| {
+                                                let done = event_target_checked(&ev);
+                                                let mut items = checklist.get_untracked();
+                                                items[i].done = done;
+                                                save_checklist(items);
+                                            }
+                                        />
+                                        <span class:line-through=item.done class:opacity-60=item.done>{text.clone()}</span>
+                                    </label>
+                                    <button
+                                        type="button"
+                                        class="btn btn-ghost btn-xs"
+                                        aria-label=format!("Remove \"{text}\"")
+                                        on:click=move |_| {
+                                            let mut items = checklist.get_untracked();
+                                            items.remove(i);
+                                            save_checklist(items);
+                                        }
+                                    >
+                                        "✕"
+                                    </button>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                    <form
+                        class="flex gap-2 mt-2"
+                        on:submit=move |ev: leptos::ev::SubmitEvent| {
+                            ev.prevent_default();
+                            let text = new_check.get_untracked().trim().to_string();
+                            if text.is_empty() {
+                                return;
+                            }
+                            let mut items = checklist.get_untracked();
+                            items.push(CheckItem { text, done: false });
+                            save_checklist(items);
+                            set_new_check.set(String::new());
+                        }
+                    >
+                        <input
+                            type="text"
+                            class="input input-bordered input-sm flex-1"
+                            placeholder="Add an item"
+                            aria-label="New checklist item"
+                            prop:value=move || new_check.get()
+                            on:input=move |ev| set_new_check.set(event_target_value(&ev))
+                        />
+                        <button type="submit" class="btn btn-sm">"Add"</button>
+                    </form>
+                </details>
+                {link_list("Links to", |l| &l.outgoing)}
+                {link_list("Linked from", |l| &l.backlinks)}
+                <div class="modal-action">
+                    <button
+                        type="button"
+                        class="btn"
+                        class=("hidden", move || demo_mode.get())
+                        on:click=move |_| {
+                            if let Some(id) = task.with_untracked(|t| t.as_ref().map(|t| t.id)) {
+                                open_in_editor(Some(id), set_error);
+                            }
+                        }
+                    >
+                        "Open in editor"
+                    </button>
+                    <button
+                        type="button"
+                        class="btn"
+                        class=("hidden", move || demo_mode.get())
+                        title=move || task.with(|t| t.as_ref().map(|t| format!("Line {} of todo.txt", t.line)))
+                        on:click=move |_| {
+                            if let Some(id) = task.with_untracked(|t| t.as_ref().map(|t| t.id)) {
+                                reveal_in_editor(id, set_error);
+                            }
+                        }
+                    >
+                        "Reveal in editor"
+                    </button>
+                    <button type="button" class="btn" on:click=move |_| close()>"Close"</button>
+                </div>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,74 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use types::{CancelJobArgs, JobFinished, JobProgress, JOB_FINISHED_EVENT, JOB_PROGRESS_EVENT};
+use wasm_bindgen::prelude::*;
+
+use crate::app::TauriEvent;
+use crate::backend::{invoke, listen};
+
+/// A progress bar for each job running in the backend, e.g. a sync, with a
+/// button to cancel it. A job that failed ends up in `set_error`.
+#[component]
+pub fn JobToasts(set_error: WriteSignal<Option<String>>) -> impl IntoView {
+    let (jobs, set_jobs) = signal(Vec::<JobProgress>::new());
+
+    spawn_local(async move {
+        let on_progress = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<JobProgress>>(event) {
+                let progress = event.payload;
+                set_jobs.update(
+                    |jobs| match jobs.iter_mut().find(|job| job.id == progress.id) {
+                        Some(job) => *job = progress,
+                        None => jobs.push(progress),
+                    },
+                );
+            }
+        });
+        let on_finished = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<JobFinished>>(event) {
+                let finished = event.payload;
+                set_jobs.update(|jobs| jobs.retain(|job| job.id != finished.id));
+                if let Some(e) = finished.error {
+                    set_error.set(Some(e));
+                }
+            }
+        });
+        listen(JOB_PROGRESS_EVENT, &on_progress).await;
+        listen(JOB_FINISHED_EVENT, &on_finished).await;
+        // The listeners live as long as the app
+        on_progress.forget();
+        on_finished.forget();
+    });
+
+    let cancel = move |id: u64| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&CancelJobArgs { id }).unwrap();
+            let result = invoke("cancel_job", args).await;
+            if let Err(e) = serde_wasm_bindgen::from_value::<bool>(result) {
+                set_error.set(Some(format!("Failed to cancel: {e}")));
+            }
+        });
+    };
+
+    view! {
+        <div class="toast toast-end z-40" role="status" aria-live="polite">
+            <For each=move || jobs.get() key=|job| (job.id, job.done, job.message.clone()) let:job>
+                <div class="alert flex flex-col items-stretch gap-1 w-72">
+                    <div class="flex items-center justify-between gap-2">
+                        <span class="font-semibold truncate">{job.name.clone()}</span>
+                        <button
+                            type="button"
+                            class="btn btn-ghost btn-xs"
+                            aria-label=format!("Cancel {}", job.name)
+                            on:click=move |_| cancel(job.id)
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                    <progress class="progress progress-primary w-full" value=job.done max=job.total.max(1)></progress>
+                    <span class="text-xs opacity-70">{job.message.clone()}</span>
+                </div>
+            </For>
+        </div>
+    }
+}
@@ -1,14 +1,62 @@
 mod app;
-mod project_tree;
+mod archive_dialog;
+mod backend;
+mod backups;
+mod captured;
+mod celebrate;
+mod changelog;
+mod colors;
+mod custom_css;
+mod diff_view;
+mod due_overview;
+mod duplicates;
+mod file_changes;
+mod filter_bar;
+mod filter_panel;
+mod flag_menu;
+mod focus;
+mod geolocation;
+mod highlight;
+mod history;
+mod jobs;
+mod notify;
+mod overlay;
+mod palette;
+mod picker;
+mod plugin_views;
+mod raw_editor;
+mod recent;
+mod replace_dialog;
+mod report;
+mod review;
+mod rich_text;
+mod settings;
+mod shift_dialog;
+mod shortcuts;
+mod snippets;
+mod sort_controls;
+mod split_dialog;
+mod streak;
+mod tag_manager;
+mod tag_tree;
+mod voice;
+mod week;
 
 use app::*;
 use leptos::prelude::*;
 
 fn main() {
     console_error_panic_hook::set_once();
-    mount_to_body(|| {
-        view! {
-            <App/>
+    // The overlay is a window of its own, see overlay.rs
+    let overlay = window()
+        .location()
+        .hash()
+        .is_ok_and(|hash| hash == overlay::ROUTE);
+    mount_to_body(move || {
+        if overlay {
+            view! { <overlay::Overlay/> }.into_any()
+        } else {
+            view! { <App/> }.into_any()
         }
     })
 }
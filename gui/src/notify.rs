@@ -0,0 +1,58 @@
+//! System notifications through the webview's Notification API.
+
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+fn notification_class() -> Option<Function> {
+    let window = web_sys::window()?;
+    Reflect::get(&window, &"Notification".into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()
+}
+
+/// Asks for permission to show notifications unless already answered.
+pub fn request_permission() {
+    let Some(class) = notification_class() else {
+        return;
+    };
+    let permission = Reflect::get(&class, &"permission".into())
+        .ok()
+        .and_then(|p| p.as_string());
+    if permission.as_deref() == Some("default") {
+        let request = Reflect::get(&class, &"requestPermission".into())
+            .and_then(|f| f.dyn_into::<Function>());
+        if let Ok(request) = request {
+            let _ = request.call0(&class);
+        }
+    }
+}
+
+/// Shows `body` under `title`, running `on_click` when it is clicked.
+/// Returns whether it could be shown.
+pub fn show(title: &str, body: &str, on_click: impl FnOnce() + 'static) -> bool {
+    let Some(class) = notification_class() else {
+        return false;
+    };
+    let permission = Reflect::get(&class, &"permission".into())
+        .ok()
+        .and_then(|p| p.as_string());
+    if permission.as_deref() != Some("granted") {
+        return false;
+    }
+    let options = Object::new();
+    let _ = Reflect::set(&options, &"body".into(), &body.into());
+    let args = js_sys::Array::of2(&JsValue::from_str(title), &options);
+    let Ok(notification) = Reflect::construct(&class, &args) else {
+        return false;
+    };
+    let on_click = Closure::once_into_js(move || {
+        if let Some(window) = web_sys::window() {
+            let _ = window.focus();
+        }
+        on_click();
+    });
+    let _ = Reflect::set(&notification, &"onclick".into(), &on_click);
+    true
+}
@@ -0,0 +1,109 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use types::settings::{OverlaySettings, Settings};
+use types::{Revised, Todo, ToggleTodoArgs, SETTINGS_CHANGED_EVENT};
+use wasm_bindgen::prelude::*;
+
+use crate::app::TauriEvent;
+use crate::backend::{invoke, listen};
+
+/// The route the overlay window opens, see `src-tauri/src/overlay.rs`.
+pub const ROUTE: &str = "#/overlay";
+
+/// Todos shown at most, the rest are counted.
+const SHOWN: usize = 12;
+
+/// The pending todos in a translucent window without borders. It is moved
+/// by its title bar and reloads whenever the list changes.
+#[component]
+pub fn Overlay() -> impl IntoView {
+    let (todos, set_todos) = signal(Vec::<Todo>::new());
+    let (settings, set_settings) = signal(OverlaySettings::default());
+
+    let reload = move || {
+        spawn_local(async move {
+            let result = invoke("get_todos", JsValue::NULL).await;
+            if let Ok(items) = serde_wasm_bindgen::from_value::<Revised<Vec<Todo>>>(result) {
+                set_todos.set(
+                    items
+                        .value
+                        .into_iter()
+                        .filter(|todo| !todo.finished)
+                        .collect(),
+                );
+            }
+        });
+    };
+    reload();
+
+    spawn_local(async move {
+        let result = invoke("get_settings", JsValue::NULL).await;
+        if let Ok(loaded) = serde_wasm_bindgen::from_value::<Settings>(result) {
+            set_settings.set(loaded.overlay);
+        }
+        // The backend pushes fresh counts after every write
+        let on_counts = Closure::<dyn FnMut(JsValue)>::new(move |_: JsValue| reload());
+        let on_settings = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<TauriEvent<Settings>>(event) {
+                set_settings.set(event.payload.overlay);
+            }
+        });
+        listen("counts-changed", &on_counts).await;
+        listen(SETTINGS_CHANGED_EVENT, &on_settings).await;
+        // The listeners live as long as the window
+        on_counts.forget();
+        on_settings.forget();
+    });
+
+    let toggle = move |id: usize| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ToggleTodoArgs { id }).unwrap();
+            invoke("toggle_todo", args).await;
+        });
+    };
+
+    let background = move || {
+        let opacity = settings.with(|s| s.opacity.min(100));
+        format!(
+            "background-color: color-mix(in oklab, var(--color-base-100) {opacity}%, transparent)"
+        )
+    };
+
+    view! {
+        <div class="overlay flex flex-col h-screen rounded-box text-sm" style=background>
+            <header class="flex items-center justify-between px-3 py-1 cursor-move" data-tauri-drag-region>
+                <span class="font-bold" data-tauri-drag-region>{move || format!("{} to do", todos.with(Vec::len))}</span>
+                <button
+                    type="button"
+                    class="btn btn-ghost btn-xs"
+                    aria-label="Close overlay"
+                    on:click=move |_| spawn_local(async move {
+                        invoke("close_overlay", JsValue::NULL).await;
+                    })
+                >
+                    "✕"
+                </button>
+            </header>
+            <ul class="overflow-y-auto px-3 pb-2" aria-label="Pending todos">
+                {move || todos.get().into_iter().take(SHOWN).map(|todo| {
+                    let id = todo.id;
+                    view! {
+                        <li class="flex items-start gap-2 py-0.5">
+                            <input
+                                type="checkbox"
+                                class="checkbox checkbox-xs mt-0.5"
+                                aria-label=format!("Complete {}", todo.subject)
+                                on:change=move |_| toggle(id)
+                            />
+                            <span>{todo.subject.clone()}</span>
+                        </li>
+                    }
+                }).collect_view()}
+                {move || {
+                    let more = todos.with(Vec::len).saturating_sub(SHOWN);
+                    (more > 0).then(|| view! { <li class="opacity-60 py-0.5">{format!("and {more} more")}</li> })
+                }}
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,94 @@
+use leptos::prelude::*;
+use todotxt::fuzzy;
+
+/// Something the palette can run.
+#[derive(Clone)]
+pub struct Command {
+    pub label: &'static str,
+    pub run: Callback<()>,
+}
+
+/// The commands whose label matches `query`, best first, see [`fuzzy::score`].
+fn matching(commands: &[Command], query: &str) -> Vec<Command> {
+    fuzzy::rank(query, commands, |command| command.label)
+        .into_iter()
+        .map(|(command, _)| command.clone())
+        .collect()
+}
+
+/// Runs one of `commands` picked by typing part of its name, opened with
+/// its shortcut, Ctrl+K (⌘K on macOS) unless changed. Enter runs the first
+/// that matches.
+#[component]
+pub fn CommandPalette(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    commands: Vec<Command>,
+) -> impl IntoView {
+    let (query, set_query) = signal(String::new());
+    let input_ref = NodeRef::<leptos::html::Input>::new();
+    let commands = StoredValue::new(commands);
+    let shown =
+        Memo::new(move |_| query.with(|q| commands.with_value(|commands| matching(commands, q))));
+
+    Effect::new(move |_| {
+        if open.get() {
+            set_query.set(String::new());
+            if let Some(input) = input_ref.get() {
+                let _ = input.focus();
+            }
+        }
+    });
+
+    let run = move |command: Command| {
+        set_open.set(false);
+        command.run.run(());
+    };
+
+    view! {
+        <dialog
+            class="modal modal-top"
+            class:modal-open=move || open.get()
+            aria-modal="true"
+            aria-label="Command palette"
+        >
+            <div
+                class="modal-box max-w-lg mx-auto mt-16"
+                on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { set_open.set(false) }
+            >
+                <form on:submit=move |ev| {
+                    ev.prevent_default();
+                    if let Some(command) = shown.with_untracked(|shown| shown.first().cloned()) {
+                        run(command);
+                    }
+                }>
+                    <input
+                        type="text"
+                        class="input input-bordered w-full"
+                        placeholder="Type a command"
+                        aria-label="Command"
+                        node_ref=input_ref
+                        prop:value=move || query.get()
+                        on:input=move |ev| set_query.set(event_target_value(&ev))
+                    />
+                </form>
+                <ul class="menu w-full mt-2" aria-label="Commands">
+                    {move || shown.get().into_iter().map(|command| {
+                        let label = command.label;
+                        view! {
+                            <li>
+                                <button type="button" on:click=move |_| run(command.clone())>{label}</button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                {move || shown.with(Vec::is_empty).then(|| view! {
+                    <p class="opacity-60 px-4">"No matching command."</p>
+                })}
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| set_open.set(false) />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,115 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::filter::Filter;
+use types::{PickTodoArgs, Todo, ToggleTodoArgs};
+
+use crate::backend::invoke;
+
+/// "What should I do now?": one todo from those matching `filter`, picked at
+/// random with important, overdue and quick ones more likely, shown on its
+/// own. Skipping picks another one; each is offered once per opening.
+#[component]
+pub fn Picker(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    filter: ReadSignal<Filter>,
+    /// Show lorem ipsum instead of the picked todo
+    demo_mode: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (picked, set_picked) = signal(Option::<Todo>::None);
+    let (skipped, set_skipped) = signal(Vec::<usize>::new());
+    // Whether everything was skipped or done
+    let (exhausted, set_exhausted) = signal(false);
+
+    let pick = move || {
+        let filter = filter.get_untracked();
+        let skip = skipped.get_untracked();
+        spawn_local(async move {
+            let args = PickTodoArgs {
+                filter: &filter,
+                skip: &skip,
+                roll: js_sys::Math::random(),
+            };
+            let result = invoke("pick_todo", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            match serde_wasm_bindgen::from_value::<Option<Todo>>(result) {
+                Ok(todo) => {
+                    set_exhausted.set(todo.is_none());
+                    set_picked.set(if demo_mode.get_untracked() {
+                        todo.map(|t| t.redacted())
+                    } else {
+                        todo
+                    });
+                }
+                Err(e) => set_error.set(Some(format!("Failed to pick a todo: {e}"))),
+            }
+        });
+    };
+    Effect::new(move |_| {
+        if open.get() {
+            set_skipped.set(Vec::new());
+            pick();
+        }
+    });
+
+    let close = move || {
+        set_open.set(false);
+        set_picked.set(None);
+    };
+    let skip = move |_| {
+        if let Some(todo) = picked.get_untracked() {
+            set_skipped.update(|ids| ids.push(todo.id));
+        }
+        pick();
+    };
+    let done = move |_| {
+        let Some(todo) = picked.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ToggleTodoArgs { id: todo.id }).unwrap();
+            let result = invoke("toggle_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    pick();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to complete todo: {e}"))),
+            }
+        });
+    };
+
+    view! {
+        <div
+            class="fixed inset-0 z-50 bg-base-200 flex flex-col items-center justify-center gap-8 p-8"
+            class=("hidden", move || !open.get())
+            role="dialog"
+            aria-modal="true"
+            aria-label="What should I do now?"
+            on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }
+        >
+            <p class="text-sm uppercase tracking-wide opacity-60">"What should I do now?"</p>
+            <h2 class="text-4xl font-bold text-center max-w-3xl" aria-live="polite">
+                {move || match picked.get() {
+                    Some(todo) => todo.subject,
+                    None if exhausted.get() => "Nothing left to pick.".to_string(),
+                    None => String::new(),
+                }}
+            </h2>
+            <div class="flex gap-2">
+                <button type="button" class="btn btn-primary" disabled=move || picked.with(Option::is_none) on:click=move |_| close()>
+                    "Start"
+                </button>
+                <button type="button" class="btn" disabled=move || picked.with(Option::is_none) on:click=skip>
+                    "Skip"
+                </button>
+                <button type="button" class="btn btn-success" disabled=move || picked.with(Option::is_none) on:click=done>
+                    "Done"
+                </button>
+                <button type="button" class="btn btn-ghost" on:click=move |_| close()>
+                    "Close"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,74 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::plugin::SidebarView;
+use types::PluginInfo;
+use wasm_bindgen::JsValue;
+
+use crate::backend::invoke;
+use crate::settings::Settings;
+
+/// The sidebar views of the plugins allowed to run. Picking one sets
+/// `plugin_view` to it as (plugin, export), picking it again clears it.
+#[component]
+pub fn PluginViews(
+    settings: ReadSignal<Settings>,
+    /// Plugin views only look at the user's own list
+    watching: Signal<bool>,
+    plugin_view: ReadSignal<Option<(String, String)>>,
+    set_plugin_view: WriteSignal<Option<(String, String)>>,
+) -> impl IntoView {
+    // Sidebar views of the plugins allowed to run, as (plugin, view)
+    let (plugin_views, set_plugin_views) = signal(Vec::<(String, SidebarView)>::new());
+    Effect::new(move |_| {
+        settings.track();
+        spawn_local(async move {
+            let result = invoke("get_plugins", JsValue::NULL).await;
+            if let Ok(plugins) = serde_wasm_bindgen::from_value::<Vec<PluginInfo>>(result) {
+                let views = plugins
+                    .into_iter()
+                    .filter(|plugin| plugin.missing.is_empty())
+                    .filter_map(|plugin| plugin.manifest)
+                    .flat_map(|manifest| {
+                        let name = manifest.name;
+                        manifest
+                            .views
+                            .into_iter()
+                            .map(move |sidebar_view| (name.clone(), sidebar_view))
+                    })
+                    .collect();
+                set_plugin_views.set(views);
+            }
+        });
+    });
+
+    view! {
+        {move || (!plugin_views.with(Vec::is_empty) && !watching.get()).then(|| view! {
+            <h2 class="text-sm font-semibold tracking-wide opacity-60 mt-4 mb-2">"Plugin views"</h2>
+            <ul class="menu menu-sm p-0 w-full" aria-label="Plugin views">
+                {plugin_views.get().into_iter().map(|(plugin, sidebar_view)| {
+                    let key = (plugin, sidebar_view.export);
+                    let is_active = {
+                        let key = key.clone();
+                        Memo::new(move |_| plugin_view.with(|active| active.as_ref() == Some(&key)))
+                    };
+                    view! {
+                        <li>
+                            <button
+                                type="button"
+                                class=("menu-active", move || is_active.get())
+                                aria-pressed=move || is_active.get().to_string()
+                                on:click=move |_| {
+                                    // Picking the open view again closes it
+                                    let next = (!is_active.get_untracked()).then(|| key.clone());
+                                    set_plugin_view.set(next);
+                                }
+                            >
+                                {sidebar_view.title}
+                            </button>
+                        </li>
+                    }
+                }).collect_view()}
+            </ul>
+        })}
+    }
+}
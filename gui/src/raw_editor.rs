@@ -0,0 +1,237 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::check::{Finding, Fix};
+use todotxt::redact;
+use todotxt::LineError;
+use types::{OpenInEditorArgs, RevealInEditorArgs, SaveRawArgs};
+use wasm_bindgen::prelude::*;
+
+use crate::backend::invoke;
+use crate::highlight::highlight_line;
+
+fn render_highlighted(content: &str) -> impl IntoView {
+    content
+        .split('\n')
+        .map(|line| {
+            let segments = highlight_line(line)
+                .into_iter()
+                .map(|(class, text)| view! { <span class=class>{text.to_string()}</span> })
+                .collect_view();
+            view! { {segments}"\n" }
+        })
+        .collect_view()
+}
+
+/// Opens the todo `id`, or the whole file for `None`, in the user's editor.
+/// Saves there are taken back by a job in the backend.
+pub(crate) fn open_in_editor(id: Option<usize>, set_error: WriteSignal<Option<String>>) {
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&OpenInEditorArgs { id }).unwrap();
+        let result = invoke("open_in_editor", args).await;
+        match serde_wasm_bindgen::from_value::<u64>(result) {
+            Ok(_) => set_error.set(None),
+            Err(e) => set_error.set(Some(format!("Failed to open editor: {e}"))),
+        }
+    });
+}
+
+/// Opens todo.txt itself at the line of the todo `id`, in the editor set up
+/// in the settings. Unlike [`open_in_editor`] nothing is watched; the app
+/// picks up saves like any other change to the file.
+pub(crate) fn reveal_in_editor(id: usize, set_error: WriteSignal<Option<String>>) {
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&RevealInEditorArgs { id }).unwrap();
+        let result = invoke("reveal_in_editor", args).await;
+        match serde_wasm_bindgen::from_value::<()>(result) {
+            Ok(()) => set_error.set(None),
+            Err(e) => set_error.set(Some(format!("Failed to reveal in editor: {e}"))),
+        }
+    });
+}
+
+#[component]
+pub fn RawEditor(
+    active: Signal<bool>,
+    /// Show lorem ipsum and don't allow editing
+    demo_mode: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (content, set_content) = signal(String::new());
+    let (line_errors, set_line_errors) = signal(Vec::<LineError>::new());
+    let (dirty, set_dirty) = signal(false);
+    let highlight_ref = NodeRef::<leptos::html::Pre>::new();
+
+    // What the last health check found, `None` before checking
+    let (findings, set_findings) = signal(Option::<Vec<Finding>>::None);
+
+    let reload = move || {
+        spawn_local(async move {
+            let result = invoke("get_raw", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<String>(result) {
+                Ok(raw) => {
+                    set_content.set(raw);
+                    set_line_errors.set(Vec::new());
+                    set_dirty.set(false);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to load raw file: {e}"))),
+            }
+        });
+    };
+
+    // Reload the file every time the tab becomes visible so edits made elsewhere show up
+    Effect::new(move |_| {
+        if active.get() {
+            set_findings.set(None);
+            reload();
+        }
+    });
+
+    // Checks and repairs work on the saved file
+    let run_check = move |command: &'static str| {
+        spawn_local(async move {
+            let result = invoke(command, JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<Finding>>(result) {
+                Ok(found) => {
+                    set_error.set(None);
+                    set_findings.set(Some(found));
+                    if command == "repair_file" {
+                        reload();
+                    }
+                }
+                Err(e) => set_error.set(Some(format!("Failed to check file: {e}"))),
+            }
+        });
+    };
+    let fixable = move || findings.with(|f| f.iter().flatten().filter(|f| f.fix.is_some()).count());
+
+    let on_save = move |_| {
+        let text = content.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SaveRawArgs { content: &text }).unwrap();
+            let result = invoke("save_raw", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<LineError>>(result) {
+                Ok(errors) => {
+                    set_error.set(None);
+                    if errors.is_empty() {
+                        set_dirty.set(false);
+                    }
+                    set_line_errors.set(errors);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to save raw file: {e}"))),
+            }
+        });
+    };
+
+    // Keep the highlight layer aligned with the textarea while scrolling
+    let on_scroll = move |ev: leptos::ev::Event| {
+        let textarea = event_target::<leptos::web_sys::HtmlTextAreaElement>(&ev);
+        if let Some(pre) = highlight_ref.get_untracked() {
+            pre.set_scroll_top(textarea.scroll_top());
+        }
+    };
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body">
+                <div class="relative font-mono text-sm h-[60vh]">
+                    <pre
+                        aria-hidden="true"
+                        class="absolute inset-0 m-0 p-3 overflow-hidden whitespace-pre-wrap break-words pointer-events-none"
+                        node_ref=highlight_ref
+                    >
+                        {move || {
+                            let content = content.get();
+                            if demo_mode.get() {
+                                let redacted: Vec<String> = content.split('\n').map(redact::line).collect();
+                                render_highlighted(&redacted.join("\n")).into_any()
+                            } else {
+                                render_highlighted(&content).into_any()
+                            }
+                        }}
+                    </pre>
+                    <textarea
+                        class="textarea absolute inset-0 w-full h-full p-3 bg-transparent text-transparent caret-base-content resize-none whitespace-pre-wrap break-words font-mono text-sm"
+                        aria-label="todo.txt contents"
+                        spellcheck="false"
+                        prop:readOnly=move || demo_mode.get()
+                        prop:value=move || content.get()
+                        on:input=move |ev| {
+                            set_content.set(event_target_value(&ev));
+                            set_dirty.set(true);
+                        }
+                        on:scroll=on_scroll
+                    />
+                </div>
+
+                {move || {
+                    let errors = line_errors.get();
+                    (!errors.is_empty()).then(|| view! {
+                        <div class="alert alert-warning" role="alert">
+                            <ul>
+                                {errors.into_iter().map(|e| view! {
+                                    <li>{format!("Line {}: {}", e.line, e.message)}</li>
+                                }).collect_view()}
+                            </ul>
+                        </div>
+                    })
+                }}
+
+                {move || findings.get().filter(|_| !demo_mode.get()).map(|found| if found.is_empty() {
+                    view! { <div class="alert alert-success" role="status">"No problems found."</div> }.into_any()
+                } else {
+                    view! {
+                        <ul class="list max-h-64 overflow-y-auto" aria-label="Problems found">
+                            {found.into_iter().map(|finding| {
+                                let after = match finding.fix {
+                                    Some(Fix::Replace(line)) => line,
+                                    Some(Fix::Remove) => "(removed)".to_string(),
+                                    None => "(fix by hand)".to_string(),
+                                };
+                                view! {
+                                    <li class="list-row p-2 block">
+                                        <div class="text-sm">{format!("Line {}: {}", finding.line, finding.message)}</div>
+                                        <div class="font-mono text-xs opacity-60 line-through whitespace-pre">{finding.before}</div>
+                                        <div class="font-mono text-xs whitespace-pre">{after}</div>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    }.into_any()
+                })}
+
+                <div class="card-actions justify-end">
+                    <button
+                        type="button"
+                        class="btn"
+                        prop:disabled=move || dirty.get() || demo_mode.get()
+                        title="Save first to check the file"
+                        on:click=move |_| run_check("check_file")
+                    >
+                        "Check file"
+                    </button>
+                    <button
+                        type="button"
+                        class="btn"
+                        class=("hidden", move || fixable() == 0 || demo_mode.get())
+                        prop:disabled=move || dirty.get()
+                        on:click=move |_| run_check("repair_file")
+                    >
+                        {move || format!("Apply {} fixes", fixable())}
+                    </button>
+                    <button
+                        type="button"
+                        class="btn"
+                        prop:disabled=move || dirty.get() || demo_mode.get()
+                        title="Save first to edit the file elsewhere"
+                        on:click=move |_| open_in_editor(None, set_error)
+                    >
+                        "Open in editor"
+                    </button>
+                    <button type="button" class="btn btn-primary" prop:disabled=move || !dirty.get() || demo_mode.get() on:click=on_save>
+                        "Save"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,63 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::redact;
+use types::{RecentChange, RecentlyChangedArgs, Todo};
+
+use crate::backend::invoke;
+use crate::history::action_label;
+
+/// How many todos the view lists.
+const LIMIT: usize = 50;
+
+/// The todos changed most recently, newest first, to see what a sync or a
+/// long editing session touched. Follows the list while `active`.
+#[component]
+pub fn Recent(
+    active: Signal<bool>,
+    todos: ReadSignal<Vec<Todo>>,
+    /// Show lorem ipsum instead of the todos
+    demo_mode: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (changes, set_changes) = signal(Vec::<RecentChange>::new());
+
+    Effect::new(move |_| {
+        todos.track();
+        if !active.get() {
+            return;
+        }
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RecentlyChangedArgs { limit: LIMIT }).unwrap();
+            let result = invoke("recently_changed", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<RecentChange>>(result) {
+                Ok(found) => set_changes.set(found),
+                Err(e) => set_error.set(Some(format!("Failed to load recent changes: {e}"))),
+            }
+        });
+    });
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body">
+                {move || changes.with(Vec::is_empty).then(|| view! {
+                    <p class="opacity-60">"Nothing changed through the app yet."</p>
+                })}
+                <ul class="list" aria-label="Recently changed todos">
+                    {move || changes.get().into_iter().map(|RecentChange { todo, entry }| {
+                        let raw = if demo_mode.get() { redact::line(&todo.raw) } else { todo.raw };
+                        let at = entry.at.format("%Y-%m-%d %H:%M").to_string();
+                        view! {
+                            <li class="list-row p-2 block">
+                                <div class="font-mono text-sm" class=("line-through", todo.finished)>{raw}</div>
+                                <div class="text-xs opacity-60">
+                                    <span class="font-semibold">{action_label(entry.action)}</span>
+                                    {format!(" {at} by {}", entry.actor)}
+                                </div>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </div>
+        </div>
+    }
+}
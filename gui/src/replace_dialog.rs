@@ -0,0 +1,181 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::activity::Entry;
+use todotxt::filter::Filter;
+use todotxt::replace::Replacement;
+use types::{ReplaceInTasksArgs, UndoActivitiesArgs};
+
+use crate::backend::invoke;
+
+/// Find-and-replace across the todos matching `filter`, previewing every line
+/// that would change. After replacing, the whole batch can be undone at once.
+#[component]
+pub fn ReplaceDialog(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    filter: ReadSignal<Filter>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (pattern, set_pattern) = signal(String::new());
+    let (replacement, set_replacement) = signal(String::new());
+    let (regex, set_regex) = signal(false);
+    let (preview, set_preview) = signal(Vec::<Replacement>::new());
+    let (preview_error, set_preview_error) = signal(Option::<String>::None);
+    // The changes of the last replace, until they are undone or the dialog closes
+    let (applied, set_applied) = signal(Option::<Vec<Entry>>::None);
+
+    Effect::new(move |_| {
+        let (pattern, replacement, regex, filter) =
+            (pattern.get(), replacement.get(), regex.get(), filter.get());
+        if !open.get() || pattern.is_empty() {
+            set_preview.set(Vec::new());
+            set_preview_error.set(None);
+            return;
+        }
+        spawn_local(async move {
+            let args = ReplaceInTasksArgs {
+                pattern: &pattern,
+                regex,
+                replacement: &replacement,
+                filter: &filter,
+            };
+            let result = invoke(
+                "preview_replace",
+                serde_wasm_bindgen::to_value(&args).unwrap(),
+            )
+            .await;
+            match serde_wasm_bindgen::from_value::<Vec<Replacement>>(result.clone()) {
+                Ok(lines) => {
+                    set_preview_error.set(None);
+                    set_preview.set(lines);
+                }
+                // An invalid regex, shown next to the field rather than as an app error
+                Err(_) => {
+                    set_preview.set(Vec::new());
+                    set_preview_error.set(Some(
+                        result
+                            .as_string()
+                            .unwrap_or_else(|| "Invalid pattern".to_string()),
+                    ));
+                }
+            }
+        });
+    });
+
+    let close = move || {
+        set_open.set(false);
+        set_pattern.set(String::new());
+        set_replacement.set(String::new());
+        set_applied.set(None);
+    };
+
+    let replace_all = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let (pattern, replacement, regex, filter) = (
+            pattern.get_untracked(),
+            replacement.get_untracked(),
+            regex.get_untracked(),
+            filter.get_untracked(),
+        );
+        spawn_local(async move {
+            let args = ReplaceInTasksArgs {
+                pattern: &pattern,
+                regex,
+                replacement: &replacement,
+                filter: &filter,
+            };
+            let result = invoke(
+                "replace_in_tasks",
+                serde_wasm_bindgen::to_value(&args).unwrap(),
+            )
+            .await;
+            match serde_wasm_bindgen::from_value::<Vec<Entry>>(result) {
+                Ok(entries) => {
+                    set_error.set(None);
+                    set_pattern.set(String::new());
+                    set_applied.set(Some(entries));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to replace: {e}"))),
+            }
+        });
+    };
+
+    let undo = move |_| {
+        let Some(entries) = applied.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&UndoActivitiesArgs { entries: &entries }).unwrap();
+            let result = invoke("undo_activities", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    set_applied.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to undo replace: {e}"))),
+            }
+        });
+    };
+
+    view! {
+        <dialog class="modal" class:modal-open=move || open.get() aria-modal="true" aria-labelledby="replace-title">
+            <div class="modal-box max-w-2xl" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="replace-title" class="text-lg font-bold">"Replace in shown todos"</h3>
+                <form class="flex flex-col gap-2 mt-4" on:submit=replace_all>
+                    <input
+                        type="text"
+                        class="input input-bordered w-full font-mono"
+                        class=("input-error", move || preview_error.with(Option::is_some))
+                        placeholder="Find"
+                        aria-label="Find"
+                        aria-invalid=move || preview_error.with(Option::is_some).to_string()
+                        prop:value=move || pattern.get()
+                        on:input=move |ev| set_pattern.set(event_target_value(&ev))
+                    />
+                    {move || preview_error.get().map(|e| view! { <p class="text-sm text-error">{e}</p> })}
+                    <input
+                        type="text"
+                        class="input input-bordered w-full font-mono"
+                        placeholder="Replace with"
+                        aria-label="Replace with"
+                        prop:value=move || replacement.get()
+                        on:input=move |ev| set_replacement.set(event_target_value(&ev))
+                    />
+                    <label class="label cursor-pointer justify-start gap-2">
+                        <input
+                            type="checkbox"
+                            class="checkbox checkbox-sm"
+                            prop:checked=move || regex.get()
+                            on:change=move |ev| set_regex.set(event_target_checked(&ev))
+                        />
+                        <span>"Regular expression ($1 refers to the first group)"</span>
+                    </label>
+                    <ul class="list max-h-64 overflow-y-auto" aria-label="Lines that would change">
+                        {move || preview.get().into_iter().map(|line| view! {
+                            <li class="list-row p-2 block">
+                                <div class="font-mono text-xs opacity-60 line-through">{line.before}</div>
+                                <div class="font-mono text-xs">{line.after}</div>
+                            </li>
+                        }).collect_view()}
+                    </ul>
+                    {move || applied.get().map(|entries| view! {
+                        <div class="flex items-center gap-2" role="status">
+                            <span>{format!("Replaced in {} todos.", entries.len())}</span>
+                            <button type="button" class="btn btn-ghost btn-sm" on:click=undo>"Undo"</button>
+                        </div>
+                    })}
+                    <div class="modal-action">
+                        <button type="button" class="btn" on:click=move |_| close()>"Close"</button>
+                        <button type="submit" class="btn btn-primary" disabled=move || preview.with(Vec::is_empty)>
+                            {move || format!("Replace in {} todos", preview.with(Vec::len))}
+                        </button>
+                    </div>
+                </form>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,130 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::report::ReportFormat;
+use todotxt::Date;
+use types::settings::Settings;
+use types::GenerateReportArgs;
+
+use crate::app::write_clipboard;
+use crate::backend::invoke;
+use crate::review::today;
+
+/// A report of what was completed, added and is still open in a date range,
+/// the last seven days at first, to copy into a status email or download.
+#[component]
+pub fn Report(
+    settings: ReadSignal<Settings>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let to_default = today(&settings.get_untracked().day_ends);
+    let from_default = Date::parse_from_str(&to_default, "%Y-%m-%d")
+        .ok()
+        .and_then(|to| (0..6).try_fold(to, |day, _| day.pred_opt()))
+        .map(|from| from.to_string())
+        .unwrap_or_else(|| to_default.clone());
+    let (from, set_from) = signal(from_default);
+    let (to, set_to) = signal(to_default);
+    let (format, set_format) = signal(ReportFormat::Markdown);
+    let (report, set_report) = signal(String::new());
+    let (status, set_status) = signal(Option::<String>::None);
+
+    let generate = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let (from, to, format) = (
+            from.get_untracked(),
+            to.get_untracked(),
+            format.get_untracked(),
+        );
+        spawn_local(async move {
+            let args = GenerateReportArgs {
+                from: &from,
+                to: &to,
+                format,
+            };
+            let result = invoke(
+                "generate_report",
+                serde_wasm_bindgen::to_value(&args).unwrap(),
+            )
+            .await;
+            match serde_wasm_bindgen::from_value::<String>(result) {
+                Ok(text) => {
+                    set_error.set(None);
+                    set_status.set(None);
+                    set_report.set(text);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to generate report: {e}"))),
+            }
+        });
+    };
+    let copy = move |_| {
+        let text = report.get_untracked();
+        spawn_local(async move {
+            match write_clipboard(&text).await {
+                Ok(_) => set_status.set(Some("Copied".to_string())),
+                Err(e) => set_error.set(Some(format!("Failed to copy to clipboard: {e:?}"))),
+            }
+        });
+    };
+    let download_href = move || {
+        let mime = match format.get() {
+            ReportFormat::Markdown => "text/markdown",
+            ReportFormat::Html => "text/html",
+        };
+        format!(
+            "data:{mime};charset=utf-8,{}",
+            js_sys::encode_uri_component(&report.get())
+        )
+    };
+    let download_name = move || {
+        let extension = match format.get() {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        };
+        format!("report-{}-to-{}.{extension}", from.get(), to.get())
+    };
+
+    view! {
+        <section class="mb-6" aria-label="Report">
+            <h2 class="text-lg font-semibold mb-2">"Report"</h2>
+            <form class="flex flex-wrap items-center gap-2" on:submit=generate>
+                <input
+                    type="date"
+                    class="input input-bordered input-sm"
+                    aria-label="From"
+                    prop:value=move || from.get()
+                    on:change=move |ev| set_from.set(event_target_value(&ev))
+                />
+                <span>"to"</span>
+                <input
+                    type="date"
+                    class="input input-bordered input-sm"
+                    aria-label="To"
+                    prop:value=move || to.get()
+                    on:change=move |ev| set_to.set(event_target_value(&ev))
+                />
+                <select
+                    class="select select-bordered select-sm w-32"
+                    aria-label="Format"
+                    on:change=move |ev| {
+                        set_format.set(if event_target_value(&ev) == "html" { ReportFormat::Html } else { ReportFormat::Markdown });
+                        set_report.set(String::new());
+                    }
+                >
+                    <option value="markdown" selected=move || format.get() == ReportFormat::Markdown>"Markdown"</option>
+                    <option value="html" selected=move || format.get() == ReportFormat::Html>"HTML"</option>
+                </select>
+                <button type="submit" class="btn btn-sm btn-primary">"Generate"</button>
+            </form>
+            <div class=("hidden", move || report.with(String::is_empty))>
+                <textarea class="textarea textarea-bordered w-full h-64 font-mono text-xs mt-2" readonly aria-label="Report text">
+                    {move || report.get()}
+                </textarea>
+                <div class="flex items-center gap-2 mt-2">
+                    <button type="button" class="btn btn-sm" on:click=copy>"Copy"</button>
+                    <a class="btn btn-sm" href=download_href download=download_name>"Download"</a>
+                    <span class="text-sm opacity-70" role="status">{move || status.get()}</span>
+                </div>
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,143 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::Date;
+use types::settings::Settings;
+use types::{BulkAction, BulkActionArgs, Todo};
+
+use crate::backend::invoke;
+use crate::duplicates::Duplicates;
+use crate::report::Report;
+
+/// Today as `YYYY-MM-DD` in local time, comparable with due dates, with the
+/// day ending at `day_ends` (`HH:MM`).
+pub(crate) fn today(day_ends: &str) -> String {
+    let now = js_sys::Date::new_0();
+    let minutes = |time: &str| {
+        let (hours, minutes) = time.split_once(':')?;
+        Some(hours.parse::<u32>().ok()? * 60 + minutes.parse::<u32>().ok()?)
+    };
+    if minutes(day_ends).is_some_and(|ends| now.get_hours() * 60 + now.get_minutes() < ends) {
+        now.set_date(now.get_date() - 1);
+    }
+    format!(
+        "{:04}-{:02}-{:02}",
+        now.get_full_year(),
+        now.get_month() + 1,
+        now.get_date()
+    )
+}
+
+/// Days from `created` to `today`, both `YYYY-MM-DD`.
+pub(crate) fn age_days(created: &str, today: &str) -> Option<i64> {
+    let parse = |date: &str| Date::parse_from_str(date, "%Y-%m-%d").ok();
+    Some((parse(today)? - parse(created)?).num_days())
+}
+
+fn review_section(
+    title: &'static str,
+    empty: &'static str,
+    items: Signal<Vec<Todo>>,
+) -> impl IntoView {
+    view! {
+        <section class="mb-6" aria-label=title>
+            <h2 class="text-lg font-semibold mb-2">{title}</h2>
+            {move || {
+                let items = items.get();
+                if items.is_empty() {
+                    view! { <p class="text-sm opacity-60">{empty}</p> }.into_any()
+                } else {
+                    view! {
+                        <ul class="list bg-base-100 rounded-box">
+                            {items.into_iter().map(|todo| view! {
+                                <li class="list-row font-mono text-sm">{todo.raw}</li>
+                            }).collect_view()}
+                        </ul>
+                    }
+                    .into_any()
+                }
+            }}
+        </section>
+    }
+}
+
+/// A walk through what needs attention, e.g. for a weekly review: overdue
+/// tasks, tasks not sorted into a project or context yet, tasks waiting
+/// on someone else and tasks captured twice.
+#[component]
+pub fn Review(
+    todos: Signal<Vec<Todo>>,
+    settings: ReadSignal<Settings>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let pending = move || todos.get().into_iter().filter(|todo| !todo.finished);
+    let due = Signal::derive(move || {
+        let today = settings.with(|settings| today(&settings.day_ends));
+        pending()
+            .filter(|todo| todo.due_date.as_ref().is_some_and(|due| *due <= today))
+            .collect()
+    });
+    let unsorted = Signal::derive(move || {
+        pending()
+            .filter(|todo| todo.projects.is_empty() && todo.contexts.is_empty())
+            .collect()
+    });
+    let waiting =
+        Signal::derive(move || pending().filter(|todo| todo.assignee.is_some()).collect());
+    // Oldest first
+    let stale = Signal::derive(move || {
+        let (today, after_days) = settings.with(|s| (today(&s.day_ends), s.stale_after_days));
+        let mut stale: Vec<Todo> = pending()
+            .filter(|todo| {
+                let age = todo
+                    .creation_date
+                    .as_deref()
+                    .and_then(|created| age_days(created, &today));
+                age.is_some_and(|age| age > i64::from(after_days))
+            })
+            .collect();
+        stale.sort_by(|a, b| a.creation_date.cmp(&b.creation_date));
+        stale
+    });
+
+    let apply = move |action: BulkAction| {
+        let ids: Vec<usize> =
+            stale.with_untracked(|todos| todos.iter().map(|todo| todo.id).collect());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&BulkActionArgs { ids: &ids, action }).unwrap();
+            let result = invoke("bulk_action", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => set_error.set(None),
+                Err(e) => set_error.set(Some(format!("Failed to update stale todos: {e}"))),
+            }
+        });
+    };
+
+    view! {
+        <div>
+            {review_section("Due and overdue", "Nothing is due.", due)}
+            {review_section("Not sorted into a project or context", "Everything is sorted.", unsorted)}
+            {review_section("Waiting on others", "No tasks are assigned to anyone.", waiting)}
+            {review_section("Stale tasks", "Nothing has gone stale.", stale)}
+            <div class="flex items-center gap-2" class=("hidden", move || stale.with(Vec::is_empty)) role="group" aria-label="Stale tasks">
+                <span class="text-sm opacity-60">
+                    {move || format!("Created more than {} days ago.", settings.with(|s| s.stale_after_days))}
+                </span>
+                <button type="button" class="btn btn-sm" on:click=move |_| apply(BulkAction::Archive)>
+                    "Archive all"
+                </button>
+                <button type="button" class="btn btn-sm" on:click=move |_| apply(BulkAction::Reschedule)>
+                    "Due in a week"
+                </button>
+                <button type="button" class="btn btn-sm btn-error" on:click=move |_| apply(BulkAction::Delete)>
+                    "Delete all"
+                </button>
+            </div>
+            <div class="mt-6">
+                <Duplicates todos=todos set_error=set_error />
+            </div>
+            <div class="mt-6">
+                <Report settings=settings set_error=set_error />
+            </div>
+        </div>
+    }
+}
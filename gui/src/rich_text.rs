@@ -0,0 +1,56 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::markdown::{self, Inline};
+use types::OpenUrlArgs;
+
+use crate::backend::{self, invoke};
+
+/// Opens `url` in the browser, or the mail app for `mailto:`.
+fn open_url(url: String, set_error: WriteSignal<Option<String>>) {
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&OpenUrlArgs { url: &url }).unwrap();
+        let result = invoke("open_url", args).await;
+        match serde_wasm_bindgen::from_value::<()>(result) {
+            Ok(()) => set_error.set(None),
+            Err(e) => set_error.set(Some(format!("Failed to open link: {e}"))),
+        }
+    });
+}
+
+/// `text` with its bold, code and links shown as such, see
+/// [`todotxt::markdown`]. Built from elements, never from HTML, so nothing
+/// in a todo gets interpreted as markup.
+pub fn rich_text(text: &str, set_error: WriteSignal<Option<String>>) -> impl IntoView {
+    markdown::parse(text)
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text { text } => text.into_any(),
+            Inline::Bold { text } => view! { <strong>{text}</strong> }.into_any(),
+            Inline::Code { text } => view! { <code class="font-mono text-[0.9em] bg-base-300 rounded px-1">{text}</code> }.into_any(),
+            Inline::Link { text, url } => {
+                let target = url.clone();
+                view! {
+                    <a
+                        class="link link-primary"
+                        href=url
+                        target="_blank"
+                        rel="noopener noreferrer"
+                        on:click=move |ev: leptos::ev::MouseEvent| {
+                            // Links don't start editing the todo they're in
+                            ev.stop_propagation();
+                            // The browser opens them itself in the web viewer
+                            if !backend::READ_ONLY {
+                                ev.prevent_default();
+                                open_url(target.clone(), set_error);
+                            }
+                        }
+                        on:keydown=move |ev: leptos::ev::KeyboardEvent| ev.stop_propagation()
+                    >
+                        {text}
+                    </a>
+                }
+                .into_any()
+            }
+        })
+        .collect_view()
+}
@@ -0,0 +1,2080 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::archive::Compaction;
+use todotxt::filter::{Due, Filter, Status};
+use todotxt::geo::ContextLocation;
+use todotxt::group::GroupBy;
+use todotxt::reminders::StandingReminder;
+use todotxt::replace::Replacement;
+use todotxt::rules::WipLimit;
+use todotxt::tags::TagKind;
+use todotxt::Completion;
+pub use types::settings::*;
+use types::{ImportConfigArgs, PluginExportArgs, PluginInfo, PreviewNormalizeArgs};
+use wasm_bindgen::prelude::*;
+
+use crate::backend::invoke;
+use crate::geolocation;
+
+/// Meters around a place that count as being there.
+const DEFAULT_PLACE_RADIUS: f64 = 200.0;
+
+fn hook_label(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::TaskAdded => "Task added",
+        HookEvent::TaskCompleted => "Task completed",
+        HookEvent::BeforeSave => "Before save",
+    }
+}
+
+fn toggle_row(
+    label: &'static str,
+    settings: ReadSignal<Settings>,
+    on_change: Callback<Settings>,
+    field: fn(&mut DisplaySettings) -> &mut bool,
+) -> impl IntoView {
+    view! {
+        <label class="label cursor-pointer justify-between py-2">
+            <span>{label}</span>
+            <input
+                type="checkbox"
+                class="toggle toggle-primary"
+                prop:checked=move || *field(&mut settings.get().display)
+                on:change=move |ev| {
+                    let mut next = settings.get_untracked();
+                    *field(&mut next.display) = event_target_checked(&ev);
+                    on_change.run(next);
+                }
+            />
+        </label>
+    }
+}
+
+/// Tag names as typed in a todo, e.g. `+inbox +work`.
+fn tag_list(sigil: char, names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{sigil}{name}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The names in a space separated list of tags, with or without their sigil.
+fn parse_tag_list(sigil: char, text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_start_matches(sigil).to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Like [`parse_tag_list`], keeping the sigil on each name.
+fn tag_list_labels(sigil: char, text: &str) -> Vec<String> {
+    parse_tag_list(sigil, text)
+        .into_iter()
+        .map(|name| format!("{sigil}{name}"))
+        .collect()
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = crypto, js_name = randomUUID)]
+    fn random_uuid() -> String;
+}
+
+/// A new token for the server, unguessable and safe in a URL.
+fn random_token() -> String {
+    random_uuid().replace('-', "")
+}
+
+#[component]
+pub fn SettingsPage(
+    settings: ReadSignal<Settings>,
+    on_change: Callback<Settings>,
+    /// Called with the new separator and whether existing project tags should be rewritten.
+    on_separator_change: Callback<(String, bool)>,
+    /// Called with the address of a device to sync with now.
+    on_sync: Callback<String>,
+    /// Opens the keyboard shortcuts editor.
+    on_edit_shortcuts: Callback<()>,
+) -> impl IntoView {
+    let (new_device_name, set_new_device_name) = signal(String::new());
+    let (new_device_address, set_new_device_address) = signal(String::new());
+    let add_device = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let address = new_device_address.get_untracked().trim().to_string();
+        if address.is_empty() {
+            return;
+        }
+        let name = new_device_name.get_untracked().trim().to_string();
+        let mut next = settings.get_untracked();
+        next.sync.devices.push(SyncDevice {
+            name: if name.is_empty() {
+                address.clone()
+            } else {
+                name
+            },
+            address,
+        });
+        on_change.run(next);
+        set_new_device_name.set(String::new());
+        set_new_device_address.set(String::new());
+    };
+    // None until looked for, then the addresses found
+    let (found_devices, set_found_devices) = signal(Option::<Vec<String>>::None);
+    let (finding_devices, set_finding_devices) = signal(false);
+    let find_devices = move |_| {
+        set_finding_devices.set(true);
+        spawn_local(async move {
+            let result = invoke("find_sync_devices", JsValue::NULL).await;
+            set_found_devices.set(Some(
+                serde_wasm_bindgen::from_value::<Vec<String>>(result).unwrap_or_default(),
+            ));
+            set_finding_devices.set(false);
+        });
+    };
+
+    let (new_place_context, set_new_place_context) = signal(String::new());
+    let (new_place_radius, set_new_place_radius) = signal(DEFAULT_PLACE_RADIUS);
+    let (place_error, set_place_error) = signal(Option::<String>::None);
+    let add_place = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let context = new_place_context
+            .get_untracked()
+            .trim()
+            .trim_start_matches('@')
+            .to_string();
+        if context.is_empty() {
+            return;
+        }
+        set_place_error.set(None);
+        spawn_local(async move {
+            match geolocation::current_location().await {
+                Ok(location) => {
+                    let mut next = settings.get_untracked();
+                    next.context_locations.push(ContextLocation {
+                        context,
+                        location,
+                        radius: new_place_radius.get_untracked(),
+                    });
+                    on_change.run(next);
+                    set_new_place_context.set(String::new());
+                }
+                Err(e) => set_place_error.set(Some(format!("Failed to get location: {e}"))),
+            }
+        });
+    };
+
+    let (new_watched_name, set_new_watched_name) = signal(String::new());
+    let (new_watched_path, set_new_watched_path) = signal(String::new());
+    let add_watched_list = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let path = new_watched_path.get_untracked().trim().to_string();
+        let name = new_watched_name.get_untracked().trim().to_string();
+        if path.is_empty() || name.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        // Lists are looked up by name, so a second one with the same name replaces the first
+        next.watched_lists.retain(|list| list.name != name);
+        next.watched_lists.push(WatchedList { name, path });
+        on_change.run(next);
+        set_new_watched_name.set(String::new());
+        set_new_watched_path.set(String::new());
+    };
+
+    let (new_token_name, set_new_token_name) = signal(String::new());
+    let add_token = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let name = new_token_name.get_untracked().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        next.calendar.tokens.push(ApiToken {
+            name,
+            token: random_token(),
+            endpoints: Vec::new(),
+        });
+        on_change.run(next);
+        set_new_token_name.set(String::new());
+    };
+
+    let (new_snippet_trigger, set_new_snippet_trigger) = signal(String::new());
+    let (new_snippet_expansion, set_new_snippet_expansion) = signal(String::new());
+    let add_snippet = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let trigger = new_snippet_trigger.get_untracked().trim().to_string();
+        if trigger.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        next.snippets.retain(|s| s.trigger != trigger);
+        next.snippets.push(Snippet {
+            trigger,
+            expansion: new_snippet_expansion.get_untracked(),
+        });
+        on_change.run(next);
+        set_new_snippet_trigger.set(String::new());
+        set_new_snippet_expansion.set(String::new());
+    };
+
+    let (new_limit_tag, set_new_limit_tag) = signal(String::new());
+    let (new_limit_max, set_new_limit_max) = signal(3_usize);
+    let add_limit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let tag = new_limit_tag.get_untracked();
+        let tag = tag.trim();
+        let (kind, name) = match tag.strip_prefix('+') {
+            Some(name) => (TagKind::Project, name),
+            None => (TagKind::Context, tag.trim_start_matches('@')),
+        };
+        if name.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        next.wip_limits
+            .retain(|limit| limit.kind != kind || limit.name != name);
+        next.wip_limits.push(WipLimit {
+            kind,
+            name: name.to_string(),
+            max: new_limit_max.get_untracked(),
+        });
+        on_change.run(next);
+        set_new_limit_tag.set(String::new());
+    };
+
+    let (new_standing_title, set_new_standing_title) = signal(String::from("Weekly review"));
+    let (new_standing_weekday, set_new_standing_weekday) = signal(String::from("friday"));
+    let (new_standing_time, set_new_standing_time) = signal(String::from("16:00"));
+    let add_standing = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let title = new_standing_title.get_untracked().trim().to_string();
+        let time = new_standing_time.get_untracked();
+        if title.is_empty() || time.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        next.reminders.standing.push(StandingReminder {
+            title,
+            weekday: new_standing_weekday.get_untracked(),
+            time,
+        });
+        on_change.run(next);
+    };
+
+    let (compact_status, set_compact_status) = signal(Option::<String>::None);
+    let compact_now = move |_| {
+        spawn_local(async move {
+            let result = invoke("compact_archive", JsValue::NULL).await;
+            let status = match serde_wasm_bindgen::from_value::<Compaction>(result) {
+                Ok(Compaction { moved: 0, .. }) => "Nothing from past years to move.".to_string(),
+                Ok(compaction) => format!(
+                    "Moved {} tasks to {}.",
+                    compaction.moved,
+                    compaction.files.join(", ")
+                ),
+                Err(e) => format!("Failed to compact done.txt: {e}"),
+            };
+            set_compact_status.set(Some(status));
+        });
+    };
+
+    let (new_hook_event, set_new_hook_event) = signal(HookEvent::TaskAdded);
+    let (new_hook_command, set_new_hook_command) = signal(String::new());
+    let add_hook = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let command = new_hook_command.get_untracked().trim().to_string();
+        if command.is_empty() {
+            return;
+        }
+        let mut next = settings.get_untracked();
+        next.hooks.push(Hook {
+            event: new_hook_event.get_untracked(),
+            command,
+        });
+        on_change.run(next);
+        set_new_hook_command.set(String::new());
+    };
+
+    // What the last plugin command answered, or how it failed
+    let (plugin_status, set_plugin_status) = signal(Option::<String>::None);
+    let run_plugin_command = move |plugin: String, export: String, title: String| {
+        set_plugin_status.set(Some(format!("Running {title}…")));
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PluginExportArgs {
+                plugin: &plugin,
+                export: &export,
+            })
+            .unwrap();
+            let result = invoke("run_plugin_command", args).await;
+            let status = match serde_wasm_bindgen::from_value::<Option<String>>(result) {
+                Ok(Some(output)) => output,
+                Ok(None) => format!("{title} done."),
+                Err(e) => format!("{title} failed: {e}"),
+            };
+            set_plugin_status.set(Some(status));
+        });
+    };
+
+    // Re-list plugins whenever settings change, since grants change what's missing
+    let (plugins, set_plugins) = signal(Vec::<PluginInfo>::new());
+    Effect::new(move |_| {
+        settings.track();
+        spawn_local(async move {
+            let result = invoke("get_plugins", JsValue::NULL).await;
+            if let Ok(found) = serde_wasm_bindgen::from_value::<Vec<PluginInfo>>(result) {
+                set_plugins.set(found);
+            }
+        });
+    });
+
+    let (dnd_supported, set_dnd_supported) = signal(false);
+    spawn_local(async move {
+        let result = invoke("do_not_disturb_supported", JsValue::NULL).await;
+        if let Ok(supported) = serde_wasm_bindgen::from_value::<bool>(result) {
+            set_dnd_supported.set(supported);
+        }
+    });
+
+    // Logs and anonymized settings for bug reports, saved to the downloads folder
+    let (export_status, set_export_status) = signal(Option::<String>::None);
+    let export_diagnostics = move || {
+        spawn_local(async move {
+            let result = invoke("export_diagnostics", JsValue::NULL).await;
+            set_export_status.set(Some(
+                match serde_wasm_bindgen::from_value::<String>(result) {
+                    Ok(path) => format!("Saved to {path}"),
+                    Err(e) => format!("Failed to export diagnostics: {e}"),
+                },
+            ));
+        });
+    };
+
+    // The whole setup without secrets, to move to another machine
+    let (config_status, set_config_status) = signal(Option::<String>::None);
+    let (import_path, set_import_path) = signal(String::new());
+    let export_config = move || {
+        spawn_local(async move {
+            let result = invoke("export_config", JsValue::NULL).await;
+            set_config_status.set(Some(
+                match serde_wasm_bindgen::from_value::<String>(result) {
+                    Ok(path) => format!("Saved to {path}"),
+                    Err(e) => format!("Failed to export settings: {e}"),
+                },
+            ));
+        });
+    };
+    let import_config = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let path = import_path.get_untracked().trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ImportConfigArgs { path: &path }).unwrap();
+            let result = invoke("import_config", args).await;
+            match serde_wasm_bindgen::from_value::<Settings>(result) {
+                Ok(imported) => {
+                    on_change.run(imported);
+                    set_import_path.set(String::new());
+                    set_config_status.set(Some("Imported".to_string()));
+                }
+                Err(e) => set_config_status.set(Some(format!("Failed to import settings: {e}"))),
+            }
+        });
+    };
+
+    // Tidying up lines on save rewrites the whole file, so turning it on
+    // shows what would change first
+    let (normalize_preview, set_normalize_preview) =
+        signal(Option::<(Settings, Vec<Replacement>)>::None);
+    let (normalize_error, set_normalize_error) = signal(Option::<String>::None);
+    let set_normalize = move |normalize: NormalizeSettings| {
+        let mut next = settings.get_untracked();
+        next.normalize = normalize;
+        if !next.normalize.enabled {
+            set_normalize_preview.set(None);
+            on_change.run(next);
+            return;
+        }
+        spawn_local(async move {
+            let args = PreviewNormalizeArgs {
+                settings: &next.normalize,
+            };
+            let result = invoke(
+                "preview_normalize",
+                serde_wasm_bindgen::to_value(&args).unwrap(),
+            )
+            .await;
+            match serde_wasm_bindgen::from_value::<Vec<Replacement>>(result) {
+                Ok(lines) if lines.is_empty() => on_change.run(next),
+                Ok(lines) => {
+                    set_normalize_error.set(None);
+                    set_normalize_preview.set(Some((next, lines)));
+                }
+                Err(e) => {
+                    set_normalize_error.set(Some(format!("Failed to preview tidying up: {e}")))
+                }
+            }
+        });
+    };
+
+    let (separator, set_separator) = signal(String::new());
+    let (migrate, set_migrate) = signal(true);
+    // Start editing from the saved value whenever it changes
+    Effect::new(move |_| set_separator.set(settings.with(|s| s.project_separator.clone())));
+    let separator_valid =
+        move || separator.with(|s| !s.is_empty() && !s.contains(char::is_whitespace));
+    let separator_changed =
+        move || separator.get() != settings.with(|s| s.project_separator.clone());
+
+    let set_density = move |density: Density| {
+        let mut next = settings.get_untracked();
+        next.display.density = density;
+        on_change.run(next);
+    };
+
+    let set_count_mode = move |mode: CountMode| {
+        let mut next = settings.get_untracked();
+        next.display.project_count = mode;
+        on_change.run(next);
+    };
+
+    let set_completion = move |completion: Completion| {
+        let mut next = settings.get_untracked();
+        next.completion = completion;
+        on_change.run(next);
+    };
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body">
+                <h2 class="card-title">"Display"</h2>
+                <fieldset class="flex items-center justify-between py-2">
+                    <legend class="sr-only">"Row density"</legend>
+                    <span>"Row density"</span>
+                    <div class="join">
+                        <button
+                            type="button"
+                            class="btn btn-sm join-item"
+                            class=("btn-primary", move || settings.get().display.density == Density::Compact)
+                            aria-pressed=move || (settings.get().display.density == Density::Compact).to_string()
+                            on:click=move |_| set_density(Density::Compact)
+                        >
+                            "Compact"
+                        </button>
+                        <button
+                            type="button"
+                            class="btn btn-sm join-item"
+                            class=("btn-primary", move || settings.get().display.density == Density::Comfortable)
+                            aria-pressed=move || (settings.get().display.density == Density::Comfortable).to_string()
+                            on:click=move |_| set_density(Density::Comfortable)
+                        >
+                            "Comfortable"
+                        </button>
+                    </div>
+                </fieldset>
+                <label class="label justify-between py-2">
+                    <span>{move || settings.with(|s| {
+                        let (zoom_in, zoom_out) = (s.keymap.keys(ShortcutAction::ZoomIn), s.keymap.keys(ShortcutAction::ZoomOut));
+                        if zoom_in.is_empty() || zoom_out.is_empty() {
+                            format!("Zoom ({}%)", s.display.zoom)
+                        } else {
+                            format!("Zoom ({}%, {zoom_in} and {zoom_out})", s.display.zoom)
+                        }
+                    })}</span>
+                    <input
+                        type="range"
+                        class="range range-sm w-48"
+                        min=DisplaySettings::ZOOM_MIN
+                        max=DisplaySettings::ZOOM_MAX
+                        step=DisplaySettings::ZOOM_STEP
+                        prop:value=move || settings.get().display.zoom.to_string()
+                        on:change=move |ev| {
+                            if let Ok(zoom) = event_target_value(&ev).parse() {
+                                let mut next = settings.get_untracked();
+                                next.display.zoom = zoom;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Badge colors"</span>
+                    <select
+                        class="select select-bordered select-sm w-48"
+                        on:change=move |ev| {
+                            let name = event_target_value(&ev);
+                            if let Some(palette) = Palette::ALL.into_iter().find(|p| p.name() == name) {
+                                let mut next = settings.get_untracked();
+                                next.display.palette = palette;
+                                on_change.run(next);
+                            }
+                        }
+                    >
+                        {Palette::ALL.into_iter().map(|palette| view! {
+                            <option value=palette.name() selected=move || settings.get().display.palette == palette>{palette.label()}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                {toggle_row("Show creation date", settings, on_change, |d| &mut d.show_creation_date)}
+                {toggle_row("Show due date", settings, on_change, |d| &mut d.show_due_date)}
+                {toggle_row("Show raw todo.txt line", settings, on_change, |d| &mut d.show_raw)}
+                {toggle_row("Show id", settings, on_change, |d| &mut d.show_id)}
+                {toggle_row("Show bold, code and links in todo text", settings, on_change, |d| &mut d.render_markdown)}
+                {toggle_row("Checkmark on completion", settings, on_change, |d| &mut d.celebrate)}
+                {toggle_row("Sound on completion", settings, on_change, |d| &mut d.completion_sound)}
+                {toggle_row("Demo mode (lorem ipsum instead of tasks, read-only)", settings, on_change, |d| &mut d.demo_mode)}
+                <label class="label justify-between py-2">
+                    <span>"Custom CSS file"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="/path/to/theme.css"
+                        prop:value=move || settings.get().custom_css.unwrap_or_default()
+                        on:change=move |ev| {
+                            let path = event_target_value(&ev).trim().to_string();
+                            let mut next = settings.get_untracked();
+                            next.custom_css = (!path.is_empty()).then_some(path);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <fieldset class="flex items-center justify-between py-2">
+                    <legend class="sr-only">"Project tree counts"</legend>
+                    <span>"Project tree counts"</span>
+                    <div class="join">
+                        <button
+                            type="button"
+                            class="btn btn-sm join-item"
+                            class=("btn-primary", move || settings.get().display.project_count == CountMode::All)
+                            aria-pressed=move || (settings.get().display.project_count == CountMode::All).to_string()
+                            on:click=move |_| set_count_mode(CountMode::All)
+                        >
+                            "All"
+                        </button>
+                        <button
+                            type="button"
+                            class="btn btn-sm join-item"
+                            class=("btn-primary", move || settings.get().display.project_count == CountMode::Pending)
+                            aria-pressed=move || (settings.get().display.project_count == CountMode::Pending).to_string()
+                            on:click=move |_| set_count_mode(CountMode::Pending)
+                        >
+                            "Pending"
+                        </button>
+                    </div>
+                </fieldset>
+                <fieldset class="flex items-center justify-between py-2">
+                    <legend class="sr-only">"Completed todos"</legend>
+                    <span>"Completed todos"</span>
+                    <div class="join">
+                        {[
+                            (Completion::Strike, "Strike through"),
+                            (Completion::MoveToBottom, "Move to bottom"),
+                            (Completion::Hide, "Hide"),
+                        ]
+                            .into_iter()
+                            .map(|(completion, label)| view! {
+                                <button
+                                    type="button"
+                                    class="btn btn-sm join-item"
+                                    class=("btn-primary", move || settings.get().completion == completion)
+                                    aria-pressed=move || (settings.get().completion == completion).to_string()
+                                    on:click=move |_| set_completion(completion)
+                                >
+                                    {label}
+                                </button>
+                            })
+                            .collect_view()}
+                    </div>
+                </fieldset>
+                <label class="label justify-between py-2">
+                    <span>"Group the list by"</span>
+                    <select
+                        class="select select-bordered select-sm w-32"
+                        on:change=move |ev| {
+                            let name = event_target_value(&ev);
+                            if let Some(by) = GroupBy::ALL.into_iter().find(|by| by.name() == name) {
+                                let mut next = settings.get_untracked();
+                                next.group.by = by;
+                                on_change.run(next);
+                            }
+                        }
+                    >
+                        {GroupBy::ALL.into_iter().map(|by| view! {
+                            <option value=by.name() selected=move || settings.get().group.by == by>{by.name()}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label
+                    class="label justify-between py-2"
+                    class=("hidden", move || settings.get().group.by == GroupBy::None)
+                >
+                    <span>"Group order (the rest follow by name)"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder=move || match settings.get().group.by {
+                            GroupBy::Priority => "A B C",
+                            GroupBy::Project => "+work +home",
+                            _ => "@todo @doing @review",
+                        }
+                        prop:value=move || settings.get().group.order().join(" ")
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            let by = next.group.by;
+                            if let Some(order) = next.group.order_mut() {
+                                let text = event_target_value(&ev);
+                                *order = match by {
+                                    GroupBy::Priority => text.split_whitespace().map(str::to_uppercase).collect(),
+                                    GroupBy::Project => tag_list_labels('+', &text),
+                                    _ => tag_list_labels('@', &text),
+                                };
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Confirm completing critical todos"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().confirm.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.confirm.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Priority A todos are critical"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:disabled=move || !settings.get().confirm.enabled
+                        prop:checked=move || settings.get().confirm.priority_a
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.confirm.priority_a = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"So are todos in project"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="work"
+                        prop:disabled=move || !settings.get().confirm.enabled
+                        prop:value=move || {
+                            settings.get().confirm.filter.and_then(|filter| filter.project).unwrap_or_default()
+                        }
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            let project = event_target_value(&ev).trim().trim_start_matches('+').to_string();
+                            next.confirm.filter = (!project.is_empty()).then(|| Filter {
+                                project: Some(project),
+                                status: Status::Pending,
+                                ..Filter::default()
+                            });
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Tidy up lines on save"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || {
+                            settings.get().normalize.enabled || normalize_preview.with(Option::is_some)
+                        }
+                        on:change=move |ev| {
+                            let normalize = settings.get_untracked().normalize;
+                            set_normalize(NormalizeSettings { enabled: event_target_checked(&ev), ..normalize });
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Move projects and contexts to the end"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:disabled=move || !settings.get().normalize.enabled
+                        prop:checked=move || settings.get().normalize.order_tags
+                        on:change=move |ev| {
+                            let normalize = settings.get_untracked().normalize;
+                            set_normalize(NormalizeSettings { order_tags: event_target_checked(&ev), ..normalize });
+                        }
+                    />
+                </label>
+                {move || normalize_error.get().map(|e| view! { <p class="text-sm text-error">{e}</p> })}
+                {move || normalize_preview.get().map(|(next, lines)| view! {
+                    <div class="rounded-box border border-base-300 p-2" role="group" aria-label="Lines tidied up on save">
+                        <p class="text-sm">{format!("Saving will rewrite {} lines:", lines.len())}</p>
+                        <ul class="list max-h-64 overflow-y-auto">
+                            {lines.into_iter().map(|line| view! {
+                                <li class="list-row p-2 block">
+                                    <div class="font-mono text-xs opacity-60 line-through">{line.before}</div>
+                                    <div class="font-mono text-xs">{line.after}</div>
+                                </li>
+                            }).collect_view()}
+                        </ul>
+                        <div class="flex justify-end gap-2 mt-2">
+                            <button type="button" class="btn btn-sm" on:click=move |_| set_normalize_preview.set(None)>
+                                "Cancel"
+                            </button>
+                            <button
+                                type="button"
+                                class="btn btn-sm btn-primary"
+                                on:click=move |_| {
+                                    set_normalize_preview.set(None);
+                                    on_change.run(next.clone());
+                                }
+                            >
+                                "Apply"
+                            </button>
+                        </div>
+                    </div>
+                })}
+
+                <h2 class="card-title mt-4">"Projects"</h2>
+                <label class="label justify-between py-2">
+                    <span>"Hierarchy separator"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-24 font-mono"
+                        class=("input-error", move || !separator_valid())
+                        aria-invalid=move || (!separator_valid()).to_string()
+                        prop:value=move || separator.get()
+                        on:input=move |ev| set_separator.set(event_target_value(&ev))
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Rewrite existing project tags"</span>
+                    <input
+                        type="checkbox"
+                        class="checkbox checkbox-primary"
+                        prop:checked=move || migrate.get()
+                        on:change=move |ev| set_migrate.set(event_target_checked(&ev))
+                    />
+                </label>
+                <div class="flex justify-end">
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-primary"
+                        prop:disabled=move || !separator_valid() || !separator_changed()
+                        on:click=move |_| on_separator_change.run((separator.get_untracked(), migrate.get_untracked()))
+                    >
+                        "Apply"
+                    </button>
+                </div>
+
+                <h2 class="card-title mt-4">"Sync"</h2>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Allow paired devices to sync with this one"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().sync.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.sync.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Port"</span>
+                    <input
+                        type="number"
+                        min="1"
+                        max="65535"
+                        class="input input-bordered input-sm w-28"
+                        prop:value=move || settings.get().sync.port.to_string()
+                        on:change=move |ev| {
+                            if let Ok(port) = event_target_value(&ev).parse::<u16>() {
+                                let mut next = settings.get_untracked();
+                                next.sync.port = port;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Pairing code"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-40 font-mono"
+                        placeholder="Same on every device"
+                        minlength=MIN_PAIRING_CODE.to_string()
+                        prop:value=move || settings.get().sync.pairing_code
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.sync.pairing_code = event_target_value(&ev).trim().to_string();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <p class="text-sm opacity-60">{format!("The pairing code needs at least {MIN_PAIRING_CODE} characters; a long random one keeps other devices from guessing it. Changes to these take effect after restarting the app.")}</p>
+                <ul class="mt-2" aria-label="Paired devices">
+                    {move || settings.get().sync.devices.into_iter().enumerate().map(|(index, device)| {
+                        let address = device.address.clone();
+                        view! {
+                            <li class="flex items-center justify-between gap-2 py-1">
+                                <span>
+                                    {device.name.clone()}
+                                    <span class="text-sm opacity-60 font-mono">{format!(" {}", device.address)}</span>
+                                </span>
+                                <div class="flex gap-1">
+                                    <button type="button" class="btn btn-primary btn-xs" on:click=move |_| on_sync.run(address.clone())>
+                                        "Sync now"
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="btn btn-ghost btn-xs"
+                                        aria-label=format!("Remove {}", device.name)
+                                        on:click=move |_| {
+                                            let mut next = settings.get_untracked();
+                                            next.sync.devices.remove(index);
+                                            on_change.run(next);
+                                        }
+                                    >
+                                        "Remove"
+                                    </button>
+                                </div>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_device>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1"
+                        placeholder="Name"
+                        aria-label="Device name"
+                        prop:value=move || new_device_name.get()
+                        on:input=move |ev| set_new_device_name.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="host:port"
+                        aria-label="Device address"
+                        prop:value=move || new_device_address.get()
+                        on:input=move |ev| set_new_device_address.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Add device"</button>
+                </form>
+                <div class="flex items-center gap-2 mt-2">
+                    <button
+                        type="button"
+                        class="btn btn-sm"
+                        disabled=move || finding_devices.get()
+                        on:click=find_devices
+                    >
+                        "Find devices"
+                    </button>
+                    <span class="text-sm opacity-60">"Finds devices on this network that allow syncing on the same port."</span>
+                </div>
+                {move || found_devices.get().map(|found| if found.is_empty() {
+                    view! { <p class="text-sm opacity-60 mt-1">"No other devices found."</p> }.into_any()
+                } else {
+                    view! {
+                        <ul class="mt-1" aria-label="Devices found">
+                            {found.into_iter().map(|address| {
+                                let add = address.clone();
+                                view! {
+                                    <li class="flex items-center justify-between gap-2 py-1">
+                                        <span class="font-mono text-sm">{address.clone()}</span>
+                                        <button
+                                            type="button"
+                                            class="btn btn-ghost btn-xs"
+                                            aria-label=format!("Pair {address}")
+                                            on:click=move |_| {
+                                                let mut next = settings.get_untracked();
+                                                next.sync.devices.push(SyncDevice {
+                                                    name: add.clone(),
+                                                    address: add.clone(),
+                                                });
+                                                on_change.run(next);
+                                                set_found_devices.update(|found| {
+                                                    if let Some(found) = found {
+                                                        found.retain(|other| *other != add);
+                                                    }
+                                                });
+                                            }
+                                        >
+                                            "Add"
+                                        </button>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    }.into_any()
+                })}
+
+                <h2 class="card-title mt-4">"Reminders"</h2>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Remind me of tasks due today"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().reminders.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.reminders.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"At"</span>
+                    <input
+                        type="time"
+                        class="input input-bordered input-sm w-32"
+                        prop:value=move || settings.get().reminders.time
+                        on:change=move |ev| {
+                            let time = event_target_value(&ev);
+                            if !time.is_empty() {
+                                let mut next = settings.get_untracked();
+                                next.reminders.time = time;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <p class="text-sm opacity-60">"Reminders missed while the computer was asleep arrive together when it wakes up, as do those held back in quiet hours when they end."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Quiet hours"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().reminders.quiet_hours
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.reminders.quiet_hours = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <div class="flex items-center justify-end gap-2 py-2">
+                    <input
+                        type="time"
+                        class="input input-bordered input-sm w-32"
+                        aria-label="Quiet hours start"
+                        prop:value=move || settings.get().reminders.quiet_start
+                        on:change=move |ev| {
+                            let time = event_target_value(&ev);
+                            if !time.is_empty() {
+                                let mut next = settings.get_untracked();
+                                next.reminders.quiet_start = time;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                    <span>"to"</span>
+                    <input
+                        type="time"
+                        class="input input-bordered input-sm w-32"
+                        aria-label="Quiet hours end"
+                        prop:value=move || settings.get().reminders.quiet_end
+                        on:change=move |ev| {
+                            let time = event_target_value(&ev);
+                            if !time.is_empty() {
+                                let mut next = settings.get_untracked();
+                                next.reminders.quiet_end = time;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </div>
+                <label class="label justify-between py-2">
+                    <span>"Only for priority"</span>
+                    <select
+                        class="select select-bordered select-sm w-32"
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.reminders.min_priority = event_target_value(&ev).chars().next();
+                            on_change.run(next);
+                        }
+                    >
+                        <option value="" selected=move || settings.get().reminders.min_priority.is_none()>"Any"</option>
+                        {('A'..='Z').map(|letter| view! {
+                            <option value=letter.to_string() selected=move || settings.get().reminders.min_priority == Some(letter)>
+                                {if letter == 'A' { "A".to_string() } else { format!("{letter} or higher") }}
+                            </option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Muted projects"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="+someday"
+                        prop:value=move || settings.with(|s| tag_list('+', &s.reminders.muted_projects))
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.reminders.muted_projects = parse_tag_list('+', &event_target_value(&ev));
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <h3 class="font-semibold mt-2">"Standing reminders"</h3>
+                <p class="text-sm opacity-60">"Reminders of your own, e.g. for a weekly review. Clicking one opens the Review."</p>
+                <ul class="mt-2" aria-label="Standing reminders">
+                    {move || settings.get().reminders.standing.into_iter().enumerate().map(|(index, reminder)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span>
+                                {reminder.title.clone()}
+                                <span class="text-sm opacity-60">
+                                    {match reminder.weekday.as_str() {
+                                        "" => format!(" every day at {}", reminder.time),
+                                        weekday => format!(" every {weekday} at {}", reminder.time),
+                                    }}
+                                </span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Remove reminder {}", reminder.title)
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.reminders.standing.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_standing>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1"
+                        aria-label="Reminder title"
+                        prop:value=move || new_standing_title.get()
+                        on:input=move |ev| set_new_standing_title.set(event_target_value(&ev))
+                    />
+                    <select
+                        class="select select-bordered select-sm w-36"
+                        aria-label="Reminder day"
+                        on:change=move |ev| set_new_standing_weekday.set(event_target_value(&ev))
+                    >
+                        <option value="" selected=move || new_standing_weekday.get().is_empty()>"Every day"</option>
+                        {["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"].map(|day| view! {
+                            <option value=day selected=move || new_standing_weekday.get() == day>{day}</option>
+                        }).collect_view()}
+                    </select>
+                    <input
+                        type="time"
+                        class="input input-bordered input-sm w-28"
+                        aria-label="Reminder time"
+                        prop:value=move || new_standing_time.get()
+                        on:input=move |ev| set_new_standing_time.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Add reminder"</button>
+                </form>
+
+                <h2 class="card-title mt-4">"Calendar"</h2>
+                <p class="text-sm opacity-60">"Lets calendar apps subscribe to your due dates."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Serve a calendar subscription"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().calendar.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.calendar.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Allow other devices on the network"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().calendar.network
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.calendar.network = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Port"</span>
+                    <input
+                        type="number"
+                        min="1"
+                        max="65535"
+                        class="input input-bordered input-sm w-28"
+                        prop:value=move || settings.get().calendar.port.to_string()
+                        on:change=move |ev| {
+                            if let Ok(port) = event_target_value(&ev).parse::<u16>() {
+                                let mut next = settings.get_untracked();
+                                next.calendar.port = port;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Access token"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-40 font-mono"
+                        placeholder="Letters and digits"
+                        prop:value=move || settings.get().calendar.token
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            // Kept to characters that need no escaping in the URL
+                            next.calendar.token = event_target_value(&ev)
+                                .chars()
+                                .filter(char::is_ascii_alphanumeric)
+                                .collect();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Web viewer directory"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-60 font-mono"
+                        placeholder="e.g. /home/me/todotxt/gui/dist-web"
+                        aria-describedby="web-dir-hint"
+                        prop:value=move || settings.get().calendar.web_dir
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.calendar.web_dir = event_target_value(&ev).trim().to_string();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <p id="web-dir-hint" class="text-sm opacity-60">
+                    "The read-only web viewer, served at "
+                    <span class="font-mono">"http://<this computer>:<port>/?token=…"</span>
+                    " from its build in this directory."
+                </p>
+                <h3 class="font-semibold mt-2">"Other clients"</h3>
+                <p class="text-sm opacity-60">
+                    "Tokens for e.g. a dashboard, sent as "<span class="font-mono">"?token="</span>" or "
+                    <span class="font-mono">"Authorization: Bearer"</span>". Each may only use the ticked endpoints, none until one is ticked, and only read."
+                </p>
+                <ul class="mt-2" aria-label="Access tokens">
+                    {move || settings.get().calendar.tokens.into_iter().enumerate().map(|(index, token)| {
+                        let name = token.name.clone();
+                        view! {
+                            <li class="flex flex-wrap items-center gap-2 py-1">
+                                <span class="flex-1">
+                                    {token.name.clone()}
+                                    <span class="text-sm opacity-60 font-mono select-all">{format!(" {}", token.token)}</span>
+                                </span>
+                                {Endpoint::ALL.into_iter().map(|endpoint| {
+                                    let allowed = token.endpoints.contains(&endpoint);
+                                    view! {
+                                        <label class="label cursor-pointer gap-1 text-sm">
+                                            <input
+                                                type="checkbox"
+                                                class="checkbox checkbox-xs"
+                                                prop:checked=allowed
+                                                on:change=move |ev| {
+                                                    let mut next = settings.get_untracked();
+                                                    let endpoints = &mut next.calendar.tokens[index].endpoints;
+                                                    endpoints.retain(|e| *e != endpoint);
+                                                    if event_target_checked(&ev) {
+                                                        endpoints.push(endpoint);
+                                                    }
+                                                    on_change.run(next);
+                                                }
+                                            />
+                                            {endpoint.label()}
+                                        </label>
+                                    }
+                                }).collect_view()}
+                                <button
+                                    type="button"
+                                    class="btn btn-ghost btn-xs"
+                                    aria-label=format!("Revoke the token of {name}")
+                                    on:click=move |_| {
+                                        let mut next = settings.get_untracked();
+                                        next.calendar.tokens.remove(index);
+                                        on_change.run(next);
+                                    }
+                                >
+                                    "Revoke"
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_token>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1"
+                        placeholder="Kitchen dashboard"
+                        aria-label="Token name"
+                        prop:value=move || new_token_name.get()
+                        on:input=move |ev| set_new_token_name.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Add token"</button>
+                </form>
+                <p class="text-sm opacity-60">
+                    "Changes to these take effect after restarting the app. Subscribe to "
+                    <span class="font-mono select-all">
+                        {move || settings.with(|s| format!(
+                            "webcal://<this computer>:{}/calendar.ics?token={}",
+                            s.calendar.port,
+                            s.calendar.token,
+                        ))}
+                    </span>
+                </p>
+
+                <h2 class="card-title mt-4">"New tasks"</h2>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Add creation date"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().task_defaults.creation_date
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.task_defaults.creation_date = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Default priority"</span>
+                    <select
+                        class="select select-bordered select-sm w-24"
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.task_defaults.priority = event_target_value(&ev).chars().next();
+                            on_change.run(next);
+                        }
+                    >
+                        <option value="" selected=move || settings.get().task_defaults.priority.is_none()>"None"</option>
+                        {('A'..='Z').map(|letter| view! {
+                            <option value=letter.to_string() selected=move || settings.get().task_defaults.priority == Some(letter)>
+                                {letter.to_string()}
+                            </option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Default projects"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="+inbox"
+                        prop:value=move || settings.with(|s| tag_list('+', &s.task_defaults.projects))
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.task_defaults.projects = parse_tag_list('+', &event_target_value(&ev));
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Default contexts"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="@home"
+                        prop:value=move || settings.with(|s| tag_list('@', &s.task_defaults.contexts))
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.task_defaults.contexts = parse_tag_list('@', &event_target_value(&ev));
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Capture shared text to the inbox"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().capture_to_inbox
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.capture_to_inbox = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Snippets"</h2>
+                <p class="text-sm opacity-60">"Typing a trigger in a todo field replaces it with its expansion."</p>
+                <ul class="mt-2" aria-label="Snippets">
+                    {move || settings.get().snippets.into_iter().enumerate().map(|(index, snippet)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span class="font-mono">
+                                {snippet.trigger.clone()}
+                                <span class="opacity-60">{format!(" → {}", snippet.expansion)}</span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Remove snippet {}", snippet.trigger)
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.snippets.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_snippet>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-24 font-mono"
+                        placeholder=";;w"
+                        aria-label="Snippet trigger"
+                        prop:value=move || new_snippet_trigger.get()
+                        on:input=move |ev| set_new_snippet_trigger.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="@work due:friday"
+                        aria-label="Snippet expansion"
+                        prop:value=move || new_snippet_expansion.get()
+                        on:input=move |ev| set_new_snippet_expansion.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Add snippet"</button>
+                </form>
+
+                <h2 class="card-title mt-4">"WIP limits"</h2>
+                <p class="text-sm opacity-60">
+                    "Adding or moving a task over a limit shows a warning and tags the task overflow:1."
+                </p>
+                <ul class="mt-2" aria-label="WIP limits">
+                    {move || settings.get().wip_limits.into_iter().enumerate().map(|(index, limit)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span class="font-mono">
+                                {limit.tag()}
+                                <span class="opacity-60">{format!(" at most {}", limit.max)}</span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Remove the limit on {}", limit.tag())
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.wip_limits.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_limit>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="@doing"
+                        aria-label="Limited context or project"
+                        prop:value=move || new_limit_tag.get()
+                        on:input=move |ev| set_new_limit_tag.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="number"
+                        min="1"
+                        class="input input-bordered input-sm w-24"
+                        aria-label="Most open tasks"
+                        prop:value=move || new_limit_max.get().to_string()
+                        on:change=move |ev| {
+                            if let Some(max) = event_target_value(&ev).parse::<usize>().ok().filter(|&m| m > 0) {
+                                set_new_limit_max.set(max);
+                            }
+                        }
+                    />
+                    <button type="submit" class="btn btn-sm">"Add limit"</button>
+                </form>
+
+                <h2 class="card-title mt-4">"Places"</h2>
+                <p class="text-sm opacity-60">"The Here view shows the todos for contexts placed near where you are."</p>
+                <ul class="mt-2" aria-label="Context places">
+                    {move || settings.get().context_locations.into_iter().enumerate().map(|(index, place)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span>
+                                {format!("@{}", place.context)}
+                                <span class="text-sm opacity-60 font-mono">
+                                    {format!(
+                                        " {:.5}, {:.5} ({} m)",
+                                        place.location.latitude,
+                                        place.location.longitude,
+                                        place.radius,
+                                    )}
+                                </span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Remove the place of @{}", place.context)
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.context_locations.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_place>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="@store"
+                        aria-label="Context"
+                        prop:value=move || new_place_context.get()
+                        on:input=move |ev| set_new_place_context.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="number"
+                        min="10"
+                        class="input input-bordered input-sm w-28"
+                        aria-label="Radius in meters"
+                        prop:value=move || new_place_radius.get().to_string()
+                        on:change=move |ev| {
+                            if let Ok(radius) = event_target_value(&ev).parse::<f64>() {
+                                set_new_place_radius.set(radius);
+                            }
+                        }
+                    />
+                    <button type="submit" class="btn btn-sm">"Add at my location"</button>
+                </form>
+                {move || place_error.get().map(|e| view! { <p class="text-sm text-error mt-1" role="alert">{e}</p> })}
+
+                <h2 class="card-title mt-4">"Watched lists"</h2>
+                <p class="text-sm opacity-60">
+                    "Other people's todo.txt files, e.g. a partner's synced list. They show up in the sidebar and can't be changed from here."
+                </p>
+                <ul class="mt-2" aria-label="Watched lists">
+                    {move || settings.get().watched_lists.into_iter().enumerate().map(|(index, list)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span>
+                                {list.name.clone()}
+                                <span class="text-sm opacity-60 font-mono">{format!(" {}", list.path)}</span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Stop watching {}", list.name)
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.watched_lists.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_watched_list>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-32"
+                        placeholder="Name"
+                        aria-label="Watched list name"
+                        prop:value=move || new_watched_name.get()
+                        on:input=move |ev| set_new_watched_name.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="/path/to/todo.txt"
+                        aria-label="Watched list file"
+                        prop:value=move || new_watched_path.get()
+                        on:input=move |ev| set_new_watched_path.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Watch"</button>
+                </form>
+
+                <h2 class="card-title mt-4">"Feed"</h2>
+                <p class="text-sm opacity-60">"An Atom feed of matching todos, rewritten on every change for feed readers and dashboards."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Write a feed"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().feed.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.feed.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Feed file"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="/path/to/todo.atom"
+                        prop:value=move || settings.get().feed.path
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.feed.path = event_target_value(&ev).trim().to_string();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Include"</span>
+                    <select
+                        class="select select-bordered select-sm w-40"
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.feed.filter.due = match event_target_value(&ev).as_str() {
+                                "today" => Some(Due::Today),
+                                "upcoming" => Some(Due::Upcoming),
+                                _ => None,
+                            };
+                            on_change.run(next);
+                        }
+                    >
+                        <option value="today" selected=move || settings.get().feed.filter.due == Some(Due::Today)>
+                            "Due today or overdue"
+                        </option>
+                        <option value="upcoming" selected=move || settings.get().feed.filter.due == Some(Due::Upcoming)>
+                            "Due later"
+                        </option>
+                        <option value="" selected=move || settings.get().feed.filter.due.is_none()>"All pending"</option>
+                    </select>
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Only project"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="work"
+                        prop:value=move || settings.get().feed.filter.project.unwrap_or_default()
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            let project = event_target_value(&ev).trim().trim_start_matches('+').to_string();
+                            next.feed.filter.project = (!project.is_empty()).then_some(project);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Only context"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="home"
+                        prop:value=move || settings.get().feed.filter.context.unwrap_or_default()
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            let context = event_target_value(&ev).trim().trim_start_matches('@').to_string();
+                            next.feed.filter.context = (!context.is_empty()).then_some(context);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Widget snapshot"</h2>
+                <p class="text-sm opacity-60">"A JSON summary rewritten on every change, for desktop widgets like Conky or waybar."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Write a snapshot"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().snapshot.enabled
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.snapshot.enabled = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Snapshot file"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="/path/to/todo.json"
+                        prop:value=move || settings.get().snapshot.path
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.snapshot.path = event_target_value(&ev).trim().to_string();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Tasks listed"</span>
+                    <input
+                        type="number"
+                        min="0"
+                        class="input input-bordered input-sm w-28"
+                        prop:value=move || settings.get().snapshot.limit.to_string()
+                        on:change=move |ev| {
+                            if let Ok(limit) = event_target_value(&ev).parse::<usize>() {
+                                let mut next = settings.get_untracked();
+                                next.snapshot.limit = limit;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Only project"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-64 font-mono"
+                        placeholder="work"
+                        prop:value=move || settings.get().snapshot.filter.project.unwrap_or_default()
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            let project = event_target_value(&ev).trim().trim_start_matches('+').to_string();
+                            next.snapshot.filter.project = (!project.is_empty()).then_some(project);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Include counts"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().snapshot.include_counts
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.snapshot.include_counts = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Include the next due task"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().snapshot.include_next_due
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.snapshot.include_next_due = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Hooks"</h2>
+                <p class="text-sm opacity-60">
+                    "Programs run with the event name as argument and the task or changes as JSON on stdin. "
+                    "A failing before-save hook stops the save."
+                </p>
+                <ul class="mt-2" aria-label="Hooks">
+                    {move || settings.get().hooks.into_iter().enumerate().map(|(index, hook)| view! {
+                        <li class="flex items-center justify-between gap-2 py-1">
+                            <span>
+                                {hook_label(hook.event)}
+                                <span class="text-sm opacity-60 font-mono">{format!(" {}", hook.command)}</span>
+                            </span>
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                aria-label=format!("Remove hook {}", hook.command)
+                                on:click=move |_| {
+                                    let mut next = settings.get_untracked();
+                                    next.hooks.remove(index);
+                                    on_change.run(next);
+                                }
+                            >
+                                "Remove"
+                            </button>
+                        </li>
+                    }).collect_view()}
+                </ul>
+                <form class="flex gap-2 mt-2" on:submit=add_hook>
+                    <select
+                        class="select select-bordered select-sm"
+                        aria-label="Hook event"
+                        on:change=move |ev| {
+                            let name = event_target_value(&ev);
+                            if let Some(event) = HookEvent::ALL.into_iter().find(|e| e.name() == name) {
+                                set_new_hook_event.set(event);
+                            }
+                        }
+                    >
+                        {HookEvent::ALL.into_iter().map(|event| view! {
+                            <option value=event.name() selected=move || new_hook_event.get() == event>{hook_label(event)}</option>
+                        }).collect_view()}
+                    </select>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1 font-mono"
+                        placeholder="/path/to/executable"
+                        aria-label="Hook command"
+                        prop:value=move || new_hook_command.get()
+                        on:input=move |ev| set_new_hook_command.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm">"Add hook"</button>
+                </form>
+
+                <h2 class="card-title mt-4">"Plugins"</h2>
+                {move || plugins.with(Vec::is_empty).then(|| view! {
+                    <p class="text-sm opacity-60">"No plugins installed."</p>
+                })}
+                <ul aria-label="Plugins">
+                    {move || plugins.get().into_iter().map(|plugin| {
+                        let Some(manifest) = plugin.manifest else {
+                            return view! {
+                                <li class="py-1">
+                                    <span class="font-mono">{plugin.dir}</span>
+                                    <span class="text-sm text-error">{format!(" {}", plugin.error.unwrap_or_default())}</span>
+                                </li>
+                            }.into_any();
+                        };
+                        let granted = plugin.missing.is_empty();
+                        let name = manifest.name.clone();
+                        let capabilities = manifest.capabilities.clone();
+                        let commands = granted.then(|| manifest.commands.clone()).unwrap_or_default();
+                        view! {
+                            <li class="flex items-center justify-between gap-2 py-1">
+                                <div>
+                                    <div>{manifest.name.clone()}<span class="text-sm opacity-60">{format!(" {}", manifest.version)}</span></div>
+                                    <div class="text-sm opacity-60">{manifest.description.clone()}</div>
+                                    <div class="text-xs opacity-60">
+                                        {manifest.capabilities.iter().map(|c| c.description()).collect::<Vec<_>>().join(", ")}
+                                    </div>
+                                    <div class="flex flex-wrap gap-1 mt-1">
+                                        {commands.into_iter().map(|command| {
+                                            let plugin = manifest.name.clone();
+                                            let title = command.title.clone();
+                                            view! {
+                                                <button
+                                                    type="button"
+                                                    class="btn btn-outline btn-xs"
+                                                    on:click=move |_| run_plugin_command(plugin.clone(), command.export.clone(), command.title.clone())
+                                                >
+                                                    {title}
+                                                </button>
+                                            }
+                                        }).collect_view()}
+                                    </div>
+                                </div>
+                                <button
+                                    type="button"
+                                    class="btn btn-xs"
+                                    class=("btn-primary", !granted)
+                                    class=("btn-ghost", granted)
+                                    on:click=move |_| {
+                                        let mut next = settings.get_untracked();
+                                        if granted {
+                                            next.plugin_grants.remove(&name);
+                                        } else {
+                                            next.plugin_grants.insert(name.clone(), capabilities.clone());
+                                        }
+                                        on_change.run(next);
+                                    }
+                                >
+                                    {if granted { "Revoke" } else { "Grant" }}
+                                </button>
+                            </li>
+                        }.into_any()
+                    }).collect_view()}
+                </ul>
+                <p class="text-sm opacity-70 whitespace-pre-wrap" role="status">{move || plugin_status.get()}</p>
+
+                <h2 class="card-title mt-4">"Goals"</h2>
+                <label class="label justify-between py-2">
+                    <span>"Daily completion goal"</span>
+                    <input
+                        type="number"
+                        min="0"
+                        class="input input-bordered input-sm w-24"
+                        placeholder="Off"
+                        prop:value=move || settings.get().daily_goal.map(|g| g.to_string()).unwrap_or_default()
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.daily_goal = event_target_value(&ev).parse::<usize>().ok().filter(|&g| g > 0);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <label class="label justify-between py-2">
+                    <span>"Hours of work per day (for est: tags)"</span>
+                    <input
+                        type="number"
+                        min="0"
+                        step="0.5"
+                        class="input input-bordered input-sm w-24"
+                        placeholder="Off"
+                        prop:value=move || {
+                            settings.get().workday_minutes.map(|m| (m as f64 / 60.0).to_string()).unwrap_or_default()
+                        }
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.workday_minutes = event_target_value(&ev)
+                                .parse::<f64>()
+                                .ok()
+                                .map(|hours| (hours * 60.0).round() as u32)
+                                .filter(|&m| m > 0);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Focus"</h2>
+                <label class="label justify-between py-2">
+                    <span>"Session length (minutes)"</span>
+                    <input
+                        type="number"
+                        min="1"
+                        class="input input-bordered input-sm w-24"
+                        prop:value=move || settings.get().focus.minutes.to_string()
+                        on:change=move |ev| {
+                            if let Some(minutes) = event_target_value(&ev).parse::<u32>().ok().filter(|&m| m > 0) {
+                                let mut next = settings.get_untracked();
+                                next.focus.minutes = minutes;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Turn on Do Not Disturb during a session"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:disabled=move || !dnd_supported.get()
+                        prop:checked=move || settings.get().focus.do_not_disturb
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.focus.do_not_disturb = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                {move || (!dnd_supported.get()).then(|| view! {
+                    <p class="text-sm opacity-60">"This system doesn't let apps turn on Do Not Disturb."</p>
+                })}
+
+                <h2 class="card-title mt-4">"Overlay"</h2>
+                <p class="text-sm opacity-60">"A small see-through window with the pending todos that stays on top of other windows."</p>
+                <label class="label justify-between py-2">
+                    <span>{move || format!("Background opacity ({}%)", settings.get().overlay.opacity)}</span>
+                    <input
+                        type="range"
+                        class="range range-sm w-48"
+                        min="0"
+                        max="100"
+                        step="5"
+                        prop:value=move || settings.get().overlay.opacity.to_string()
+                        on:change=move |ev| {
+                            if let Ok(opacity) = event_target_value(&ev).parse() {
+                                let mut next = settings.get_untracked();
+                                next.overlay.opacity = opacity;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Let clicks through to the windows below"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().overlay.click_through
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.overlay.click_through = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <div class="flex gap-2 py-2">
+                    <button
+                        type="button"
+                        class="btn btn-sm"
+                        on:click=move |_| spawn_local(async move {
+                            invoke("open_overlay", JsValue::NULL).await;
+                        })
+                    >
+                        "Open overlay"
+                    </button>
+                    <button
+                        type="button"
+                        class="btn btn-sm btn-ghost"
+                        on:click=move |_| spawn_local(async move {
+                            invoke("close_overlay", JsValue::NULL).await;
+                        })
+                    >
+                        "Close overlay"
+                    </button>
+                </div>
+
+                <h2 class="card-title mt-4">"Dates"</h2>
+                <p class="text-sm opacity-60">"Until then it still counts as the day before, for due, creation and completion dates."</p>
+                <label class="label justify-between py-2">
+                    <span>"Day ends at"</span>
+                    <input
+                        type="time"
+                        class="input input-bordered input-sm w-28"
+                        prop:value=move || settings.get().day_ends
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.day_ends = event_target_value(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <label class="label justify-between py-2">
+                    <span>"Tasks go stale after (days)"</span>
+                    <input
+                        type="number"
+                        min="1"
+                        class="input input-bordered input-sm w-24"
+                        prop:value=move || settings.get().stale_after_days.to_string()
+                        on:change=move |ev| {
+                            if let Some(days) = event_target_value(&ev).parse::<u32>().ok().filter(|&d| d > 0) {
+                                let mut next = settings.get_untracked();
+                                next.stale_after_days = days;
+                                on_change.run(next);
+                            }
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Archive"</h2>
+                <p class="text-sm opacity-60">"Completions from past years move from done.txt to one file per year, e.g. done-2024.txt. Streaks still count them."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Rotate done.txt at every start"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().rotate_done
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.rotate_done = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <div class="flex items-center gap-2">
+                    <button type="button" class="btn btn-sm" on:click=compact_now>"Compact now"</button>
+                    <span class="text-sm opacity-70" role="status">{move || compact_status.get()}</span>
+                </div>
+
+                <h2 class="card-title mt-4">"Upgrades"</h2>
+                <p class="text-sm opacity-60">"When a new version changes how something is written, the settings are upgraded at the next start and a copy of the old file is kept."</p>
+                <label class="label cursor-pointer justify-between py-2">
+                    <span>"Also upgrade todo.txt"</span>
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-primary"
+                        prop:checked=move || settings.get().migrate_todo_file
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.migrate_todo_file = event_target_checked(&ev);
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+
+                <h2 class="card-title mt-4">"Keyboard shortcuts"</h2>
+                <div class="flex items-center justify-between py-2">
+                    <span>{move || match settings.with(|s| s.keymap.bindings.len()) {
+                        0 => "The default shortcuts".to_string(),
+                        1 => "1 shortcut changed".to_string(),
+                        n => format!("{n} shortcuts changed"),
+                    }}</span>
+                    <button type="button" class="btn btn-sm" on:click=move |_| on_edit_shortcuts.run(())>"Edit shortcuts…"</button>
+                </div>
+
+                <h2 class="card-title mt-4">"Reveal in editor"</h2>
+                <p class="text-sm opacity-60">"Opens todo.txt at a todo's line, from its history. A URI opens the app registered for it; anything else runs as a command. {path} and {line} are filled in."</p>
+                <label class="label justify-between py-2">
+                    <span>"Editor"</span>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm w-72 font-mono"
+                        list="reveal-templates"
+                        placeholder=REVEAL_TEMPLATES[0].1
+                        prop:value=move || settings.get().reveal_template
+                        on:change=move |ev| {
+                            let mut next = settings.get_untracked();
+                            next.reveal_template = event_target_value(&ev).trim().to_string();
+                            on_change.run(next);
+                        }
+                    />
+                </label>
+                <datalist id="reveal-templates">
+                    {REVEAL_TEMPLATES.iter().map(|(name, template)| view! {
+                        <option value=*template>{*name}</option>
+                    }).collect_view()}
+                </datalist>
+
+                <h2 class="card-title mt-4">"Move to another machine"</h2>
+                <p class="text-sm opacity-60">"All settings in one file, including saved filters, snippets, WIP limits and shortcuts. The pairing code, calendar tokens, sync devices, hooks, plugin permissions, the editor to reveal todos in, the web viewer folder and whether the calendar allows other devices stay on this machine; importing keeps the ones set here."</p>
+                <div class="flex items-center gap-2 py-2">
+                    <button type="button" class="btn btn-sm" on:click=move |_| export_config()>
+                        "Export settings"
+                    </button>
+                    <span class="text-sm opacity-70" role="status">{move || config_status.get()}</span>
+                </div>
+                <form class="flex gap-2 py-2" on:submit=import_config>
+                    <input
+                        type="text"
+                        class="input input-bordered input-sm flex-1"
+                        placeholder="Path of an exported file"
+                        aria-label="Settings file to import"
+                        prop:value=move || import_path.get()
+                        on:input=move |ev| set_import_path.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-sm" disabled=move || import_path.with(|p| p.trim().is_empty())>
+                        "Import settings"
+                    </button>
+                </form>
+
+                <h2 class="card-title mt-4">"Diagnostics"</h2>
+                <label class="label justify-between py-2">
+                    <span>"Log level (applies after a restart)"</span>
+                    <select
+                        class="select select-bordered select-sm w-32"
+                        on:change=move |ev| {
+                            let name = event_target_value(&ev);
+                            if let Some(level) = LogLevel::ALL.into_iter().find(|l| l.name() == name) {
+                                let mut next = settings.get_untracked();
+                                next.log_level = level;
+                                on_change.run(next);
+                            }
+                        }
+                    >
+                        {LogLevel::ALL.into_iter().map(|level| view! {
+                            <option value=level.name() selected=move || settings.get().log_level == level>{level.name()}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <div class="flex items-center justify-between py-2">
+                    <span class="text-sm opacity-60" role="status">{move || export_status.get()}</span>
+                    <button type="button" class="btn btn-sm" on:click=move |_| export_diagnostics()>
+                        "Export diagnostics"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
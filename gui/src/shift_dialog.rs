@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::activity::Entry;
+use todotxt::filter::Filter;
+use todotxt::replace::Replacement;
+use types::{PreviewShiftDatesArgs, ShiftDatesArgs, UndoActivitiesArgs};
+
+use crate::backend::invoke;
+
+/// Moves the due and threshold dates of the todos matching `filter` by a
+/// number of days, e.g. all of a project when it slips a week. Every line
+/// that would change is previewed and can be left out. After shifting, the
+/// whole batch can be undone at once.
+#[component]
+pub fn ShiftDialog(
+    open: ReadSignal<bool>,
+    set_open: WriteSignal<bool>,
+    filter: ReadSignal<Filter>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (days, set_days) = signal(7i64);
+    let (preview, set_preview) = signal(Vec::<Replacement>::new());
+    // Ids of previewed lines the user unticked
+    let (skip, set_skip) = signal(HashSet::<usize>::new());
+    // The changes of the last shift, until they are undone or the dialog closes
+    let (applied, set_applied) = signal(Option::<Vec<Entry>>::None);
+
+    Effect::new(move |_| {
+        let (days, filter) = (days.get(), filter.get());
+        // Shifting also changes the preview, so it follows the list
+        applied.track();
+        if !open.get() || days == 0 {
+            set_preview.set(Vec::new());
+            return;
+        }
+        spawn_local(async move {
+            let args = PreviewShiftDatesArgs {
+                filter: &filter,
+                days,
+            };
+            let result = invoke(
+                "preview_shift_dates",
+                serde_wasm_bindgen::to_value(&args).unwrap(),
+            )
+            .await;
+            match serde_wasm_bindgen::from_value::<Vec<Replacement>>(result) {
+                Ok(lines) => set_preview.set(lines),
+                Err(e) => set_error.set(Some(format!("Failed to preview shifting dates: {e}"))),
+            }
+        });
+    });
+
+    let close = move || {
+        set_open.set(false);
+        set_skip.set(HashSet::new());
+        set_applied.set(None);
+    };
+
+    let shift = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let (days, filter) = (days.get_untracked(), filter.get_untracked());
+        let skip: Vec<usize> = skip.with_untracked(|s| s.iter().copied().collect());
+        spawn_local(async move {
+            let args = ShiftDatesArgs {
+                filter: &filter,
+                days,
+                skip: &skip,
+            };
+            let result = invoke("shift_dates", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            match serde_wasm_bindgen::from_value::<Vec<Entry>>(result) {
+                Ok(entries) => {
+                    set_error.set(None);
+                    set_skip.set(HashSet::new());
+                    set_applied.set(Some(entries));
+                }
+                Err(e) => set_error.set(Some(format!("Failed to shift dates: {e}"))),
+            }
+        });
+    };
+
+    let undo = move |_| {
+        let Some(entries) = applied.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&UndoActivitiesArgs { entries: &entries }).unwrap();
+            let result = invoke("undo_activities", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    set_applied.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to undo shifting dates: {e}"))),
+            }
+        });
+    };
+
+    let chosen = move || {
+        preview
+            .with(|lines| skip.with(|s| lines.iter().filter(|line| !s.contains(&line.id)).count()))
+    };
+
+    view! {
+        <dialog class="modal" class:modal-open=move || open.get() aria-modal="true" aria-labelledby="shift-title">
+            <div class="modal-box max-w-2xl" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="shift-title" class="text-lg font-bold">"Shift dates of shown todos"</h3>
+                <form class="flex flex-col gap-2 mt-4" on:submit=shift>
+                    <label class="flex items-center gap-2">
+                        <span>"Move due and start dates by"</span>
+                        <input
+                            type="number"
+                            class="input input-bordered input-sm w-24"
+                            aria-label="Days to move dates by"
+                            prop:value=move || days.get().to_string()
+                            on:input=move |ev| set_days.set(event_target_value(&ev).parse().unwrap_or(0))
+                        />
+                        <span>"days"</span>
+                    </label>
+                    <p class="text-sm opacity-60">"Negative numbers move them earlier."</p>
+                    <ul class="list max-h-64 overflow-y-auto" aria-label="Lines that would change">
+                        {move || preview.get().into_iter().map(|line| {
+                            let id = line.id;
+                            view! {
+                                <li class="list-row p-2 flex items-start gap-2">
+                                    <input
+                                        type="checkbox"
+                                        class="checkbox checkbox-sm mt-1"
+                                        aria-label=format!("Shift \"{}\"", line.before)
+                                        prop:checked=move || skip.with(|s| !s.contains(&id))
+                                        on:change=move |ev| {
+                                            let checked = event_target_checked(&ev);
+                                            set_skip.update(|s| {
+                                                if checked {
+                                                    s.remove(&id);
+                                                } else {
+                                                    s.insert(id);
+                                                }
+                                            });
+                                        }
+                                    />
+                                    <div>
+                                        <div class="font-mono text-xs opacity-60 line-through">{line.before}</div>
+                                        <div class="font-mono text-xs">{line.after}</div>
+                                    </div>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                    {move || applied.get().map(|entries| view! {
+                        <div class="flex items-center gap-2" role="status">
+                            <span>{format!("Shifted dates of {} todos.", entries.len())}</span>
+                            <button type="button" class="btn btn-ghost btn-sm" on:click=undo>"Undo"</button>
+                        </div>
+                    })}
+                    <div class="modal-action">
+                        <button type="button" class="btn" on:click=move |_| close()>"Close"</button>
+                        <button type="submit" class="btn btn-primary" disabled=move || chosen() == 0>
+                            {move || format!("Shift {} todos", chosen())}
+                        </button>
+                    </div>
+                </form>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,157 @@
+use leptos::prelude::*;
+use types::settings::{shortcut_keys, Keymap, Settings, ShortcutAction};
+use wasm_bindgen::JsCast;
+
+/// Whether a key goes to a text field rather than to shortcuts.
+fn typing(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|el| {
+            el.is_content_editable()
+                || matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        })
+}
+
+/// The keys of a key press as a [`Keymap`] writes them. Cmd counts as Ctrl.
+fn pressed(ev: &web_sys::KeyboardEvent) -> Option<String> {
+    shortcut_keys(
+        ev.ctrl_key() || ev.meta_key(),
+        ev.alt_key(),
+        ev.shift_key(),
+        &ev.key(),
+    )
+}
+
+/// Runs the action `keymap` binds to each key pressed in the window, the one
+/// place shortcuts are handled. Keys without Ctrl or Alt are left to text
+/// fields, as are keys a component already handled.
+pub fn dispatch(keymap: Signal<Keymap>, run: impl Fn(ShortcutAction) + 'static) {
+    let _ = window_event_listener(leptos::ev::keydown, move |ev| {
+        if ev.default_prevented()
+            || (!(ev.ctrl_key() || ev.meta_key() || ev.alt_key()) && typing(&ev))
+        {
+            return;
+        }
+        let Some(action) = pressed(&ev).and_then(|keys| keymap.with_untracked(|k| k.action(&keys)))
+        else {
+            return;
+        };
+        ev.prevent_default();
+        run(action);
+    });
+}
+
+/// Every shortcut, each changed by clicking it and pressing the new keys.
+/// Actions sharing a shortcut are flagged; only the first of them runs.
+#[component]
+pub fn ShortcutsPage(
+    settings: ReadSignal<Settings>,
+    on_change: Callback<Settings>,
+) -> impl IntoView {
+    // The action waiting for its new keys
+    let (recording, set_recording) = signal(Option::<ShortcutAction>::None);
+
+    let bind = move |action: ShortcutAction, keys: &str| {
+        let mut next = settings.get_untracked();
+        next.keymap.bind(action, keys);
+        on_change.run(next);
+    };
+
+    let row = move |action: ShortcutAction| {
+        let keys = move || settings.with(|s| s.keymap.keys(action).to_string());
+        let conflicts = move || settings.with(|s| s.keymap.conflicts(action));
+        let on_keydown = move |ev: leptos::ev::KeyboardEvent| {
+            if recording.get_untracked() != Some(action) {
+                return;
+            }
+            // Keep the keys from running what they're bound to now
+            ev.prevent_default();
+            ev.stop_propagation();
+            if ev.key() == "Escape" {
+                set_recording.set(None);
+                return;
+            }
+            if let Some(keys) = pressed(&ev) {
+                bind(action, &keys);
+                set_recording.set(None);
+            }
+        };
+        view! {
+            <li class="list-row p-2 flex flex-wrap items-center gap-2">
+                <span class="flex-1 min-w-48">{action.label()}</span>
+                <button
+                    type="button"
+                    class="btn btn-sm min-w-32"
+                    class=("btn-primary", move || recording.get() == Some(action))
+                    aria-label=move || format!("Shortcut for {}: {}", action.label(), match keys() {
+                        keys if keys.is_empty() => "none".to_string(),
+                        keys => keys,
+                    })
+                    on:click=move |_| set_recording.set(Some(action))
+                    on:blur=move |_| set_recording.update(|r| if *r == Some(action) { *r = None })
+                    on:keydown=on_keydown
+                >
+                    {move || match keys() {
+                        _ if recording.get() == Some(action) => view! { <span>"Press keys…"</span> }.into_any(),
+                        keys if keys.is_empty() => view! { <span class="opacity-60">"None"</span> }.into_any(),
+                        keys => view! { <kbd class="kbd kbd-sm">{keys}</kbd> }.into_any(),
+                    }}
+                </button>
+                <button
+                    type="button"
+                    class="btn btn-ghost btn-sm"
+                    prop:disabled=move || keys().is_empty()
+                    on:click=move |_| bind(action, "")
+                >
+                    "Remove"
+                </button>
+                <button
+                    type="button"
+                    class="btn btn-ghost btn-sm"
+                    prop:disabled=move || keys() == action.default_keys()
+                    on:click=move |_| bind(action, action.default_keys())
+                >
+                    "Reset"
+                </button>
+                {move || {
+                    let conflicts = conflicts();
+                    (!conflicts.is_empty()).then(|| view! {
+                        <span class="text-warning text-sm w-full" role="status">
+                            {format!(
+                                "Also the shortcut of {}",
+                                conflicts.iter().map(|other| other.label()).collect::<Vec<_>>().join(", "),
+                            )}
+                        </span>
+                    })
+                }}
+            </li>
+        }
+    };
+
+    let reset_all = move |_| {
+        let mut next = settings.get_untracked();
+        next.keymap = Keymap::default();
+        on_change.run(next);
+    };
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body">
+                <p class="opacity-60">"Click a shortcut and press the new keys, or Escape to keep it. Ctrl also stands for ⌘ on macOS."</p>
+                <ul class="list" aria-label="Keyboard shortcuts">
+                    {ShortcutAction::ALL.into_iter().map(row).collect_view()}
+                </ul>
+                <div class="card-actions justify-end">
+                    <button
+                        type="button"
+                        class="btn btn-sm"
+                        prop:disabled=move || settings.with(|s| s.keymap.bindings.is_empty())
+                        on:click=reset_all
+                    >
+                        "Reset all"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
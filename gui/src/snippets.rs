@@ -0,0 +1,32 @@
+use leptos::prelude::*;
+use types::settings::Snippet;
+
+/// `text` with the snippet trigger it ends in replaced by the expansion, or
+/// `None` if it doesn't end in one. The longest trigger wins, so `;;wk` can
+/// coexist with `;;k`.
+pub fn expand(text: &str, snippets: &[Snippet]) -> Option<String> {
+    let snippet = snippets
+        .iter()
+        .filter(|s| !s.trigger.is_empty() && text.ends_with(&s.trigger))
+        .max_by_key(|s| s.trigger.len())?;
+    Some(format!(
+        "{}{}",
+        &text[..text.len() - snippet.trigger.len()],
+        snippet.expansion
+    ))
+}
+
+/// Input handler for a todo text field: stores what was typed, expanded.
+pub fn on_input(
+    snippets: Memo<Vec<Snippet>>,
+    set_text: WriteSignal<String>,
+) -> impl Fn(leptos::ev::Event) + Copy {
+    move |ev| {
+        let text = event_target_value(&ev);
+        set_text.set(
+            snippets
+                .with_untracked(|s| expand(&text, s))
+                .unwrap_or(text),
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use leptos::prelude::*;
+use todotxt::sort::{self, SortBy};
+use todotxt::TodoItem;
+use types::Todo;
+
+use crate::settings::{Settings, SortSettings};
+
+/// Picks what the list is sorted by, a built-in order or a tag of the
+/// todos in `todos`, and which way round.
+#[component]
+pub fn SortControls(
+    todos: Signal<Vec<Todo>>,
+    settings: ReadSignal<Settings>,
+    sorting: Memo<SortSettings>,
+    on_settings_change: Callback<Settings>,
+) -> impl IntoView {
+    // Tags the list can be sorted by, with the kind of their values
+    let sort_keys = Memo::new(move |_| {
+        let items: Vec<TodoItem> =
+            todos.with(|todos| todos.iter().map(|todo| TodoItem::new(&todo.raw)).collect());
+        sort::tag_keys(&items)
+    });
+
+    view! {
+        <div class="join">
+            <select
+                class="select select-bordered select-sm join-item w-40"
+                aria-label="Sort by"
+                on:change=move |ev| {
+                    if let Some(by) = SortBy::parse(&event_target_value(&ev)) {
+                        let mut next = settings.get_untracked();
+                        next.sort.by = by;
+                        on_settings_change.run(next);
+                    }
+                }
+            >
+                {SortBy::BUILT_IN.into_iter().map(|by| {
+                    let selected = by.clone();
+                    view! {
+                        <option value=by.name() selected=move || sorting.with(|s| s.by == selected)>{by.label()}</option>
+                    }
+                }).collect_view()}
+                <optgroup label="Tags">
+                    {move || {
+                        let mut keys = sort_keys.get();
+                        // Keep the current choice even once no todo has the tag
+                        if let SortBy::Tag(key) = sorting.with(|s| s.by.clone()) {
+                            if !keys.iter().any(|(k, _)| *k == key) {
+                                keys.push((key, sort::ValueKind::Text));
+                            }
+                        }
+                        keys.into_iter().map(|(key, kind)| {
+                            let by = SortBy::Tag(key);
+                            let label = format!("{} ({})", by.label(), kind.name());
+                            let value = by.name();
+                            view! {
+                                <option value=value selected=move || sorting.with(|s| s.by == by)>{label}</option>
+                            }
+                        }).collect_view()
+                    }}
+                </optgroup>
+            </select>
+            <button
+                type="button"
+                class="btn btn-sm join-item"
+                aria-label=move || if sorting.with(|s| s.descending) { "Sorted descending" } else { "Sorted ascending" }
+                title="Reverse the order"
+                on:click=move |_| {
+                    let mut next = settings.get_untracked();
+                    next.sort.descending = !next.sort.descending;
+                    on_settings_change.run(next);
+                }
+            >
+                {move || if sorting.with(|s| s.descending) { "↓" } else { "↑" }}
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,106 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::SplitOriginal;
+use types::{SplitTodoArgs, Todo};
+
+use crate::backend::invoke;
+
+/// Splits a todo into several, one per line of its subject as edited here.
+/// The new todos take on the original's priority and tags.
+#[component]
+pub fn SplitDialog(
+    task: ReadSignal<Option<Todo>>,
+    set_task: WriteSignal<Option<Todo>>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (text, set_text) = signal(String::new());
+    let (original, set_original) = signal(SplitOriginal::Complete);
+
+    // Start from the subject whenever a todo is picked
+    Effect::new(move |_| {
+        if let Some(subject) = task.with(|t| t.as_ref().map(|t| t.subject.clone())) {
+            set_text.set(subject);
+        }
+    });
+
+    let close = move || set_task.set(None);
+
+    let split = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(id) = task.with_untracked(|t| t.as_ref().map(|t| t.id)) else {
+            return;
+        };
+        let lines: Vec<String> = text.get_untracked().lines().map(str::to_string).collect();
+        let original = original.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SplitTodoArgs {
+                id,
+                lines: &lines,
+                original,
+            })
+            .unwrap();
+            let result = invoke("split_todo", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    close();
+                }
+                Err(e) => set_error.set(Some(format!("Failed to split todo: {e}"))),
+            }
+        });
+    };
+
+    let line_count =
+        move || text.with(|t| t.lines().filter(|line| !line.trim().is_empty()).count());
+
+    view! {
+        <dialog class="modal" class:modal-open=move || task.with(Option::is_some) aria-modal="true" aria-labelledby="split-title">
+            <div class="modal-box" on:keydown=move |ev: leptos::ev::KeyboardEvent| if ev.key() == "Escape" { close() }>
+                <h3 id="split-title" class="text-lg font-bold">
+                    "Split "{move || task.with(|t| t.as_ref().map(|t| format!("\"{}\"", t.subject)))}
+                </h3>
+                <form class="flex flex-col gap-2 mt-4" on:submit=split>
+                    <p class="text-sm opacity-60">"Each line becomes a todo with the original's priority and tags."</p>
+                    <textarea
+                        class="textarea textarea-bordered w-full font-mono"
+                        rows="6"
+                        aria-label="One todo per line"
+                        prop:value=move || text.get()
+                        on:input=move |ev| set_text.set(event_target_value(&ev))
+                    ></textarea>
+                    <fieldset class="flex items-center gap-4">
+                        <legend class="text-sm mb-1">"Afterwards, the original is"</legend>
+                        {[
+                            (SplitOriginal::Complete, "Completed"),
+                            (SplitOriginal::Delete, "Deleted"),
+                            (SplitOriginal::Keep, "Kept"),
+                        ]
+                            .into_iter()
+                            .map(|(choice, label)| view! {
+                                <label class="label cursor-pointer gap-2">
+                                    <input
+                                        type="radio"
+                                        name="split-original"
+                                        class="radio radio-sm"
+                                        prop:checked=move || original.get() == choice
+                                        on:change=move |_| set_original.set(choice)
+                                    />
+                                    <span>{label}</span>
+                                </label>
+                            })
+                            .collect_view()}
+                    </fieldset>
+                    <div class="modal-action">
+                        <button type="button" class="btn" on:click=move |_| close()>"Cancel"</button>
+                        <button type="submit" class="btn btn-primary" disabled=move || line_count() == 0>
+                            {move || format!("Split into {} todos", line_count())}
+                        </button>
+                    </div>
+                </form>
+            </div>
+            <form method="dialog" class="modal-backdrop">
+                <button type="button" tabindex="-1" aria-label="Close" on:click=move |_| close() />
+            </form>
+        </dialog>
+    }
+}
@@ -0,0 +1,44 @@
+use leptos::prelude::*;
+use todotxt::stats::Streaks;
+
+use crate::settings::Settings;
+
+/// How many days in a row todos got done, and how far today is towards the
+/// daily goal if there is one.
+#[component]
+pub fn Streak(
+    streaks: ReadSignal<Streaks>,
+    settings: ReadSignal<Settings>,
+    /// Set while a completion is being celebrated
+    celebrating: ReadSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <span
+            class="badge badge-warning badge-outline"
+            title=move || format!("Longest streak: {} days", streaks.get().longest)
+        >
+            {move || match streaks.get().current {
+                1 => "1 day streak".to_string(),
+                n => format!("{n} day streak"),
+            }}
+        </span>
+        {move || settings.get().daily_goal.map(|goal| {
+            let done = streaks.get().completed_today;
+            view! {
+                <div class="flex items-center gap-2">
+                    <progress
+                        class="progress w-24"
+                        class=("progress-success", done >= goal)
+                        class=("progress-primary", done < goal)
+                        value=done.min(goal)
+                        max=goal
+                        aria-label="Daily goal progress"
+                    ></progress>
+                    <span class="text-sm opacity-70" class=("animate-pulse", move || celebrating.get())>
+                        {format!("{done}/{goal} today")}
+                    </span>
+                </div>
+            }
+        })}
+    }
+}
@@ -0,0 +1,295 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use types::Todo;
+use wasm_bindgen::prelude::*;
+
+use crate::backend::invoke;
+use crate::colors::{badge_style, resolve, tag_color};
+use crate::settings::Settings;
+use crate::tag_tree::TagKind;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+struct TagUsage {
+    kind: TagKind,
+    name: String,
+    count: usize,
+    last_used: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RenameTagArgs<'a> {
+    kind: TagKind,
+    from: &'a str,
+    to: &'a str,
+}
+
+#[derive(Serialize)]
+struct DeleteTagArgs<'a> {
+    kind: TagKind,
+    name: &'a str,
+}
+
+/// Which inline action a row is showing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowMode {
+    Idle,
+    Renaming,
+    Merging,
+    ConfirmDelete,
+}
+
+#[component]
+pub fn TagManager(
+    active: Signal<bool>,
+    todos: ReadSignal<Vec<Todo>>,
+    settings: ReadSignal<Settings>,
+    set_settings: WriteSignal<Settings>,
+    on_settings_change: Callback<Settings>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let (usage, set_usage) = signal(Vec::<TagUsage>::new());
+
+    // Usage only matters while the page is visible; refresh it whenever the list changes
+    Effect::new(move |_| {
+        todos.track();
+        if !active.get() {
+            return;
+        }
+        spawn_local(async move {
+            let result = invoke("get_tag_usage", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<TagUsage>>(result) {
+                Ok(tags) => set_usage.set(tags),
+                Err(e) => set_error.set(Some(format!("Failed to load tags: {e}"))),
+            }
+        });
+    });
+
+    let rename = Callback::new(move |(kind, from, to): (TagKind, String, String)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RenameTagArgs {
+                kind,
+                from: &from,
+                to: &to,
+            })
+            .unwrap();
+            let result = invoke("rename_tag", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    // A picked color moves along with the tag
+                    let result = invoke("get_settings", JsValue::NULL).await;
+                    if let Ok(loaded) = serde_wasm_bindgen::from_value::<Settings>(result) {
+                        set_settings.set(loaded);
+                    }
+                }
+                Err(e) => set_error.set(Some(format!("Failed to rename tag: {e}"))),
+            }
+        });
+    });
+
+    let delete = Callback::new(move |(kind, name): (TagKind, String)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&DeleteTagArgs { kind, name: &name }).unwrap();
+            let result = invoke("delete_tag", args).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(format!("Failed to delete tag: {e}"))),
+            }
+        });
+    });
+
+    let set_color = move |key: String, color: Option<String>| {
+        let mut next = settings.get_untracked();
+        match color {
+            Some(color) => next.tag_colors.insert(key, color),
+            None => next.tag_colors.remove(&key),
+        };
+        on_settings_change.run(next);
+    };
+
+    view! {
+        <div class="card bg-base-100 shadow-xl">
+            <div class="card-body p-0">
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>"Tag"</th>
+                            <th class="text-right">"Todos"</th>
+                            <th>"Last used"</th>
+                            <th>"Color"</th>
+                            <th><span class="sr-only">"Actions"</span></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || usage.get()
+                            key=|tag| tag.clone()
+                            children=move |tag| {
+                                let kind = tag.kind;
+                                let name = tag.name.clone();
+                                let label = format!("{}{}", kind.sigil(), tag.name);
+                                let (mode, set_mode) = signal(RowMode::Idle);
+                                let (new_name, set_new_name) = signal(tag.name.clone());
+
+                                let key = label.clone();
+                                let color = Signal::derive(move || settings.with(|s| tag_color(&key, &s.tag_colors)));
+                                let key = label.clone();
+                                let has_custom_color = move || settings.with(|s| s.tag_colors.contains_key(&key));
+                                let color_key = label.clone();
+                                let reset_key = label.clone();
+
+                                // Other tags of the same kind to merge into
+                                let merge_name = name.clone();
+                                let merge_targets = move || {
+                                    usage.with(|tags| {
+                                        tags.iter()
+                                            .filter(|t| t.kind == kind && t.name != merge_name)
+                                            .map(|t| t.name.clone())
+                                            .collect::<Vec<_>>()
+                                    })
+                                };
+
+                                let rename_from = name.clone();
+                                let submit_rename = move || {
+                                    set_mode.set(RowMode::Idle);
+                                    let to = new_name.get_untracked().trim().to_string();
+                                    if !to.is_empty() && to != rename_from {
+                                        rename.run((kind, rename_from.clone(), to));
+                                    }
+                                };
+                                let merge_from = name.clone();
+                                let delete_name = name.clone();
+
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <span class="badge badge-outline" style=move || badge_style(&color.get())>
+                                                {label.clone()}
+                                            </span>
+                                        </td>
+                                        <td class="text-right">{tag.count}</td>
+                                        <td class="text-sm opacity-70">{tag.last_used.clone().unwrap_or_else(|| "–".to_string())}</td>
+                                        <td>
+                                            <div class="flex items-center gap-1">
+                                                <input
+                                                    type="color"
+                                                    class="h-6 w-10 cursor-pointer"
+                                                    aria-label=format!("Color for {label}")
+                                                    prop:value=move || resolve(&color.get())
+                                                    on:change=move |ev| set_color(color_key.clone(), Some(event_target_value(&ev)))
+                                                />
+                                                <button
+                                                    type="button"
+                                                    class="btn btn-ghost btn-xs"
+                                                    aria-label=format!("Reset color for {label}")
+                                                    prop:disabled=move || !has_custom_color()
+                                                    on:click=move |_| set_color(reset_key.clone(), None)
+                                                >
+                                                    "Reset"
+                                                </button>
+                                            </div>
+                                        </td>
+                                        <td>
+                                            {move || match mode.get() {
+                                                RowMode::Idle => view! {
+                                                    <div class="flex justify-end gap-1">
+                                                        <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_mode.set(RowMode::Renaming)>
+                                                            "Rename"
+                                                        </button>
+                                                        <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_mode.set(RowMode::Merging)>
+                                                            "Merge"
+                                                        </button>
+                                                        <button type="button" class="btn btn-ghost btn-xs text-error" on:click=move |_| set_mode.set(RowMode::ConfirmDelete)>
+                                                            "Delete"
+                                                        </button>
+                                                    </div>
+                                                }.into_any(),
+                                                RowMode::Renaming => {
+                                                    let submit_rename = submit_rename.clone();
+                                                    view! {
+                                                        <form
+                                                            class="flex justify-end gap-1"
+                                                            on:submit=move |ev| {
+                                                                ev.prevent_default();
+                                                                submit_rename();
+                                                            }
+                                                        >
+                                                            <input
+                                                                type="text"
+                                                                class="input input-bordered input-xs w-32"
+                                                                aria-label="New name"
+                                                                prop:value=move || new_name.get()
+                                                                on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                                                            />
+                                                            <button type="submit" class="btn btn-primary btn-xs">"Save"</button>
+                                                            <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_mode.set(RowMode::Idle)>
+                                                                "Cancel"
+                                                            </button>
+                                                        </form>
+                                                    }.into_any()
+                                                }
+                                                RowMode::Merging => {
+                                                    let merge_from = merge_from.clone();
+                                                    view! {
+                                                        <div class="flex justify-end gap-1">
+                                                            <select
+                                                                class="select select-bordered select-xs"
+                                                                aria-label="Merge into"
+                                                                on:change=move |ev| {
+                                                                    let target = event_target_value(&ev);
+                                                                    if !target.is_empty() {
+                                                                        set_mode.set(RowMode::Idle);
+                                                                        rename.run((kind, merge_from.clone(), target));
+                                                                    }
+                                                                }
+                                                            >
+                                                                <option value="" selected>"Merge into…"</option>
+                                                                {merge_targets().into_iter().map(|target| view! {
+                                                                    <option value=target.clone()>{format!("{}{}", kind.sigil(), target)}</option>
+                                                                }).collect_view()}
+                                                            </select>
+                                                            <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_mode.set(RowMode::Idle)>
+                                                                "Cancel"
+                                                            </button>
+                                                        </div>
+                                                    }.into_any()
+                                                }
+                                                RowMode::ConfirmDelete => {
+                                                    let delete_name = delete_name.clone();
+                                                    view! {
+                                                        <div class="flex justify-end items-center gap-1">
+                                                            <span class="text-sm">{format!("Remove from {} todos?", tag.count)}</span>
+                                                            <button
+                                                                type="button"
+                                                                class="btn btn-error btn-xs"
+                                                                on:click=move |_| {
+                                                                    set_mode.set(RowMode::Idle);
+                                                                    delete.run((kind, delete_name.clone()));
+                                                                }
+                                                            >
+                                                                "Delete"
+                                                            </button>
+                                                            <button type="button" class="btn btn-ghost btn-xs" on:click=move |_| set_mode.set(RowMode::Idle)>
+                                                                "Cancel"
+                                                            </button>
+                                                        </div>
+                                                    }.into_any()
+                                                }
+                                            }}
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+                {move || usage.with(Vec::is_empty).then(|| view! {
+                    <p class="p-4 opacity-60">"No projects or contexts yet."</p>
+                })}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, HashSet};
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use todotxt::filter::Filter;
+use types::Todo;
+
+use crate::colors::tag_color;
+use crate::settings::CountMode;
+
+/// Kind of tag a tree is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Project,
+    Context,
+}
+
+impl TagKind {
+    pub fn sigil(self) -> char {
+        match self {
+            TagKind::Project => '+',
+            TagKind::Context => '@',
+        }
+    }
+
+    pub fn tags(self, todo: &Todo) -> &[String] {
+        match self {
+            TagKind::Project => &todo.projects,
+            TagKind::Context => &todo.contexts,
+        }
+    }
+
+    /// The filter criterion for this kind of tag.
+    pub fn criterion(self, filter: &Filter) -> Option<&str> {
+        match self {
+            TagKind::Project => filter.project.as_deref(),
+            TagKind::Context => filter.context.as_deref(),
+        }
+    }
+
+    pub fn criterion_mut(self, filter: &mut Filter) -> &mut Option<String> {
+        match self {
+            TagKind::Project => &mut filter.project,
+            TagKind::Context => &mut filter.context,
+        }
+    }
+}
+
+/// Identifies a tree node, e.g. for remembering which ones are collapsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TagPath {
+    pub kind: TagKind,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TagNode {
+    pub name: String,
+    pub full_path: String,
+    pub direct_count: usize,
+    /// Todos tagged with this tag or any of its descendants, each counted once
+    pub total_count: usize,
+    /// Finished todos among `total_count`
+    pub done_count: usize,
+    pub children: Vec<TagNode>,
+}
+
+impl TagNode {
+    /// Badge count for this node including all descendants.
+    pub fn count(&self, mode: CountMode) -> usize {
+        match mode {
+            CountMode::All => self.total_count,
+            CountMode::Pending => self.total_count - self.done_count,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TempNode {
+    count: usize,
+    total: usize,
+    done: usize,
+    children: BTreeMap<String, TempNode>,
+}
+
+/// Builds a hierarchy from the tags `tags` returns for each todo by splitting
+/// their names on `separator`.
+pub fn build_tag_tree<'a>(
+    todos: &'a [Todo],
+    tags: impl Fn(&'a Todo) -> &'a [String],
+    separator: &str,
+) -> Vec<TagNode> {
+    let mut root = BTreeMap::<String, TempNode>::new();
+
+    for todo in todos {
+        // A todo tagged +a---b and +a---c still only counts once towards "a"
+        let mut visited = HashSet::<String>::new();
+        for tag in tags(todo) {
+            let parts: Vec<&str> = if separator.is_empty() {
+                vec![tag.as_str()]
+            } else {
+                tag.split(separator).collect()
+            };
+            let len = parts.len();
+            let mut current = &mut root;
+            let mut path = String::new();
+            for (i, part) in parts.into_iter().enumerate() {
+                if i > 0 {
+                    path.push_str(separator);
+                }
+                path.push_str(part);
+                let node = current.entry(part.to_string()).or_default();
+                if i == len - 1 {
+                    node.count += 1;
+                }
+                if visited.insert(path.clone()) {
+                    node.total += 1;
+                    if todo.finished {
+                        node.done += 1;
+                    }
+                }
+                current = &mut node.children;
+            }
+        }
+    }
+
+    fn convert(map: &BTreeMap<String, TempNode>, prefix: &str, separator: &str) -> Vec<TagNode> {
+        map.iter()
+            .map(|(name, node)| {
+                let full_path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}{}{}", prefix, separator, name)
+                };
+                let children = convert(&node.children, &full_path, separator);
+                TagNode {
+                    name: name.clone(),
+                    full_path,
+                    direct_count: node.count,
+                    total_count: node.total,
+                    done_count: node.done,
+                    children,
+                }
+            })
+            .collect()
+    }
+
+    convert(&root, "", separator)
+}
+
+/// Signals shared by every level of the project and context trees.
+#[derive(Clone, Copy)]
+pub struct TagTreeState {
+    pub filter: ReadSignal<Filter>,
+    pub set_filter: WriteSignal<Filter>,
+    pub collapsed_nodes: ReadSignal<HashSet<TagPath>>,
+    pub set_collapsed_nodes: WriteSignal<HashSet<TagPath>>,
+    pub count_mode: Signal<CountMode>,
+    pub tag_colors: Signal<BTreeMap<String, String>>,
+}
+
+/// Renders one level of the tree. Nodes are keyed by their full contents, so a
+/// rebuild only re-mounts the subtrees whose counts or children changed, and
+/// collapsed subtrees are not mounted at all.
+pub fn render_tag_tree(
+    nodes: Signal<Vec<TagNode>>,
+    kind: TagKind,
+    depth: usize,
+    state: TagTreeState,
+) -> AnyView {
+    let TagTreeState {
+        filter,
+        set_filter,
+        collapsed_nodes,
+        set_collapsed_nodes,
+        count_mode,
+        tag_colors,
+    } = state;
+    let pad_class = match depth {
+        0 => "pl-0",
+        1 => "pl-4",
+        2 => "pl-8",
+        3 => "pl-12",
+        _ => "pl-16",
+    };
+
+    view! {
+        <For
+            each=move || nodes.get()
+            key=|node| node.clone()
+            children=move |node| {
+                let tag_path = TagPath { kind, path: node.full_path.clone() };
+                let full_path_click = node.full_path.clone();
+                let full_path_active = node.full_path.clone();
+                let tag_path_toggle = tag_path.clone();
+                let tag_path_collapsed = tag_path;
+                let has_children = !node.children.is_empty();
+                let children = node.children.clone();
+                let color_key = format!("{}{}", kind.sigil(), node.full_path);
+                let color = move || tag_colors.with(|colors| tag_color(&color_key, colors));
+                let name = node.name.clone();
+                let name_label = node.name.clone();
+                let total = node.total_count;
+                let done = node.done_count;
+
+                let count = {
+                    let node = node.clone();
+                    move || node.count(count_mode.get())
+                };
+
+                let is_active =
+                    Signal::derive(move || filter.with(|f| kind.criterion(f) == Some(full_path_active.as_str())));
+                let is_collapsed = Signal::derive(move || collapsed_nodes.get().contains(&tag_path_collapsed));
+
+                let set_collapsed = Callback::new(move |collapsed: bool| {
+                    let mut set = collapsed_nodes.get_untracked();
+                    if collapsed {
+                        set.insert(tag_path_toggle.clone());
+                    } else {
+                        set.remove(&tag_path_toggle);
+                    }
+                    set_collapsed_nodes.set(set);
+                });
+
+                let on_toggle_collapse = move |ev: leptos::ev::MouseEvent| {
+                    ev.stop_propagation();
+                    set_collapsed.run(!is_collapsed.get_untracked());
+                };
+
+                let select = Callback::new(move |_: ()| {
+                    set_filter.update(|f| *kind.criterion_mut(f) = Some(full_path_click.clone()));
+                });
+
+                // Tree keyboard navigation: Enter/Space selects, Right/Left expand and collapse
+                let on_keydown = move |ev: leptos::ev::KeyboardEvent| {
+                    let handled = match ev.key().as_str() {
+                        "Enter" | " " => {
+                            select.run(());
+                            true
+                        }
+                        "ArrowRight" if has_children => {
+                            set_collapsed.run(false);
+                            true
+                        }
+                        "ArrowLeft" if has_children => {
+                            set_collapsed.run(true);
+                            true
+                        }
+                        _ => false,
+                    };
+                    if handled {
+                        ev.prevent_default();
+                        ev.stop_propagation();
+                    }
+                };
+
+                view! {
+                    <div
+                        role="treeitem"
+                        tabindex="0"
+                        class="rounded focus:outline-none focus-visible:ring-2 focus-visible:ring-primary"
+                        aria-selected=move || if is_active.get() { "true" } else { "false" }
+                        aria-expanded=move || has_children.then(|| if is_collapsed.get() { "false" } else { "true" })
+                        on:keydown=on_keydown
+                    >
+                        <div
+                            class=format!("flex items-center gap-1 px-2 py-1 cursor-pointer rounded hover:bg-base-200 {}", pad_class)
+                            class=("bg-primary/20", is_active)
+                            on:click=move |_| select.run(())
+                        >
+                            // Chevron for expand/collapse
+                            {if has_children {
+                                view! {
+                                    <button
+                                        class="btn btn-ghost btn-xs p-0 min-h-0 h-4 w-4"
+                                        tabindex="-1"
+                                        aria-label=move || if is_collapsed.get() { "Expand" } else { "Collapse" }
+                                        on:click=on_toggle_collapse
+                                    >
+                                        <svg
+                                            xmlns="http://www.w3.org/2000/svg"
+                                            class="h-3 w-3 transition-transform"
+                                            class=("rotate-90", move || !is_collapsed.get())
+                                            aria-hidden="true"
+                                            fill="none"
+                                            viewBox="0 0 24 24"
+                                            stroke="currentColor"
+                                        >
+                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 5l7 7-7 7"/>
+                                        </svg>
+                                    </button>
+                                }.into_any()
+                            } else {
+                                view! { <span class="w-4"></span> }.into_any()
+                            }}
+                            {match kind {
+                                // Folder icon
+                                TagKind::Project => view! {
+                                    <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" style=move || format!("color: {}", color()) aria-hidden="true" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M3 7v10a2 2 0 002 2h14a2 2 0 002-2V9a2 2 0 00-2-2h-6l-2-2H5a2 2 0 00-2 2z"/>
+                                    </svg>
+                                }.into_any(),
+                                TagKind::Context => view! {
+                                    <span class="h-4 w-4 text-center text-sm leading-4" style=move || format!("color: {}", color()) aria-hidden="true">"@"</span>
+                                }.into_any(),
+                            }}
+                            <span class="text-sm truncate flex-1">{name}</span>
+                            {move || {
+                                let count = count();
+                                (count > 0).then(|| view! {
+                                    <span class="badge badge-xs badge-neutral" aria-label=format!("{count} todos")>{count}</span>
+                                })
+                            }}
+                        </div>
+                        // Completion progress across this tag and the tags nested below it
+                        {(total > 0).then(|| view! {
+                            <div class=format!("px-2 pb-1 {}", pad_class)>
+                                <progress
+                                    class="progress progress-success h-1 w-full block"
+                                    value=done
+                                    max=total
+                                    aria-label=format!("{name_label}: {done} of {total} done")
+                                ></progress>
+                            </div>
+                        })}
+                        // Children
+                        {move || (has_children && !is_collapsed.get()).then(|| {
+                            view! {
+                                <div role="group">
+                                    {render_tag_tree(Signal::stored(children.clone()), kind, depth + 1, state)}
+                                </div>
+                            }
+                        })}
+                    </div>
+                }
+            }
+        />
+    }
+    .into_any()
+}
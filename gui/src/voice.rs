@@ -0,0 +1,77 @@
+//! Dictation through the speech recognition the webview gets from the OS,
+//! e.g. Android's or iOS's speech-to-text. Not every webview has it, so
+//! callers check [`is_available`] first.
+
+use js_sys::{Array, Function, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+fn recognition_class() -> Option<Function> {
+    let window = web_sys::window()?;
+    ["SpeechRecognition", "webkitSpeechRecognition"]
+        .into_iter()
+        .find_map(|name| {
+            Reflect::get(&window, &name.into())
+                .ok()?
+                .dyn_into::<Function>()
+                .ok()
+        })
+}
+
+pub fn is_available() -> bool {
+    recognition_class().is_some()
+}
+
+/// Listens for one utterance and calls `on_result` with what was said, or
+/// `on_error` with why nothing was recognized. `on_end` runs when listening
+/// stopped either way.
+pub fn listen(
+    on_result: impl FnOnce(String) + 'static,
+    on_error: impl FnOnce(String) + 'static,
+    on_end: impl FnOnce() + 'static,
+) {
+    let recognition =
+        recognition_class().and_then(|class| Reflect::construct(&class, &Array::new()).ok());
+    let Some(recognition) = recognition else {
+        on_error("Speech recognition is not available".to_string());
+        on_end();
+        return;
+    };
+    let language = web_sys::window().and_then(|window| window.navigator().language());
+    if let Some(language) = language {
+        let _ = Reflect::set(&recognition, &"lang".into(), &language.into());
+    }
+
+    // event.results[0][0].transcript
+    let onresult = Closure::once_into_js(move |event: JsValue| {
+        let transcript = Reflect::get(&event, &"results".into())
+            .and_then(|results| Reflect::get_u32(&results, 0))
+            .and_then(|result| Reflect::get_u32(&result, 0))
+            .and_then(|alternative| Reflect::get(&alternative, &"transcript".into()))
+            .ok()
+            .and_then(|transcript| transcript.as_string());
+        if let Some(transcript) = transcript {
+            on_result(transcript);
+        }
+    });
+    let onerror = Closure::once_into_js(move |event: JsValue| {
+        let error = Reflect::get(&event, &"error".into())
+            .ok()
+            .and_then(|error| error.as_string())
+            .unwrap_or_default();
+        on_error(error);
+    });
+    let onend = Closure::once_into_js(on_end);
+    let _ = Reflect::set(&recognition, &"onresult".into(), &onresult);
+    let _ = Reflect::set(&recognition, &"onerror".into(), &onerror);
+    let _ = Reflect::set(&recognition, &"onend".into(), &onend);
+
+    let started = Reflect::get(&recognition, &"start".into())
+        .and_then(|start| start.dyn_into::<Function>())
+        .and_then(|start| start.call0(&recognition));
+    if started.is_err() {
+        if let Some(onend) = onend.dyn_ref::<Function>() {
+            let _ = onend.call0(&JsValue::NULL);
+        }
+    }
+}
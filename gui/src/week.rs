@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use leptos::ev::DragEvent;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use todotxt::plan::{self, PlanField};
+use todotxt::Date;
+use types::{PlanTodosArgs, Todo};
+
+use crate::backend::invoke;
+
+/// The date of `todo` that planning sets.
+fn planned(todo: &Todo, field: PlanField) -> Option<&str> {
+    match field {
+        PlanField::Due => todo.due_date.as_deref(),
+        PlanField::Threshold => todo.threshold_date.as_deref(),
+    }
+}
+
+/// A week to plan: pending todos without a date in a backlog column and the
+/// ones planned for each day in a column per day. Dropping todos on a day
+/// sets their due or threshold date, dropping them on the backlog clears
+/// it. The ticked todos move together.
+#[component]
+pub fn WeekPlanner(
+    todos: Signal<Vec<Todo>>,
+    /// Today as `YYYY-MM-DD`
+    today: Memo<String>,
+    read_only: Signal<bool>,
+    set_error: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    // Weeks from the current one
+    let (offset, set_offset) = signal(0i64);
+    let (field, set_field) = signal(PlanField::Due);
+    let (selected, set_selected) = signal(HashSet::<usize>::new());
+    let (dragging, set_dragging) = signal(Vec::<usize>::new());
+    // The column a drag hovers, the backlog being `Some(None)`
+    let (drop_target, set_drop_target) = signal(Option::<Option<Date>>::None);
+
+    let days = Memo::new(move |_| {
+        let today = today
+            .with(|t| Date::parse_from_str(t, "%Y-%m-%d").ok())
+            .unwrap_or_default();
+        plan::week(today, offset.get())
+    });
+
+    let plan_onto = move |ids: Vec<usize>, date: Option<Date>| {
+        if ids.is_empty() {
+            return;
+        }
+        let field = field.get_untracked();
+        spawn_local(async move {
+            let date = date.map(|d| d.to_string());
+            let args = PlanTodosArgs {
+                ids: &ids,
+                field,
+                date: date.as_deref(),
+            };
+            let result = invoke("plan_todos", serde_wasm_bindgen::to_value(&args).unwrap()).await;
+            match serde_wasm_bindgen::from_value::<()>(result) {
+                Ok(()) => {
+                    set_error.set(None);
+                    set_selected.set(HashSet::new());
+                }
+                Err(e) => set_error.set(Some(format!("Failed to plan todos: {e}"))),
+            }
+        });
+    };
+
+    let card = move |todo: Todo| {
+        let id = todo.id;
+        let subject = todo.subject;
+        let time = todo
+            .due_time
+            .filter(|_| field.get_untracked() == PlanField::Due);
+        view! {
+            <li
+                class="bg-base-100 rounded-box p-2 text-sm flex items-start gap-2 cursor-grab"
+                class=("ring-2", move || selected.with(|s| s.contains(&id)))
+                draggable=move || (!read_only.get()).to_string()
+                on:dragstart=move |ev: DragEvent| {
+                    let mut ids = vec![id];
+                    if selected.with_untracked(|s| s.contains(&id)) {
+                        ids = selected.with_untracked(|s| s.iter().copied().collect());
+                        ids.sort_unstable();
+                    }
+                    // Some browsers only start a drag that carries data
+                    if let Some(transfer) = ev.data_transfer() {
+                        let ids: Vec<String> = ids.iter().map(usize::to_string).collect();
+                        let _ = transfer.set_data("text/plain", &ids.join(","));
+                    }
+                    set_dragging.set(ids);
+                }
+                on:dragend=move |_| {
+                    set_dragging.set(Vec::new());
+                    set_drop_target.set(None);
+                }
+            >
+                <input
+                    type="checkbox"
+                    class="checkbox checkbox-xs mt-0.5"
+                    class=("hidden", move || read_only.get())
+                    aria-label=format!("Select \"{subject}\"")
+                    prop:checked=move || selected.with(|s| s.contains(&id))
+                    on:change=move |ev| {
+                        let checked = event_target_checked(&ev);
+                        set_selected.update(|s| {
+                            if checked {
+                                s.insert(id);
+                            } else {
+                                s.remove(&id);
+                            }
+                        });
+                    }
+                />
+                <span>
+                    {time.map(|time| view! { <time class="font-mono opacity-70">{time}</time>" " })}
+                    {subject.clone()}
+                </span>
+            </li>
+        }
+    };
+
+    let column = move |date: Option<Date>| {
+        let label = date.map_or("Backlog".to_string(), |d| d.format("%a %-d %b").to_string());
+        let is_today = move || date.is_some_and(|d| today.with(|t| *t == d.to_string()));
+        let shown = move || {
+            let date = date.map(|d| d.to_string());
+            let field = field.get();
+            let mut shown = todos.with(|items| {
+                items
+                    .iter()
+                    .filter(|todo| !todo.finished && planned(todo, field) == date.as_deref())
+                    .cloned()
+                    .collect::<Vec<Todo>>()
+            });
+            // Timed todos after the ones due all day, by time
+            if field == PlanField::Due {
+                shown.sort_by(|a, b| a.due_time.cmp(&b.due_time));
+            }
+            shown
+        };
+        view! {
+            <section
+                class="flex-1 min-w-36 bg-base-200 rounded-box p-2"
+                class=("ring-2", move || drop_target.get() == Some(date))
+                class=("ring-primary", move || drop_target.get() == Some(date))
+                aria-label=label.clone()
+                on:dragover=move |ev: DragEvent| {
+                    ev.prevent_default();
+                    set_drop_target.set(Some(date));
+                }
+                on:dragleave=move |_| set_drop_target.set(None)
+                on:drop=move |ev: DragEvent| {
+                    ev.prevent_default();
+                    set_drop_target.set(None);
+                    plan_onto(dragging.get_untracked(), date);
+                    set_dragging.set(Vec::new());
+                }
+            >
+                <h3 class="font-semibold text-sm mb-2" class=("text-primary", is_today)>{label}</h3>
+                <ul class="flex flex-col gap-1">
+                    {move || shown().into_iter().map(card).collect_view()}
+                </ul>
+            </section>
+        }
+    };
+
+    view! {
+        <div class="flex flex-wrap items-center gap-2 mb-4">
+            <div class="join" role="group" aria-label="Week">
+                <button type="button" class="btn btn-sm join-item" aria-label="Previous week" on:click=move |_| set_offset.update(|o| *o -= 1)>
+                    "‹"
+                </button>
+                <button type="button" class="btn btn-sm join-item" on:click=move |_| set_offset.set(0)>
+                    "This week"
+                </button>
+                <button type="button" class="btn btn-sm join-item" aria-label="Next week" on:click=move |_| set_offset.update(|o| *o += 1)>
+                    "›"
+                </button>
+            </div>
+            <select
+                class="select select-bordered select-sm w-auto"
+                aria-label="Date to plan"
+                on:change=move |ev| {
+                    set_field.set(if event_target_value(&ev) == "threshold" { PlanField::Threshold } else { PlanField::Due });
+                }
+            >
+                <option value="due" selected=move || field.get() == PlanField::Due>"Due date"</option>
+                <option value="threshold" selected=move || field.get() == PlanField::Threshold>"Start date (t:)"</option>
+            </select>
+            // Moving without a mouse
+            <select
+                class="select select-bordered select-sm w-auto"
+                class=("hidden", move || read_only.get() || selected.with(HashSet::is_empty))
+                aria-label="Move selected todos to"
+                // Back to the prompt for the next selection
+                prop:value=move || {
+                    selected.track();
+                    String::new()
+                }
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    if value.is_empty() {
+                        return;
+                    }
+                    let date = Date::parse_from_str(&value, "%Y-%m-%d").ok();
+                    plan_onto(selected.with_untracked(|s| s.iter().copied().collect()), date);
+                }
+            >
+                <option value="" selected=true>"Move selected to…"</option>
+                <option value="backlog">"Backlog"</option>
+                {move || days.get().into_iter().map(|day| view! {
+                    <option value=day.to_string()>{day.format("%A %-d %b").to_string()}</option>
+                }).collect_view()}
+            </select>
+        </div>
+        <div class="flex gap-2 overflow-x-auto pb-2">
+            {column(None)}
+            {move || days.get().into_iter().map(|day| column(Some(day))).collect_view()}
+        </div>
+    }
+}
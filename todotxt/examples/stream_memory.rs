@@ -0,0 +1,76 @@
+//! Peak memory of loading a large done.txt versus streaming it, for the
+//! completion statistics.
+//!
+//! ```sh
+//! cargo run --release -p todotxt --example stream_memory -- 200000
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use todotxt::{stats, stream, TodoList};
+
+/// Counts the bytes allocated through it, and the most at any one time.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Peak bytes allocated while `run` runs, on top of what was allocated before.
+fn peak(run: impl FnOnce() -> usize) -> (usize, usize) {
+    let before = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let days = run();
+    (PEAK.load(Ordering::Relaxed) - before, days)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let lines: usize = match std::env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 100_000,
+    };
+    let path = std::env::temp_dir().join("todotxt-stream-memory.txt");
+    let mut out = BufWriter::new(File::create(&path)?);
+    for i in 0..lines {
+        let day = i % 28 + 1;
+        writeln!(
+            out,
+            "x 2024-02-{day:02} 2024-01-01 Archived task number {i} +project @context"
+        )?;
+    }
+    out.flush()?;
+    drop(out);
+
+    let (loaded, days) = peak(|| {
+        let list = TodoList::from_file(&path).unwrap();
+        stats::completions_per_day(list.items()).len()
+    });
+    println!("loaded:   {:>10} KiB peak, {days} days", loaded / 1024);
+
+    let (streamed, days) = peak(|| {
+        let items = stream::items(BufReader::new(File::open(&path).unwrap()));
+        stats::completions_per_day(items.map(Result::unwrap)).len()
+    });
+    println!("streamed: {:>10} KiB peak, {days} days", streamed / 1024);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
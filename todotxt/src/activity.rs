@@ -0,0 +1,281 @@
+//! Append-only journal of changes to a todo list, stored as one JSON object
+//! per line (`todo.log.jsonl`).
+//!
+//! Entries record lines rather than ids, since ids are only stable while a
+//! list is loaded. A task's history is found by following its current line
+//! back through the edits that produced it.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{TodoItem, TodoList};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Add,
+    Edit,
+    Complete,
+    Uncomplete,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: NaiveDateTime,
+    /// What made the change, e.g. `app` or `sync desktop.local:47811`
+    pub actor: String,
+    pub action: Action,
+    /// The line before the change; `None` for additions
+    pub before: Option<String>,
+    /// The line after the change; `None` for deletions
+    pub after: Option<String>,
+}
+
+/// The current local time, for stamping entries.
+pub fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// The changes that turn `before` into `after`. Lines present in both are
+/// unchanged; of the rest, lines sharing an id count as edits of each other.
+pub fn changes(before: &TodoList, after: &TodoList, actor: &str, at: NaiveDateTime) -> Vec<Entry> {
+    // Lines of `list` that aren't matched by a line of `other`
+    fn unmatched<'a>(list: &'a TodoList, other: &TodoList) -> Vec<&'a TodoItem> {
+        let mut counts = HashMap::<String, usize>::new();
+        for item in other.items() {
+            *counts.entry(item.raw()).or_insert(0) += 1;
+        }
+        list.items()
+            .iter()
+            .filter(|item| match counts.get_mut(&item.raw()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .collect()
+    }
+    let removed = unmatched(before, after);
+    let mut added = unmatched(after, before);
+
+    let entry = |action, before: Option<String>, after: Option<String>| Entry {
+        at,
+        actor: actor.to_string(),
+        action,
+        before,
+        after,
+    };
+    let mut entries = Vec::new();
+    for old in &removed {
+        match added.iter().position(|new| new.id == old.id) {
+            Some(pos) => {
+                let new = added.remove(pos);
+                let action = match (old.finished(), new.finished()) {
+                    (false, true) => Action::Complete,
+                    (true, false) => Action::Uncomplete,
+                    _ => Action::Edit,
+                };
+                entries.push(entry(action, Some(old.raw()), Some(new.raw())));
+            }
+            None => entries.push(entry(Action::Delete, Some(old.raw()), None)),
+        }
+    }
+    for new in added {
+        entries.push(entry(Action::Add, None, Some(new.raw())));
+    }
+    entries
+}
+
+/// Appends `entries` to the log at `path`, creating it if needed.
+pub fn append(path: impl AsRef<Path>, entries: &[Entry]) -> Result<(), std::io::Error> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())
+}
+
+/// Reads the log at `path`, oldest entry first. A missing log is empty and
+/// lines that don't parse, e.g. one cut off by a crash, are skipped.
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<Entry>, std::io::Error> {
+    let content = match fs::read_to_string(path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        result => result?,
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("skipping activity log line: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// The entries that led to the task whose line is now `raw`, newest first.
+pub fn history<'a>(entries: &'a [Entry], raw: &str) -> Vec<&'a Entry> {
+    let mut current = Some(raw.to_string());
+    let mut found = Vec::new();
+    for entry in entries.iter().rev() {
+        let Some(line) = &current else {
+            break;
+        };
+        if entry.after.as_ref() == Some(line) {
+            found.push(entry);
+            current = entry.before.clone();
+        }
+    }
+    found
+}
+
+/// The tasks among `items` that changed most recently, newest first, each
+/// with the entry of its last change. At most `limit` are returned; tasks
+/// whose line was changed outside the app since aren't found.
+pub fn recent<'a>(
+    entries: &'a [Entry],
+    items: &'a [TodoItem],
+    limit: usize,
+) -> Vec<(&'a TodoItem, &'a Entry)> {
+    let mut taken = vec![false; items.len()];
+    let mut found = Vec::new();
+    for entry in entries.iter().rev() {
+        if found.len() == limit {
+            break;
+        }
+        let Some(line) = &entry.after else {
+            continue;
+        };
+        let pos = items
+            .iter()
+            .enumerate()
+            .position(|(i, item)| !taken[i] && item.raw() == *line);
+        if let Some(pos) = pos {
+            taken[pos] = true;
+            found.push((&items[pos], entry));
+        }
+    }
+    found
+}
+
+/// Reverts the change `entry` describes in `list`. Returns false if the line
+/// it produced is no longer there.
+pub fn undo(list: &mut TodoList, entry: &Entry) -> bool {
+    let Some(after) = &entry.after else {
+        if let Some(before) = &entry.before {
+            list.add(before);
+        }
+        return entry.before.is_some();
+    };
+    let Some(id) = list
+        .items()
+        .iter()
+        .find(|item| &item.raw() == after)
+        .map(|item| item.id)
+    else {
+        return false;
+    };
+    match &entry.before {
+        Some(before) => {
+            if let Some(item) = list.get_mut(id) {
+                item.set_raw(before);
+            }
+        }
+        None => {
+            list.remove(id);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at() -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2025, 3, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = TodoList::from_content("Call Bob\nWrite report\nBuy milk\n");
+        let mut after = before.clone();
+        after.complete(1);
+        after.get_mut(2).unwrap().set_raw("(A) Write report");
+        after.remove(3);
+        after.add("Water plants");
+
+        let entries = changes(&before, &after, "app", at());
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.action, e.before.as_deref(), e.after.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Action::Complete, Some("Call Bob"), Some("x Call Bob")),
+                (Action::Edit, Some("Write report"), Some("(A) Write report")),
+                (Action::Delete, Some("Buy milk"), None),
+                (Action::Add, None, Some("Water plants")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_and_undo() {
+        let mut list = TodoList::from_content("Call Bob\nBuy milk\n");
+        let mut log = Vec::new();
+        for edit in ["Call Bob +work", "(A) Call Bob +work"] {
+            let before = list.clone();
+            list.get_mut(1).unwrap().set_raw(edit);
+            log.extend(changes(&before, &list, "app", at()));
+        }
+
+        let found = history(&log, "(A) Call Bob +work");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].before.as_deref(), Some("Call Bob"));
+        assert!(history(&log, "Buy milk").is_empty());
+
+        assert!(undo(&mut list, found[0]));
+        assert_eq!(list.get(1).unwrap().raw(), "Call Bob +work");
+        assert!(!undo(&mut list, found[0]));
+    }
+
+    #[test]
+    fn test_recent() {
+        let mut list = TodoList::from_content("Call Bob\nBuy milk\nWater plants\n");
+        let mut log = Vec::new();
+        for (id, edit) in [
+            (1, "Call Bob +work"),
+            (2, "x Buy milk"),
+            (1, "(A) Call Bob +work"),
+        ] {
+            let before = list.clone();
+            list.get_mut(id).unwrap().set_raw(edit);
+            log.extend(changes(&before, &list, "app", at()));
+        }
+
+        let found: Vec<(usize, Action)> = recent(&log, list.items(), 10)
+            .into_iter()
+            .map(|(item, entry)| (item.id, entry.action))
+            .collect();
+        assert_eq!(found, [(1, Action::Edit), (2, Action::Complete)]);
+        assert_eq!(recent(&log, list.items(), 1).len(), 1);
+    }
+}
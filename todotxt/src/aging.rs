@@ -0,0 +1,53 @@
+//! How long tasks have been on the list, from their creation dates, to spot
+//! the ones that went stale.
+
+use crate::{Date, TodoItem};
+
+/// Days since `item` was created, `None` without a creation date.
+pub fn age(item: &TodoItem, today: Date) -> Option<u32> {
+    let days = (today - item.creation_date()?).num_days();
+    Some(days.max(0) as u32)
+}
+
+/// The pending `items` created more than `after_days` days ago, oldest first.
+pub fn stale<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    today: Date,
+    after_days: u32,
+) -> Vec<&'a TodoItem> {
+    let mut stale: Vec<&TodoItem> = items
+        .into_iter()
+        .filter(|item| !item.finished())
+        .filter(|item| age(item, today).is_some_and(|age| age > after_days))
+        .collect();
+    stale.sort_by_key(|item| item.creation_date());
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_age_and_stale() {
+        let today = Date::from_ymd_opt(2025, 3, 10).unwrap();
+        assert_eq!(
+            age(&TodoItem::new("2025-03-01 Water plants"), today),
+            Some(9)
+        );
+        assert_eq!(age(&TodoItem::new("Water plants"), today), None);
+
+        let mut list = TodoList::new();
+        list.add("2025-02-01 Fix the fence");
+        list.add("2025-01-01 Learn Italian");
+        list.add("x 2025-03-09 2025-01-01 Done already");
+        list.add("2025-03-01 Call Bob");
+        list.add("Undated");
+        let subjects: Vec<&str> = stale(list.items(), today, 30)
+            .into_iter()
+            .map(|item| item.subject())
+            .collect();
+        assert_eq!(subjects, vec!["Learn Italian", "Fix the fence"]);
+    }
+}
@@ -0,0 +1,234 @@
+//! Archiving finished tasks from todo.txt to done.txt, and keeping done.txt
+//! small: completions from past years move to one file per year next to it
+//! (`done-2024.txt`), and an index keeps their counts per day so statistics
+//! such as streaks don't need to read them.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::stats::completions_per_day;
+use crate::{Date, TodoItem, TodoList};
+
+/// Completions per day in the yearly files, kept next to done.txt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveIndex {
+    pub completions: BTreeMap<Date, usize>,
+}
+
+impl ArchiveIndex {
+    /// The index at `path`; missing means nothing was archived yet.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        match fs::read_to_string(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            result => Ok(serde_json::from_str(&result?)?),
+        }
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// Adds the completions of `items`.
+    pub fn add<'a>(&mut self, items: impl IntoIterator<Item = &'a TodoItem>) {
+        for (date, count) in completions_per_day(items) {
+            *self.completions.entry(date).or_insert(0) += count;
+        }
+    }
+
+    /// `per_day` with the archived completions added, for
+    /// [`crate::stats::streaks`].
+    pub fn merged(&self, mut per_day: BTreeMap<Date, usize>) -> BTreeMap<Date, usize> {
+        for (&date, &count) in &self.completions {
+            *per_day.entry(date).or_insert(0) += count;
+        }
+        per_day
+    }
+}
+
+/// The yearly file for `year` next to `done_path`, e.g. `done-2024.txt`.
+pub fn year_path(done_path: &Path, year: i32) -> PathBuf {
+    let stem = done_path
+        .file_stem()
+        .map_or("done".into(), |stem| stem.to_string_lossy());
+    done_path.with_file_name(format!("{stem}-{year}.txt"))
+}
+
+/// The index file next to `done_path`.
+pub fn index_path(done_path: &Path) -> PathBuf {
+    done_path.with_file_name("done-index.json")
+}
+
+/// The finished `items` completed before `year`, by year of completion.
+/// Everything else, including tasks without a completion date, stays.
+pub fn split_by_year<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    year: i32,
+) -> (Vec<&'a TodoItem>, BTreeMap<i32, Vec<&'a TodoItem>>) {
+    let mut keep = Vec::new();
+    let mut years: BTreeMap<i32, Vec<&TodoItem>> = BTreeMap::new();
+    for item in items {
+        match item.completion_date().filter(|_| item.finished()) {
+            Some(date) if date.year() < year => years.entry(date.year()).or_default().push(item),
+            _ => keep.push(item),
+        }
+    }
+    (keep, years)
+}
+
+/// A line of todo.txt in an [`ArchivePreview`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveLine {
+    pub id: usize,
+    pub raw: String,
+}
+
+/// Which lines archiving moves to done.txt and which stay in todo.txt.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivePreview {
+    pub moved: Vec<ArchiveLine>,
+    pub kept: Vec<ArchiveLine>,
+}
+
+/// What [`archive_done`] would do to `list`, without doing it: the finished
+/// items move, except those in `skip`.
+pub fn preview(list: &TodoList, skip: &[usize]) -> ArchivePreview {
+    let mut preview = ArchivePreview::default();
+    for item in list.items() {
+        let line = ArchiveLine {
+            id: item.id,
+            raw: item.raw(),
+        };
+        if item.finished() && !skip.contains(&item.id) {
+            preview.moved.push(line);
+        } else {
+            preview.kept.push(line);
+        }
+    }
+    preview
+}
+
+/// Moves the finished items of `list` but those in `skip` to the end of
+/// `done`, as [`preview`] shows it, and returns what it did.
+pub fn archive_done(list: &mut TodoList, done: &mut TodoList, skip: &[usize]) -> ArchivePreview {
+    let preview = preview(list, skip);
+    for line in &preview.moved {
+        if let Some(item) = list.remove(line.id) {
+            done.add(&item.raw());
+        }
+    }
+    preview
+}
+
+/// What [`compact`] moved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Compaction {
+    /// Tasks moved out of done.txt
+    pub moved: usize,
+    /// The yearly files written to
+    pub files: Vec<String>,
+}
+
+/// Moves the completions from before `year` out of the done.txt at
+/// `done_path` into yearly files, appending to those that exist, and adds
+/// them to the index. The yearly files are written before done.txt, so an
+/// interruption can duplicate tasks but never lose them.
+pub fn compact(done_path: &Path, year: i32) -> Result<Compaction, std::io::Error> {
+    let done = TodoList::from_file(done_path)?;
+    let (keep, years) = split_by_year(done.items(), year);
+    if years.is_empty() {
+        return Ok(Compaction::default());
+    }
+
+    let mut compaction = Compaction::default();
+    let mut index = ArchiveIndex::read(index_path(done_path))?;
+    for (year, items) in &years {
+        let path = year_path(done_path, *year);
+        let lines: String = items.iter().map(|item| item.raw() + "\n").collect();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        file.write_all(lines.as_bytes())?;
+        index.add(items.iter().copied());
+        compaction.moved += items.len();
+        compaction.files.push(path.display().to_string());
+    }
+    index.write(index_path(done_path))?;
+
+    let content: String = keep.iter().map(|item| item.raw() + "\n").collect();
+    fs::write(done_path, content)?;
+    tracing::info!(moved = compaction.moved, "compacted done.txt");
+    Ok(compaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_by_year() {
+        let mut list = TodoList::new();
+        list.add("x 2023-12-31 2023-12-01 Old");
+        list.add("x 2024-06-01 2024-05-01 Last year");
+        list.add("x 2025-01-02 2025-01-01 This year");
+        list.add("x No dates");
+        list.add("2023-01-01 Somehow still pending");
+
+        let (keep, years) = split_by_year(list.items(), 2025);
+        let subjects = |items: &[&TodoItem]| -> Vec<String> {
+            items
+                .iter()
+                .map(|item| item.subject().to_string())
+                .collect()
+        };
+        assert_eq!(
+            subjects(&keep),
+            vec!["This year", "No dates", "Somehow still pending"]
+        );
+        assert_eq!(subjects(&years[&2023]), vec!["Old"]);
+        assert_eq!(subjects(&years[&2024]), vec!["Last year"]);
+
+        assert_eq!(
+            year_path(Path::new("/todo/done.txt"), 2024),
+            Path::new("/todo/done-2024.txt")
+        );
+    }
+
+    #[test]
+    fn test_archive_done_skips_opted_out_lines() {
+        let mut list =
+            TodoList::from_content("x 2025-01-02 Call Bob\nWater plants\nx 2025-01-03 Pay rent");
+        let mut done = TodoList::new();
+        let planned = preview(&list, &[3]);
+        let raws = |lines: &[ArchiveLine]| -> Vec<String> {
+            lines.iter().map(|line| line.raw.clone()).collect()
+        };
+        assert_eq!(raws(&planned.moved), vec!["x 2025-01-02 Call Bob"]);
+        assert_eq!(
+            raws(&planned.kept),
+            vec!["Water plants", "x 2025-01-03 Pay rent"]
+        );
+        // The dry run changed nothing
+        assert_eq!(list.items().len(), 3);
+
+        assert_eq!(archive_done(&mut list, &mut done, &[3]), planned);
+        assert_eq!(list.to_content(), "Water plants\nx 2025-01-03 Pay rent");
+        assert_eq!(done.to_content(), "x 2025-01-02 Call Bob");
+    }
+
+    #[test]
+    fn test_index() {
+        let mut list = TodoList::new();
+        list.add("x 2024-06-01 2024-05-01 Mow lawn");
+        list.add("x 2024-06-01 2024-05-01 Water plants");
+        let mut index = ArchiveIndex::default();
+        index.add(list.items());
+
+        let june_1 = Date::from_ymd_opt(2024, 6, 1).unwrap();
+        let june_2 = Date::from_ymd_opt(2024, 6, 2).unwrap();
+        let merged = index.merged(BTreeMap::from([(june_1, 1), (june_2, 1)]));
+        assert_eq!(merged, BTreeMap::from([(june_1, 3), (june_2, 1)]));
+    }
+}
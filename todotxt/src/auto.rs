@@ -0,0 +1,48 @@
+//! Todos that complete themselves, like entries of a calendar: one tagged
+//! `auto:done` is checked off once its due date has passed, e.g. a chore
+//! that either happened on the day or is moot by now.
+
+use crate::{Date, TodoList};
+
+/// The pending `auto:done` todos due before `today`.
+pub fn due(list: &TodoList, today: Date) -> Vec<usize> {
+    list.pending()
+        .filter(|item| item.auto_done())
+        .filter(|item| item.due_date().is_some_and(|due| due < today))
+        .map(|item| item.id)
+        .collect()
+}
+
+/// Completes the todos [`due`] returns, each on its due date. Returns their
+/// ids.
+pub fn complete(list: &mut TodoList, today: Date) -> Vec<usize> {
+    let ids = due(list, today);
+    for &id in &ids {
+        if let Some(date) = list.get(id).and_then(|item| item.due_date()) {
+            list.complete_on(id, date);
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete() {
+        let mut list = TodoList::from_content(
+            "2025-02-01 Bins out due:2025-03-03 auto:done\n\
+             2025-02-01 Pay rent due:2025-03-03\n\
+             2025-02-01 Dentist due:2025-03-05 auto:done\n\
+             Undated auto:done",
+        );
+        let today = Date::from_ymd_opt(2025, 3, 5).unwrap();
+        assert_eq!(complete(&mut list, today), vec![1]);
+        let bins = list.get(1).unwrap();
+        assert!(bins.finished());
+        assert_eq!(bins.completion_date(), Date::from_ymd_opt(2025, 3, 3));
+        assert!(!list.get(3).unwrap().finished());
+        assert!(complete(&mut list, today).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Rotating backups of todo.txt: before the list is written, a copy of the
+//! file is kept in a `backups` directory next to it if the newest one is
+//! older than [`INTERVAL`], and only the newest [`KEEP`] copies are kept.
+//! Each is named after when it was taken, e.g.
+//! `todo-2025-03-01T14-30-00.txt`.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+pub const BACKUP_DIR: &str = "backups";
+/// How long at least lies between two backups.
+pub const INTERVAL: TimeDelta = TimeDelta::hours(1);
+/// How many backups are kept, two days' worth while working.
+pub const KEEP: usize = 48;
+
+const PREFIX: &str = "todo-";
+const STAMP: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// A copy of todo.txt from [`Backup::taken`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    /// The file name in the backup directory
+    pub name: String,
+    pub taken: NaiveDateTime,
+    /// Non-blank lines in it
+    pub lines: usize,
+}
+
+/// The backup directory for `todo_path`.
+pub fn dir(todo_path: &Path) -> PathBuf {
+    todo_path.with_file_name(BACKUP_DIR)
+}
+
+/// When the backup called `name` was taken, or `None` if `name` isn't the
+/// name of a backup.
+pub fn parse_name(name: &str) -> Option<NaiveDateTime> {
+    let stamp = name.strip_prefix(PREFIX)?.strip_suffix(".txt")?;
+    NaiveDateTime::parse_from_str(stamp, STAMP).ok()
+}
+
+fn name(taken: NaiveDateTime) -> String {
+    format!("{PREFIX}{}.txt", taken.format(STAMP))
+}
+
+/// The path of the backup `name` in `dir`, or `None` if `name` isn't the
+/// name of a backup, so nothing outside it is reached.
+pub fn path(dir: &Path, name: &str) -> Option<PathBuf> {
+    parse_name(name).map(|_| dir.join(name))
+}
+
+/// The backups in `dir`, newest first; none if there is no `dir` yet.
+pub fn list(dir: &Path) -> Result<Vec<Backup>, std::io::Error> {
+    let entries = match fs::read_dir(dir) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        result => result?,
+    };
+    let mut backups = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let Some(taken) = parse_name(&name) else {
+            continue;
+        };
+        let content = fs::read_to_string(dir.join(&name))?;
+        backups.push(Backup {
+            lines: content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count(),
+            name,
+            taken,
+        });
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.taken));
+    Ok(backups)
+}
+
+/// Copies `todo_path` into `dir` if the newest backup is older than
+/// [`INTERVAL`] or `force` is set, then drops all but the newest [`KEEP`].
+/// Returns the new backup, if one was taken.
+pub fn rotate(
+    todo_path: &Path,
+    dir: &Path,
+    now: NaiveDateTime,
+    force: bool,
+) -> Result<Option<Backup>, std::io::Error> {
+    let content = match fs::read_to_string(todo_path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        result => result?,
+    };
+    let backups = list(dir)?;
+    if !force
+        && backups
+            .first()
+            .is_some_and(|newest| now - newest.taken < INTERVAL)
+    {
+        return Ok(None);
+    }
+    fs::create_dir_all(dir)?;
+    let backup = Backup {
+        name: name(now),
+        taken: now,
+        lines: content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    };
+    fs::write(dir.join(&backup.name), content)?;
+    for old in backups.iter().skip(KEEP - 1) {
+        fs::remove_file(dir.join(&old.name))?;
+    }
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let taken =
+            NaiveDateTime::parse_from_str("2025-03-01 14:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(name(taken), "todo-2025-03-01T14-30-00.txt");
+        assert_eq!(parse_name(&name(taken)), Some(taken));
+        assert_eq!(parse_name("todo.txt"), None);
+        assert_eq!(path(Path::new("backups"), "../todo.txt"), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! iCalendar renderings of due dates, for calendar apps to subscribe to.
+
+use chrono::{Days, NaiveDateTime, TimeDelta};
+
+use crate::feed::hash;
+use crate::TodoItem;
+
+/// The current time in UTC, for stamping calendars.
+pub fn now() -> NaiveDateTime {
+    chrono::Utc::now().naive_utc()
+}
+
+/// Length of an event for a todo due at a time and without an estimate.
+const DEFAULT_MINUTES: u32 = 60;
+
+/// A calendar with an event on the due date of every pending item that has
+/// one: all day, or at its `at:` time in local time for as long as it is
+/// estimated to take. `stamp` is when the calendar was generated, in UTC.
+pub fn ics<'a>(items: impl IntoIterator<Item = &'a TodoItem>, stamp: NaiveDateTime) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ");
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//todotxt//EN".to_string(),
+        "X-WR-CALNAME:todo.txt".to_string(),
+    ];
+    for item in items.into_iter().filter(|item| !item.finished()) {
+        let Some(due) = item.due_date() else {
+            continue;
+        };
+        let (start, end) = match item.due_time() {
+            Some(time) => {
+                let start = due.and_time(time);
+                let minutes = item.estimate().unwrap_or(DEFAULT_MINUTES);
+                let end = start + TimeDelta::minutes(minutes.into());
+                (
+                    format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")),
+                    format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")),
+                )
+            }
+            None => {
+                let end = due.checked_add_days(Days::new(1)).unwrap_or(due);
+                (
+                    format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+                    format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+                )
+            }
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@todotxt", hash(&item.raw())),
+            format!("DTSTAMP:{stamp}"),
+            start,
+            end,
+            format!("SUMMARY:{}", escape(item.subject())),
+            format!("DESCRIPTION:{}", escape(&item.raw())),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        fold(line, &mut out);
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Appends `line` split into lines of at most 75 bytes, continuations
+/// starting with a space, each ending in CRLF as the format requires.
+fn fold(line: &str, out: &mut String) {
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_ics() {
+        let mut list = TodoList::new();
+        list.add("(A) Call Bob, Alice; Carol due:2025-03-01");
+        list.add("Water plants");
+        list.add("Dentist due:2025-03-02 at:14:30 est:45m");
+        list.add("x 2025-02-01 Pay rent due:2025-02-01");
+        let stamp =
+            NaiveDateTime::parse_from_str("2025-02-20 08:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let calendar = ics(list.items(), stamp);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("DTSTAMP:20250220T083000Z\r\n"));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20250301\r\n"));
+        assert!(calendar.contains("DTEND;VALUE=DATE:20250302\r\n"));
+        assert!(calendar.contains("DTSTART:20250302T143000\r\n"));
+        assert!(calendar.contains("DTEND:20250302T151500\r\n"));
+        assert!(calendar.contains("SUMMARY:Call Bob\\, Alice\\; Carol"));
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut out = String::new();
+        fold(&"a".repeat(100), &mut out);
+        assert_eq!(
+            out,
+            format!("{}\r\n {}\r\n", "a".repeat(75), "a".repeat(25))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! A health check of raw todo.txt content, for problems the parser lets
+//! through: duplicate tasks, lines [`validate_line`] rejects, completed tasks
+//! without a completion date and trailing whitespace. Most come with a fix;
+//! callers show the [`check`] findings first and then [`repair`] the file.
+//!
+//! Tasks are identified by their line, so a duplicate id shows up as the same
+//! line twice.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parse::{tokenize, Token};
+use crate::{validate_line, Date};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Issue {
+    Duplicate,
+    Malformed,
+    MissingCompletionDate,
+    TrailingWhitespace,
+}
+
+/// What repairing does about a finding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fix {
+    /// The line becomes this one
+    Replace(String),
+    Remove,
+}
+
+/// A problem found on a line. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub line: usize,
+    pub issue: Issue,
+    pub message: String,
+    pub before: String,
+    /// `None` if it needs fixing by hand
+    pub fix: Option<Fix>,
+}
+
+/// The problems in `content`, in line order. Completion dates that are
+/// missing would be filled in with `today`.
+pub fn check(content: &str, today: Date) -> Vec<Finding> {
+    let mut seen = HashMap::new();
+    let mut findings = Vec::new();
+    for (i, raw) in content.lines().enumerate() {
+        let line = i + 1;
+        let mut finding = |issue, message: String, fix| {
+            findings.push(Finding {
+                line,
+                issue,
+                message,
+                before: raw.to_string(),
+                fix,
+            })
+        };
+        let trimmed = raw.trim_end();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(first) = seen.get(trimmed) {
+            finding(
+                Issue::Duplicate,
+                format!("same task as line {first}"),
+                Some(Fix::Remove),
+            );
+            continue;
+        }
+        seen.insert(trimmed, line);
+        if trimmed.len() < raw.len() {
+            finding(
+                Issue::TrailingWhitespace,
+                "trailing whitespace".to_string(),
+                Some(Fix::Replace(trimmed.to_string())),
+            );
+        }
+        if let Some(message) = validate_line(trimmed.trim_start()) {
+            finding(Issue::Malformed, message, None);
+        }
+        if missing_completion_date(trimmed) {
+            finding(
+                Issue::MissingCompletionDate,
+                "completed without a completion date".to_string(),
+                Some(Fix::Replace(add_completion_date(trimmed, today))),
+            );
+        }
+    }
+    findings
+}
+
+/// `content` with every fix from [`check`] applied. Lines that need fixing by
+/// hand are kept as they are.
+pub fn repair(content: &str, today: Date) -> String {
+    let mut seen = HashSet::new();
+    let mut repaired = String::with_capacity(content.len());
+    for raw in content.lines() {
+        let line = raw.trim_end();
+        if !line.is_empty() && !seen.insert(line) {
+            continue;
+        }
+        if missing_completion_date(line) {
+            repaired.push_str(&add_completion_date(line, today));
+        } else {
+            repaired.push_str(line);
+        }
+        repaired.push('\n');
+    }
+    if !content.ends_with('\n') {
+        repaired.pop();
+    }
+    repaired
+}
+
+fn missing_completion_date(line: &str) -> bool {
+    let tokens = tokenize(line);
+    matches!(tokens.first().map(|t| t.token), Some(Token::CompletionMark))
+        && !matches!(tokens.get(1).map(|t| t.token), Some(Token::Date(_)))
+}
+
+fn add_completion_date(line: &str, today: Date) -> String {
+    let rest = line
+        .trim_start()
+        .strip_prefix('x')
+        .unwrap_or(line)
+        .trim_start();
+    format!("x {} {rest}", today.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_repair() {
+        let today = Date::from_ymd_opt(2025, 3, 10).unwrap();
+        let content = "Water plants  \nx Call mom\n(A) Pay rent due:someday\nWater plants\nx 2025-03-01 Done\n";
+        let findings = check(content, today);
+        let issues: Vec<(usize, Issue)> = findings.iter().map(|f| (f.line, f.issue)).collect();
+        assert_eq!(
+            issues,
+            vec![
+                (1, Issue::TrailingWhitespace),
+                (2, Issue::MissingCompletionDate),
+                (3, Issue::Malformed),
+                (4, Issue::Duplicate),
+            ]
+        );
+        assert_eq!(findings[3].message, "same task as line 1");
+        assert_eq!(
+            findings[1].fix,
+            Some(Fix::Replace("x 2025-03-10 Call mom".to_string()))
+        );
+        assert_eq!(findings[2].fix, None);
+
+        assert_eq!(
+            repair(content, today),
+            "Water plants\nx 2025-03-10 Call mom\n(A) Pay rent due:someday\nx 2025-03-01 Done\n"
+        );
+        assert!(check(&repair(content, today), today)
+            .iter()
+            .all(|f| f.fix.is_none()));
+        assert_eq!(repair("A\nA", today), "A");
+    }
+}
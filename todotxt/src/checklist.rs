@@ -0,0 +1,137 @@
+//! Checklists inside a todo, e.g. the things to pack for `Pack for trip
+//! chk:3`. The todo only carries a `chk:<key>` tag; the items live in a
+//! store of their own next to todo.txt so the line stays short.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::TodoItem;
+
+/// Tag key of the `chk:<key>` convention tying a todo to its checklist.
+pub const CHECKLIST_KEY: &str = "chk";
+/// File name of the checklist store, kept next to todo.txt.
+pub const CHECKLIST_FILE: &str = "checklists.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// How many items of a checklist are ticked off, shown as "2/5".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Every checklist by the key its todo's `chk:` tag names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Checklists {
+    lists: BTreeMap<String, Vec<CheckItem>>,
+}
+
+impl Checklists {
+    /// Reads the store at `path`, which is empty if there's no file yet.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+
+    /// The items of the checklist `key`, none if there is no such checklist.
+    pub fn get(&self, key: &str) -> &[CheckItem] {
+        self.lists.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replaces the checklist `key` with `items`, dropping it if there are none.
+    pub fn set(&mut self, key: &str, items: Vec<CheckItem>) {
+        if items.is_empty() {
+            self.lists.remove(key);
+        } else {
+            self.lists.insert(key.to_string(), items);
+        }
+    }
+
+    /// How far the checklist `key` is along, or `None` if it has no items.
+    pub fn progress(&self, key: &str) -> Option<Progress> {
+        let items = self.lists.get(key)?;
+        Some(Progress {
+            done: items.iter().filter(|item| item.done).count(),
+            total: items.len(),
+        })
+    }
+
+    /// A key no checklist uses yet, one past the highest numeric key.
+    pub fn next_key(&self) -> String {
+        let highest = self
+            .lists
+            .keys()
+            .filter_map(|key| key.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        (highest + 1).to_string()
+    }
+}
+
+/// The id and checklist progress of each of `items` with a checklist.
+pub fn progress<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    checklists: &Checklists,
+) -> Vec<(usize, Progress)> {
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let key = item.checklist()?;
+            Some((item.id, checklists.progress(key)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    fn item(text: &str, done: bool) -> CheckItem {
+        CheckItem {
+            text: text.to_string(),
+            done,
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let list = TodoList::from_content("Pack for trip chk:3\nWater plants\nShop chk:7");
+        let mut checklists = Checklists::default();
+        checklists.set(
+            "3",
+            vec![
+                item("Passport", true),
+                item("Charger", false),
+                item("Socks", true),
+            ],
+        );
+        checklists.set("7", Vec::new());
+        assert_eq!(checklists.next_key(), "4");
+        assert_eq!(
+            progress(list.items(), &checklists),
+            [(1, Progress { done: 2, total: 3 })]
+        );
+    }
+}
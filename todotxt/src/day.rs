@@ -0,0 +1,48 @@
+//! Which day it is, for due, threshold and creation dates.
+//!
+//! Dates in todo.txt have no time zone; they are read as days of the local
+//! calendar. A day may end after midnight, e.g. at 03:00 for people who
+//! work late, so a task finished at 01:00 still counts for the day before.
+//! The boundary is wall-clock time, so it stays at 03:00 across DST changes.
+
+use chrono::{Days, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::Date;
+
+/// The day `now` (local wall-clock time) belongs to, if days end at `day_ends`.
+pub fn date_at(now: NaiveDateTime, day_ends: NaiveTime) -> Date {
+    let date = now.date();
+    if day_ends.num_seconds_from_midnight() > 0 && now.time() < day_ends {
+        date.checked_sub_days(Days::new(1)).unwrap_or(date)
+    } else {
+        date
+    }
+}
+
+/// Today in the local time zone, if days end at `day_ends`.
+pub fn today(day_ends: NaiveTime) -> Date {
+    date_at(chrono::Local::now().naive_local(), day_ends)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_date_at() {
+        let midnight = NaiveTime::MIN;
+        let three = NaiveTime::from_hms_opt(3, 0, 0).unwrap();
+        let march_1 = Date::from_ymd_opt(2025, 3, 1).unwrap();
+        let feb_28 = Date::from_ymd_opt(2025, 2, 28).unwrap();
+
+        assert_eq!(date_at(at("2025-03-01 00:00"), midnight), march_1);
+        assert_eq!(date_at(at("2025-03-01 02:59"), midnight), march_1);
+        assert_eq!(date_at(at("2025-03-01 02:59"), three), feb_28);
+        assert_eq!(date_at(at("2025-03-01 03:00"), three), march_1);
+        assert_eq!(date_at(at("2025-03-01 23:59"), three), march_1);
+    }
+}
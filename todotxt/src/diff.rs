@@ -0,0 +1,167 @@
+//! Line diffs between two versions of a list, e.g. a backup and the file as
+//! it is now, for showing what differs line by line, and the tasks added,
+//! removed or modified between them, e.g. by a sync.
+
+use serde::{Deserialize, Serialize};
+
+use crate::similarity::similarity;
+use crate::TodoItem;
+
+/// A removed and an added line at least this similar are one modified task.
+pub const MODIFIED_THRESHOLD: f64 = 0.5;
+
+/// A line of a diff from an old text to a new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "line", rename_all = "lowercase")]
+pub enum DiffLine {
+    /// In both
+    Same(String),
+    /// Only in the new text
+    Added(String),
+    /// Only in the old text
+    Removed(String),
+}
+
+/// The lines turning `old` into `new`, keeping as many lines as possible
+/// in order, with each run of removals before the additions replacing it.
+/// Blank lines are left out, they don't count in todo.txt.
+pub fn lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().filter(|line| !line.trim().is_empty()).collect();
+    let new: Vec<&str> = new.lines().filter(|line| !line.trim().is_empty()).collect();
+    // kept[i][j]: how many lines of old[i..] and new[j..] can be kept
+    let mut kept = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            kept[i][j] = if old[i] == new[j] {
+                kept[i + 1][j + 1] + 1
+            } else {
+                kept[i + 1][j].max(kept[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || kept[i + 1][j] >= kept[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// A task that differs between two versions of a list, see [`tasks`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TaskChange {
+    Added {
+        line: String,
+    },
+    Removed {
+        line: String,
+    },
+    /// Reworded, completed, retagged and the like
+    Modified {
+        old: String,
+        new: String,
+    },
+}
+
+/// The tasks turning `old` into `new`, in the order of [`lines`]. A removed
+/// line and an added one of the same run of changes count as one modified
+/// task if they are [`MODIFIED_THRESHOLD`] similar.
+pub fn tasks(old: &str, new: &str) -> Vec<TaskChange> {
+    let mut changes = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+    for line in lines(old, new).into_iter().map(Some).chain([None]) {
+        match line {
+            Some(DiffLine::Removed(line)) => removed.push(line),
+            Some(DiffLine::Added(line)) => added.push(line),
+            _ => pair_run(&mut changes, &mut removed, &mut added),
+        }
+    }
+    changes
+}
+
+/// Adds the changes of a run of `removed` and `added` lines to `changes`,
+/// pairing each removed line with the most similar added one left.
+fn pair_run(changes: &mut Vec<TaskChange>, removed: &mut Vec<String>, added: &mut Vec<String>) {
+    let mut added: Vec<Option<String>> = added.drain(..).map(Some).collect();
+    for old in removed.drain(..) {
+        let old_item = TodoItem::new(&old);
+        let best = added
+            .iter()
+            .enumerate()
+            .filter_map(|(i, new)| {
+                let new = new.as_deref()?;
+                Some((i, similarity(&old_item, &TodoItem::new(new))))
+            })
+            .filter(|&(_, score)| score >= MODIFIED_THRESHOLD)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        match best.and_then(|(i, _)| added[i].take()) {
+            Some(new) => changes.push(TaskChange::Modified { old, new }),
+            None => changes.push(TaskChange::Removed { line: old }),
+        }
+    }
+    changes.extend(
+        added
+            .into_iter()
+            .flatten()
+            .map(|line| TaskChange::Added { line }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let old = "Pay rent\nCall Bob\n\nWater plants\nBuy milk";
+        let new = "Pay rent\nCall Bob @phone\nWater plants\nBuy milk\nFix bike";
+        assert_eq!(
+            lines(old, new),
+            vec![
+                DiffLine::Same("Pay rent".to_string()),
+                DiffLine::Removed("Call Bob".to_string()),
+                DiffLine::Added("Call Bob @phone".to_string()),
+                DiffLine::Same("Water plants".to_string()),
+                DiffLine::Same("Buy milk".to_string()),
+                DiffLine::Added("Fix bike".to_string()),
+            ]
+        );
+        assert!(lines("", "").is_empty());
+        assert_eq!(lines("a", ""), vec![DiffLine::Removed("a".to_string())]);
+    }
+
+    #[test]
+    fn test_tasks() {
+        let old = "Pay rent\nCall Bob\nWater plants\nBuy milk";
+        let new = "Pay rent\nx 2025-03-02 Call Bob @phone\nFix bike\nBuy milk";
+        assert_eq!(
+            tasks(old, new),
+            vec![
+                TaskChange::Modified {
+                    old: "Call Bob".to_string(),
+                    new: "x 2025-03-02 Call Bob @phone".to_string(),
+                },
+                TaskChange::Removed {
+                    line: "Water plants".to_string()
+                },
+                TaskChange::Added {
+                    line: "Fix bike".to_string()
+                },
+            ]
+        );
+        assert!(tasks(old, old).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Effort estimates from `est:` tags, e.g. `est:30m`, `est:2h` or
+//! `est:1h30m`, and the workload they add up to.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Tag key of effort estimates.
+pub const ESTIMATE_KEY: &str = "est";
+
+/// Minutes in an estimate such as `45m`, `2h` or `1h30m`.
+pub fn parse(text: &str) -> Option<u32> {
+    let text = text.trim().to_lowercase();
+    let (hours, rest) = match text.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<u32>().ok()?, rest),
+        None => (0, text.as_str()),
+    };
+    let minutes = match rest {
+        "" if hours > 0 => 0,
+        rest => rest.strip_suffix('m')?.parse::<u32>().ok()?,
+    };
+    Some(hours * 60 + minutes)
+}
+
+/// `minutes` the way estimates are written, e.g. `45m`, `4h` or `1h30m`.
+pub fn format(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes}m"),
+    }
+}
+
+/// Tasks and estimated minutes planned for one day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workload {
+    pub tasks: usize,
+    /// Tasks without an estimate add nothing
+    pub minutes: u32,
+}
+
+impl Workload {
+    /// Whether the estimates add up to more than `capacity` minutes.
+    pub fn overbooked(&self, capacity: u32) -> bool {
+        self.minutes > capacity
+    }
+}
+
+impl std::iter::Sum for Workload {
+    fn sum<I: Iterator<Item = Workload>>(workloads: I) -> Self {
+        workloads.fold(Workload::default(), |sum, workload| Workload {
+            tasks: sum.tasks + workload.tasks,
+            minutes: sum.minutes + workload.minutes,
+        })
+    }
+}
+
+/// The workload of tasks with these estimates.
+pub fn total(estimates: impl IntoIterator<Item = Option<u32>>) -> Workload {
+    let mut workload = Workload::default();
+    for estimate in estimates {
+        workload.tasks += 1;
+        workload.minutes += estimate.unwrap_or(0);
+    }
+    workload
+}
+
+/// The workload of each day, from pairs of a day and a task's estimate.
+pub fn per_day<D: Ord>(tasks: impl IntoIterator<Item = (D, Option<u32>)>) -> BTreeMap<D, Workload> {
+    let mut days: BTreeMap<D, Vec<Option<u32>>> = BTreeMap::new();
+    for (day, estimate) in tasks {
+        days.entry(day).or_default().push(estimate);
+    }
+    days.into_iter()
+        .map(|(day, estimates)| (day, total(estimates)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse("30m"), Some(30));
+        assert_eq!(parse("2h"), Some(120));
+        assert_eq!(parse("1h30m"), Some(90));
+        assert_eq!(parse("2H"), Some(120));
+        assert_eq!(parse("h"), None);
+        assert_eq!(parse("30"), None);
+        assert_eq!(parse("soon"), None);
+
+        assert_eq!(format(45), "45m");
+        assert_eq!(format(240), "4h");
+        assert_eq!(format(90), "1h30m");
+    }
+
+    #[test]
+    fn test_workload() {
+        let today = total([Some(30), None, Some(120)]);
+        assert_eq!(
+            today,
+            Workload {
+                tasks: 3,
+                minutes: 150
+            }
+        );
+        assert!(today.overbooked(120));
+        assert!(!today.overbooked(480));
+
+        let days = per_day([
+            ("2025-03-02", Some(60)),
+            ("2025-03-01", Some(30)),
+            ("2025-03-02", None),
+        ]);
+        assert_eq!(
+            days["2025-03-01"],
+            Workload {
+                tasks: 1,
+                minutes: 30
+            }
+        );
+        assert_eq!(
+            days["2025-03-02"],
+            Workload {
+                tasks: 2,
+                minutes: 60
+            }
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Plain text renderings of (filtered) todos for copying or sharing.
+
+use serde::{Deserialize, Serialize};
+
+use crate::TodoItem;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// The todo.txt lines, unchanged
+    #[default]
+    Raw,
+    /// A Markdown checklist, e.g. `- [ ] (A) Call Bob +work (due 2025-03-01)`
+    Checklist,
+}
+
+/// Renders `items` one per line in `format`.
+pub fn render<'a>(items: impl IntoIterator<Item = &'a TodoItem>, format: ExportFormat) -> String {
+    let mut out = String::new();
+    for item in items {
+        match format {
+            ExportFormat::Raw => out.push_str(&item.raw()),
+            ExportFormat::Checklist => out.push_str(&checklist_line(item)),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn checklist_line(item: &TodoItem) -> String {
+    let mut line = String::from(if item.finished() { "- [x] " } else { "- [ ] " });
+    if let Some(priority) = priority_letter(item.priority()) {
+        line.push_str(&format!("({priority}) "));
+    }
+    line.push_str(item.subject());
+    if let Some(due) = item.due_label() {
+        line.push_str(&format!(" (due {due})"));
+    }
+    line
+}
+
+/// `todo_txt` stores priority A as 0 and "no priority" as 26.
+pub(crate) fn priority_letter(priority: u8) -> Option<char> {
+    (priority < 26).then(|| (b'A' + priority) as char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_render() {
+        let mut list = TodoList::new();
+        list.add("(A) Call Bob +work due:2025-03-01");
+        list.add("x 2025-03-02 Email Alice @computer");
+
+        assert_eq!(
+            render(list.items(), ExportFormat::Raw),
+            "(A) Call Bob +work due:2025-03-01\nx 2025-03-02 Email Alice @computer\n"
+        );
+        assert_eq!(
+            render(list.items(), ExportFormat::Checklist),
+            "- [ ] (A) Call Bob +work (due 2025-03-01)\n- [x] Email Alice @computer\n"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Atom feeds of (filtered) todos, for feed readers and dashboards.
+
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+
+use crate::TodoItem;
+
+/// The current local time with its offset, as feeds need it.
+pub fn now() -> DateTime<FixedOffset> {
+    chrono::Local::now().fixed_offset()
+}
+
+/// An Atom feed called `title` with one entry per item. Entries are
+/// identified by their line, so editing a task shows up as a new entry.
+pub fn atom<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    title: &str,
+    updated: DateTime<FixedOffset>,
+) -> String {
+    let updated = updated.to_rfc3339_opts(SecondsFormat::Secs, false);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    out.push_str(&format!("  <id>urn:todotxt:{}</id>\n", hash(title)));
+    out.push_str(&format!("  <updated>{updated}</updated>\n"));
+    out.push_str("  <author><name>todo.txt</name></author>\n");
+    for item in items {
+        let raw = item.raw();
+        let summary = match item.due_label() {
+            Some(due) => format!("Due {due}"),
+            None => "No due date".to_string(),
+        };
+        out.push_str("  <entry>\n");
+        out.push_str(&format!("    <title>{}</title>\n", escape(item.subject())));
+        out.push_str(&format!("    <id>urn:todotxt:{}</id>\n", hash(&raw)));
+        out.push_str(&format!("    <updated>{updated}</updated>\n"));
+        out.push_str(&format!("    <summary>{summary}</summary>\n"));
+        out.push_str(&format!(
+            "    <content type=\"text\">{}</content>\n",
+            escape(&raw)
+        ));
+        out.push_str("  </entry>\n");
+    }
+    out.push_str("</feed>\n");
+    out
+}
+
+/// A short stable id for `text`, used to identify entries. It's the 64-bit
+/// FNV-1a hash, which unlike the standard library's hashers stays the same
+/// across Rust releases, so readers don't see every entry anew after an
+/// upgrade.
+pub(crate) fn hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_atom() {
+        let mut list = TodoList::new();
+        list.add("(A) Call Bob & Alice <3 due:2025-03-01");
+        list.add("Water plants");
+        let updated = DateTime::parse_from_rfc3339("2025-03-01T08:00:00+01:00").unwrap();
+
+        let feed = atom(list.items(), "Due soon", updated);
+
+        assert!(feed.starts_with("<?xml"));
+        assert!(feed.contains("<title>Due soon</title>"));
+        assert!(feed.contains("<updated>2025-03-01T08:00:00+01:00</updated>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(feed.contains("<title>Call Bob &amp; Alice &lt;3</title>"));
+        assert!(feed.contains("<summary>Due 2025-03-01</summary>"));
+        assert!(feed.contains("<summary>No due date</summary>"));
+        assert!(feed.ends_with("</feed>\n"));
+        // Ids must not change with the toolchain
+        assert!(feed.contains("<id>urn:todotxt:bed13320969cfc52</id>"));
+    }
+}
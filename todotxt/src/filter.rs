@@ -0,0 +1,380 @@
+//! Combinable criteria for narrowing down a todo list.
+
+use chrono::Days;
+use serde::{Deserialize, Serialize};
+
+use crate::export::priority_letter;
+use crate::{Date, TodoItem};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    All,
+    Pending,
+    Done,
+}
+
+/// Due date window relative to today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Due {
+    /// Due today or overdue
+    Today,
+    /// Due after today
+    Upcoming,
+    /// Due before today
+    Overdue,
+    /// Due today, not before
+    TodayOnly,
+    /// Due in the six days after today, which with today make a week
+    ThisWeek,
+}
+
+/// Days after today that [`Due::ThisWeek`] covers.
+pub const WEEK_DAYS: u64 = 6;
+
+impl Due {
+    pub fn matches(self, item: &TodoItem, today: Date) -> bool {
+        match (self, item.due_date()) {
+            (Due::Today, Some(due)) => due <= today,
+            (Due::Upcoming, Some(due)) => due > today,
+            (Due::Overdue, Some(due)) => due < today,
+            (Due::TodayOnly, Some(due)) => due == today,
+            (Due::ThisWeek, Some(due)) => {
+                due > today
+                    && today
+                        .checked_add_days(Days::new(WEEK_DAYS))
+                        .is_some_and(|end| due <= end)
+            }
+            (_, None) => false,
+        }
+    }
+
+    /// The name in links and settings, e.g. `this-week`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Due::Today => "today",
+            Due::Upcoming => "upcoming",
+            Due::Overdue => "overdue",
+            Due::TodayOnly => "today-only",
+            Due::ThisWeek => "this-week",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Due> {
+        [
+            Due::Today,
+            Due::Upcoming,
+            Due::Overdue,
+            Due::TodayOnly,
+            Due::ThisWeek,
+        ]
+        .into_iter()
+        .find(|due| due.name() == name)
+    }
+}
+
+/// Tag key of [`Energy`] levels.
+pub const ENERGY_KEY: &str = "energy";
+
+/// How much energy a task takes, from an `energy:low`, `energy:med` or
+/// `energy:high` tag, so tired people can find something doable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Energy {
+    Low,
+    Med,
+    High,
+}
+
+impl Energy {
+    /// The tag value, e.g. `low`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Energy::Low => "low",
+            Energy::Med => "med",
+            Energy::High => "high",
+        }
+    }
+
+    /// A tag value, also accepting `medium`.
+    pub fn parse(value: &str) -> Option<Energy> {
+        match value.to_lowercase().as_str() {
+            "low" => Some(Energy::Low),
+            "med" | "medium" => Some(Energy::Med),
+            "high" => Some(Energy::High),
+            _ => None,
+        }
+    }
+}
+
+/// Tag key of [`Flag`]s.
+pub const FLAG_KEY: &str = "flag";
+
+/// A color a task is flagged with, from a `flag:red` tag, for sorting tasks
+/// into a few kinds at a glance whatever their priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Flag {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl Flag {
+    pub const ALL: [Flag; 6] = [
+        Flag::Red,
+        Flag::Orange,
+        Flag::Yellow,
+        Flag::Green,
+        Flag::Blue,
+        Flag::Purple,
+    ];
+
+    /// The tag value, e.g. `red`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::Red => "red",
+            Flag::Orange => "orange",
+            Flag::Yellow => "yellow",
+            Flag::Green => "green",
+            Flag::Blue => "blue",
+            Flag::Purple => "purple",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Flag::Red => "Red",
+            Flag::Orange => "Orange",
+            Flag::Yellow => "Yellow",
+            Flag::Green => "Green",
+            Flag::Blue => "Blue",
+            Flag::Purple => "Purple",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Flag> {
+        let value = value.to_lowercase();
+        Flag::ALL.into_iter().find(|flag| flag.name() == value)
+    }
+}
+
+/// Every criterion that is set must match. Projects and contexts also match
+/// the tags nested below them, e.g. `work` matches `+work---email`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Filter {
+    pub project: Option<String>,
+    pub context: Option<String>,
+    pub status: Status,
+    pub due: Option<Due>,
+    pub assignee: Option<String>,
+    /// Matches items with at least one of these contexts, e.g. the ones
+    /// placed near the user, see [`crate::geo`]. An empty list matches nothing.
+    pub any_context: Option<Vec<String>>,
+    pub energy: Option<Energy>,
+    pub flag: Option<Flag>,
+    /// Only starred items, see [`TodoItem::starred`]
+    pub starred: bool,
+    /// Matches items with one of these priority letters, `None` standing for
+    /// no priority. An empty list matches every priority.
+    pub priorities: Vec<Option<char>>,
+    /// Only the item with this id, e.g. one followed from a link
+    pub id: Option<usize>,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `item` matches, with `separator` splitting hierarchical tag names.
+    pub fn matches(&self, item: &TodoItem, separator: &str, today: Date) -> bool {
+        let status = match self.status {
+            Status::All => true,
+            Status::Pending => !item.finished(),
+            Status::Done => item.finished(),
+        };
+        status
+            && self.id.is_none_or(|id| item.id == id)
+            && (!self.starred || item.starred())
+            && (self.priorities.is_empty()
+                || self.priorities.contains(&priority_letter(item.priority())))
+            && self.due.is_none_or(|due| due.matches(item, today))
+            && self
+                .energy
+                .is_none_or(|energy| item.energy() == Some(energy))
+            && self.flag.is_none_or(|flag| item.flag() == Some(flag))
+            && self
+                .assignee
+                .as_deref()
+                .is_none_or(|assignee| item.assignee() == Some(assignee))
+            && self
+                .project
+                .as_deref()
+                .is_none_or(|path| has_tag(&item.unique_projects(), path, separator))
+            && self
+                .context
+                .as_deref()
+                .is_none_or(|path| has_tag(&item.unique_contexts(), path, separator))
+            && self.any_context.as_ref().is_none_or(|paths| {
+                let contexts = item.unique_contexts();
+                paths.iter().any(|path| has_tag(&contexts, path, separator))
+            })
+    }
+}
+
+/// Whether `tags` has `path` or a tag nested below it.
+pub(crate) fn has_tag(tags: &[String], path: &str, separator: &str) -> bool {
+    tags.iter().any(|tag| {
+        tag == path
+            || (!separator.is_empty()
+                && tag
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with(separator)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_filter_combines_criteria() {
+        let mut list = TodoList::new();
+        list.add("Call Bob +work---sales @phone");
+        list.add("x Call Alice +work @phone");
+        list.add("Write report +work @desk");
+        list.add("Call mom +family @phone");
+        list.add("Tidy up +workshop @phone");
+
+        let today = Date::from_ymd_opt(2025, 3, 1).unwrap();
+        let filter = Filter {
+            project: Some("work".to_string()),
+            context: Some("phone".to_string()),
+            status: Status::Pending,
+            due: None,
+            assignee: None,
+            any_context: None,
+            energy: None,
+            flag: None,
+            starred: false,
+            priorities: Vec::new(),
+            id: None,
+        };
+        let matched: Vec<&str> = list
+            .items()
+            .iter()
+            .filter(|item| filter.matches(item, "---", today))
+            .map(|item| item.subject())
+            .collect();
+        assert_eq!(matched, vec!["Call Bob +work---sales @phone"]);
+
+        list.add("Call the bank star:1 @phone");
+        let starred = Filter {
+            starred: true,
+            ..Filter::default()
+        };
+        let matched: Vec<&str> = list
+            .items()
+            .iter()
+            .filter(|item| starred.matches(item, "---", today))
+            .map(|item| item.subject())
+            .collect();
+        assert_eq!(matched, vec!["Call the bank @phone"]);
+
+        list.add("(A) Pay rent +work");
+        list.add("(C) Sort photos +work");
+        let bands = Filter {
+            priorities: vec![Some('A'), None],
+            status: Status::Pending,
+            project: Some("work".to_string()),
+            ..Filter::default()
+        };
+        let matched: Vec<&str> = list
+            .items()
+            .iter()
+            .filter(|item| bands.matches(item, "---", today))
+            .map(|item| item.subject())
+            .collect();
+        assert_eq!(
+            matched,
+            vec![
+                "Call Bob +work---sales @phone",
+                "Write report +work @desk",
+                "Pay rent +work"
+            ]
+        );
+
+        let nearby = Filter {
+            any_context: Some(vec!["desk".to_string(), "store".to_string()]),
+            ..Filter::default()
+        };
+        assert_eq!(
+            list.items()
+                .iter()
+                .filter(|item| nearby.matches(item, "---", today))
+                .count(),
+            1
+        );
+        let nowhere = Filter {
+            any_context: Some(Vec::new()),
+            ..Filter::default()
+        };
+        assert!(!list
+            .items()
+            .iter()
+            .any(|item| nowhere.matches(item, "---", today)));
+
+        let tired = Filter {
+            energy: Some(Energy::Low),
+            ..Filter::default()
+        };
+        let easy = TodoItem::new("Sort photos energy:low");
+        assert!(tired.matches(&easy, "---", today));
+        assert!(!tired.matches(&TodoItem::new("File taxes energy:high"), "---", today));
+        assert!(!tired.matches(&TodoItem::new("Sort socks"), "---", today));
+        assert_eq!(
+            TodoItem::new("Plan trip energy:Medium").energy(),
+            Some(Energy::Med)
+        );
+
+        let red = Filter {
+            flag: Some(Flag::Red),
+            ..Filter::default()
+        };
+        let mut flagged = TodoItem::new("Renew passport flag:Red");
+        assert!(red.matches(&flagged, "---", today));
+        flagged.set_flag(Some(Flag::Blue));
+        assert_eq!(flagged.raw(), "Renew passport flag:blue");
+        assert!(!red.matches(&flagged, "---", today));
+
+        assert!(Filter::default().is_empty());
+        assert!(list
+            .items()
+            .iter()
+            .all(|item| Filter::default().matches(item, "---", today)));
+    }
+
+    #[test]
+    fn test_due_windows() {
+        let today = Date::from_ymd_opt(2025, 3, 10).unwrap();
+        let overdue = TodoItem::new("File taxes due:2025-03-01");
+        let due_today = TodoItem::new("Pay rent due:2025-03-10");
+        let later = TodoItem::new("Renew passport due:2025-04-01");
+        let undated = TodoItem::new("Someday");
+
+        assert!(Due::Today.matches(&overdue, today));
+        assert!(Due::Today.matches(&due_today, today));
+        assert!(!Due::Today.matches(&later, today));
+        assert!(Due::Upcoming.matches(&later, today));
+        assert!(!Due::Upcoming.matches(&due_today, today));
+        assert!(!Due::Today.matches(&undated, today) && !Due::Upcoming.matches(&undated, today));
+    }
+}
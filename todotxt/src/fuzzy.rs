@@ -0,0 +1,150 @@
+//! Fuzzy matching of what the user typed against text, e.g. in the command
+//! palette, so each place that matches doesn't grow its own rules: `cb inv`
+//! matches "Call Bob about the invoice". Case and accents don't matter, so
+//! `cafe` matches "Café", which finding duplicates relies on too.
+
+/// `text` in lower case with accents taken off, e.g. "Straße" → "strasse".
+pub fn normalize(text: &str) -> String {
+    text.chars().flat_map(fold).collect()
+}
+
+/// The lower case letters `c` is written with when accents don't matter.
+fn fold(c: char) -> impl Iterator<Item = char> {
+    let base = match c {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' => 'a',
+        'Ç' | 'ç' | 'Ć'..='č' => 'c',
+        'Ď'..='đ' => 'd',
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => 'e',
+        'Ĝ'..='ģ' => 'g',
+        'Ĥ'..='ħ' => 'h',
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' => 'i',
+        'Ĵ' | 'ĵ' => 'j',
+        'Ķ' | 'ķ' => 'k',
+        'Ĺ'..='ł' => 'l',
+        'Ñ' | 'ñ' | 'Ń'..='ň' => 'n',
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' => 'o',
+        'Ŕ'..='ř' => 'r',
+        'Ś'..='š' => 's',
+        'Ţ'..='ŧ' => 't',
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' => 'u',
+        'Ŵ' | 'ŵ' => 'w',
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ' | 'ŷ' | 'Ÿ' => 'y',
+        'Ź'..='ž' => 'z',
+        'ß' => return "ss".chars().collect::<Vec<_>>().into_iter(),
+        'Æ' | 'æ' => return "ae".chars().collect::<Vec<_>>().into_iter(),
+        'Œ' | 'œ' => return "oe".chars().collect::<Vec<_>>().into_iter(),
+        c => return c.to_lowercase().collect::<Vec<_>>().into_iter(),
+    };
+    vec![base].into_iter()
+}
+
+/// How well a query matched, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Higher is better, only comparable between matches of one query
+    pub score: u32,
+    /// The indices of the matched chars of the text, in order, e.g. to
+    /// highlight them
+    pub positions: Vec<usize>,
+}
+
+/// Points for each matched char, more if it starts a word and more still if
+/// it follows the previous match, so runs of letters rank first.
+const CHAR_SCORE: u32 = 1;
+const WORD_START_BONUS: u32 = 8;
+const ADJACENT_BONUS: u32 = 10;
+
+/// Matches `query` against `text`: every word of `query` has to be found
+/// in `text` with its letters in order, not necessarily next to each other.
+/// `None` if one isn't. An empty query matches everything with score 0.
+pub fn score(query: &str, text: &str) -> Option<Match> {
+    // The normalized chars of `text`, each with the index of the char it
+    // comes from
+    let chars: Vec<(usize, char)> = text
+        .chars()
+        .enumerate()
+        .flat_map(|(i, c)| fold(c).map(move |folded| (i, folded)))
+        .collect();
+    let mut total = Match {
+        score: 0,
+        positions: Vec::new(),
+    };
+    for word in query.split_whitespace() {
+        let word: Vec<char> = normalize(word).chars().collect();
+        let best = (0..chars.len())
+            .filter(|&start| chars[start].1 == word[0])
+            .filter_map(|start| match_from(&chars, &word, start))
+            .max_by_key(|m| m.score)?;
+        total.score += best.score;
+        total.positions.extend(best.positions);
+    }
+    total.positions.sort_unstable();
+    total.positions.dedup();
+    Some(total)
+}
+
+/// Matches `word` against `chars` starting at `start`, taking each next
+/// letter as early as possible.
+fn match_from(chars: &[(usize, char)], word: &[char], start: usize) -> Option<Match> {
+    let mut m = Match {
+        score: 0,
+        positions: Vec::new(),
+    };
+    let mut previous: Option<usize> = None;
+    let mut from = start;
+    for &wanted in word {
+        let at = from + chars[from..].iter().position(|&(_, c)| c == wanted)?;
+        m.score += CHAR_SCORE;
+        if at == 0 || !chars[at - 1].1.is_alphanumeric() {
+            m.score += WORD_START_BONUS;
+        }
+        if previous.is_some_and(|previous| previous + 1 == at) {
+            m.score += ADJACENT_BONUS;
+        }
+        m.positions.push(chars[at].0);
+        previous = Some(at);
+        from = at + 1;
+    }
+    Some(m)
+}
+
+/// The `items` matching `query` by the text `key` gives for each, best
+/// first; equally good matches keep their order.
+pub fn rank<T>(
+    query: &str,
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> &str,
+) -> Vec<(T, Match)> {
+    let mut ranked: Vec<(T, Match)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let m = score(query, key(&item))?;
+            Some((item, m))
+        })
+        .collect();
+    ranked.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(normalize("Crème BRÛLÉE Straße"), "creme brulee strasse");
+        assert_eq!(normalize("Ωμέγα"), "ωμέγα");
+
+        let m = score("cb inv", "Call Bob about the invoice").unwrap();
+        assert_eq!(m.positions, [0, 5, 19, 20, 21]);
+        assert!(score("cafe", "Café au lait").is_some());
+        assert!(score("strasse", "Hauptstraße 1").is_some());
+        assert_eq!(score("xyz", "Call Bob"), None);
+        assert_eq!(score("", "anything").map(|m| m.score), Some(0));
+
+        // Runs of letters beat word starts, which beat letters inside words
+        let ranked = rank("ab", ["crab", "about", "a b"], |s| *s);
+        let order: Vec<&str> = ranked.iter().map(|(s, _)| *s).collect();
+        assert_eq!(order, ["about", "a b", "crab"]);
+    }
+}
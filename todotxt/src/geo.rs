@@ -0,0 +1,82 @@
+//! Places attached to contexts, e.g. `@store` at the supermarket, for
+//! showing the tasks that can be done where the user is.
+
+use serde::{Deserialize, Serialize};
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Location {
+    /// Great-circle distance in meters.
+    pub fn distance(self, other: Location) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+}
+
+/// `context` counts as nearby within `radius` meters of `location`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextLocation {
+    /// Without the `@`
+    pub context: String,
+    pub location: Location,
+    pub radius: f64,
+}
+
+/// The contexts placed near `here`, each once.
+pub fn contexts_near(places: &[ContextLocation], here: Location) -> Vec<String> {
+    let mut contexts = Vec::new();
+    for place in places {
+        if place.location.distance(here) <= place.radius && !contexts.contains(&place.context) {
+            contexts.push(place.context.clone());
+        }
+    }
+    contexts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contexts_near() {
+        let store = Location {
+            latitude: 52.5200,
+            longitude: 13.4050,
+        };
+        let office = Location {
+            latitude: 48.1351,
+            longitude: 11.5820,
+        };
+        let places = [
+            ContextLocation {
+                context: "store".to_string(),
+                location: store,
+                radius: 500.0,
+            },
+            ContextLocation {
+                context: "work".to_string(),
+                location: office,
+                radius: 500.0,
+            },
+        ];
+        // About 200m north of the store
+        let here = Location {
+            latitude: 52.5218,
+            longitude: 13.4050,
+        };
+
+        assert!((store.distance(here) - 200.0).abs() < 5.0);
+        assert!((store.distance(office) - 504_000.0).abs() < 2_000.0);
+        assert_eq!(contexts_near(&places, here), vec!["store"]);
+        assert!(contexts_near(&places, Location::default()).is_empty());
+    }
+}
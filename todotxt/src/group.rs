@@ -0,0 +1,116 @@
+//! Grouping todos under headers, e.g. one per priority or project, with the
+//! groups in an order the user picks instead of by name.
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::priority_letter;
+use crate::tags::TagKind;
+use crate::TodoItem;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    #[default]
+    None,
+    Priority,
+    Project,
+    Context,
+}
+
+impl GroupBy {
+    pub const ALL: [GroupBy; 4] = [
+        GroupBy::None,
+        GroupBy::Priority,
+        GroupBy::Project,
+        GroupBy::Context,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::Priority => "priority",
+            GroupBy::Project => "project",
+            GroupBy::Context => "context",
+        }
+    }
+}
+
+/// The group `item` goes in, e.g. `A` or `+work`, or `None` if it has no
+/// priority or tag to group by. A todo with several projects or contexts goes
+/// in the first of them `order` names, else in the first by name.
+pub fn label(item: &TodoItem, by: GroupBy, order: &[String]) -> Option<String> {
+    let kind = match by {
+        GroupBy::None => return None,
+        GroupBy::Priority => return priority_letter(item.priority()).map(String::from),
+        GroupBy::Project => TagKind::Project,
+        GroupBy::Context => TagKind::Context,
+    };
+    let tags: Vec<String> = item
+        .tags(kind)
+        .into_iter()
+        .map(|name| format!("{}{name}", kind.sigil()))
+        .collect();
+    order
+        .iter()
+        .find(|label| tags.contains(label))
+        .cloned()
+        .or_else(|| tags.into_iter().next())
+}
+
+/// Sorts `items` by the group `label` puts them in, keeping their order
+/// within a group. Groups named in `order` come first in that order, the
+/// others follow by name and items without a group go last.
+pub fn sort<T>(items: &mut [T], order: &[String], label: impl Fn(&T) -> Option<String>) {
+    items.sort_by_cached_key(|item| match label(item) {
+        Some(label) => match order.iter().position(|l| *l == label) {
+            Some(rank) => (0, rank, String::new()),
+            None => (1, 0, label),
+        },
+        None => (2, 0, String::new()),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_sort_in_custom_order() {
+        let list = TodoList::from_content(
+            "Fix bug @doing\nPlan week\nWrite docs @todo\nShip it @review\nCall Bob @todo @phone",
+        );
+        let order = vec!["@todo".to_string(), "@doing".to_string()];
+        let mut items: Vec<&TodoItem> = list.items().iter().collect();
+        sort(&mut items, &order, |item| {
+            label(item, GroupBy::Context, &order)
+        });
+        let subjects: Vec<&str> = items.iter().map(|item| item.subject()).collect();
+        assert_eq!(
+            subjects,
+            [
+                "Write docs @todo",
+                "Call Bob @todo @phone",
+                "Fix bug @doing",
+                "Ship it @review",
+                "Plan week"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_priority_label() {
+        assert_eq!(
+            label(&TodoItem::new("(B) Call Bob"), GroupBy::Priority, &[]),
+            Some("B".to_string())
+        );
+        assert_eq!(
+            label(&TodoItem::new("Call Bob"), GroupBy::Priority, &[]),
+            None
+        );
+        assert_eq!(
+            label(&TodoItem::new("(B) Call Bob"), GroupBy::None, &[]),
+            None
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Taking back edits made to a copy of the list, or of one of its tasks, in
+//! an external editor. The list may have changed since the copy was made,
+//! e.g. by a sync or in the app; such changes are kept, not overwritten.
+
+use std::fmt;
+
+use crate::merge;
+use crate::{LineError, TodoItem, TodoList};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandoffError {
+    /// The task was changed or removed since the copy was made
+    Conflict,
+    Invalid(Vec<LineError>),
+}
+
+impl fmt::Display for HandoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandoffError::Conflict => write!(f, "the task changed in the meantime"),
+            HandoffError::Invalid(errors) => {
+                let lines: Vec<String> = errors
+                    .iter()
+                    .map(|e| format!("line {}: {}", e.line, e.message))
+                    .collect();
+                write!(f, "{}", lines.join(", "))
+            }
+        }
+    }
+}
+
+/// Takes the copy of the whole list `edited` into `list`, the copy having
+/// started out as `base`. If `list` changed since, both sides' changes are
+/// merged line by line, see [`merge::merge`]. Returns whether they were.
+pub fn import_list(list: &mut TodoList, base: &str, edited: &str) -> Result<bool, HandoffError> {
+    let current = list.to_content();
+    let unchanged = current.lines().eq(base.lines());
+    let content = if unchanged {
+        edited.to_string()
+    } else {
+        merge::merge(base, &current, edited)
+    };
+    list.replace_content(&content)
+        .map_err(HandoffError::Invalid)?;
+    Ok(!unchanged)
+}
+
+/// Takes the copy of a task `edited` back into `list`, the copy having
+/// started out as the task's line `base`. Each line of `edited` becomes a
+/// task in its place, so emptying the copy removes the task. Returns the
+/// lines as they are in `list` now, the `base` to find them by next time.
+pub fn import_task(list: &mut TodoList, base: &str, edited: &str) -> Result<String, HandoffError> {
+    let base: Vec<&str> = non_empty_lines(base).collect();
+    let raws: Vec<String> = list.items().iter().map(TodoItem::raw).collect();
+    let start = raws
+        .windows(base.len().max(1))
+        .position(|lines| !base.is_empty() && lines.iter().zip(&base).all(|(a, b)| a == b))
+        .ok_or(HandoffError::Conflict)?;
+    let edited: Vec<&str> = non_empty_lines(edited).collect();
+    let mut content: Vec<&str> = raws[..start].iter().map(String::as_str).collect();
+    content.extend(&edited);
+    content.extend(raws[start + base.len()..].iter().map(String::as_str));
+    list.replace_content(&content.join("\n"))
+        .map_err(HandoffError::Invalid)?;
+    let now: Vec<String> = list.items()[start..start + edited.len()]
+        .iter()
+        .map(TodoItem::raw)
+        .collect();
+    Ok(now.join("\n"))
+}
+
+fn non_empty_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_list_keeps_changes_made_meanwhile() {
+        let base = "Call Bob\nWater plants";
+        let mut list = TodoList::from_content(base);
+        assert_eq!(
+            import_list(&mut list, base, "Call Bob\nWater plants +home"),
+            Ok(false)
+        );
+        assert_eq!(list.to_content(), "Call Bob\nWater plants +home");
+
+        let base = list.to_content();
+        list.add("Pay rent");
+        assert_eq!(
+            import_list(&mut list, &base, "Call Alice\nWater plants +home"),
+            Ok(true)
+        );
+        // The list's order is kept, with what the editor added at the end
+        assert_eq!(
+            list.to_content(),
+            "Water plants +home\nPay rent\nCall Alice"
+        );
+    }
+
+    #[test]
+    fn test_import_task() {
+        let mut list = TodoList::from_content("Call Bob\nWater plants\nPay rent");
+        let base = import_task(&mut list, "Water plants", "Water plants\nFeed cat\n");
+        assert_eq!(base, Ok("Water plants\nFeed cat".to_string()));
+        assert_eq!(
+            list.to_content(),
+            "Call Bob\nWater plants\nFeed cat\nPay rent"
+        );
+        assert_eq!(
+            import_task(&mut list, "Call Alice", "Call Bob +work"),
+            Err(HandoffError::Conflict)
+        );
+        assert_eq!(
+            import_task(&mut list, "Water plants\nFeed cat", ""),
+            Ok(String::new())
+        );
+        assert_eq!(list.to_content(), "Call Bob\nPay rent");
+    }
+}
@@ -0,0 +1,46 @@
+//! The inbox: a second file, `inbox.txt` next to todo.txt, that quick
+//! captures land in without being sorted, e.g. a link shared from the
+//! browser or a thought typed in passing. Refiling moves a captured line
+//! into the list, with the projects, contexts and tags it is filed under.
+
+use crate::{missing_tags, TodoList};
+
+pub const INBOX_FILE: &str = "inbox.txt";
+
+/// Moves the todo `id` from `inbox` to `list`, adding those of the
+/// projects, contexts and `key:value` tags in `tags` it doesn't have yet,
+/// e.g. `+garden @home`. Returns its id in `list`, or `None` if there is no
+/// todo `id` in `inbox`.
+pub fn refile(inbox: &mut TodoList, list: &mut TodoList, id: usize, tags: &str) -> Option<usize> {
+    let new_id = inbox.move_to(id, list)?;
+    if let Some(item) = list.get_mut(new_id) {
+        let mut raw = item.raw();
+        raw.push_str(&missing_tags(&raw, tags));
+        item.set_raw(&raw);
+    }
+    Some(new_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refile() {
+        let mut inbox = TodoList::from_content("Buy seeds +garden\nCall Bob");
+        let mut list = TodoList::from_content("Pay rent");
+
+        let id = refile(&mut inbox, &mut list, 1, "+garden @shop due:2025-03-01").unwrap();
+        assert_eq!(
+            list.get(id).unwrap().raw(),
+            "Buy seeds +garden @shop due:2025-03-01"
+        );
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(refile(&mut inbox, &mut list, 1, ""), None);
+
+        let id = refile(&mut inbox, &mut list, 2, "").unwrap();
+        assert_eq!(list.get(id).unwrap().raw(), "Call Bob");
+        assert!(inbox.is_empty());
+        assert_eq!(list.len(), 3);
+    }
+}
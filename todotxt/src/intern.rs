@@ -0,0 +1,132 @@
+//! Tag names stored once per list instead of once per todo, and an index
+//! from each tag to the todos carrying it. [`TodoList::tag_index`] builds it
+//! on first use and keeps it, shared by copies of the list, until the list
+//! changes.
+//!
+//! [`TodoList::tag_index`]: crate::TodoList::tag_index
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::tags::TagKind;
+use crate::TodoItem;
+
+/// A tag name in a [`TagTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TagId(u32);
+
+/// Each distinct tag name once, shared by projects and contexts.
+#[derive(Debug, Clone, Default)]
+pub struct TagTable {
+    names: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, TagId>,
+}
+
+impl TagTable {
+    /// The id of `name`, adding it if it's new.
+    pub fn intern(&mut self, name: &str) -> TagId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = TagId(self.names.len() as u32);
+        let name: Arc<str> = Arc::from(name);
+        self.names.push(name.clone());
+        self.ids.insert(name, id);
+        id
+    }
+
+    pub fn get(&self, name: &str) -> Option<TagId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: TagId) -> &Arc<str> {
+        &self.names[id.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// The todos carrying each tag, by their position in the list.
+#[derive(Debug, Clone, Default)]
+pub struct TagIndex {
+    table: TagTable,
+    positions: HashMap<(TagKind, TagId), Vec<usize>>,
+}
+
+impl TagIndex {
+    pub(crate) fn build(items: &[TodoItem]) -> Self {
+        let mut index = Self::default();
+        for (position, item) in items.iter().enumerate() {
+            for kind in [TagKind::Project, TagKind::Context] {
+                for name in item.tags(kind) {
+                    let id = index.table.intern(&name);
+                    index
+                        .positions
+                        .entry((kind, id))
+                        .or_default()
+                        .push(position);
+                }
+            }
+        }
+        index
+    }
+
+    pub fn table(&self) -> &TagTable {
+        &self.table
+    }
+
+    /// The positions of the todos tagged `name`, in list order.
+    pub fn positions(&self, kind: TagKind, name: &str) -> &[usize] {
+        self.table
+            .get(name)
+            .and_then(|id| self.positions.get(&(kind, id)))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Every tag of `kind` with the positions of its todos, in no particular
+    /// order.
+    pub fn tags(&self, kind: TagKind) -> impl Iterator<Item = (TagId, &[usize])> {
+        self.positions
+            .iter()
+            .filter(move |((tag_kind, _), _)| *tag_kind == kind)
+            .map(|((_, id), positions)| (*id, positions.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_tag_index() {
+        let mut list = TodoList::new();
+        list.add("Call Bob +work @phone");
+        list.add("Email Alice +work @work");
+        list.add("Tidy up +home");
+
+        let index = list.tag_index();
+        assert_eq!(index.positions(TagKind::Project, "work"), [0, 1]);
+        assert_eq!(index.positions(TagKind::Context, "work"), [1]);
+        assert!(index.positions(TagKind::Context, "home").is_empty());
+        // "work" is stored once for the project and the context
+        assert_eq!(index.table().len(), 3);
+
+        // Kept for copies of the list, rebuilt once it changes
+        let copy = list.clone();
+        assert!(std::ptr::eq(copy.tag_index(), list.tag_index()));
+        let id = list.items()[2].id;
+        list.get_mut(id).unwrap().add_tag("+work");
+        assert_eq!(
+            list.tag_index().positions(TagKind::Project, "work"),
+            [0, 1, 2]
+        );
+        assert_eq!(copy.tag_index().positions(TagKind::Project, "work"), [0, 1]);
+    }
+}
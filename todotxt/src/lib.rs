@@ -1,18 +1,86 @@
+use chrono::{NaiveTime, TimeDelta};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+pub mod activity;
+pub mod aging;
+pub mod archive;
+pub mod auto;
+pub mod backup;
+pub mod calendar;
+pub mod check;
+pub mod checklist;
+pub mod day;
+pub mod diff;
+pub mod estimate;
+pub mod export;
+pub mod feed;
+pub mod filter;
+pub mod fuzzy;
+pub mod geo;
+pub mod group;
+pub mod handoff;
+pub mod inbox;
+pub mod intern;
+pub mod links;
+pub mod markdown;
+pub mod merge;
+pub mod natural;
+pub mod normalize;
+pub mod oplog;
+pub mod order;
+pub mod parse;
+pub mod patch;
+pub mod pick;
+pub mod plan;
+pub mod plugin;
+pub mod redact;
+pub mod relative;
+pub mod reminders;
+pub mod replace;
+pub mod report;
+pub mod rules;
+pub mod share;
+pub mod similarity;
+pub mod snapshot;
+pub mod sort;
+pub mod stats;
+pub mod stream;
+pub mod tags;
+pub mod view;
+
+use parse::{tokenize, Token};
+use tags::{TagEdit, TagKind};
+
+pub use todo_txt::Date;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TodoItem {
     #[serde(skip)]
     inner: todo_txt::task::Simple,
     pub id: usize,
+    #[serde(skip)]
+    line: usize,
 }
 
 impl TodoItem {
     pub fn new(subject: &str) -> Self {
         let inner = todo_txt::task::Simple::from(subject.to_string());
-        Self { inner, id: 0 }
+        Self {
+            inner,
+            id: 0,
+            line: 0,
+        }
+    }
+
+    /// The line of the file the todo is on, counting from 1 and blank lines
+    /// included; 0 if it is in no list. Todos added or moved since the file
+    /// was read are numbered as the list writes them, without blank lines.
+    pub fn line(&self) -> usize {
+        self.line
     }
 
     pub fn subject(&self) -> &str {
@@ -31,6 +99,14 @@ impl TodoItem {
         self.inner.complete();
     }
 
+    /// Like [`TodoItem::complete`], but with a given completion date.
+    pub fn complete_on(&mut self, date: Date) {
+        self.inner.finished = true;
+        if self.inner.create_date.is_some() {
+            self.inner.finish_date = Some(date);
+        }
+    }
+
     pub fn uncomplete(&mut self) {
         self.inner.uncomplete();
     }
@@ -43,6 +119,62 @@ impl TodoItem {
         self.inner.priority = priority.into();
     }
 
+    pub fn creation_date(&self) -> Option<Date> {
+        self.inner.create_date
+    }
+
+    pub fn set_creation_date(&mut self, date: Option<Date>) {
+        self.inner.create_date = date;
+    }
+
+    pub fn completion_date(&self) -> Option<Date> {
+        self.inner.finish_date
+    }
+
+    pub fn due_date(&self) -> Option<Date> {
+        self.inner.due_date
+    }
+
+    /// The time of day the todo is due, from an `at:HH:MM` tag. Only means
+    /// something alongside a due date; without one the todo is due all day.
+    pub fn due_time(&self) -> Option<NaiveTime> {
+        tokenize(&self.raw())
+            .into_iter()
+            .find_map(|spanned| match spanned.token {
+                Token::Tag { key: AT_KEY, value } => NaiveTime::parse_from_str(value, "%H:%M").ok(),
+                _ => None,
+            })
+    }
+
+    pub fn set_due_time(&mut self, time: Option<NaiveTime>) {
+        let value = time.map(|time| time.format("%H:%M").to_string());
+        self.set_tag(AT_KEY, value.as_deref());
+    }
+
+    /// When the todo is due, for sorting: todos due all day come before the
+    /// timed ones of the same day.
+    pub fn due_at(&self) -> Option<(Date, Option<NaiveTime>)> {
+        self.due_date().map(|date| (date, self.due_time()))
+    }
+
+    /// The due date with its time if it has one, e.g. "2025-03-01 14:00".
+    pub fn due_label(&self) -> Option<String> {
+        self.due_at().map(|(date, time)| match time {
+            Some(time) => format!("{date} {}", time.format("%H:%M")),
+            None => date.to_string(),
+        })
+    }
+
+    fn tag_names(&self, pick: impl Fn(Token<'_>) -> Option<&str>) -> Vec<String> {
+        let mut names: Vec<String> = tokenize(&self.inner.subject)
+            .into_iter()
+            .filter_map(|spanned| pick(spanned.token).map(str::to_string))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     pub fn contexts(&self) -> &[String] {
         &self.inner.contexts
     }
@@ -51,6 +183,311 @@ impl TodoItem {
         &self.inner.projects
     }
 
+    /// The contexts as the todo.txt spec reads them, see [`parse`], so
+    /// nested ones like `@work.calls` stay whole. Sorted, each once.
+    pub fn unique_contexts(&self) -> Vec<String> {
+        self.tag_names(|token| match token {
+            Token::Context(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    /// Like [`TodoItem::unique_contexts`] for projects.
+    pub fn unique_projects(&self) -> Vec<String> {
+        self.tag_names(|token| match token {
+            Token::Project(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    /// The tags of `kind`, see [`TodoItem::unique_projects`].
+    pub fn tags(&self, kind: TagKind) -> Vec<String> {
+        match kind {
+            TagKind::Project => self.unique_projects(),
+            TagKind::Context => self.unique_contexts(),
+        }
+    }
+
+    /// Renames or removes tags of `kind` in place. `edit` is called with each
+    /// tag name. Returns whether the line changed.
+    pub fn edit_tags(&mut self, kind: TagKind, mut edit: impl FnMut(&str) -> TagEdit) -> bool {
+        let raw = self.raw();
+        let mut out = String::with_capacity(raw.len());
+        let mut pos = 0;
+        let mut changed = false;
+        for spanned in tokenize(&raw) {
+            let name = match (kind, spanned.token) {
+                (TagKind::Project, Token::Project(name))
+                | (TagKind::Context, Token::Context(name)) => name,
+                _ => continue,
+            };
+            let (start, end) = (spanned.span.start, spanned.span.end);
+            match edit(name) {
+                TagEdit::Keep => {}
+                TagEdit::Rename(new_name) if new_name == name => {}
+                TagEdit::Rename(new_name) => {
+                    let name_start = start + 1;
+                    out.push_str(&raw[pos..name_start]);
+                    out.push_str(&new_name);
+                    pos = name_start + name.len();
+                    changed = true;
+                }
+                TagEdit::Remove => {
+                    let (cut_start, cut_end) = removal_range(&raw, pos, start, end);
+                    out.push_str(&raw[pos..cut_start]);
+                    pos = cut_end;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return false;
+        }
+        out.push_str(&raw[pos..]);
+        self.set_raw(&out);
+        true
+    }
+
+    /// Adds `tag`, a `+project`, `@context` or `key:value`, at the end of the
+    /// line; a `key:value` replaces the value the key had. Returns whether
+    /// the line changed, which it doesn't if it has the tag already or `tag`
+    /// isn't one, see [`parse::tag`].
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let Some(token) = parse::tag(tag) else {
+            return false;
+        };
+        let raw = self.raw();
+        if tokenize(&raw).iter().any(|spanned| spanned.token == token) {
+            return false;
+        }
+        match token {
+            Token::Tag { key, value } => self.set_tag(key, Some(value)),
+            _ => self.set_raw(&format!("{raw} {tag}")),
+        }
+        true
+    }
+
+    /// Removes `tag`, a `+project`, `@context` or `key:value`, wherever it is
+    /// on the line. Returns whether the line changed.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let Some(token) = parse::tag(tag) else {
+            return false;
+        };
+        let raw = self.raw();
+        let mut out = String::with_capacity(raw.len());
+        let mut pos = 0;
+        let mut changed = false;
+        for spanned in tokenize(&raw) {
+            if spanned.token == token {
+                let (cut_start, cut_end) =
+                    removal_range(&raw, pos, spanned.span.start, spanned.span.end);
+                out.push_str(&raw[pos..cut_start]);
+                pos = cut_end;
+                changed = true;
+            }
+        }
+        if !changed {
+            return false;
+        }
+        out.push_str(&raw[pos..]);
+        self.set_raw(out.trim());
+        true
+    }
+
+    /// The todo's `key:value` tags, e.g. `energy:low`, in line order, but for
+    /// those of [`FIELD_KEYS`].
+    pub fn free_tags(&self) -> Vec<String> {
+        tokenize(&self.raw())
+            .into_iter()
+            .filter_map(|spanned| match spanned.token {
+                Token::Tag { key, value } if !FIELD_KEYS.contains(&key) => {
+                    Some(format!("{key}:{value}"))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn set_due_date(&mut self, due: Option<Date>) {
+        self.inner.due_date = due;
+    }
+
+    /// The day the todo starts to matter, from a `t:` tag.
+    pub fn threshold_date(&self) -> Option<Date> {
+        self.inner.threshold_date
+    }
+
+    pub fn set_threshold_date(&mut self, threshold: Option<Date>) {
+        self.inner.threshold_date = threshold;
+    }
+
+    /// The value of the todo's `key:value` tag with `key`, e.g. `low` for
+    /// `energy`.
+    pub fn tag_value(&self, key: &str) -> Option<&str> {
+        self.inner.tags.get(key).map(String::as_str)
+    }
+
+    /// The keys of the todo's `key:value` tags, without the due and
+    /// threshold dates.
+    pub fn tag_keys(&self) -> impl Iterator<Item = &str> {
+        self.inner.tags.keys().map(String::as_str)
+    }
+
+    /// Estimated effort in minutes, from an `est:` tag, see [`estimate::parse`].
+    pub fn estimate(&self) -> Option<u32> {
+        self.inner
+            .tags
+            .get(estimate::ESTIMATE_KEY)
+            .and_then(|value| estimate::parse(value))
+    }
+
+    /// How much energy the todo takes, from an `energy:` tag.
+    pub fn energy(&self) -> Option<filter::Energy> {
+        self.inner
+            .tags
+            .get(filter::ENERGY_KEY)
+            .and_then(|value| filter::Energy::parse(value))
+    }
+
+    /// The color the todo is flagged with, from a `flag:` tag.
+    pub fn flag(&self) -> Option<filter::Flag> {
+        self.inner
+            .tags
+            .get(filter::FLAG_KEY)
+            .and_then(|value| filter::Flag::parse(value))
+    }
+
+    /// Replaces any `flag:` tag with one for `flag`, or drops it for `None`.
+    pub fn set_flag(&mut self, flag: Option<filter::Flag>) {
+        self.set_tag(filter::FLAG_KEY, flag.map(filter::Flag::name));
+    }
+
+    /// The todo's place in a manual order, from an `ord:` tag, see [`order`].
+    pub fn order(&self) -> Option<&str> {
+        self.inner.tags.get(order::ORDER_KEY).map(String::as_str)
+    }
+
+    pub fn set_order(&mut self, key: Option<&str>) {
+        self.set_tag(order::ORDER_KEY, key);
+    }
+
+    /// Who the todo is assigned to, from an `assignee:name` tag.
+    pub fn assignee(&self) -> Option<&str> {
+        self.inner.tags.get(ASSIGNEE_KEY).map(String::as_str)
+    }
+
+    /// Replaces any `assignee:` tags with one for `assignee`, or drops them for `None`.
+    pub fn set_assignee(&mut self, assignee: Option<&str>) {
+        self.set_tag(ASSIGNEE_KEY, assignee);
+    }
+
+    /// Whether the todo is pinned above the others, from a `star:1` tag.
+    pub fn starred(&self) -> bool {
+        self.inner
+            .tags
+            .get(STAR_KEY)
+            .is_some_and(|value| value == "1")
+    }
+
+    pub fn set_starred(&mut self, starred: bool) {
+        self.set_tag(STAR_KEY, starred.then_some("1"));
+    }
+
+    /// Whether the todo completes itself once its due date passes, from an
+    /// `auto:done` tag.
+    pub fn auto_done(&self) -> bool {
+        self.inner
+            .tags
+            .get(AUTO_KEY)
+            .is_some_and(|value| value == "done")
+    }
+
+    pub fn set_auto_done(&mut self, auto_done: bool) {
+        self.set_tag(AUTO_KEY, auto_done.then_some("done"));
+    }
+
+    /// The key of the todo's checklist, from a `chk:<key>` tag, see [`checklist`].
+    pub fn checklist(&self) -> Option<&str> {
+        self.inner
+            .tags
+            .get(checklist::CHECKLIST_KEY)
+            .map(String::as_str)
+    }
+
+    pub fn set_checklist(&mut self, key: Option<&str>) {
+        self.set_tag(checklist::CHECKLIST_KEY, key);
+    }
+
+    /// Whether the todo went over a WIP limit, from an `overflow:1` tag, see
+    /// [`rules::enforce`].
+    pub fn overflowing(&self) -> bool {
+        self.inner
+            .tags
+            .get(OVERFLOW_KEY)
+            .is_some_and(|value| value == "1")
+    }
+
+    pub fn set_overflowing(&mut self, overflowing: bool) {
+        self.set_tag(OVERFLOW_KEY, overflowing.then_some("1"));
+    }
+
+    /// Replaces any `key:` tags with one for `value`, or drops them for `None`.
+    fn set_tag(&mut self, key: &str, value: Option<&str>) {
+        let raw = self.raw();
+        let mut out = String::with_capacity(raw.len());
+        let mut pos = 0;
+        for spanned in tokenize(&raw) {
+            if matches!(spanned.token, Token::Tag { key: k, .. } if k == key) {
+                let (cut_start, cut_end) =
+                    removal_range(&raw, pos, spanned.span.start, spanned.span.end);
+                out.push_str(&raw[pos..cut_start]);
+                pos = cut_end;
+            }
+        }
+        out.push_str(&raw[pos..]);
+        if let Some(value) = value {
+            out.push_str(&format!(" {key}:{value}"));
+        }
+        self.set_raw(out.trim());
+    }
+
+    /// Fills in what `defaults` sets and the task doesn't have yet.
+    pub fn apply_defaults(&mut self, defaults: &TaskDefaults, today: Date) {
+        if defaults.creation_date && self.inner.create_date.is_none() {
+            self.inner.create_date = Some(today);
+        }
+        if let Some(priority) = defaults.priority {
+            if self.inner.priority.is_lowest() {
+                if let Ok(priority) = todo_txt::Priority::try_from(priority) {
+                    self.inner.priority = priority;
+                }
+            }
+        }
+        let mut raw = self.raw();
+        if self.projects().is_empty() {
+            for project in &defaults.projects {
+                raw.push_str(&format!(" +{project}"));
+            }
+        }
+        if self.contexts().is_empty() {
+            for context in &defaults.contexts {
+                raw.push_str(&format!(" @{context}"));
+            }
+        }
+        self.set_raw(&raw);
+    }
+
+    /// Tidies up the description, see [`normalize`]. Returns whether the
+    /// line changed.
+    pub fn normalize(&mut self, options: normalize::Options) -> bool {
+        let subject = normalize::subject(self.subject(), options);
+        if subject == self.subject() {
+            return false;
+        }
+        self.set_subject(&subject);
+        true
+    }
+
     pub fn raw(&self) -> String {
         self.inner.to_string()
     }
@@ -60,17 +497,213 @@ impl TodoItem {
     }
 }
 
+/// Metadata new tasks start with, see [`TodoList::add_with_defaults`].
+/// Whatever the typed text sets itself wins: default projects only go on
+/// tasks without a project, contexts likewise.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskDefaults {
+    /// Stamp new tasks with today's date
+    pub creation_date: bool,
+    /// Priority letter, e.g. `'C'`
+    pub priority: Option<char>,
+    /// Project names without the `+`
+    pub projects: Vec<String>,
+    /// Context names without the `@`
+    pub contexts: Vec<String>,
+}
+
+/// What completing a task does to it besides marking it done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Completion {
+    /// Stays where it is, struck through
+    #[default]
+    Strike,
+    /// Moves to the end of the file, below the open tasks
+    MoveToBottom,
+    /// Stays where it is, but views leave it out unless asked for done tasks
+    Hide,
+}
+
+/// What happens to the todo split up by [`TodoList::split`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitOriginal {
+    #[default]
+    Keep,
+    Complete,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SplitOptions {
+    pub original: SplitOriginal,
+    /// Completion date of the original if it is completed, today if `None`
+    pub completed_on: Option<Date>,
+}
+
+/// The projects, contexts and `key:value` tags of the line `from` that `raw`
+/// doesn't have yet, each with a space in front, to append to `raw`. Tags
+/// are compared by key, so a `due:` in `raw` keeps out the one in `from`.
+pub(crate) fn missing_tags(raw: &str, from: &str) -> String {
+    let tokens: Vec<Token<'_>> = tokenize(raw)
+        .into_iter()
+        .map(|spanned| spanned.token)
+        .collect();
+    let mut missing = String::new();
+    for spanned in tokenize(from) {
+        let new = match spanned.token {
+            Token::Project(_) | Token::Context(_) => !tokens.contains(&spanned.token),
+            Token::Tag { key, .. } => !tokens
+                .iter()
+                .any(|token| matches!(token, Token::Tag { key: k, .. } if *k == key)),
+            _ => false,
+        };
+        if new {
+            missing.push(' ');
+            missing.push_str(&from[spanned.span.range()]);
+        }
+    }
+    missing
+}
+
+/// Tag key of the `assignee:name` convention for dividing work in a shared list.
+pub const ASSIGNEE_KEY: &str = "assignee";
+/// Tag key of the `star:1` convention for pinning important todos.
+pub const STAR_KEY: &str = "star";
+/// Tag key of the `auto:done` convention for todos that complete themselves
+/// once their due date passes, see [`auto`].
+pub const AUTO_KEY: &str = "auto";
+/// Tag key of the `at:HH:MM` convention for the time of day a todo is due.
+pub const AT_KEY: &str = "at";
+/// Tag key of the `overflow:1` mark on todos that went over a WIP limit.
+pub const OVERFLOW_KEY: &str = "overflow";
+
+/// Keys of the tags read as fields of a todo, like its due date or
+/// estimate, rather than as tags of its own.
+pub const FIELD_KEYS: [&str; 11] = [
+    "due",
+    "t",
+    AT_KEY,
+    estimate::ESTIMATE_KEY,
+    ASSIGNEE_KEY,
+    STAR_KEY,
+    AUTO_KEY,
+    OVERFLOW_KEY,
+    checklist::CHECKLIST_KEY,
+    filter::FLAG_KEY,
+    order::ORDER_KEY,
+];
+
+/// Byte range to cut when removing the token at `start..end` from `raw`. One
+/// neighbouring space goes along so no double space is left behind; `pos` is
+/// where the unprocessed part of `raw` begins.
+fn removal_range(raw: &str, pos: usize, start: usize, end: usize) -> (usize, usize) {
+    if start > pos && raw[..start].ends_with(' ') {
+        (start - 1, end)
+    } else if raw[end..].starts_with(' ') {
+        (start, end + 1)
+    } else {
+        (start, end)
+    }
+}
+
 impl fmt::Display for TodoItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.inner)
     }
 }
 
+/// A problem found on a single line of todo.txt content. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Checks a single todo.txt line for content the parser would silently drop
+/// (an unparsable priority or an invalid `due:`/`t:` date).
+pub fn validate_line(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("x ").unwrap_or(line);
+    let bytes = rest.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[2] == b')'
+        && bytes[3] == b' '
+        && !bytes[1].is_ascii_alphabetic()
+    {
+        return Some(format!("invalid priority \"{}\"", &rest[..3]));
+    }
+
+    for word in line.split_whitespace() {
+        if let Some((key @ ("due" | "t"), value)) = word.split_once(':') {
+            if Date::parse_from_str(value, "%Y-%m-%d").is_err() {
+                return Some(format!("invalid {key}: date \"{value}\""));
+            }
+        }
+    }
+
+    None
+}
+
+/// What the file a list was read from or last saved to holds, for
+/// [`TodoList::save_to`] to tell an append from any other change.
+#[derive(Debug, Clone)]
+struct Loaded {
+    /// Length of the file in bytes
+    len: u64,
+    /// [`line_hash`] of each item in it, in file order
+    items: Vec<u64>,
+}
+
+/// A hash of the item line `raw`, to tell whether it changed since the
+/// file was read. Only compared within one run.
+fn line_hash(raw: &str) -> u64 {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    raw.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Counts the bytes a reader hands out.
+struct Counted<R> {
+    inner: R,
+    len: u64,
+}
+
+impl<R: std::io::Read> std::io::Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.len += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: std::io::BufRead> std::io::BufRead for Counted<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.len += amount as u64;
+        self.inner.consume(amount);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TodoList {
     items: Vec<TodoItem>,
     path: Option<PathBuf>,
     next_id: usize,
+    completion: Completion,
+    revision: u64,
+    /// `None` once the items were removed or reordered, or if the list
+    /// wasn't read from its file or saved to it
+    loaded: Option<Loaded>,
+    /// Built on first use, shared with copies until either one changes
+    tags: Arc<OnceLock<intern::TagIndex>>,
 }
 
 impl TodoList {
@@ -79,16 +712,51 @@ impl TodoList {
             items: Vec::new(),
             path: None,
             next_id: 1,
+            completion: Completion::default(),
+            revision: 0,
+            loaded: None,
+            tags: Arc::default(),
         }
     }
 
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
         let path = path.as_ref();
-        let content = fs::read_to_string(path)?;
-        let mut list = Self::new();
+        let mut list = Self::from_reader(std::io::BufReader::new(fs::File::open(path)?))?;
         list.path = Some(path.to_path_buf());
+        tracing::debug!(path = %path.display(), items = list.len(), "loaded list");
+        Ok(list)
+    }
 
-        for line in content.lines() {
+    /// Parses the todo.txt content `reader` yields a line at a time, without
+    /// holding all of it in memory first. See [`stream::items`] to go
+    /// through the items without keeping them either. The list takes the
+    /// content for that of the file it's given with [`TodoList::set_path`],
+    /// if it has none yet.
+    pub fn from_reader(reader: impl std::io::BufRead) -> Result<Self, std::io::Error> {
+        let mut list = Self::new();
+        let mut reader = Counted {
+            inner: reader,
+            len: 0,
+        };
+        for item in stream::items(&mut reader) {
+            list.items.push(item?);
+        }
+        list.next_id = list.items.len() + 1;
+        list.loaded = Some(Loaded {
+            len: reader.len,
+            items: list
+                .items
+                .iter()
+                .map(|item| line_hash(&item.raw()))
+                .collect(),
+        });
+        Ok(list)
+    }
+
+    /// Parses todo.txt content without validating it, like [`TodoList::from_file`].
+    pub fn from_content(content: &str) -> Self {
+        let mut list = Self::new();
+        for (i, line) in content.lines().enumerate() {
             let line = line.trim();
             if line.is_empty() {
                 continue;
@@ -96,13 +764,16 @@ impl TodoList {
             let inner = todo_txt::task::Simple::from(line.to_string());
             let id = list.next_id;
             list.next_id += 1;
-            list.items.push(TodoItem { inner, id });
+            list.items.push(TodoItem {
+                inner,
+                id,
+                line: i + 1,
+            });
         }
-
-        Ok(list)
+        list
     }
 
-    pub fn save(&self) -> Result<(), std::io::Error> {
+    pub fn save(&mut self) -> Result<(), std::io::Error> {
         let path = self
             .path
             .as_ref()
@@ -110,18 +781,104 @@ impl TodoList {
         self.save_to(path.clone())
     }
 
-    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
-        let content: String = self
+    /// Writes the list to `path`. Saving to the file it was read from writes
+    /// nothing if no item changed, and only appends the new lines if todos
+    /// were only added; anything else replaces the file whole, see
+    /// [`patch`]. Afterwards the list's own file counts as holding what was
+    /// saved, so the next save again only writes what changed since.
+    pub fn save_to(&mut self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        let path = path.as_ref();
+        let hashes: Vec<u64> = self
             .items
+            .iter()
+            .map(|item| line_hash(&item.raw()))
+            .collect();
+        let loaded = self
+            .loaded
+            .as_ref()
+            .filter(|_| self.path.as_deref() == Some(path))
+            .filter(|loaded| hashes.starts_with(&loaded.items));
+        let len = match loaded {
+            Some(loaded) if loaded.items.len() == self.items.len() => loaded.len,
+            Some(loaded) => {
+                let added = self.items[loaded.items.len()..]
+                    .iter()
+                    .map(|item| item.inner.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                patch::append(path, loaded.len, &added, || self.to_content())?
+            }
+            None => patch::replace(path, &self.to_content())?,
+        };
+        if self.path.as_deref() == Some(path) {
+            self.loaded = Some(Loaded { len, items: hashes });
+        }
+        tracing::debug!(path = %path.display(), items = self.len(), "saved list");
+        Ok(())
+    }
+
+    /// Serializes the list to todo.txt content, one task per line.
+    pub fn to_content(&self) -> String {
+        self.items
             .iter()
             .map(|item| item.inner.to_string())
             .collect::<Vec<_>>()
-            .join("\n");
-        fs::write(path, content)
+            .join("\n")
+    }
+
+    /// Replaces all items with the tasks in `content`. Lines that parse to a task
+    /// already in the list keep that item's id; new lines get fresh ids. Nothing
+    /// is changed if any line fails validation.
+    pub fn replace_content(&mut self, content: &str) -> Result<(), Vec<LineError>> {
+        let errors: Vec<LineError> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                validate_line(line.trim()).map(|message| LineError {
+                    line: i + 1,
+                    message,
+                })
+            })
+            .collect();
+        if !errors.is_empty() {
+            tracing::debug!(errors = errors.len(), "rejected content");
+            return Err(errors);
+        }
+
+        self.loaded = None;
+        let mut previous = std::mem::take(&mut self.items);
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let inner = todo_txt::task::Simple::from(line.to_string());
+            let id = match previous.iter().position(|item| item.inner == inner) {
+                Some(pos) => previous.remove(pos).id,
+                None => {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    id
+                }
+            };
+            self.items.push(TodoItem {
+                inner,
+                id,
+                line: i + 1,
+            });
+        }
+        self.touch();
+
+        Ok(())
     }
 
     pub fn set_path(&mut self, path: impl AsRef<Path>) {
-        self.path = Some(path.as_ref().to_path_buf());
+        let path = path.as_ref();
+        // What was read is only known to be in the first file the list gets
+        if self.path.as_ref().is_some_and(|old| old != path) {
+            self.loaded = None;
+        }
+        self.path = Some(path.to_path_buf());
     }
 
     pub fn path(&self) -> Option<&Path> {
@@ -132,35 +889,158 @@ impl TodoList {
         let inner = todo_txt::task::Simple::from(subject.to_string());
         let id = self.next_id;
         self.next_id += 1;
-        self.items.push(TodoItem { inner, id });
+        let line = self.items.len() + 1;
+        self.items.push(TodoItem { inner, id, line });
+        self.touch();
         id
     }
 
+    /// Like [`TodoList::add`], with `defaults` applied to the new task.
+    pub fn add_with_defaults(
+        &mut self,
+        subject: &str,
+        defaults: &TaskDefaults,
+        today: Date,
+    ) -> usize {
+        let id = self.add(subject);
+        if let Some(item) = self.get_mut(id) {
+            item.apply_defaults(defaults, today);
+        }
+        id
+    }
+
+    /// Replaces the todo `id` with one new todo per non-empty line of
+    /// `lines`, right after it. The new todos take on the original's
+    /// priority, creation date, projects, contexts and tags unless the line
+    /// sets its own. Returns the new ids, or `None` if there is no todo `id`.
+    pub fn split(
+        &mut self,
+        id: usize,
+        lines: &[String],
+        options: SplitOptions,
+    ) -> Option<Vec<usize>> {
+        let pos = self.items.iter().position(|item| item.id == id)?;
+        let original = self.items[pos].clone();
+        let original_raw = original.raw();
+        let mut ids = Vec::new();
+        for line in lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+        {
+            let mut item = TodoItem::new(line);
+            item.id = self.next_id;
+            self.next_id += 1;
+            let mut raw = item.raw();
+            raw.push_str(&missing_tags(&raw, &original_raw));
+            item.set_raw(&raw);
+            if item.inner.priority.is_lowest() {
+                item.inner.priority = original.inner.priority.clone();
+            }
+            if item.inner.create_date.is_none() {
+                item.inner.create_date = original.inner.create_date;
+            }
+            ids.push(item.id);
+            self.items.insert(pos + ids.len(), item);
+        }
+        self.loaded = None;
+        self.renumber();
+        match options.original {
+            SplitOriginal::Keep => {}
+            SplitOriginal::Complete => match options.completed_on {
+                Some(date) => {
+                    self.complete_on(id, date);
+                }
+                None => {
+                    self.complete(id);
+                }
+            },
+            SplitOriginal::Delete => {
+                self.remove(id);
+            }
+        }
+        self.touch();
+        Some(ids)
+    }
+
     pub fn remove(&mut self, id: usize) -> Option<TodoItem> {
         if let Some(pos) = self.items.iter().position(|item| item.id == id) {
-            Some(self.items.remove(pos))
+            self.touch();
+            self.loaded = None;
+            let item = self.items.remove(pos);
+            self.renumber();
+            Some(item)
         } else {
             None
         }
     }
 
+    /// Moves the todo `id` to the end of the list `to`, e.g. from one file
+    /// to another. Returns its id there, or `None` if there is no todo `id`.
+    pub fn move_to(&mut self, id: usize, to: &mut TodoList) -> Option<usize> {
+        let item = self.remove(id)?;
+        Some(to.add(&item.raw()))
+    }
+
     pub fn get(&self, id: usize) -> Option<&TodoItem> {
         self.items.iter().find(|item| item.id == id)
     }
 
+    /// The item with `id` to change. Counts as a change for
+    /// [`TodoList::revision`] whether or not it is changed; saving only
+    /// rewrites its line if it was.
     pub fn get_mut(&mut self, id: usize) -> Option<&mut TodoItem> {
-        self.items.iter_mut().find(|item| item.id == id)
+        let pos = self.items.iter().position(|item| item.id == id)?;
+        self.touch();
+        Some(&mut self.items[pos])
+    }
+
+    /// Sets what [`TodoList::complete`] does besides marking the task done.
+    pub fn set_completion(&mut self, completion: Completion) {
+        self.completion = completion;
     }
 
     pub fn complete(&mut self, id: usize) -> bool {
         if let Some(item) = self.get_mut(id) {
             item.complete();
+            self.after_complete(id);
             true
         } else {
             false
         }
     }
 
+    /// Like [`TodoList::complete`], with `date` as the completion date.
+    pub fn complete_on(&mut self, id: usize, date: Date) -> bool {
+        if let Some(item) = self.get_mut(id) {
+            item.complete_on(date);
+            self.after_complete(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn after_complete(&mut self, id: usize) {
+        if self.completion != Completion::MoveToBottom {
+            return;
+        }
+        if let Some(pos) = self.items.iter().position(|item| item.id == id) {
+            self.loaded = None;
+            let item = self.items.remove(pos);
+            self.items.push(item);
+            self.renumber();
+        }
+    }
+
+    /// Numbers the lines as [`TodoList::to_content`] writes them, after the
+    /// items were reordered.
+    fn renumber(&mut self) {
+        for (i, item) in self.items.iter_mut().enumerate() {
+            item.line = i + 1;
+        }
+    }
+
     pub fn uncomplete(&mut self, id: usize) -> bool {
         if let Some(item) = self.get_mut(id) {
             item.uncomplete();
@@ -170,6 +1050,163 @@ impl TodoList {
         }
     }
 
+    /// Rewrites hierarchical project names from one separator to another,
+    /// e.g. `+home---garden` to `+home.garden`. Returns the number of changed items.
+    pub fn replace_project_separator(&mut self, old: &str, new: &str) -> usize {
+        if old.is_empty() || old == new {
+            return 0;
+        }
+        let changed = self
+            .items
+            .iter_mut()
+            .map(|item| {
+                item.edit_tags(TagKind::Project, |name| {
+                    if name.contains(old) {
+                        TagEdit::Rename(name.replace(old, new))
+                    } else {
+                        TagEdit::Keep
+                    }
+                })
+            })
+            .filter(|&changed| changed)
+            .count();
+        self.touch_if(changed > 0);
+        changed
+    }
+
+    /// Renames a tag on every line. Renaming to a tag a line already carries
+    /// merges the two, dropping the duplicate. Returns the number of changed items.
+    pub fn rename_tag(&mut self, kind: TagKind, from: &str, to: &str) -> usize {
+        let changed = self
+            .items
+            .iter_mut()
+            .map(|item| {
+                let merge = item.tags(kind).iter().any(|tag| tag == to);
+                item.edit_tags(kind, |name| match name == from {
+                    true if merge => TagEdit::Remove,
+                    true => TagEdit::Rename(to.to_string()),
+                    false => TagEdit::Keep,
+                })
+            })
+            .filter(|&changed| changed)
+            .count();
+        self.touch_if(changed > 0);
+        changed
+    }
+
+    /// Removes a tag from every line. Returns the number of changed items.
+    pub fn remove_tag(&mut self, kind: TagKind, name: &str) -> usize {
+        let changed = self
+            .items
+            .iter_mut()
+            .map(|item| {
+                item.edit_tags(kind, |tag| {
+                    if tag == name {
+                        TagEdit::Remove
+                    } else {
+                        TagEdit::Keep
+                    }
+                })
+            })
+            .filter(|&changed| changed)
+            .count();
+        self.touch_if(changed > 0);
+        changed
+    }
+
+    /// Normalizes every task, see [`TodoItem::normalize`], and returns the
+    /// lines that changed.
+    pub fn normalize(&mut self, options: normalize::Options) -> Vec<replace::Replacement> {
+        let mut changed = Vec::new();
+        for item in &mut self.items {
+            let before = item.raw();
+            if item.normalize(options) {
+                changed.push(replace::Replacement {
+                    id: item.id,
+                    before,
+                    after: item.raw(),
+                });
+            }
+        }
+        self.touch_if(!changed.is_empty());
+        changed
+    }
+
+    /// Moves the due and threshold dates of the pending todos `filter` picks
+    /// by `days`, e.g. when a project slips a week, and returns the lines
+    /// that changed. Dates that would leave the calendar stay as they are.
+    pub fn shift_dates(
+        &mut self,
+        filter: impl Fn(&TodoItem) -> bool,
+        days: i64,
+    ) -> Vec<replace::Replacement> {
+        let delta = TimeDelta::days(days);
+        let shift = |date: Option<Date>| date.map(|d| d.checked_add_signed(delta).unwrap_or(d));
+        let mut changed = Vec::new();
+        for item in &mut self.items {
+            if item.finished() || !filter(item) {
+                continue;
+            }
+            let before = item.raw();
+            item.inner.due_date = shift(item.inner.due_date);
+            item.inner.threshold_date = shift(item.inner.threshold_date);
+            let after = item.raw();
+            if after != before {
+                changed.push(replace::Replacement {
+                    id: item.id,
+                    before,
+                    after,
+                });
+            }
+        }
+        self.touch_if(!changed.is_empty());
+        changed
+    }
+
+    /// A number that goes up with every change to the list, including
+    /// reading it again, so a consumer can tell an older copy from a newer one.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Sets where [`TodoList::revision`] counts on from, e.g. to carry it
+    /// over to a list read anew.
+    pub fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+
+    fn touch_if(&mut self, changed: bool) {
+        if changed {
+            self.touch();
+        }
+    }
+
+    /// Counts a change of the items.
+    fn touch(&mut self) {
+        self.revision += 1;
+        self.tags = Arc::default();
+    }
+
+    /// The index from each tag to the todos carrying it, see
+    /// [`intern::TagIndex`].
+    pub fn tag_index(&self) -> &intern::TagIndex {
+        self.tags
+            .get_or_init(|| intern::TagIndex::build(&self.items))
+    }
+
+    /// The todos tagged `name`, in list order.
+    pub fn tagged(&self, kind: TagKind, name: &str) -> impl Iterator<Item = &TodoItem> {
+        self.tag_index()
+            .positions(kind, name)
+            .iter()
+            .map(|&position| &self.items[position])
+    }
+
+    /// The links between items from their `ref:` tags, see [`links::Links`].
+    pub fn links(&self) -> links::Links {
+        links::Links::build(&self.items)
+    }
+
     pub fn items(&self) -> &[TodoItem] {
         &self.items
     }
@@ -201,6 +1238,34 @@ impl Default for TodoList {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_add_with_defaults() {
+        let defaults = TaskDefaults {
+            creation_date: true,
+            priority: Some('C'),
+            projects: vec!["inbox".to_string()],
+            contexts: vec!["home".to_string()],
+        };
+        let today = Date::from_ymd_opt(2025, 3, 14).unwrap();
+        let mut list = TodoList::new();
+
+        let id = list.add_with_defaults("Water plants", &defaults, today);
+        assert_eq!(
+            list.get(id).unwrap().raw(),
+            "(C) 2025-03-14 Water plants +inbox @home"
+        );
+
+        // What the text sets itself is kept
+        let id = list.add_with_defaults("(A) 2025-01-01 Call Bob +work", &defaults, today);
+        assert_eq!(
+            list.get(id).unwrap().raw(),
+            "(A) 2025-01-01 Call Bob +work @home"
+        );
+
+        let id = list.add_with_defaults("Buy milk", &TaskDefaults::default(), today);
+        assert_eq!(list.get(id).unwrap().raw(), "Buy milk");
+    }
+
     #[test]
     fn test_add_and_get() {
         let mut list = TodoList::new();
@@ -222,6 +1287,95 @@ mod tests {
         assert!(!list.get(id).unwrap().finished());
     }
 
+    #[test]
+    fn test_complete_on() {
+        // Finished after midnight on a day that ends at 03:00
+        let mut list = TodoList::new();
+        let id = list.add("2025-02-27 Late night work");
+        let feb_28 = Date::from_ymd_opt(2025, 2, 28).unwrap();
+        assert!(list.complete_on(id, feb_28));
+        assert_eq!(list.get(id).unwrap().completion_date(), Some(feb_28));
+        assert!(!list.complete_on(42, feb_28));
+    }
+
+    #[test]
+    fn test_complete_moves_to_bottom() {
+        let mut list = TodoList::from_content("First\nSecond\nThird");
+        list.complete(1);
+        assert!(list.to_content().starts_with("x "));
+
+        list.set_completion(Completion::MoveToBottom);
+        list.complete(2);
+        let lines: Vec<String> = list
+            .items()
+            .iter()
+            .map(|item| item.subject().to_string())
+            .collect();
+        assert_eq!(lines, ["First", "Third", "Second"]);
+        assert!(list.get(2).unwrap().finished());
+    }
+
+    #[test]
+    fn test_revision() {
+        let mut list = TodoList::from_content("First +a\nSecond");
+        let mut last = list.revision();
+        let mut assert_bumped = |list: &TodoList| {
+            assert!(list.revision() > last);
+            last = list.revision();
+        };
+        list.add("Third");
+        assert_bumped(&list);
+        list.complete(1);
+        assert_bumped(&list);
+        list.rename_tag(TagKind::Project, "a", "b");
+        assert_bumped(&list);
+        list.remove(3);
+        assert_bumped(&list);
+        list.replace_content("First +b\nSecond").unwrap();
+        assert_bumped(&list);
+
+        // Nothing to change, nothing to count
+        list.remove_tag(TagKind::Project, "missing");
+        list.remove(42);
+        assert_eq!(list.revision(), last);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut list =
+            TodoList::from_content("(B) 2025-03-01 Plan party +home @phone due:2025-04-01\nOther");
+        let lines = [
+            "Book venue".to_string(),
+            "  ".to_string(),
+            "(A) Order cake @bakery due:2025-03-20".to_string(),
+        ];
+        let ids = list.split(1, &lines, SplitOptions::default()).unwrap();
+        assert_eq!(ids.len(), 2);
+        let order: Vec<usize> = list.items().iter().map(|item| item.id).collect();
+        assert_eq!(order, [1, ids[0], ids[1], 2]);
+
+        let venue = list.get(ids[0]).unwrap();
+        assert_eq!(venue.priority(), 1);
+        assert_eq!(venue.creation_date(), Date::from_ymd_opt(2025, 3, 1));
+        assert_eq!(venue.projects(), ["home"]);
+        assert_eq!(venue.contexts(), ["phone"]);
+        assert_eq!(venue.due_date(), Date::from_ymd_opt(2025, 4, 1));
+
+        let cake = list.get(ids[1]).unwrap();
+        assert_eq!(cake.priority(), 0);
+        assert_eq!(cake.contexts(), ["bakery", "phone"]);
+        assert_eq!(cake.due_date(), Date::from_ymd_opt(2025, 3, 20));
+
+        let options = SplitOptions {
+            original: SplitOriginal::Delete,
+            ..SplitOptions::default()
+        };
+        list.split(1, &["Send invites".to_string()], options)
+            .unwrap();
+        assert!(list.get(1).is_none());
+        assert!(list.split(1, &lines, options).is_none());
+    }
+
     #[test]
     fn test_remove() {
         let mut list = TodoList::new();
@@ -231,6 +1385,20 @@ mod tests {
         assert_eq!(list.len(), 0);
     }
 
+    #[test]
+    fn test_line_numbers() {
+        let mut list = TodoList::from_content("First\n\nSecond\n  \nThird");
+        let lines =
+            |list: &TodoList| -> Vec<usize> { list.items().iter().map(TodoItem::line).collect() };
+        assert_eq!(lines(&list), [1, 3, 5]);
+
+        let id = list.add("Fourth");
+        assert_eq!(list.get(id).unwrap().line(), 4);
+        list.remove(2);
+        assert_eq!(lines(&list), [1, 2, 3]);
+        assert_eq!(TodoItem::new("Loose").line(), 0);
+    }
+
     #[test]
     fn test_pending_and_done() {
         let mut list = TodoList::new();
@@ -242,4 +1410,185 @@ mod tests {
         assert_eq!(list.pending().count(), 2);
         assert_eq!(list.done().count(), 1);
     }
+
+    #[test]
+    fn test_dates() {
+        let mut list = TodoList::new();
+        let id = list.add("2025-01-02 Pay rent due:2025-02-01");
+        let item = list.get(id).unwrap();
+        assert_eq!(item.creation_date(), Date::from_ymd_opt(2025, 1, 2));
+        assert_eq!(item.due_date(), Date::from_ymd_opt(2025, 2, 1));
+        assert_eq!(item.subject(), "Pay rent");
+
+        let item = list.get_mut(id).unwrap();
+        item.set_due_date(Date::from_ymd_opt(2025, 3, 1));
+        assert_eq!(item.raw(), "2025-01-02 Pay rent due:2025-03-01");
+
+        item.set_due_time(NaiveTime::from_hms_opt(14, 30, 0));
+        assert_eq!(item.raw(), "2025-01-02 Pay rent due:2025-03-01 at:14:30");
+        assert_eq!(item.due_label().as_deref(), Some("2025-03-01 14:30"));
+        let all_day = TodoItem::new("Pay rent due:2025-03-01");
+        assert!(all_day.due_at() < item.due_at());
+    }
+
+    #[test]
+    fn test_shift_dates() {
+        let mut list = TodoList::from_content(
+            "Draft +launch due:2025-03-03 t:2025-03-01\n\
+             Review +launch\n\
+             x 2025-02-01 Kickoff +launch due:2025-02-01\n\
+             Pay rent due:2025-03-01",
+        );
+        let changed = list.shift_dates(|item| item.projects().contains(&"launch".to_string()), 7);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(
+            changed[0].after,
+            "Draft +launch due:2025-03-10 t:2025-03-08"
+        );
+        assert_eq!(
+            list.get(3).unwrap().due_date(),
+            Date::from_ymd_opt(2025, 2, 1)
+        );
+        assert_eq!(
+            list.get(4).unwrap().due_date(),
+            Date::from_ymd_opt(2025, 3, 1)
+        );
+
+        list.shift_dates(|_| true, -7);
+        assert_eq!(
+            list.get(1).unwrap().raw(),
+            "Draft +launch due:2025-03-03 t:2025-03-01"
+        );
+    }
+
+    #[test]
+    fn test_replace_project_separator() {
+        let mut list = TodoList::new();
+        let nested = list.add("(A) Rake leaves +home---garden +home @yard due:2025-01-01");
+        let flat = list.add("Call mom +family");
+
+        assert_eq!(list.replace_project_separator("---", "."), 1);
+
+        let item = list.get(nested).unwrap();
+        assert_eq!(
+            item.raw(),
+            "(A) Rake leaves +home.garden +home @yard due:2025-01-01"
+        );
+        assert_eq!(item.unique_projects(), vec!["home", "home.garden"]);
+        assert_eq!(list.get(flat).unwrap().raw(), "Call mom +family");
+    }
+
+    #[test]
+    fn test_rename_and_merge_tags() {
+        let mut list = TodoList::new();
+        let renamed = list.add("Call Bob @phone +sales");
+        let merged = list.add("Call Alice @phone @calls");
+        let untouched = list.add("Write report @desk");
+
+        assert_eq!(list.rename_tag(TagKind::Context, "phone", "calls"), 2);
+        assert_eq!(list.get(renamed).unwrap().raw(), "Call Bob @calls +sales");
+        assert_eq!(list.get(merged).unwrap().raw(), "Call Alice @calls");
+        assert_eq!(list.get(untouched).unwrap().raw(), "Write report @desk");
+    }
+
+    #[test]
+    fn test_assignee() {
+        let mut item = TodoItem::new("(B) Fix login bug assignee:alice +web");
+        assert_eq!(item.assignee(), Some("alice"));
+
+        item.set_assignee(Some("bob"));
+        assert_eq!(item.assignee(), Some("bob"));
+        assert_eq!(item.raw(), "(B) Fix login bug +web assignee:bob");
+
+        item.set_assignee(None);
+        assert_eq!(item.assignee(), None);
+        assert_eq!(item.raw(), "(B) Fix login bug +web");
+    }
+
+    #[test]
+    fn test_starred() {
+        let mut item = TodoItem::new("Renew passport star:1 +travel");
+        assert!(item.starred());
+        assert!(!TodoItem::new("Renew passport star:0").starred());
+
+        item.set_starred(false);
+        assert!(!item.starred());
+        assert_eq!(item.raw(), "Renew passport +travel");
+        item.set_starred(true);
+        assert_eq!(item.raw(), "Renew passport +travel star:1");
+    }
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(TodoItem::new("Write report est:1h30m").estimate(), Some(90));
+        assert_eq!(TodoItem::new("Write report est:lots").estimate(), None);
+        assert_eq!(TodoItem::new("Write report").estimate(), None);
+    }
+
+    #[test]
+    fn test_add_and_remove_single_tags() {
+        let mut item = TodoItem::new("Paint fence @home energy:high due:2025-03-01");
+        assert_eq!(item.free_tags(), vec!["energy:high"]);
+
+        assert!(item.add_tag("+garden"));
+        assert!(!item.add_tag("+garden"));
+        assert_eq!(item.projects(), vec!["garden"]);
+        assert!(item.add_tag("energy:low"));
+        assert_eq!(item.free_tags(), vec!["energy:low"]);
+
+        assert!(item.remove_tag("@home"));
+        assert!(!item.remove_tag("@home"));
+        assert!(item.contexts().is_empty());
+        assert!(item.remove_tag("due:2025-03-01"));
+        assert_eq!(item.due_date(), None);
+        assert!(!item.add_tag("two words"));
+    }
+
+    #[test]
+    fn test_remove_tag() {
+        let mut list = TodoList::new();
+        let middle = list.add("(A) Call +work Bob");
+        let last = list.add("Email Alice +work");
+        let first = list.add("+work Plan the week due:2025-01-01");
+
+        assert_eq!(list.remove_tag(TagKind::Project, "work"), 3);
+        assert_eq!(list.get(middle).unwrap().raw(), "(A) Call Bob");
+        assert_eq!(list.get(last).unwrap().raw(), "Email Alice");
+        assert_eq!(
+            list.get(first).unwrap().raw(),
+            "Plan the week due:2025-01-01"
+        );
+    }
+
+    #[test]
+    fn test_replace_content_keeps_ids_of_unchanged_lines() {
+        let mut list = TodoList::new();
+        let keep = list.add("Keep me +project");
+        let drop = list.add("Drop me");
+
+        list.replace_content("New task\nKeep me +project\n")
+            .unwrap();
+
+        assert_eq!(list.len(), 2);
+        assert!(list.get(drop).is_none());
+        assert_eq!(list.get(keep).unwrap().subject(), "Keep me +project");
+        assert_eq!(list.items()[1].id, keep);
+        assert!(list.items()[0].id > drop);
+    }
+
+    #[test]
+    fn test_replace_content_reports_line_errors() {
+        let mut list = TodoList::new();
+        list.add("Untouched");
+
+        let errors = list
+            .replace_content("Fine\n(1) Bad priority\nPay rent due:2025-13-01")
+            .unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(list.items()[0].subject(), "Untouched");
+    }
 }
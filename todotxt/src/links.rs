@@ -0,0 +1,87 @@
+//! Links between todos from `ref:<id>` tags, e.g. `Book hotel ref:12` for a
+//! task that belongs with todo 12, and the backlinks they make.
+
+use std::collections::BTreeMap;
+
+use crate::parse::{tokenize, Token};
+use crate::TodoItem;
+
+/// Tag key of the `ref:<id>` convention for linking todos.
+pub const REF_KEY: &str = "ref";
+
+/// The ids `item` links to, in the order its `ref:` tags come in. Tags that
+/// aren't ids are left out.
+pub fn refs(item: &TodoItem) -> Vec<usize> {
+    let raw = item.raw();
+    let mut ids = Vec::new();
+    for spanned in tokenize(&raw) {
+        if let Token::Tag {
+            key: REF_KEY,
+            value,
+        } = spanned.token
+        {
+            if let Ok(id) = value.parse() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Every link in a list both ways, see [`crate::TodoList::links`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Links {
+    outgoing: BTreeMap<usize, Vec<usize>>,
+    backlinks: BTreeMap<usize, Vec<usize>>,
+}
+
+impl Links {
+    /// Links among `items`. Links to ids that aren't among them are dropped.
+    pub fn build<'a>(items: impl IntoIterator<Item = &'a TodoItem>) -> Self {
+        let items: Vec<&TodoItem> = items.into_iter().collect();
+        let mut links = Links::default();
+        for item in &items {
+            let targets: Vec<usize> = refs(item)
+                .into_iter()
+                .filter(|id| *id != item.id && items.iter().any(|other| other.id == *id))
+                .collect();
+            for &target in &targets {
+                links.backlinks.entry(target).or_default().push(item.id);
+            }
+            if !targets.is_empty() {
+                links.outgoing.insert(item.id, targets);
+            }
+        }
+        links
+    }
+
+    /// The todos `id` links to.
+    pub fn outgoing(&self, id: usize) -> &[usize] {
+        self.outgoing.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// The todos linking to `id`.
+    pub fn backlinks(&self, id: usize) -> &[usize] {
+        self.backlinks.get(&id).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TodoList;
+
+    #[test]
+    fn test_links_both_ways() {
+        let list = TodoList::from_content(
+            "Plan trip\nBook hotel ref:1\nPack ref:1 ref:2 ref:99 ref:next\nSelf ref:4",
+        );
+        let links = list.links();
+        assert_eq!(links.outgoing(3), [1, 2]);
+        assert_eq!(links.backlinks(1), [2, 3]);
+        assert_eq!(links.backlinks(2), [3]);
+        assert!(links.outgoing(1).is_empty());
+        assert!(links.outgoing(4).is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! A safe subset of Markdown in todo text: `**bold**`, `` `code` ``,
+//! `[text](url)` and bare URLs. Anything else stays plain text, and only
+//! http(s) and mailto links get through, so a todo can't carry markup or
+//! `javascript:` URLs into the app. The line itself is never changed.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Inline {
+    Text { text: String },
+    Bold { text: String },
+    Code { text: String },
+    Link { text: String, url: String },
+}
+
+/// Schemes links may have.
+const SCHEMES: [&str; 3] = ["https://", "http://", "mailto:"];
+
+/// Punctuation after a bare URL that ends the sentence rather than the URL.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '"', '\''];
+
+/// Whether `url` may be linked to and opened.
+pub fn safe_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    SCHEMES
+        .iter()
+        .any(|scheme| lower.starts_with(scheme) && lower.len() > scheme.len())
+        && !url
+            .contains(|c: char| c.is_whitespace() || c.is_control() || matches!(c, '"' | '<' | '>'))
+}
+
+/// The inline at the start of `rest`, if any, and its length in bytes.
+/// Bare URLs only count at the start of a word.
+fn inline(rest: &str, word_start: bool) -> Option<(Inline, usize)> {
+    if let Some(after) = rest.strip_prefix("**") {
+        let end = after.find("**")?;
+        let text = &after[..end];
+        return (!text.trim().is_empty()).then(|| {
+            (
+                Inline::Bold {
+                    text: text.to_string(),
+                },
+                end + 4,
+            )
+        });
+    }
+    if let Some(after) = rest.strip_prefix('`') {
+        let end = after.find('`')?;
+        let text = &after[..end];
+        return (!text.is_empty()).then(|| {
+            (
+                Inline::Code {
+                    text: text.to_string(),
+                },
+                end + 2,
+            )
+        });
+    }
+    if let Some(after) = rest.strip_prefix('[') {
+        let close = after.find("](")?;
+        let (text, target) = (&after[..close], &after[close + 2..]);
+        let end = target.find(')')?;
+        let url = &target[..end];
+        return (!text.is_empty() && safe_url(url)).then(|| {
+            let link = Inline::Link {
+                text: text.to_string(),
+                url: url.to_string(),
+            };
+            (link, close + end + 4)
+        });
+    }
+    let lower = rest.get(..8).unwrap_or(rest).to_ascii_lowercase();
+    if word_start && (lower.starts_with("https://") || lower.starts_with("http://")) {
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let url = rest[..len].trim_end_matches(TRAILING_PUNCTUATION);
+        return safe_url(url).then(|| {
+            let link = Inline::Link {
+                text: url.to_string(),
+                url: url.to_string(),
+            };
+            (link, url.len())
+        });
+    }
+    None
+}
+
+/// `text` split into inlines; text around them comes as [`Inline::Text`].
+pub fn parse(text: &str) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    let mut plain = String::new();
+    let mut pos = 0;
+    let mut word_start = true;
+    while let Some(c) = text[pos..].chars().next() {
+        if let Some((found, len)) = inline(&text[pos..], word_start) {
+            if !plain.is_empty() {
+                inlines.push(Inline::Text {
+                    text: std::mem::take(&mut plain),
+                });
+            }
+            inlines.push(found);
+            pos += len;
+            word_start = false;
+            continue;
+        }
+        plain.push(c);
+        pos += c.len_utf8();
+        word_start = c.is_whitespace();
+    }
+    if !plain.is_empty() {
+        inlines.push(Inline::Text { text: plain });
+    }
+    inlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> Inline {
+        Inline::Text {
+            text: text.to_string(),
+        }
+    }
+
+    fn link(text: &str, url: &str) -> Inline {
+        Inline::Link {
+            text: text.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Read **this** `now`, see [docs](https://x.org/a) or https://y.org/b."),
+            vec![
+                text("Read "),
+                Inline::Bold {
+                    text: "this".to_string()
+                },
+                text(" "),
+                Inline::Code {
+                    text: "now".to_string()
+                },
+                text(", see "),
+                link("docs", "https://x.org/a"),
+                text(" or "),
+                link("https://y.org/b", "https://y.org/b"),
+                text("."),
+            ]
+        );
+        assert_eq!(parse("2 ** 3 is `` odd"), vec![text("2 ** 3 is `` odd")]);
+    }
+
+    #[test]
+    fn test_unsafe_links_stay_text() {
+        let line = "Click [here](javascript:alert(1)) or [me](https://a.org/\"onclick)";
+        assert_eq!(parse(line), vec![text(line)]);
+        assert_eq!(
+            parse("see xhttps://a.org"),
+            vec![text("see xhttps://a.org")]
+        );
+        assert!(safe_url("mailto:me@example.org"));
+        assert!(!safe_url("https://"));
+    }
+}
@@ -0,0 +1,63 @@
+//! Line-level three-way merge of todo.txt contents, used to reconcile two
+//! copies of a list that were edited independently since they last agreed.
+
+use std::collections::HashSet;
+
+/// Merges `local` and `remote`, which both started out as `base`.
+///
+/// Lines are treated as a set: a line removed on either side is removed, a
+/// line added on either side is added. An edit is a removal plus an addition,
+/// so if both sides edit the same line both versions are kept rather than one
+/// being lost. Local order is kept, with remote additions appended.
+pub fn merge(base: &str, local: &str, remote: &str) -> String {
+    let base_lines: HashSet<&str> = non_empty_lines(base).collect();
+    let remote_lines: HashSet<&str> = non_empty_lines(remote).collect();
+    let local_lines: HashSet<&str> = non_empty_lines(local).collect();
+
+    let mut merged = String::new();
+    let mut seen = HashSet::new();
+    let kept_local = non_empty_lines(local)
+        .filter(|line| !base_lines.contains(line) || remote_lines.contains(line));
+    let added_remote = non_empty_lines(remote)
+        .filter(|line| !base_lines.contains(line) && !local_lines.contains(line));
+    for line in kept_local.chain(added_remote) {
+        if seen.insert(line) {
+            merged.push_str(line);
+            merged.push('\n');
+        }
+    }
+    tracing::debug!(lines = seen.len(), "merged lists");
+    merged
+}
+
+fn non_empty_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().filter(|line| !line.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_combines_independent_changes() {
+        let base = "Call Bob\nEmail Alice\nWrite report\n";
+        let local = "Call Bob\nx Email Alice\nWrite report\nBuy milk\n";
+        let remote = "Call Bob\nEmail Alice\nPlan trip\n";
+
+        assert_eq!(
+            merge(base, local, remote),
+            "Call Bob\nx Email Alice\nBuy milk\nPlan trip\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_both_versions_of_a_conflicting_edit() {
+        let base = "Call Bob\n";
+        assert_eq!(
+            merge(base, "(A) Call Bob\n", "Call Bob +work\n"),
+            "(A) Call Bob\nCall Bob +work\n"
+        );
+        // Identical edits on both sides collapse into one line
+        assert_eq!(merge(base, "x Call Bob\n", "x Call Bob\n"), "x Call Bob\n");
+    }
+}
@@ -0,0 +1,116 @@
+//! Plain language dates in quickly captured tasks, e.g. dictated ones.
+//!
+//! `call dentist tomorrow` becomes `call dentist due:2025-03-02`. Understood
+//! are `today`, `tomorrow`, weekdays (optionally after `on` or `next`),
+//! `next week` and `in <n> days` or `weeks`, with `n` in digits or words up
+//! to ten. Only the first date phrase is used; everything else is kept.
+
+use chrono::{Days, Weekday};
+
+use crate::relative::next_weekday;
+use crate::Date;
+
+const NUMBERS: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// `text` with its first date phrase replaced by a `due:` tag at the end.
+/// Text that already has a due date is returned unchanged.
+pub fn parse_quick_add(text: &str, today: Date) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.iter().any(|word| word.starts_with("due:")) {
+        return words.join(" ");
+    }
+    for start in 0..words.len() {
+        if let Some((len, due)) = date_phrase(&words[start..], today) {
+            let mut kept: Vec<&str> = words[..start].to_vec();
+            kept.extend(&words[start + len..]);
+            let due = format!("due:{due}");
+            kept.push(&due);
+            return kept.join(" ");
+        }
+    }
+    words.join(" ")
+}
+
+/// The number of words the date phrase at the start of `words` takes and its date.
+fn date_phrase(words: &[&str], today: Date) -> Option<(usize, Date)> {
+    let word = |i: usize| {
+        words.get(i).map(|word| {
+            word.trim_end_matches(|c: char| c.is_ascii_punctuation())
+                .to_lowercase()
+        })
+    };
+    let first = word(0)?;
+    match first.as_str() {
+        "today" => return Some((1, today)),
+        "tomorrow" => return Some((1, today + Days::new(1))),
+        "next" if word(1).as_deref() == Some("week") => return Some((2, today + Days::new(7))),
+        "in" => {
+            let count = word(1)?;
+            let count = count
+                .parse::<u64>()
+                .ok()
+                .or_else(|| NUMBERS.iter().position(|n| *n == count).map(|n| n as u64))?;
+            let days = match word(2)?.as_str() {
+                "day" | "days" => count,
+                "week" | "weeks" => count * 7,
+                _ => return None,
+            };
+            return Some((3, today.checked_add_days(Days::new(days))?));
+        }
+        _ => {}
+    }
+    let (len, name) = match first.as_str() {
+        "on" | "next" => (2, word(1)?),
+        _ => (1, first),
+    };
+    // Only full names, "sun" or "sat" are more likely meant as words
+    if name.len() <= 3 {
+        return None;
+    }
+    let weekday = name.parse::<Weekday>().ok()?;
+    Some((len, next_weekday(today, weekday)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quick_add() {
+        // A Saturday
+        let today = Date::from_ymd_opt(2025, 3, 1).unwrap();
+
+        assert_eq!(
+            parse_quick_add("call dentist tomorrow", today),
+            "call dentist due:2025-03-02"
+        );
+        assert_eq!(
+            parse_quick_add("Pay rent today.", today),
+            "Pay rent due:2025-03-01"
+        );
+        assert_eq!(
+            parse_quick_add("water plants on Friday @home", today),
+            "water plants @home due:2025-03-07"
+        );
+        assert_eq!(
+            parse_quick_add("call mom saturday", today),
+            "call mom due:2025-03-08"
+        );
+        assert_eq!(
+            parse_quick_add("renew passport in two weeks", today),
+            "renew passport due:2025-03-15"
+        );
+        assert_eq!(
+            parse_quick_add("plan trip next week", today),
+            "plan trip due:2025-03-08"
+        );
+        assert_eq!(parse_quick_add("read in bed", today), "read in bed");
+        assert_eq!(parse_quick_add("buy sun cream", today), "buy sun cream");
+        assert_eq!(
+            parse_quick_add("call Bob tomorrow due:2025-04-01", today),
+            "call Bob tomorrow due:2025-04-01"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Tidying up task lines without changing what they mean: runs of spaces
+//! collapse, empty tags go and, optionally, `+project` and `@context` tags
+//! move behind the text in a fixed order. Only the description is touched;
+//! the header (completion, priority, dates) and `key:value` tags are kept as
+//! the parser read them.
+
+use crate::parse::{tokenize, Token};
+
+/// Keys whose tags mean nothing without a value, e.g. a lone `due:`. Other
+/// words ending in a colon are ordinary text, like `Note:`.
+const VALUED_KEYS: [&str; 7] = [
+    "due",
+    "t",
+    "rec",
+    crate::estimate::ESTIMATE_KEY,
+    crate::filter::ENERGY_KEY,
+    crate::STAR_KEY,
+    crate::ASSIGNEE_KEY,
+];
+
+/// How far to go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Move projects, then contexts, behind the text, each in the order
+    /// they appear
+    pub order_tags: bool,
+}
+
+fn is_empty_tag(word: &str) -> bool {
+    word == "+"
+        || word == "@"
+        || word
+            .strip_suffix(':')
+            .is_some_and(|key| VALUED_KEYS.contains(&key))
+}
+
+/// The description `subject` tidied up as `options` say.
+pub fn subject(subject: &str, options: Options) -> String {
+    let mut text = Vec::new();
+    let mut projects = Vec::new();
+    let mut contexts = Vec::new();
+    for spanned in tokenize(subject) {
+        let word = &subject[spanned.span.range()];
+        match spanned.token {
+            _ if is_empty_tag(word) => {}
+            Token::Project(_) if options.order_tags => projects.push(word),
+            Token::Context(_) if options.order_tags => contexts.push(word),
+            _ => text.push(word),
+        }
+    }
+    text.extend(projects);
+    text.extend(contexts);
+    text.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{TodoItem, TodoList};
+
+    const LINES: [&str; 8] = [
+        "Call  mom   +family  @phone due:2025-03-01",
+        "(A) 2025-01-01 Pay rent +home @  + due:",
+        "(A)  2025-01-01 Pay rent",
+        "x 2025-01-02 2025-01-01 Sort   photos  +home",
+        "+work @desk Write report est:1h Note: check figures",
+        "Water plants @home +garden @outside star:1",
+        "Buy milk",
+        "(B) Plan trip +travel---japan t:2025-04-01 @desk",
+    ];
+
+    /// Everything the app reads from a line, as sets where order doesn't
+    /// matter. Empty tags are the only words allowed to go.
+    fn meaning(item: &TodoItem) -> impl PartialEq + std::fmt::Debug {
+        let words: Vec<String> = item
+            .subject()
+            .split_whitespace()
+            .filter(|w| !w.starts_with('+') && !w.starts_with('@') && !is_empty_tag(w))
+            .map(str::to_string)
+            .collect();
+        (
+            (item.finished(), item.priority()),
+            (
+                item.creation_date(),
+                item.completion_date(),
+                item.due_date(),
+            ),
+            item.unique_projects().into_iter().collect::<BTreeSet<_>>(),
+            item.unique_contexts().into_iter().collect::<BTreeSet<_>>(),
+            (item.estimate(), item.starred(), words),
+        )
+    }
+
+    #[test]
+    fn test_normalize_keeps_meaning() {
+        for options in [Options::default(), Options { order_tags: true }] {
+            for line in LINES {
+                let before = TodoItem::new(line);
+                let mut after = before.clone();
+                after.normalize(options);
+                assert_eq!(meaning(&after), meaning(&before), "{line}");
+                assert!(!after.raw().contains("  "), "{line}");
+
+                // Normalizing twice changes nothing more
+                let mut again = after.clone();
+                assert!(!again.normalize(options), "{line}");
+                assert_eq!(again.raw(), after.raw());
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_lines() {
+        let normalized = |line: &str, order_tags| {
+            let mut item = TodoItem::new(line);
+            item.normalize(Options { order_tags });
+            item.raw()
+        };
+        assert_eq!(
+            normalized("Call  mom   +family  @phone", false),
+            "Call mom +family @phone"
+        );
+        assert_eq!(
+            normalized("(A) 2025-01-01 Pay rent +home @  + due:", false),
+            "(A) 2025-01-01 Pay rent +home"
+        );
+        assert_eq!(
+            normalized("+work @desk Write report Note: check", true),
+            "Write report Note: check +work @desk"
+        );
+        assert_eq!(
+            normalized("Water plants @home +garden @outside", true),
+            "Water plants +garden @home @outside"
+        );
+    }
+
+    #[test]
+    fn test_normalize_list() {
+        let mut list = TodoList::from_content("Buy milk\nCall  mom\nSort photos  @home  +\n");
+        let changed = list.normalize(Options::default());
+        let lines: Vec<(usize, &str, &str)> = changed
+            .iter()
+            .map(|c| (c.id, c.before.as_str(), c.after.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (2, "Call  mom", "Call mom"),
+                (3, "Sort photos  @home  +", "Sort photos @home"),
+            ]
+        );
+        assert!(list.normalize(Options::default()).is_empty());
+    }
+}
@@ -0,0 +1,449 @@
+//! Operation log over todo list changes, for syncing replicas that were
+//! edited independently.
+//!
+//! Every change is recorded as an [`Op`] stamped with a Lamport timestamp.
+//! Replicas exchange ops and [`OpLog::merge`] them; because every op is
+//! identified by its stamp and the list is derived from the set of ops alone,
+//! replicas that have seen the same ops produce the same list regardless of
+//! the order they arrived in:
+//!
+//! - todos are ordered by the stamp of the op that added them
+//! - the text of a todo is the one from its latest add or edit
+//! - its completion state comes from the latest complete/uncomplete, if that
+//!   is newer than the text
+//! - a delete wins over any concurrent change
+//! - of todos with the same line only the oldest shows, so a todo both
+//!   replicas had before they first synced isn't doubled
+//!
+//! Changes made to the file by whatever program are turned into ops with
+//! [`OpLog::record`], and replicas only send each other the ops the other
+//! lacks, see [`OpLog::vector`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::similarity::similarity;
+use crate::{Date, TodoItem, TodoList};
+
+/// How alike a changed line must be to a todo that went away to count as
+/// that todo edited, rather than one deleted and another added.
+const EDIT_THRESHOLD: f64 = 0.5;
+
+/// Lamport timestamp. Ties between replicas are broken by the replica id, so
+/// stamps are totally ordered.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Stamp {
+    pub counter: u64,
+    pub replica: String,
+}
+
+/// A todo is identified by the stamp of the op that added it.
+pub type TaskId = Stamp;
+
+/// The newest counter seen of each replica. A replica's ops travel oldest
+/// first, so a log holds every op of a replica up to its counter here.
+pub type Vector = BTreeMap<String, u64>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum OpKind {
+    Add { line: String },
+    Edit { task: TaskId, line: String },
+    Complete { task: TaskId, date: Date },
+    Uncomplete { task: TaskId },
+    Delete { task: TaskId },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Op {
+    pub stamp: Stamp,
+    #[serde(flatten)]
+    pub kind: OpKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpLog {
+    replica: String,
+    clock: u64,
+    #[serde(with = "ops_as_list")]
+    ops: BTreeMap<Stamp, OpKind>,
+}
+
+/// Stamps aren't strings, so the ops are kept as a list in JSON.
+mod ops_as_list {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{Op, OpKind, Stamp};
+
+    pub fn serialize<S: Serializer>(
+        ops: &BTreeMap<Stamp, OpKind>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(ops.iter().map(|(stamp, kind)| Op {
+            stamp: stamp.clone(),
+            kind: kind.clone(),
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Stamp, OpKind>, D::Error> {
+        let ops = Vec::<Op>::deserialize(deserializer)?;
+        Ok(ops.into_iter().map(|op| (op.stamp, op.kind)).collect())
+    }
+}
+
+impl OpLog {
+    /// An empty log for the replica named `replica`, which must be unique
+    /// among the replicas that sync with each other.
+    pub fn new(replica: &str) -> Self {
+        Self {
+            replica: replica.to_string(),
+            clock: 0,
+            ops: BTreeMap::new(),
+        }
+    }
+
+    fn push(&mut self, kind: OpKind) -> Stamp {
+        self.clock += 1;
+        let stamp = Stamp {
+            counter: self.clock,
+            replica: self.replica.clone(),
+        };
+        self.ops.insert(stamp.clone(), kind);
+        stamp
+    }
+
+    pub fn add(&mut self, line: &str) -> TaskId {
+        self.push(OpKind::Add {
+            line: line.to_string(),
+        })
+    }
+
+    pub fn edit(&mut self, task: &TaskId, line: &str) {
+        self.push(OpKind::Edit {
+            task: task.clone(),
+            line: line.to_string(),
+        });
+    }
+
+    pub fn complete(&mut self, task: &TaskId, date: Date) {
+        self.push(OpKind::Complete {
+            task: task.clone(),
+            date,
+        });
+    }
+
+    pub fn uncomplete(&mut self, task: &TaskId) {
+        self.push(OpKind::Uncomplete { task: task.clone() });
+    }
+
+    pub fn delete(&mut self, task: &TaskId) {
+        self.push(OpKind::Delete { task: task.clone() });
+    }
+
+    /// All ops, oldest first, e.g. to send to another replica.
+    pub fn ops(&self) -> impl Iterator<Item = Op> + '_ {
+        self.ops.iter().map(|(stamp, kind)| Op {
+            stamp: stamp.clone(),
+            kind: kind.clone(),
+        })
+    }
+
+    /// Ops this log has that `other` doesn't.
+    pub fn missing_from(&self, other: &OpLog) -> Vec<Op> {
+        self.ops()
+            .filter(|op| !other.ops.contains_key(&op.stamp))
+            .collect()
+    }
+
+    /// What ops this log has, for another replica to send it only those it
+    /// lacks with [`OpLog::ops_since`].
+    pub fn vector(&self) -> Vector {
+        let mut vector = Vector::new();
+        for stamp in self.ops.keys() {
+            let counter = vector.entry(stamp.replica.clone()).or_default();
+            *counter = (*counter).max(stamp.counter);
+        }
+        vector
+    }
+
+    /// Ops a replica whose log has `vector` lacks.
+    pub fn ops_since(&self, vector: &Vector) -> Vec<Op> {
+        self.ops()
+            .filter(|op| vector.get(&op.stamp.replica).unwrap_or(&0) < &op.stamp.counter)
+            .collect()
+    }
+
+    /// Adds ops received from another replica. Ops already known are ignored.
+    pub fn merge(&mut self, ops: impl IntoIterator<Item = Op>) {
+        for op in ops {
+            self.clock = self.clock.max(op.stamp.counter);
+            self.ops.entry(op.stamp).or_insert(op.kind);
+        }
+    }
+
+    /// The todos not deleted with their lines, oldest first, including those
+    /// hidden behind an older todo with the same line.
+    fn live(&self) -> BTreeMap<&Stamp, String> {
+        struct Task<'a> {
+            line: (&'a Stamp, &'a str),
+            done: Option<(&'a Stamp, Option<Date>)>,
+        }
+
+        let mut tasks = BTreeMap::<&Stamp, Task>::new();
+        let mut deleted = HashSet::<&Stamp>::new();
+        // Ops are visited in stamp order, so later ops simply overwrite earlier ones
+        for (stamp, kind) in &self.ops {
+            match kind {
+                OpKind::Add { line } => {
+                    tasks.insert(
+                        stamp,
+                        Task {
+                            line: (stamp, line),
+                            done: None,
+                        },
+                    );
+                }
+                OpKind::Edit { task, line } => {
+                    if let Some(task) = tasks.get_mut(task) {
+                        task.line = (stamp, line);
+                    }
+                }
+                OpKind::Complete { task, date } => {
+                    if let Some(task) = tasks.get_mut(task) {
+                        task.done = Some((stamp, Some(*date)));
+                    }
+                }
+                OpKind::Uncomplete { task } => {
+                    if let Some(task) = tasks.get_mut(task) {
+                        task.done = Some((stamp, None));
+                    }
+                }
+                OpKind::Delete { task } => {
+                    deleted.insert(task);
+                }
+            }
+        }
+
+        tasks
+            .into_iter()
+            .filter(|(id, _)| !deleted.contains(id))
+            .map(|(id, task)| {
+                let mut item = TodoItem::new(task.line.1);
+                match task.done {
+                    Some((stamp, Some(date))) if stamp > task.line.0 => item.complete_on(date),
+                    Some((stamp, None)) if stamp > task.line.0 => item.uncomplete(),
+                    _ => {}
+                }
+                (id, item.raw())
+            })
+            .collect()
+    }
+
+    /// The todo showing for each line, the oldest of those with it.
+    fn shown(live: &BTreeMap<&Stamp, String>) -> HashMap<String, TaskId> {
+        let mut shown = HashMap::new();
+        for (id, line) in live {
+            shown.entry(line.clone()).or_insert_with(|| (*id).clone());
+        }
+        shown
+    }
+
+    /// The todo list described by the ops seen so far.
+    pub fn materialize(&self) -> TodoList {
+        self.arrange(&[])
+    }
+
+    /// Like [`OpLog::materialize`], with the todos of `order` in that order
+    /// first, e.g. those of a replica's file as [`OpLog::record`] found
+    /// them, and the others after them.
+    pub fn arrange(&self, order: &[TaskId]) -> TodoList {
+        let live = self.live();
+        let shown = Self::shown(&live);
+        let mut placed = HashSet::new();
+        let mut list = TodoList::new();
+        let ids = order.iter().chain(live.keys().copied());
+        for line in ids.filter_map(|id| live.get(id)) {
+            // A todo hidden by an older one with its line shows at its place
+            if placed.insert(&shown[line]) {
+                list.add(line);
+            }
+        }
+        list
+    }
+
+    /// Records the ops that turn the list of this log into `content`, the
+    /// file as edited since. Each todo of the log is matched with a line of
+    /// the file: an unchanged one first, else one that only got checked off
+    /// or on, else the line most alike. Todos left over were deleted and
+    /// lines left over added. Returns the todos of the file's lines in order,
+    /// for [`OpLog::arrange`].
+    pub fn record(&mut self, content: &str) -> Vec<TaskId> {
+        let lines: Vec<String> = TodoList::from_content(content)
+            .items()
+            .iter()
+            .map(TodoItem::raw)
+            .collect();
+        let mut left: BTreeMap<TaskId, String> = self
+            .live()
+            .into_iter()
+            .map(|(id, line)| (id.clone(), line))
+            .collect();
+        let mut ids: Vec<Option<TaskId>> = vec![None; lines.len()];
+
+        for (id, line) in ids.iter_mut().zip(&lines) {
+            let same = left.iter().find(|(_, old)| *old == line);
+            *id = same.map(|(task, _)| task.clone());
+            if let Some(task) = id {
+                left.remove(task);
+            }
+        }
+        for (id, line) in ids.iter_mut().zip(&lines).filter(|(id, _)| id.is_none()) {
+            let new = TodoItem::new(line);
+            let toggled = left.iter().find_map(|(task, old)| {
+                let mut item = TodoItem::new(old);
+                let done = match (item.finished(), new.finished(), new.completion_date()) {
+                    (false, true, Some(date)) => {
+                        item.complete_on(date);
+                        Some(date)
+                    }
+                    (true, false, _) => {
+                        item.uncomplete();
+                        None
+                    }
+                    _ => return None,
+                };
+                (item.raw() == *line).then(|| (task.clone(), done))
+            });
+            if let Some((task, done)) = toggled {
+                left.remove(&task);
+                match done {
+                    Some(date) => self.complete(&task, date),
+                    None => self.uncomplete(&task),
+                }
+                *id = Some(task);
+            }
+        }
+        for (id, line) in ids.iter_mut().zip(&lines).filter(|(id, _)| id.is_none()) {
+            let new = TodoItem::new(line);
+            let mut alike: Option<(f64, &TaskId)> = None;
+            for (task, old) in &left {
+                let score = similarity(&TodoItem::new(old), &new);
+                if score >= EDIT_THRESHOLD && alike.is_none_or(|(best, _)| score > best) {
+                    alike = Some((score, task));
+                }
+            }
+            if let Some((_, task)) = alike {
+                let task = task.clone();
+                left.remove(&task);
+                self.edit(&task, line);
+                *id = Some(task);
+            }
+        }
+        for task in left.into_keys() {
+            self.delete(&task);
+        }
+        ids.into_iter()
+            .zip(&lines)
+            .map(|(id, line)| id.unwrap_or_else(|| self.add(line)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: u32) -> Date {
+        Date::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    fn lines(log: &OpLog) -> Vec<String> {
+        log.materialize()
+            .items()
+            .iter()
+            .map(TodoItem::raw)
+            .collect()
+    }
+
+    #[test]
+    fn test_replicas_converge() {
+        let mut laptop = OpLog::new("laptop");
+        let call = laptop.add("2025-03-01 Call Bob");
+        let report = laptop.add("Write report");
+
+        let mut desktop = OpLog::new("desktop");
+        desktop.merge(laptop.ops());
+
+        // Concurrent changes on both sides
+        laptop.edit(&call, "2025-03-01 Call Bob +work");
+        laptop.delete(&report);
+        desktop.complete(&call, date(2));
+        desktop.edit(&report, "(A) Write report");
+        desktop.add("Buy milk");
+
+        let to_desktop = laptop.missing_from(&desktop);
+        let to_laptop = desktop.missing_from(&laptop);
+        desktop.merge(to_desktop);
+        laptop.merge(to_laptop);
+
+        assert_eq!(lines(&laptop), lines(&desktop));
+        // The edit and the completion carry the same counter, "laptop" wins the tie
+        assert_eq!(
+            lines(&laptop),
+            vec!["2025-03-01 Call Bob +work", "Buy milk"]
+        );
+
+        desktop.complete(&call, date(2));
+        laptop.merge(desktop.missing_from(&laptop));
+        assert_eq!(lines(&laptop), lines(&desktop));
+        assert_eq!(
+            lines(&laptop),
+            vec!["x 2025-03-02 2025-03-01 Call Bob +work", "Buy milk"]
+        );
+    }
+
+    #[test]
+    fn test_later_edit_overrides_completion() {
+        let mut log = OpLog::new("a");
+        let task = log.add("Call Bob");
+        log.complete(&task, date(2));
+        assert_eq!(lines(&log), vec!["x Call Bob"]);
+
+        log.edit(&task, "Call Bob again");
+        assert_eq!(lines(&log), vec!["Call Bob again"]);
+    }
+
+    #[test]
+    fn test_record_files() {
+        // Both had the milk before they first synced
+        let mut laptop = OpLog::new("laptop");
+        laptop.record("Buy milk\n2025-03-01 Call Bob\n");
+        let mut desktop = OpLog::new("desktop");
+        desktop.record("Write report\nBuy milk\n");
+        laptop.merge(desktop.ops_since(&laptop.vector()));
+        desktop.merge(laptop.ops_since(&desktop.vector()));
+        assert_eq!(lines(&laptop), lines(&desktop));
+
+        let order = laptop.record("x 2025-03-02 2025-03-01 Call Bob\nBuy milk\nWrite report\n");
+        let done = order[0].clone();
+        let order = desktop.record("Write the report +work\nBuy milk\n2025-03-01 Call Bob\n");
+        laptop.merge(desktop.ops_since(&laptop.vector()));
+        desktop.merge(laptop.ops_since(&desktop.vector()));
+        assert_eq!(lines(&laptop), lines(&desktop));
+        assert_eq!(
+            desktop.arrange(&order).to_content(),
+            "Write the report +work\nBuy milk\nx 2025-03-02 2025-03-01 Call Bob"
+        );
+        assert!(laptop.ops().any(|op| op.kind
+            == OpKind::Complete {
+                task: done.clone(),
+                date: date(2)
+            }));
+        assert!(laptop.ops_since(&laptop.vector()).is_empty());
+    }
+}
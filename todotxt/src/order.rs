@@ -0,0 +1,187 @@
+//! A manual order of todos, e.g. within a project, kept in an `ord:` tag
+//! so it doesn't depend on where the lines are in todo.txt. The values are
+//! fractional indexes: base-36 digits read as a fraction and compared as
+//! text, so there's always room for a todo between two others and moving
+//! one only rewrites its own line.
+
+use crate::{TodoItem, TodoList};
+
+/// Tag key of the position in the manual order.
+pub const ORDER_KEY: &str = "ord";
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u8 = DIGITS.len() as u8;
+
+/// The digits of `key`, `None` unless it's a valid position: base-36
+/// digits, not ending in `0`, so that no two keys mean the same fraction.
+fn digits(key: &str) -> Option<Vec<u8>> {
+    if key.is_empty() || key.ends_with('0') {
+        return None;
+    }
+    key.bytes()
+        .map(|b| DIGITS.iter().position(|&d| d == b).map(|d| d as u8))
+        .collect()
+}
+
+fn key(digits: &[u8]) -> String {
+    digits.iter().map(|&d| DIGITS[d as usize] as char).collect()
+}
+
+/// Digits strictly between `a` and `b`, the end of the range if `None`.
+/// Expects `a < b` and neither to end in 0.
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+    if let Some(b) = b {
+        // Keep the prefix they share
+        let shared = (0..b.len())
+            .take_while(|&i| a.get(i).copied().unwrap_or(0) == b[i])
+            .count();
+        if shared > 0 {
+            let rest = midpoint(a.get(shared..).unwrap_or(&[]), Some(&b[shared..]));
+            return [&b[..shared], &rest].concat();
+        }
+    }
+    let low = a.first().copied().unwrap_or(0);
+    let high = b.map_or(BASE, |b| b[0]);
+    if high - low > 1 {
+        return vec![(low + high) / 2];
+    }
+    match b {
+        // b's first digit alone is past a and short of b
+        Some(b) if b.len() > 1 => vec![b[0]],
+        _ => [&[low][..], &midpoint(a.get(1..).unwrap_or(&[]), None)].concat(),
+    }
+}
+
+/// A position between `before` and `after`, either of which may be missing
+/// for the start or the end. Expects `before < after`.
+pub fn key_between(before: Option<&str>, after: Option<&str>) -> String {
+    let before = before.and_then(digits).unwrap_or_default();
+    let after = after.and_then(digits);
+    key(&midpoint(&before, after.as_deref()))
+}
+
+/// `n` positions spread evenly, in order, all of the same length.
+pub fn spread(n: usize) -> Vec<String> {
+    let mut width = 1;
+    while (BASE as usize).pow(width) <= n {
+        width += 1;
+    }
+    let range = (BASE as usize).pow(width);
+    (1..=n)
+        .map(|i| {
+            let mut value = i * range / (n + 1);
+            let mut digits = vec![0; width as usize];
+            for digit in digits.iter_mut().rev() {
+                *digit = (value % BASE as usize) as u8;
+                value /= BASE as usize;
+            }
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+            key(&digits)
+        })
+        .collect()
+}
+
+/// The todo's position, if its `ord:` tag is a valid one.
+fn position(item: &TodoItem) -> Option<&str> {
+    item.order().filter(|key| digits(key).is_some())
+}
+
+/// Gives the todos `ids` new positions spread evenly in that order, e.g.
+/// when some have none or they're out of order.
+pub fn normalize_order(list: &mut TodoList, ids: &[usize]) {
+    for (&id, key) in ids.iter().zip(spread(ids.len())) {
+        if let Some(item) = list.get_mut(id) {
+            item.set_order(Some(&key));
+        }
+    }
+}
+
+/// Puts the todo `id` where it is in `ids`, the new order of a view. Only
+/// its own position changes if the others are in order already; otherwise
+/// the whole view is renumbered with [`normalize_order`].
+pub fn place(list: &mut TodoList, ids: &[usize], id: usize) {
+    let Some(index) = ids.iter().position(|&other| other == id) else {
+        return;
+    };
+    let keys: Option<Vec<&str>> = ids
+        .iter()
+        .filter(|&&other| other != id)
+        .map(|&other| list.get(other).and_then(position))
+        .collect();
+    let in_order = keys
+        .as_ref()
+        .is_some_and(|keys| keys.windows(2).all(|pair| pair[0] < pair[1]));
+    let Some(keys) = keys.filter(|_| in_order) else {
+        normalize_order(list, ids);
+        return;
+    };
+    let before = index.checked_sub(1).map(|i| keys[i]);
+    let after = keys.get(index).copied();
+    let key = key_between(before, after);
+    if let Some(item) = list.get_mut(id) {
+        item.set_order(Some(&key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_between() {
+        assert_eq!(key_between(None, None), "i");
+        assert_eq!(key_between(Some("i"), None), "r");
+        assert_eq!(key_between(None, Some("1")), "0i");
+        assert_eq!(key_between(Some("a"), Some("b")), "ai");
+        assert_eq!(key_between(Some("az"), Some("b")), "azi");
+        assert_eq!(key_between(Some("a"), Some("a1")), "a0i");
+        for (before, after) in [("i", "j"), ("0i", "1"), ("z", "zz"), ("a0i", "a1")] {
+            let key = key_between(Some(before), Some(after));
+            assert!(
+                before < key.as_str() && key.as_str() < after,
+                "{before} {key} {after}"
+            );
+        }
+        assert_eq!(spread(3), vec!["9", "i", "r"]);
+        assert_eq!(spread(40).len(), 40);
+        assert!(spread(40).windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_place() {
+        let mut list = TodoList::from_content(
+            "Draft outline +book ord:9\n\
+             Pick a title +book\n\
+             Write chapter one +book ord:r",
+        );
+        // Without a position of its own, the view gets renumbered
+        place(&mut list, &[1, 3, 2], 3);
+        let order = |list: &TodoList| {
+            list.items()
+                .iter()
+                .map(|item| item.order().map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            order(&list),
+            vec![
+                Some("9".to_string()),
+                Some("r".to_string()),
+                Some("i".to_string())
+            ]
+        );
+        // Then only the moved one changes, and the file keeps its order
+        place(&mut list, &[2, 1, 3], 2);
+        assert_eq!(
+            order(&list),
+            vec![
+                Some("9".to_string()),
+                Some("4".to_string()),
+                Some("i".to_string())
+            ]
+        );
+        assert_eq!(list.items()[1].raw(), "Pick a title +book ord:4");
+    }
+}
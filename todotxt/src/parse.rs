@@ -0,0 +1,257 @@
+//! Lexical view of a single todo.txt line.
+//!
+//! [`tokenize`] splits a line into [`Token`]s with byte spans into the input,
+//! following the same rules as the underlying parser: an optional `x ` mark,
+//! an optional `(A) ` priority and up to two dates make up the header, the
+//! rest is the description with `@context`, `+project` and `key:value` tags.
+//! Whitespace is not tokenized; gaps between spans are plain whitespace.
+//!
+//! Context and project names follow the todo.txt spec rather than the
+//! narrower parser: they run up to the next whitespace, minus trailing
+//! punctuation, so hierarchy separators like `+home.garden` stay intact.
+
+use crate::Date;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn range(self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    CompletionMark,
+    Priority(char),
+    Date(Date),
+    Context(&'a str),
+    Project(&'a str),
+    Tag { key: &'a str, value: &'a str },
+    Word(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spanned<'a> {
+    pub token: Token<'a>,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Completion,
+    Priority,
+    FirstDate,
+    SecondDate,
+    Description,
+}
+
+fn parse_priority(word: &str) -> Option<char> {
+    let b = word.as_bytes();
+    if b.len() == 3 && b[0] == b'(' && b[2] == b')' && b[1].is_ascii_alphabetic() {
+        Some(b[1].to_ascii_uppercase() as char)
+    } else {
+        None
+    }
+}
+
+fn parse_date(word: &str) -> Option<Date> {
+    if word.len() != 10 {
+        return None;
+    }
+    Date::parse_from_str(word, "%Y-%m-%d").ok()
+}
+
+/// Punctuation that ends a sentence or clause rather than belonging to a tag name.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\''];
+
+fn sigil_name(word: &str, sigil: char) -> Option<&str> {
+    let name = word
+        .strip_prefix(sigil)?
+        .trim_end_matches(TRAILING_PUNCTUATION);
+    (!name.is_empty()).then_some(name)
+}
+
+fn description_token(word: &str) -> Token<'_> {
+    if let Some(name) = sigil_name(word, '@') {
+        return Token::Context(name);
+    }
+    if let Some(name) = sigil_name(word, '+') {
+        return Token::Project(name);
+    }
+
+    match word.split_once(':') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() && !value.starts_with('/') => {
+            Token::Tag { key, value }
+        }
+        _ => Token::Word(word),
+    }
+}
+
+/// `text` as a tag on its own, e.g. `+work`, `@home` or `due:2025-03-05`,
+/// or `None` if it's anything else.
+pub fn tag(text: &str) -> Option<Token<'_>> {
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    match description_token(text) {
+        Token::Word(_) => None,
+        token => Some(token),
+    }
+}
+
+/// Splits `line` into tokens. Header elements are only recognised when
+/// followed by more text, exactly like the parser that builds tasks.
+pub fn tokenize(line: &str) -> Vec<Spanned<'_>> {
+    let mut tokens = Vec::new();
+    let mut stage = Stage::Completion;
+    let mut pos = 0;
+
+    while pos < line.len() {
+        let rest = &line[pos..];
+        let trimmed = rest.trim_start();
+        let start = pos + (rest.len() - trimmed.len());
+        if trimmed.is_empty() {
+            break;
+        }
+        let len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let word = &trimmed[..len];
+        let end = start + len;
+        // Header parts need a single space after them and may not be preceded by whitespace
+        let header_ok = start == pos && line[end..].starts_with(' ');
+
+        let token = loop {
+            match stage {
+                Stage::Completion => {
+                    stage = Stage::Priority;
+                    if header_ok && word == "x" {
+                        break Token::CompletionMark;
+                    }
+                }
+                Stage::Priority => {
+                    stage = Stage::FirstDate;
+                    if let Some(p) = parse_priority(word).filter(|_| header_ok) {
+                        break Token::Priority(p);
+                    }
+                }
+                Stage::FirstDate => {
+                    stage = Stage::SecondDate;
+                    if let Some(date) = parse_date(word).filter(|_| header_ok) {
+                        break Token::Date(date);
+                    }
+                    stage = Stage::Description;
+                }
+                Stage::SecondDate => {
+                    stage = Stage::Description;
+                    if let Some(date) = parse_date(word).filter(|_| header_ok) {
+                        break Token::Date(date);
+                    }
+                }
+                Stage::Description => break description_token(word),
+            }
+        };
+
+        tokens.push(Spanned {
+            token,
+            span: Span { start, end },
+        });
+        pos = if stage < Stage::Description {
+            end + 1
+        } else {
+            end
+        };
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(line: &str) -> Vec<Token<'_>> {
+        tokenize(line).into_iter().map(|t| t.token).collect()
+    }
+
+    #[test]
+    fn test_header_and_description() {
+        let line = "x (A) 2025-03-02 2025-03-01 Call mom @phone +family due:2025-03-05";
+        assert_eq!(
+            kinds(line),
+            vec![
+                Token::CompletionMark,
+                Token::Priority('A'),
+                Token::Date(Date::from_ymd_opt(2025, 3, 2).unwrap()),
+                Token::Date(Date::from_ymd_opt(2025, 3, 1).unwrap()),
+                Token::Word("Call"),
+                Token::Word("mom"),
+                Token::Context("phone"),
+                Token::Project("family"),
+                Token::Tag {
+                    key: "due",
+                    value: "2025-03-05"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans_point_into_line() {
+        let line = "(B)  Water   plants @home";
+        for spanned in tokenize(line) {
+            let text = &line[spanned.span.range()];
+            assert!(!text.trim().is_empty());
+            assert_eq!(text, text.trim());
+        }
+        let last = tokenize(line).pop().unwrap();
+        assert_eq!(&line[last.span.range()], "@home");
+    }
+
+    #[test]
+    fn test_header_only_at_start() {
+        assert_eq!(
+            kinds("Buy x (A) 2025-01-01"),
+            vec![
+                Token::Word("Buy"),
+                Token::Word("x"),
+                Token::Word("(A)"),
+                Token::Word("2025-01-01"),
+            ]
+        );
+        assert_eq!(kinds("x"), vec![Token::Word("x")]);
+    }
+
+    #[test]
+    fn test_tag_names_keep_separators_but_not_trailing_punctuation() {
+        assert_eq!(
+            kinds("Rake (+home.garden/beds). Ask @mom!"),
+            vec![
+                Token::Word("Rake"),
+                Token::Word("(+home.garden/beds)."),
+                Token::Word("Ask"),
+                Token::Context("mom"),
+            ]
+        );
+        assert_eq!(
+            kinds("Rake +home.garden/beds."),
+            vec![Token::Word("Rake"), Token::Project("home.garden/beds")]
+        );
+    }
+
+    #[test]
+    fn test_urls_and_bare_sigils_are_words() {
+        assert_eq!(
+            kinds("see https://example.com @ +"),
+            vec![
+                Token::Word("see"),
+                Token::Word("https://example.com"),
+                Token::Word("@"),
+                Token::Word("+"),
+            ]
+        );
+    }
+}
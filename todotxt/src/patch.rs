@@ -0,0 +1,121 @@
+//! Writing a list back to its file. Todos only added since the file was
+//! read are appended, which leaves the rest of the file alone, so big files
+//! see far fewer writes and sync tools watching them send less. Any other
+//! change replaces the file whole through a temporary file, so a crash
+//! leaves either the old content or the new one, never a mix.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// The file next to `path` that [`replace`] writes first.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.tmp"))
+}
+
+/// Replaces the file at `path` with `content`, by writing a temporary file
+/// next to it and renaming that over it. Returns the file's new length.
+pub fn replace(path: &Path, content: &str) -> Result<u64, std::io::Error> {
+    let temp = temp_path(path);
+    let mut file = fs::File::create(&temp)?;
+    let written = file
+        .write_all(content.as_bytes())
+        .and_then(|()| file.sync_all())
+        .and_then(|()| match fs::metadata(path) {
+            Ok(meta) => fs::set_permissions(&temp, meta.permissions()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written.map(|()| content.len() as u64)
+}
+
+/// Appends the todo.txt `lines` to the file at `path`, which was `len` bytes
+/// long when it was read. If it's not anymore, it changed since, and is
+/// replaced with `content` instead. Returns the file's new length.
+pub fn append(
+    path: &Path,
+    len: u64,
+    lines: &str,
+    content: impl FnOnce() -> String,
+) -> Result<u64, std::io::Error> {
+    let mut file = match OpenOptions::new().read(true).append(true).open(path) {
+        Ok(file) if file.metadata()?.len() == len => file,
+        Ok(_) => return replace(path, &content()),
+        Err(e) if e.kind() == ErrorKind::NotFound => return replace(path, &content()),
+        Err(e) => return Err(e),
+    };
+    let mut last = [b'\n'];
+    if len > 0 {
+        file.seek(SeekFrom::Start(len - 1))?;
+        file.read_exact(&mut last)?;
+    }
+    let mut len = len;
+    if last[0] != b'\n' {
+        file.write_all(b"\n")?;
+        len += 1;
+    }
+    file.write_all(lines.as_bytes())?;
+    file.sync_data()?;
+    Ok(len + lines.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TodoList;
+
+    #[test]
+    fn test_save() {
+        let dir = std::env::temp_dir().join(format!("todotxt-patch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todo.txt");
+        std::fs::write(&path, "Call Bob\n\n  Pay rent  \n").unwrap();
+
+        // Only added todos: the rest of the file stays as it is, also when
+        // saving again, and a todo looked at but left alone changes nothing
+        let mut list = TodoList::from_file(&path).unwrap();
+        list.add("Buy milk");
+        list.save().unwrap();
+        list.get_mut(1).unwrap();
+        list.add("Pay bills");
+        list.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Call Bob\n\n  Pay rent  \nBuy milk\nPay bills"
+        );
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        list.save().unwrap();
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        // A changed todo rewrites the file
+        let mut list = TodoList::from_file(&path).unwrap();
+        list.complete(1);
+        list.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "x Call Bob\nPay rent\nBuy milk\nPay bills"
+        );
+
+        // So does a file changed since it was read
+        let mut list = TodoList::from_file(&path).unwrap();
+        std::fs::write(&path, "Water plants").unwrap();
+        list.add("Book flights");
+        list.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "x Call Bob\nPay rent\nBuy milk\nPay bills\nBook flights"
+        );
+        assert!(!super::temp_path(&path).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+//! Picking a task at random for "what should I do now?", weighted so that
+//! important, overdue and quick tasks come up more often.
+
+use crate::{Date, TodoItem};
+
+/// How much more likely `item` is to be picked than a plain task. Finished
+/// tasks are never picked.
+pub fn weight(item: &TodoItem, today: Date) -> f64 {
+    if item.finished() {
+        return 0.0;
+    }
+    let priority = match item.priority() {
+        0 => 4.0,
+        1 => 3.0,
+        2 => 2.0,
+        _ => 1.0,
+    };
+    // Up to a week overdue counts, older ones are no more urgent
+    let urgency = match item.due_date() {
+        Some(due) if due <= today => 2.0 + (today - due).num_days().min(7) as f64 / 2.0,
+        _ => 1.0,
+    };
+    let effort = match item.estimate() {
+        Some(minutes) if minutes <= 30 => 1.5,
+        Some(minutes) if minutes > 120 => 0.75,
+        _ => 1.0,
+    };
+    priority * urgency * effort
+}
+
+/// The task among `items` that `roll`, a random number from 0 up to 1,
+/// lands on when each has a share of [`weight`].
+pub fn pick<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    today: Date,
+    roll: f64,
+) -> Option<&'a TodoItem> {
+    let weighted: Vec<(&TodoItem, f64)> = items
+        .into_iter()
+        .map(|item| (item, weight(item, today)))
+        .filter(|(_, weight)| *weight > 0.0)
+        .collect();
+    let total: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+    let mut left = roll.clamp(0.0, 1.0) * total;
+    for &(item, weight) in &weighted {
+        if left < weight {
+            return Some(item);
+        }
+        left -= weight;
+    }
+    weighted.last().map(|(item, _)| *item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_weight() {
+        let today = Date::from_ymd_opt(2025, 3, 10).unwrap();
+        let weight = |text: &str| weight(&TodoItem::new(text), today);
+
+        assert_eq!(weight("Water plants"), 1.0);
+        assert_eq!(weight("x Water plants"), 0.0);
+        assert_eq!(weight("(A) Pay rent"), 4.0);
+        assert_eq!(weight("Pay rent due:2025-03-10"), 2.0);
+        assert_eq!(weight("Pay rent due:2025-03-06"), 4.0);
+        assert_eq!(weight("Pay rent due:2025-01-01"), 5.5);
+        assert_eq!(weight("Pay rent due:2025-03-20"), 1.0);
+        assert_eq!(weight("Reply to Bob est:10m"), 1.5);
+        assert_eq!(weight("Write report est:4h"), 0.75);
+    }
+
+    #[test]
+    fn test_pick() {
+        let today = Date::from_ymd_opt(2025, 3, 10).unwrap();
+        let mut list = TodoList::new();
+        list.add("x Done already");
+        list.add("Water plants");
+        list.add("(A) Pay rent");
+
+        let subject = |roll| pick(list.items(), today, roll).map(|item| item.subject());
+        // Water plants has 1 of 5 shares, Pay rent the other 4
+        assert_eq!(subject(0.0), Some("Water plants"));
+        assert_eq!(subject(0.19), Some("Water plants"));
+        assert_eq!(subject(0.2), Some("Pay rent"));
+        assert_eq!(subject(1.0), Some("Pay rent"));
+        assert!(pick(&list.items()[..1], today, 0.5).is_none());
+    }
+}
@@ -0,0 +1,86 @@
+//! Planning todos onto the days of a week, by setting their due date or
+//! their threshold date (`t:`, the day a todo starts to matter).
+
+use chrono::{Datelike, Days, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::{Date, TodoItem, TodoList};
+
+/// Which date planning a todo onto a day sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanField {
+    #[default]
+    Due,
+    Threshold,
+}
+
+impl PlanField {
+    /// The date of `item` this field is about.
+    pub fn get(self, item: &TodoItem) -> Option<Date> {
+        match self {
+            PlanField::Due => item.due_date(),
+            PlanField::Threshold => item.threshold_date(),
+        }
+    }
+
+    fn set(self, item: &mut TodoItem, date: Option<Date>) {
+        match self {
+            PlanField::Due => item.set_due_date(date),
+            PlanField::Threshold => item.set_threshold_date(date),
+        }
+    }
+}
+
+/// The days from Monday to Sunday of the week `weeks` after the one `day`
+/// is in, before it for negative `weeks`.
+pub fn week(day: Date, weeks: i64) -> [Date; 7] {
+    let monday = day - Days::new(u64::from(day.weekday().num_days_from_monday()));
+    let monday = monday
+        .checked_add_signed(TimeDelta::weeks(weeks))
+        .unwrap_or(monday);
+    std::array::from_fn(|i| monday + Days::new(i as u64))
+}
+
+/// Plans the todos `ids` onto `date` by setting their `field`, or clears it
+/// for `None`, which puts them back in the backlog. Ids not in `list` are
+/// skipped. Returns how many todos changed.
+pub fn plan(list: &mut TodoList, ids: &[usize], field: PlanField, date: Option<Date>) -> usize {
+    let mut changed = 0;
+    for &id in ids {
+        if list.get(id).is_some_and(|item| field.get(item) != date) {
+            if let Some(item) = list.get_mut(id) {
+                field.set(item, date);
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_week_starts_on_monday() {
+        let day = Date::from_ymd_opt(2025, 3, 6).unwrap();
+        let days = week(day, 0);
+        assert_eq!(days[0], Date::from_ymd_opt(2025, 3, 3).unwrap());
+        assert_eq!(days[6], Date::from_ymd_opt(2025, 3, 9).unwrap());
+        assert_eq!(week(day, -1)[0], Date::from_ymd_opt(2025, 2, 24).unwrap());
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut list =
+            TodoList::from_content("Call Bob\nPay rent due:2025-03-05\nWater plants t:2025-03-01");
+        let day = Date::from_ymd_opt(2025, 3, 5);
+        assert_eq!(plan(&mut list, &[1, 2, 42], PlanField::Due, day), 1);
+        assert_eq!(list.get(1).unwrap().due_date(), day);
+        assert_eq!(plan(&mut list, &[3], PlanField::Threshold, day), 1);
+        assert_eq!(list.get(3).unwrap().threshold_date(), day);
+        assert_eq!(plan(&mut list, &[2], PlanField::Due, None), 1);
+        assert_eq!(list.get(2).unwrap().due_date(), None);
+    }
+}
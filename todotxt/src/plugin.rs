@@ -0,0 +1,155 @@
+//! Plugin manifests and the capabilities they ask for.
+//!
+//! A plugin is a directory with a `plugin.json` manifest next to a WASM
+//! module. The manifest declares what the plugin adds (commands, task
+//! transformations, sidebar views) and which capabilities it needs; the user
+//! grants capabilities per plugin and a plugin only runs once everything it
+//! asks for is granted.
+//!
+//! The module exports its `memory` and `alloc(len: i32) -> i32`, which
+//! returns where the app may write `len` bytes. Every function the manifest
+//! names takes `(ptr: i32, len: i32)`, the UTF-8 text the app wrote there,
+//! and returns an `i64` holding the pointer to its UTF-8 answer in the high
+//! 32 bits and its length in the low ones:
+//!
+//! - a transform gets a new todo's line and answers it rewritten
+//! - a command gets all of todo.txt, or nothing without
+//!   [`Capability::ReadTasks`], and answers the new todo.txt with
+//!   [`Capability::WriteTasks`], or else a message to show; an empty answer
+//!   changes nothing
+//! - a view gets one todo's line and answers `1` to show it or `0` not to
+
+use serde::{Deserialize, Serialize};
+
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Read the todo list
+    ReadTasks,
+    /// Change the todo list, including through transformations
+    WriteTasks,
+    /// Add commands to the command list
+    Commands,
+    /// Add views to the sidebar
+    SidebarViews,
+}
+
+impl Capability {
+    pub fn description(self) -> &'static str {
+        match self {
+            Capability::ReadTasks => "Read your todos",
+            Capability::WriteTasks => "Change your todos",
+            Capability::Commands => "Add commands",
+            Capability::SidebarViews => "Add sidebar views",
+        }
+    }
+}
+
+/// A command the plugin exports, shown to the user as `title`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCommand {
+    /// Name of the exported function
+    pub export: String,
+    pub title: String,
+}
+
+/// A sidebar entry listing the todos an exported filter function selects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidebarView {
+    pub export: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Path of the WASM module, relative to the manifest
+    pub module: String,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    /// Exported functions that take a todo.txt line and return it rewritten,
+    /// run on every added task
+    #[serde(default)]
+    pub transforms: Vec<String>,
+    #[serde(default)]
+    pub views: Vec<SidebarView>,
+}
+
+impl Manifest {
+    /// Parses a manifest and checks that it asks for the capabilities the
+    /// things it adds need.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let manifest: Manifest = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if manifest.name.is_empty() || manifest.name.contains(char::is_whitespace) {
+            return Err("Plugin names must be non-empty and contain no spaces".to_string());
+        }
+        let needs = [
+            (!manifest.commands.is_empty(), Capability::Commands),
+            (!manifest.transforms.is_empty(), Capability::WriteTasks),
+            (!manifest.views.is_empty(), Capability::ReadTasks),
+            (!manifest.views.is_empty(), Capability::SidebarViews),
+        ];
+        for (needed, capability) in needs {
+            if needed && !manifest.capabilities.contains(&capability) {
+                return Err(format!(
+                    "Plugin {} must ask for the {capability:?} capability",
+                    manifest.name
+                ));
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Capabilities the plugin asks for that aren't in `granted`.
+    pub fn missing(&self, granted: &[Capability]) -> Vec<Capability> {
+        let mut missing: Vec<Capability> = self
+            .capabilities
+            .iter()
+            .copied()
+            .filter(|c| !granted.contains(c))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let manifest = Manifest::parse(
+            r#"{
+                "name": "release-notes",
+                "version": "0.1.0",
+                "module": "release_notes.wasm",
+                "capabilities": ["read-tasks", "commands"],
+                "commands": [{"export": "collect", "title": "Collect release notes"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.commands.len(), 1);
+        assert_eq!(
+            manifest.missing(&[Capability::Commands]),
+            vec![Capability::ReadTasks]
+        );
+        assert!(manifest
+            .missing(&[Capability::ReadTasks, Capability::Commands])
+            .is_empty());
+
+        let err = Manifest::parse(
+            r#"{"name": "x", "version": "1", "module": "x.wasm", "transforms": ["tidy"]}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("WriteTasks"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Lorem ipsum in place of what tasks say, for screenshots and screen sharing.
+//! Only the words go: completion marks, priorities, dates, `+project`,
+//! `@context` and `key:value` tags stay, so the list keeps its shape.
+
+use crate::parse::{tokenize, Token};
+use crate::TodoItem;
+
+const LOREM: [&str; 24] = [
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+];
+
+/// The same word always becomes the same lorem ipsum word, so repeated
+/// phrases still read alike.
+fn lorem(word: &str) -> &'static str {
+    // FNV-1a, stable across runs unlike the std hasher
+    let hash = word.bytes().fold(0xcbf29ce484222325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    });
+    LOREM[(hash % LOREM.len() as u64) as usize]
+}
+
+/// `line` with every plain word replaced by a lorem ipsum word.
+pub fn line(line: &str) -> String {
+    let mut redacted = String::with_capacity(line.len());
+    let mut pos = 0;
+    for spanned in tokenize(line) {
+        redacted.push_str(&line[pos..spanned.span.start]);
+        match spanned.token {
+            Token::Word(word) => redacted.push_str(lorem(word)),
+            _ => redacted.push_str(&line[spanned.span.range()]),
+        }
+        pos = spanned.span.end;
+    }
+    redacted.push_str(&line[pos..]);
+    redacted
+}
+
+/// `item` with its words replaced, see [`line`].
+pub fn item(item: &TodoItem) -> TodoItem {
+    let mut redacted = TodoItem::new(&line(&item.raw()));
+    redacted.id = item.id;
+    redacted.line = item.line;
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_line() {
+        let redacted = line("(A) 2025-03-10 Call mom about dinner +family @phone due:2025-03-12");
+        assert!(redacted.starts_with("(A) 2025-03-10 "));
+        assert!(redacted.ends_with(" +family @phone due:2025-03-12"));
+        assert!(!redacted.contains("mom"));
+        assert_eq!(redacted.split(' ').count(), 9);
+        assert_eq!(line("Call mom"), line("Call mom"));
+        assert_eq!(line(""), "");
+    }
+}
@@ -0,0 +1,102 @@
+//! Relative dates in date tags, e.g. `due:tomorrow` or `t:+3d`, turned into
+//! ISO dates when a task is written so the file stays valid todo.txt.
+//!
+//! Understood are ISO dates, `today`, `tomorrow`, `yesterday`, weekday names
+//! (`mon` or `monday`, meaning the next one after today) and offsets of
+//! days, weeks or months such as `+3d`, `+2w` or `+1m`.
+
+use chrono::{Datelike, Days, Months, Weekday};
+
+use crate::Date;
+
+/// Tags whose value is a date.
+const DATE_TAGS: [&str; 2] = ["due", "t"];
+
+/// The date `expr` stands for, counted from `today`.
+pub fn resolve(expr: &str, today: Date) -> Option<Date> {
+    if let Ok(date) = Date::parse_from_str(expr, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let expr = expr.to_lowercase();
+    match expr.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.checked_add_days(Days::new(1)),
+        "yesterday" => return today.checked_sub_days(Days::new(1)),
+        _ => {}
+    }
+    if let Some(offset) = expr.strip_prefix('+') {
+        let unit = offset.chars().last()?;
+        let count: u32 = offset[..offset.len() - unit.len_utf8()].parse().ok()?;
+        return match unit {
+            'd' => today.checked_add_days(Days::new(count.into())),
+            'w' => today.checked_add_days(Days::new(u64::from(count) * 7)),
+            'm' => today.checked_add_months(Months::new(count)),
+            _ => None,
+        };
+    }
+    let weekday = expr.parse::<Weekday>().ok()?;
+    Some(next_weekday(today, weekday))
+}
+
+/// `line` with the relative dates in its `due:` and `t:` tags replaced by
+/// ISO dates. Values that aren't understood are kept as they are.
+pub fn normalize(line: &str, today: Date) -> String {
+    if !line.contains(':') {
+        return line.to_string();
+    }
+    line.split(' ')
+        .map(|word| match word.split_once(':') {
+            Some((key, value)) if DATE_TAGS.contains(&key) => match resolve(value, today) {
+                Some(date) => format!("{key}:{date}"),
+                None => word.to_string(),
+            },
+            _ => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The first `weekday` after `today`.
+pub(crate) fn next_weekday(today: Date, weekday: Weekday) -> Date {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Days::new(if ahead == 0 { 7 } else { ahead.into() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        // A Saturday
+        let today = Date::from_ymd_opt(2025, 3, 1).unwrap();
+        let date = |y, m, d| Date::from_ymd_opt(y, m, d);
+
+        assert_eq!(resolve("2025-04-01", today), date(2025, 4, 1));
+        assert_eq!(resolve("today", today), date(2025, 3, 1));
+        assert_eq!(resolve("Tomorrow", today), date(2025, 3, 2));
+        assert_eq!(resolve("yesterday", today), date(2025, 2, 28));
+        assert_eq!(resolve("mon", today), date(2025, 3, 3));
+        assert_eq!(resolve("saturday", today), date(2025, 3, 8));
+        assert_eq!(resolve("+3d", today), date(2025, 3, 4));
+        assert_eq!(resolve("+2w", today), date(2025, 3, 15));
+        assert_eq!(resolve("+1m", today), date(2025, 4, 1));
+        assert_eq!(resolve("+d", today), None);
+        assert_eq!(resolve("+3y", today), None);
+        assert_eq!(resolve("soon", today), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let today = Date::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(
+            normalize("Pay rent due:tomorrow t:+1d @home", today),
+            "Pay rent due:2025-03-02 t:2025-03-02 @home"
+        );
+        assert_eq!(
+            normalize("Read due:someday url:https://example.com", today),
+            "Read due:someday url:https://example.com"
+        );
+        assert_eq!(normalize("Call Bob", today), "Call Bob");
+    }
+}
@@ -0,0 +1,278 @@
+//! Reminders for due tasks, fired at a set time of day on the due date.
+//!
+//! The scheduler asks for the reminders that came due since it last looked,
+//! so reminders missed while the machine slept or the app was closed are
+//! still delivered, merged into one notification if several piled up.
+
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::filter::has_tag;
+use crate::{Date, TodoItem};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub subject: String,
+    pub due: Date,
+    /// The time of an `at:` tag, see [`TodoItem::due_time`]
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+}
+
+/// What to show the user, one per delivery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    /// Clicking it opens the review
+    #[serde(default)]
+    pub review: bool,
+}
+
+/// A reminder independent of tasks, e.g. a weekly review on Fridays at 16:00.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandingReminder {
+    pub title: String,
+    /// English weekday name, or empty for every day
+    pub weekday: String,
+    /// `HH:MM`
+    pub time: String,
+}
+
+impl StandingReminder {
+    /// Whether the reminder came due after `since` and no later than `now`.
+    /// Only the last week is looked at, once a week is enough to remind.
+    pub fn due_between(&self, since: NaiveDateTime, now: NaiveDateTime) -> bool {
+        let Some(time) = parse_time(&self.time) else {
+            return false;
+        };
+        let weekday = match self.weekday.trim() {
+            "" => None,
+            name => match name.parse::<Weekday>() {
+                Ok(weekday) => Some(weekday),
+                Err(_) => return false,
+            },
+        };
+        (0..7)
+            .filter_map(|back| now.date().checked_sub_days(Days::new(back)))
+            .filter(|day| weekday.is_none_or(|weekday| day.weekday() == weekday))
+            .map(|day| day.and_time(time))
+            .any(|fires| since < fires && fires <= now)
+    }
+
+    pub fn notification(&self) -> Notification {
+        Notification {
+            title: self.title.clone(),
+            body: "Time to review your list".to_string(),
+            review: true,
+        }
+    }
+}
+
+/// `HH:MM`, e.g. `09:00`.
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+/// Whether `time` falls into the quiet hours from `start` to `end`, which
+/// may span midnight, e.g. 22:00 to 08:00.
+pub fn in_quiet_hours(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+/// Which tasks get reminders at all.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rules {
+    /// Projects (and the projects nested below them) never reminded of
+    pub muted_projects: Vec<String>,
+    /// Only tasks with at least this priority, e.g. `B` for A and B
+    pub min_priority: Option<char>,
+}
+
+impl Rules {
+    pub fn allows(&self, item: &TodoItem, separator: &str) -> bool {
+        let projects = item.unique_projects();
+        let muted = self
+            .muted_projects
+            .iter()
+            .any(|project| has_tag(&projects, project, separator));
+        let important = self
+            .min_priority
+            .is_none_or(|min| item.priority() <= (min as u8).wrapping_sub(b'A'));
+        !muted && important
+    }
+}
+
+/// Reminders for the pending `items` whose due date at `time` lies after
+/// `since` and no later than `now`. Todos due at a time of their own fire
+/// then instead.
+pub fn due_between<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    time: NaiveTime,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Vec<Reminder> {
+    items
+        .into_iter()
+        .filter(|item| !item.finished())
+        .filter_map(|item| {
+            let due = item.due_date()?;
+            let own = item.due_time();
+            let fires = due.and_time(own.unwrap_or(time));
+            (since < fires && fires <= now).then(|| Reminder {
+                subject: item.subject().to_string(),
+                due,
+                time: own,
+            })
+        })
+        .collect()
+}
+
+/// One notification for all of `reminders`: the task itself if there is
+/// just one, otherwise a summary.
+pub fn notification(reminders: &[Reminder]) -> Option<Notification> {
+    match reminders {
+        [] => None,
+        [reminder] => Some(Notification {
+            title: match reminder.time {
+                Some(time) => format!("Due at {}", time.format("%H:%M")),
+                None => "Due today".to_string(),
+            },
+            body: reminder.subject.clone(),
+            review: false,
+        }),
+        _ => Some(Notification {
+            title: format!("{} tasks due", reminders.len()),
+            body: reminders
+                .iter()
+                .map(|reminder| reminder.subject.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            review: false,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_due_between() {
+        let mut list = TodoList::new();
+        list.add("Pay rent due:2025-03-01");
+        list.add("Call Bob due:2025-03-02");
+        list.add("x Done already due:2025-03-01");
+        list.add("Someday");
+        list.add("Dentist due:2025-03-02 at:14:30");
+        let nine = parse_time("09:00").unwrap();
+
+        let subjects = |since, now| -> Vec<String> {
+            due_between(list.items(), nine, at(since), at(now))
+                .into_iter()
+                .map(|reminder| reminder.subject)
+                .collect()
+        };
+        assert_eq!(
+            subjects("2025-03-01 08:59", "2025-03-01 09:00"),
+            vec!["Pay rent"]
+        );
+        assert!(subjects("2025-03-01 09:00", "2025-03-01 18:00").is_empty());
+        // Asleep for two days, both are delivered on waking up
+        assert_eq!(
+            subjects("2025-02-28 22:00", "2025-03-02 10:00"),
+            vec!["Pay rent", "Call Bob"]
+        );
+        // Timed todos fire at their own time, not the reminder time
+        assert_eq!(
+            subjects("2025-03-02 14:00", "2025-03-02 14:30"),
+            vec!["Dentist"]
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let ten_pm = parse_time("22:00").unwrap();
+        let eight_am = parse_time("08:00").unwrap();
+        assert!(in_quiet_hours(
+            parse_time("23:30").unwrap(),
+            ten_pm,
+            eight_am
+        ));
+        assert!(in_quiet_hours(
+            parse_time("07:59").unwrap(),
+            ten_pm,
+            eight_am
+        ));
+        assert!(!in_quiet_hours(eight_am, ten_pm, eight_am));
+        assert!(in_quiet_hours(
+            parse_time("13:00").unwrap(),
+            parse_time("12:00").unwrap(),
+            parse_time("14:00").unwrap()
+        ));
+
+        let rules = Rules {
+            muted_projects: vec!["chores".to_string()],
+            min_priority: Some('B'),
+        };
+        assert!(rules.allows(&TodoItem::new("(A) Pay rent +home"), "---"));
+        assert!(rules.allows(&TodoItem::new("(B) Call Bob"), "---"));
+        assert!(!rules.allows(&TodoItem::new("(C) Water plants"), "---"));
+        assert!(!rules.allows(&TodoItem::new("Someday"), "---"));
+        assert!(!rules.allows(&TodoItem::new("(A) Vacuum +chores---kitchen"), "---"));
+        assert!(Rules::default().allows(&TodoItem::new("Someday"), "---"));
+    }
+
+    #[test]
+    fn test_standing_reminder() {
+        let review = StandingReminder {
+            title: "Weekly review".to_string(),
+            weekday: "friday".to_string(),
+            time: "16:00".to_string(),
+        };
+        // 2025-03-07 is a Friday
+        assert!(review.due_between(at("2025-03-07 15:59"), at("2025-03-07 16:00")));
+        assert!(!review.due_between(at("2025-03-07 16:00"), at("2025-03-07 16:01")));
+        assert!(!review.due_between(at("2025-03-06 15:59"), at("2025-03-06 16:00")));
+        // Missed while asleep over the weekend
+        assert!(review.due_between(at("2025-03-07 12:00"), at("2025-03-10 08:00")));
+
+        let daily = StandingReminder {
+            weekday: String::new(),
+            ..review
+        };
+        assert!(daily.due_between(at("2025-03-06 15:59"), at("2025-03-06 16:00")));
+    }
+
+    #[test]
+    fn test_notification() {
+        let due = Date::from_ymd_opt(2025, 3, 1).unwrap();
+        let reminder = |subject: &str| Reminder {
+            subject: subject.to_string(),
+            due,
+            time: None,
+        };
+        assert_eq!(notification(&[]), None);
+        assert_eq!(
+            notification(&[reminder("Pay rent")]).unwrap().body,
+            "Pay rent"
+        );
+        let timed = Reminder {
+            time: parse_time("14:30"),
+            ..reminder("Dentist")
+        };
+        assert_eq!(notification(&[timed]).unwrap().title, "Due at 14:30");
+        let summary = notification(&[reminder("Pay rent"), reminder("Call Bob")]).unwrap();
+        assert_eq!(summary.title, "2 tasks due");
+        assert_eq!(summary.body, "Pay rent\nCall Bob");
+    }
+}
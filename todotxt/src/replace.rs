@@ -0,0 +1,120 @@
+//! Find-and-replace across many tasks at once, e.g. renaming a recurring
+//! phrase. Callers show the [`preview`] first and then [`apply`] it.
+
+use std::borrow::Cow;
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::{TodoItem, TodoList};
+
+/// What to look for, either literal text or a regex.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+    /// Whether `$1`, `$name` etc. in the replacement refer to capture groups
+    expand: bool,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str, is_regex: bool) -> Result<Self, String> {
+        if pattern.is_empty() {
+            return Err("Enter something to find".to_string());
+        }
+        let regex = if is_regex {
+            Regex::new(pattern).map_err(|e| e.to_string())?
+        } else {
+            Regex::new(&regex::escape(pattern)).map_err(|e| e.to_string())?
+        };
+        Ok(Self {
+            regex,
+            expand: is_regex,
+        })
+    }
+
+    pub fn replace<'a>(&self, line: &'a str, replacement: &str) -> Cow<'a, str> {
+        if self.expand {
+            self.regex.replace_all(line, replacement)
+        } else {
+            self.regex.replace_all(line, NoExpand(replacement))
+        }
+    }
+}
+
+/// One task line as it is and as it would be after replacing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replacement {
+    pub id: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// The lines among `items` that replacing `pattern` with `replacement` changes.
+pub fn preview<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    pattern: &Pattern,
+    replacement: &str,
+) -> Vec<Replacement> {
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let before = item.raw();
+            let after = pattern.replace(&before, replacement).trim().to_string();
+            (after != before).then_some(Replacement {
+                id: item.id,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Applies `replacements` from [`preview`], skipping tasks that changed since.
+/// Returns how many were applied.
+pub fn apply(list: &mut TodoList, replacements: &[Replacement]) -> usize {
+    let mut applied = 0;
+    for replacement in replacements {
+        if let Some(item) = list.get_mut(replacement.id) {
+            if item.raw() == replacement.before {
+                item.set_raw(&replacement.after);
+                applied += 1;
+            }
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_literal_text_and_regex_groups() {
+        let mut list = TodoList::from_content("Call Bob about $5\nEmail Bob\nBuy milk");
+
+        let literal = Pattern::new("$5", false).unwrap();
+        let changes = preview(list.items(), &literal, "$1 refund");
+        assert_eq!(
+            changes,
+            vec![Replacement {
+                id: 1,
+                before: "Call Bob about $5".to_string(),
+                after: "Call Bob about $1 refund".to_string(),
+            }]
+        );
+
+        let regex = Pattern::new(r"(\w+) Bob", true).unwrap();
+        let changes = preview(list.items(), &regex, "$1 Robert");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(apply(&mut list, &changes), 2);
+        assert_eq!(
+            list.to_content(),
+            "Call Robert about $5\nEmail Robert\nBuy milk"
+        );
+
+        // Stale previews don't clobber newer edits
+        assert_eq!(apply(&mut list, &changes), 0);
+        assert!(Pattern::new("(", true).is_err());
+        assert!(Pattern::new("", false).is_err());
+    }
+}
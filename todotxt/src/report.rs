@@ -0,0 +1,134 @@
+//! A summary of a stretch of time, e.g. a week for a status email: what was
+//! completed, what was added and what is still open, grouped by project.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::feed::escape;
+use crate::{Date, TodoItem};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+/// The report on `items` from `from` to `to`, both included. Tasks are listed
+/// under their first project; those without one come last.
+pub fn generate<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    from: Date,
+    to: Date,
+    format: ReportFormat,
+) -> String {
+    let in_range = |date: Option<Date>| date.is_some_and(|date| from <= date && date <= to);
+    let items: Vec<&TodoItem> = items.into_iter().collect();
+    let sections = [
+        (
+            "Completed",
+            items
+                .iter()
+                .filter(|item| item.finished() && in_range(item.completion_date()))
+                .copied()
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "Added",
+            items
+                .iter()
+                .filter(|item| in_range(item.creation_date()))
+                .copied()
+                .collect(),
+        ),
+        (
+            "Still open",
+            items
+                .iter()
+                .filter(|item| !item.finished())
+                .copied()
+                .collect(),
+        ),
+    ];
+
+    let title = format!("Report {from} to {to}");
+    let mut out = match format {
+        ReportFormat::Markdown => format!("# {title}\n"),
+        ReportFormat::Html => format!("<h1>{}</h1>\n", escape(&title)),
+    };
+    for (heading, items) in sections {
+        let heading = format!("{heading} ({})", items.len());
+        match format {
+            ReportFormat::Markdown => out.push_str(&format!("\n## {heading}\n")),
+            ReportFormat::Html => out.push_str(&format!("<h2>{}</h2>\n", escape(&heading))),
+        }
+        for (project, items) in by_project(&items) {
+            let project = project.map_or("No project".to_string(), |p| format!("+{p}"));
+            match format {
+                ReportFormat::Markdown => {
+                    out.push_str(&format!("\n### {project}\n\n"));
+                    for item in items {
+                        out.push_str(&format!("- {}\n", item.subject()));
+                    }
+                }
+                ReportFormat::Html => {
+                    out.push_str(&format!("<h3>{}</h3>\n<ul>\n", escape(&project)));
+                    for item in items {
+                        out.push_str(&format!("<li>{}</li>\n", escape(item.subject())));
+                    }
+                    out.push_str("</ul>\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `items` by their first project, `None` (no project) sorted last.
+fn by_project<'a>(items: &[&'a TodoItem]) -> Vec<(Option<String>, Vec<&'a TodoItem>)> {
+    let mut groups: BTreeMap<(bool, Option<String>), Vec<&TodoItem>> = BTreeMap::new();
+    for item in items {
+        let project = item.unique_projects().into_iter().next();
+        groups
+            .entry((project.is_none(), project))
+            .or_default()
+            .push(item);
+    }
+    groups
+        .into_iter()
+        .map(|((_, project), items)| (project, items))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_generate() {
+        let mut list = TodoList::new();
+        list.add("x 2025-03-04 2025-02-20 Ship release +work");
+        list.add("x 2025-02-01 2025-01-20 Old news +work");
+        list.add("2025-03-05 Plan offsite +work");
+        list.add("2025-01-10 Fix fence");
+        let from = Date::from_ymd_opt(2025, 3, 3).unwrap();
+        let to = Date::from_ymd_opt(2025, 3, 9).unwrap();
+
+        assert_eq!(
+            generate(list.items(), from, to, ReportFormat::Markdown),
+            "# Report 2025-03-03 to 2025-03-09\n\
+             \n## Completed (1)\n\n### +work\n\n- Ship release +work\n\
+             \n## Added (1)\n\n### +work\n\n- Plan offsite +work\n\
+             \n## Still open (2)\n\n### +work\n\n- Plan offsite +work\n\
+             \n### No project\n\n- Fix fence\n"
+        );
+        let html = generate(list.items(), from, to, ReportFormat::Html);
+        assert!(
+            html.starts_with("<h1>Report 2025-03-03 to 2025-03-09</h1>\n<h2>Completed (1)</h2>\n")
+        );
+        assert!(html.contains("<h3>+work</h3>\n<ul>\n<li>Ship release +work</li>\n</ul>\n"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Rules checked when a task is added or changed. So far these are WIP
+//! limits: how many pending tasks may carry a tag at once, e.g. at most three
+//! `@doing`. Going over a limit isn't refused, the task is marked with an
+//! `overflow:1` tag instead, see [`TodoItem::overflowing`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::tags::TagKind;
+use crate::{TodoItem, TodoList};
+
+/// At most `max` pending tasks should carry the tag `name` of `kind`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WipLimit {
+    pub kind: TagKind,
+    /// Tag name without its sigil
+    pub name: String,
+    pub max: usize,
+}
+
+impl WipLimit {
+    /// The tag as typed in a todo, e.g. `@doing`.
+    pub fn tag(&self) -> String {
+        format!("{}{}", self.kind.sigil(), self.name)
+    }
+
+    /// Whether `item` counts against the limit.
+    pub fn counts(&self, item: &TodoItem) -> bool {
+        !item.finished() && item.tags(self.kind).contains(&self.name)
+    }
+}
+
+/// A limit that is exceeded, with the number of pending tasks carrying its tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overflow {
+    pub limit: WipLimit,
+    pub count: usize,
+}
+
+/// The `limits` exceeded in `items`.
+pub fn overflows(limits: &[WipLimit], items: &[TodoItem]) -> Vec<Overflow> {
+    limits
+        .iter()
+        .filter_map(|limit| {
+            let count = items.iter().filter(|item| limit.counts(item)).count();
+            (count > limit.max).then(|| Overflow {
+                limit: limit.clone(),
+                count,
+            })
+        })
+        .collect()
+}
+
+/// Checks the limits the task `id` counts against after it was added or
+/// changed. It is marked as overflowing if it went over any of them, and the
+/// mark comes off again once it doesn't. Returns the limits it went over.
+pub fn enforce(list: &mut TodoList, id: usize, limits: &[WipLimit]) -> Vec<Overflow> {
+    let Some(item) = list.get(id) else {
+        return Vec::new();
+    };
+    let exceeded: Vec<Overflow> = overflows(limits, list.items())
+        .into_iter()
+        .filter(|overflow| overflow.limit.counts(item))
+        .collect();
+    let overflowing = !exceeded.is_empty();
+    if item.overflowing() != overflowing {
+        if let Some(item) = list.get_mut(id) {
+            item.set_overflowing(overflowing);
+        }
+    }
+    exceeded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doing(max: usize) -> WipLimit {
+        WipLimit {
+            kind: TagKind::Context,
+            name: "doing".to_string(),
+            max,
+        }
+    }
+
+    #[test]
+    fn test_overflows() {
+        let list = TodoList::from_content(
+            "Write report @doing\nCall Bob @doing\nx Done already @doing\nPlan week @later",
+        );
+        assert!(overflows(&[doing(2)], list.items()).is_empty());
+        assert_eq!(
+            overflows(&[doing(1)], list.items()),
+            vec![Overflow {
+                limit: doing(1),
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_enforce_marks_and_unmarks() {
+        let mut list = TodoList::from_content("Write report @doing");
+        let id = list.add("Call Bob @doing");
+        assert_eq!(enforce(&mut list, id, &[doing(1)]).len(), 1);
+        assert_eq!(list.get(id).unwrap().raw(), "Call Bob @doing overflow:1");
+
+        // Moved out of @doing, so it's fine again
+        list.get_mut(id)
+            .unwrap()
+            .set_raw("Call Bob @later overflow:1");
+        assert!(enforce(&mut list, id, &[doing(1)]).is_empty());
+        assert_eq!(list.get(id).unwrap().raw(), "Call Bob @later");
+
+        // Tasks under every limit aren't touched
+        let revision = list.revision();
+        assert!(enforce(&mut list, 1, &[doing(1)]).is_empty());
+        assert_eq!(list.revision(), revision);
+    }
+}
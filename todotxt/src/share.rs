@@ -0,0 +1,41 @@
+//! Tasks from text shared by other apps, e.g. a page title and its link.
+
+/// A task line for the shared `text`, with its first link moved into a
+/// `url:` tag. Shared links without any text are named after their host.
+pub fn shared_task(text: &str) -> String {
+    let mut url = None;
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        if url.is_none() && (word.starts_with("https://") || word.starts_with("http://")) {
+            url = Some(word);
+        } else {
+            words.push(word);
+        }
+    }
+    let Some(url) = url else {
+        return words.join(" ");
+    };
+    if words.is_empty() {
+        let host = url.split("://").nth(1).unwrap_or(url);
+        words.push(host.split(['/', '?', '#']).next().unwrap_or(host));
+    }
+    format!("{} url:{url}", words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_task() {
+        assert_eq!(
+            shared_task("Great recipe\nhttps://example.com/pasta?x=1"),
+            "Great recipe url:https://example.com/pasta?x=1"
+        );
+        assert_eq!(
+            shared_task("https://example.com/pasta"),
+            "example.com url:https://example.com/pasta"
+        );
+        assert_eq!(shared_task("  Call Bob  back "), "Call Bob back");
+    }
+}
@@ -0,0 +1,138 @@
+//! How alike two todos read, to find the same task captured twice, and
+//! merging such duplicates back into one.
+
+use std::collections::BTreeSet;
+
+use crate::fuzzy;
+use crate::parse::{tokenize, Token};
+use crate::{missing_tags, TodoItem, TodoList};
+
+/// Todos at least this similar are offered as duplicates.
+pub const DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// The words of `item`'s description, normalized (see [`fuzzy::normalize`])
+/// and without punctuation, tags, priority or dates.
+fn words(item: &TodoItem) -> BTreeSet<String> {
+    let raw = item.raw();
+    tokenize(&raw)
+        .into_iter()
+        .filter_map(|spanned| match spanned.token {
+            Token::Word(word) => {
+                let word = fuzzy::normalize(word.trim_matches(|c: char| !c.is_alphanumeric()));
+                (!word.is_empty()).then_some(word)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// How alike the descriptions of `a` and `b` are, from 0 for no word in
+/// common to 1 for the same words (the Dice coefficient of their words).
+pub fn similarity(a: &TodoItem, b: &TodoItem) -> f64 {
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let common = a.intersection(&b).count();
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+/// Groups of pending `items` at least `threshold` similar, as ids in file
+/// order. A todo alike to any todo of a group joins it, so a group can hold
+/// two todos less alike than `threshold` through a third one.
+pub fn clusters<'a>(
+    items: impl IntoIterator<Item = &'a TodoItem>,
+    threshold: f64,
+) -> Vec<Vec<usize>> {
+    let items: Vec<&TodoItem> = items.into_iter().filter(|item| !item.finished()).collect();
+    // Index of the group each item is in, merged as matches are found
+    let mut group: Vec<usize> = (0..items.len()).collect();
+    for i in 0..items.len() {
+        for j in i + 1..items.len() {
+            if group[i] != group[j] && similarity(items[i], items[j]) >= threshold {
+                let (from, to) = (group[j], group[i]);
+                for g in group.iter_mut().filter(|g| **g == from) {
+                    *g = to;
+                }
+            }
+        }
+    }
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for index in 0..items.len() {
+        let members: Vec<usize> = (0..items.len())
+            .filter(|&i| group[i] == index)
+            .map(|i| items[i].id)
+            .collect();
+        if members.len() > 1 {
+            clusters.push(members);
+        }
+    }
+    clusters
+}
+
+/// Merges the todos `ids` into the first of them and removes the rest. The
+/// merged todo keeps its text, gains the projects, contexts and tags only
+/// the others had and is dated with the earliest creation date among them.
+/// Returns the id of the merged todo, or `None` if an id isn't in `list`.
+pub fn merge(list: &mut TodoList, ids: &[usize]) -> Option<usize> {
+    let (&keep, others) = ids.split_first()?;
+    let mut raw = list.get(keep)?.raw();
+    let mut created = list.get(keep)?.creation_date();
+    for &id in others {
+        let other = list.get(id)?;
+        created = match (created, other.creation_date()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let missing = missing_tags(&raw, &other.raw());
+        raw.push_str(&missing);
+    }
+    for &id in others {
+        list.remove(id);
+    }
+    let item = list.get_mut(keep)?;
+    item.set_raw(&raw);
+    item.set_creation_date(created);
+    Some(keep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Date;
+
+    #[test]
+    fn test_clusters() {
+        let list = TodoList::from_content(
+            "Call Bob about the invoice +work\n\
+             Water plants\n\
+             (A) call bob about the invoice! @phone\n\
+             x Water plants\n\
+             Call Bob about the invoice today",
+        );
+        assert!(similarity(&list.items()[0], &list.items()[2]) > 0.99);
+        assert_eq!(
+            clusters(list.items(), DUPLICATE_THRESHOLD),
+            vec![vec![1, 3, 5]]
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut list = TodoList::from_content(
+            "2025-03-01 Call Bob +work due:2025-03-10\n\
+             Water plants\n\
+             2025-02-01 call bob @phone +work due:2025-04-01 est:15m",
+        );
+        assert_eq!(merge(&mut list, &[1, 3]), Some(1));
+        assert_eq!(list.len(), 2);
+        let merged = list.get(1).unwrap();
+        assert!(merged.subject().starts_with("Call Bob"));
+        assert_eq!(merged.projects(), ["work"]);
+        assert_eq!(merged.contexts(), ["phone"]);
+        assert_eq!(merged.estimate(), Some(15));
+        assert_eq!(merged.due_date(), Date::from_ymd_opt(2025, 3, 10));
+        assert_eq!(merged.creation_date(), Date::from_ymd_opt(2025, 2, 1));
+        assert_eq!(merge(&mut list, &[1, 42]), None);
+    }
+}
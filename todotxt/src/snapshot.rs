@@ -0,0 +1,86 @@
+//! A small summary of the list for desktop widgets (Conky, waybar, KDE
+//! plasmoids, ...) to read, written as JSON.
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::Counts;
+use crate::{Date, TodoItem};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTask {
+    pub subject: String,
+    /// `A` to `Z`
+    pub priority: Option<char>,
+    pub due: Option<Date>,
+}
+
+impl SnapshotTask {
+    fn new(item: &TodoItem) -> Self {
+        Self {
+            subject: item.subject().to_string(),
+            priority: (item.priority() < 26).then(|| (b'A' + item.priority()) as char),
+            due: item.due_date(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The most pressing tasks, see [`top`]
+    pub tasks: Vec<SnapshotTask>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counts: Option<Counts>,
+    /// The pending task due soonest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_due: Option<SnapshotTask>,
+}
+
+/// The first `limit` of the pending `items`, by priority and then due date.
+pub fn top<'a>(items: impl IntoIterator<Item = &'a TodoItem>, limit: usize) -> Vec<SnapshotTask> {
+    let mut pending: Vec<&TodoItem> = items.into_iter().filter(|item| !item.finished()).collect();
+    // Undated tasks go last; the sort is stable, so ties keep file order
+    pending.sort_by_key(|item| (item.priority(), item.due_date().is_none(), item.due_at()));
+    pending
+        .into_iter()
+        .take(limit)
+        .map(SnapshotTask::new)
+        .collect()
+}
+
+/// The pending task among `items` due soonest, by date and time.
+pub fn next_due<'a>(items: impl IntoIterator<Item = &'a TodoItem>) -> Option<SnapshotTask> {
+    items
+        .into_iter()
+        .filter(|item| !item.finished())
+        .filter_map(|item| item.due_at().map(|due| (due, item)))
+        .min_by_key(|(due, _)| *due)
+        .map(|(_, item)| SnapshotTask::new(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_top_and_next_due() {
+        let mut list = TodoList::new();
+        list.add("Water plants");
+        list.add("(B) Call Bob due:2025-03-05");
+        list.add("(A) Pay rent");
+        list.add("(B) File taxes due:2025-03-02");
+        list.add("x (A) Done already due:2025-03-01");
+
+        let subjects: Vec<String> = top(list.items(), 3)
+            .into_iter()
+            .map(|task| task.subject)
+            .collect();
+        assert_eq!(subjects, vec!["Pay rent", "File taxes", "Call Bob"]);
+        assert_eq!(top(list.items(), 3)[0].priority, Some('A'));
+
+        let next = next_due(list.items()).unwrap();
+        assert_eq!(next.subject, "File taxes");
+        assert_eq!(next.due, Date::from_ymd_opt(2025, 3, 2));
+        assert!(next_due(&list.items()[..1]).is_none());
+    }
+}
@@ -0,0 +1,252 @@
+//! Sorting todos by a field: a built-in one like the due date, or any
+//! `key:value` tag, e.g. `est:` or `energy:`. A tag's values are compared as
+//! durations, dates or numbers if all of them read as one, else as text.
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::{estimate, Date, TodoItem};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", tag = "by", content = "key")]
+pub enum SortBy {
+    /// The order of todo.txt
+    #[default]
+    File,
+    Priority,
+    Due,
+    Created,
+    /// The manual order of the `ord:` tags, see [`crate::order`]
+    Order,
+    /// The value of the tag with this key
+    Tag(String),
+}
+
+impl SortBy {
+    pub const BUILT_IN: [SortBy; 5] = [
+        SortBy::File,
+        SortBy::Priority,
+        SortBy::Due,
+        SortBy::Created,
+        SortBy::Order,
+    ];
+
+    /// How it's written in a link or the sort selector, e.g. `due` or `tag:est`.
+    pub fn name(&self) -> String {
+        match self {
+            SortBy::File => "file".to_string(),
+            SortBy::Priority => "priority".to_string(),
+            SortBy::Due => "due".to_string(),
+            SortBy::Created => "created".to_string(),
+            SortBy::Order => "order".to_string(),
+            SortBy::Tag(key) => format!("tag:{key}"),
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<SortBy> {
+        match name {
+            "file" => Some(SortBy::File),
+            "priority" => Some(SortBy::Priority),
+            "due" => Some(SortBy::Due),
+            "created" => Some(SortBy::Created),
+            "order" => Some(SortBy::Order),
+            _ => name
+                .strip_prefix("tag:")
+                .filter(|key| !key.is_empty())
+                .map(|key| SortBy::Tag(key.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            SortBy::File => "File order".to_string(),
+            SortBy::Priority => "Priority".to_string(),
+            SortBy::Due => "Due date".to_string(),
+            SortBy::Created => "Creation date".to_string(),
+            SortBy::Order => "Manual order".to_string(),
+            SortBy::Tag(key) => format!("{key}:"),
+        }
+    }
+
+    /// What `item` is sorted by, `None` if it has nothing to sort by. Tag
+    /// values are read as `kind`.
+    fn value(&self, item: &TodoItem, kind: ValueKind) -> Option<Value> {
+        match self {
+            SortBy::File => None,
+            // Todos without a priority have one past Z
+            SortBy::Priority => Some(Value::Number(item.priority() as f64)),
+            SortBy::Due => item.due_at().map(|(date, time)| {
+                let minutes = time.map_or(0, |time| time.num_seconds_from_midnight() / 60);
+                Value::Number(days(date) + minutes as f64 / (24.0 * 60.0))
+            }),
+            SortBy::Created => item.creation_date().map(|date| Value::Number(days(date))),
+            // Positions compare as text, whatever they look like
+            SortBy::Order => item.order().map(|key| Value::Text(key.to_string())),
+            SortBy::Tag(key) => item.tag_value(key).and_then(|value| kind.read(value)),
+        }
+    }
+}
+
+fn days(date: Date) -> f64 {
+    date.num_days_from_ce() as f64
+}
+
+/// What the values of a tag are, see [`infer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    /// Like estimates, e.g. `45m` or `1h30m`
+    Duration,
+    /// `YYYY-MM-DD`
+    Date,
+    Number,
+    Text,
+}
+
+impl ValueKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueKind::Duration => "duration",
+            ValueKind::Date => "date",
+            ValueKind::Number => "number",
+            ValueKind::Text => "text",
+        }
+    }
+
+    fn read(self, value: &str) -> Option<Value> {
+        match self {
+            ValueKind::Duration => {
+                estimate::parse(value).map(|minutes| Value::Number(minutes as f64))
+            }
+            ValueKind::Date => parse_date(value).map(|date| Value::Number(days(date))),
+            ValueKind::Number => value.parse().ok().map(Value::Number),
+            ValueKind::Text => Some(Value::Text(value.to_lowercase())),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    Date::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+/// The narrowest kind all of `values` read as; text if there are none.
+pub fn infer<'a>(values: impl IntoIterator<Item = &'a str>) -> ValueKind {
+    let values: Vec<&str> = values.into_iter().collect();
+    if values.is_empty() {
+        return ValueKind::Text;
+    }
+    [ValueKind::Date, ValueKind::Number, ValueKind::Duration]
+        .into_iter()
+        .find(|kind| values.iter().all(|value| kind.read(value).is_some()))
+        .unwrap_or(ValueKind::Text)
+}
+
+/// The keys of the `key:value` tags in `items`, by name, each with the kind
+/// of its values.
+pub fn tag_keys(items: &[TodoItem]) -> Vec<(String, ValueKind)> {
+    let keys: BTreeSet<&str> = items.iter().flat_map(TodoItem::tag_keys).collect();
+    keys.into_iter()
+        .map(|key| {
+            let kind = infer(items.iter().filter_map(|item| item.tag_value(key)));
+            (key.to_string(), kind)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
+            (Value::Text(a), Value::Text(b)) => a.cmp(b),
+            (Value::Number(_), Value::Text(_)) => Ordering::Less,
+            (Value::Text(_), Value::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Sorts `items` by `by`, `item` giving the todo each one is. Equal ones
+/// keep their order, and those without a value go last either way.
+pub fn sort<T>(items: &mut Vec<T>, by: &SortBy, descending: bool, item: impl Fn(&T) -> TodoItem) {
+    if *by == SortBy::File {
+        if descending {
+            items.reverse();
+        }
+        return;
+    }
+    let todos: Vec<TodoItem> = items.iter().map(item).collect();
+    let kind = match by {
+        SortBy::Tag(key) => infer(todos.iter().filter_map(|todo| todo.tag_value(key))),
+        _ => ValueKind::Number,
+    };
+    let mut keyed: Vec<(Option<Value>, T)> = todos
+        .iter()
+        .map(|todo| by.value(todo, kind))
+        .zip(items.drain(..))
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) if descending => b.compare(a),
+        (Some(a), Some(b)) => a.compare(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    fn sorted(list: &TodoList, by: &SortBy, descending: bool) -> Vec<usize> {
+        let mut items = list.items().to_vec();
+        sort(&mut items, by, descending, TodoItem::clone);
+        items.iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn test_sort_by_tag() {
+        let list = TodoList::from_content(
+            "Paint fence est:2h energy:high\n\
+             Call mum est:15m\n\
+             File taxes energy:low\n\
+             Water plants est:1h30m energy:medium",
+        );
+        let est = SortBy::Tag("est".to_string());
+        assert_eq!(sorted(&list, &est, false), vec![2, 4, 1, 3]);
+        assert_eq!(sorted(&list, &est, true), vec![1, 4, 2, 3]);
+        assert_eq!(
+            sorted(&list, &SortBy::Tag("energy".to_string()), false),
+            vec![1, 3, 4, 2]
+        );
+        assert_eq!(
+            tag_keys(list.items()),
+            vec![
+                ("energy".to_string(), ValueKind::Text),
+                ("est".to_string(), ValueKind::Duration)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer() {
+        assert_eq!(infer(["3", "10", "2.5"]), ValueKind::Number);
+        assert_eq!(infer(["2025-03-01", "2024-12-24"]), ValueKind::Date);
+        assert_eq!(infer(["45m", "2h"]), ValueKind::Duration);
+        assert_eq!(infer(["45m", "soon"]), ValueKind::Text);
+        assert_eq!(
+            SortBy::parse("tag:est"),
+            Some(SortBy::Tag("est".to_string()))
+        );
+        assert_eq!(SortBy::parse(&SortBy::Due.name()), Some(SortBy::Due));
+    }
+}
@@ -0,0 +1,207 @@
+//! Completion statistics derived from the completion dates of finished tasks.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::filter::Due;
+use crate::{Date, TodoItem};
+
+/// Today's date in the local time zone, with days ending at midnight. See
+/// [`crate::day`] for days ending later.
+pub fn today() -> Date {
+    crate::day::today(chrono::NaiveTime::MIN)
+}
+
+/// Number of finished tasks per completion date. Finished tasks without a
+/// completion date (e.g. completed before they had a creation date) are skipped.
+/// Takes borrowed or owned items, e.g. ones streamed with [`crate::stream::items`].
+pub fn completions_per_day(
+    items: impl IntoIterator<Item = impl Borrow<TodoItem>>,
+) -> BTreeMap<Date, usize> {
+    let mut per_day = BTreeMap::new();
+    for date in items
+        .into_iter()
+        .filter(|item| item.borrow().finished())
+        .filter_map(|item| item.borrow().completion_date())
+    {
+        *per_day.entry(date).or_insert(0) += 1;
+    }
+    per_day
+}
+
+/// Todo counts shown next to the navigation entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counts {
+    /// Pending todos
+    pub inbox: usize,
+    /// Pending todos due today or overdue
+    pub today: usize,
+    /// Pending todos due after today
+    pub upcoming: usize,
+    pub completed: usize,
+}
+
+pub fn counts<'a>(items: impl IntoIterator<Item = &'a TodoItem>, today: Date) -> Counts {
+    let mut counts = Counts::default();
+    for item in items {
+        if item.finished() {
+            counts.completed += 1;
+            continue;
+        }
+        counts.inbox += 1;
+        if Due::Today.matches(item, today) {
+            counts.today += 1;
+        } else if Due::Upcoming.matches(item, today) {
+            counts.upcoming += 1;
+        }
+    }
+    counts
+}
+
+/// Pending todos per due date bucket, for the summary above the list. The
+/// buckets don't overlap, each matches its [`Due`] filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DueSummary {
+    pub overdue: usize,
+    pub today: usize,
+    /// Due in the rest of the week, see [`Due::ThisWeek`]
+    pub this_week: usize,
+}
+
+pub fn due_summary<'a>(items: impl IntoIterator<Item = &'a TodoItem>, today: Date) -> DueSummary {
+    let mut summary = DueSummary::default();
+    for item in items.into_iter().filter(|item| !item.finished()) {
+        if Due::Overdue.matches(item, today) {
+            summary.overdue += 1;
+        } else if Due::TodayOnly.matches(item, today) {
+            summary.today += 1;
+        } else if Due::ThisWeek.matches(item, today) {
+            summary.this_week += 1;
+        }
+    }
+    summary
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Streaks {
+    /// Consecutive days with at least one completion, ending today. A streak
+    /// that ended yesterday is still current until today is over.
+    pub current: usize,
+    pub longest: usize,
+    pub completed_today: usize,
+}
+
+pub fn streaks(per_day: &BTreeMap<Date, usize>, today: Date) -> Streaks {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<Date> = None;
+    for (&date, _) in per_day.range(..=today).filter(|(_, &count)| count > 0) {
+        run = match previous {
+            Some(prev) if prev.succ_opt() == Some(date) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    let has_completions = |date: &Date| per_day.get(date).is_some_and(|&count| count > 0);
+    let mut current = 0;
+    let mut day = if has_completions(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(date) = day.filter(has_completions) {
+        current += 1;
+        day = date.pred_opt();
+    }
+
+    Streaks {
+        current,
+        longest,
+        completed_today: per_day.get(&today).copied().unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    fn date(d: u32) -> Date {
+        Date::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_completions_per_day() {
+        let mut list = TodoList::new();
+        list.add("x 2025-03-02 2025-03-01 Done twice");
+        list.add("x 2025-03-02 2025-02-01 Done again");
+        list.add("x 2025-03-01 No completion date");
+        list.add("2025-03-01 Pending");
+
+        let per_day = completions_per_day(list.items());
+        assert_eq!(per_day.into_iter().collect::<Vec<_>>(), vec![(date(2), 2)]);
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut list = TodoList::new();
+        list.add("Overdue due:2025-03-01");
+        list.add("Today due:2025-03-05");
+        list.add("Later due:2025-03-20");
+        list.add("Undated");
+        list.add("x 2025-03-04 Done due:2025-03-01");
+
+        assert_eq!(
+            counts(list.items(), date(5)),
+            Counts {
+                inbox: 4,
+                today: 2,
+                upcoming: 1,
+                completed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_due_summary() {
+        let mut list = TodoList::new();
+        list.add("Overdue due:2025-03-01");
+        list.add("Today due:2025-03-05");
+        list.add("Sunday due:2025-03-11");
+        list.add("Next week due:2025-03-12");
+        list.add("x 2025-03-04 Done due:2025-03-05");
+
+        assert_eq!(
+            due_summary(list.items(), date(5)),
+            DueSummary {
+                overdue: 1,
+                today: 1,
+                this_week: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_streaks() {
+        let per_day: BTreeMap<Date, usize> = [(1, 1), (2, 3), (3, 1), (6, 2), (7, 1)]
+            .into_iter()
+            .map(|(d, n)| (date(d), n))
+            .collect();
+
+        assert_eq!(
+            streaks(&per_day, date(7)),
+            Streaks {
+                current: 2,
+                longest: 3,
+                completed_today: 1
+            }
+        );
+        // Nothing done yet today, but yesterday keeps the streak alive
+        assert_eq!(streaks(&per_day, date(8)).current, 2);
+        assert_eq!(streaks(&per_day, date(9)).current, 0);
+    }
+}
@@ -0,0 +1,75 @@
+//! Reading todo.txt files one line at a time, for files too large to hold
+//! in memory at once, such as a long-kept done.txt read for statistics.
+
+use std::io::{BufRead, Lines};
+
+use crate::TodoItem;
+
+/// The items of a todo.txt file, parsed as they are read from it. Blank
+/// lines are skipped, but counted in [`TodoItem::line`]; ids count from 1
+/// like in [`crate::TodoList`].
+pub struct Items<R> {
+    lines: Lines<R>,
+    next_id: usize,
+    line: usize,
+}
+
+/// Parses the items from `reader` one line at a time.
+pub fn items<R: BufRead>(reader: R) -> Items<R> {
+    Items {
+        lines: reader.lines(),
+        next_id: 1,
+        line: 0,
+    }
+}
+
+impl<R: BufRead> Iterator for Items<R> {
+    type Item = Result<TodoItem, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.line += 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let id = self.next_id;
+            self.next_id += 1;
+            return Some(Ok(TodoItem {
+                inner: todo_txt::task::Simple::from(line.to_string()),
+                id,
+                line: self.line,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TodoList;
+
+    #[test]
+    fn test_items() {
+        let content = "(A) Call Bob\n\n  x 2025-03-01 Water plants  \nPay rent due:2025-03-01\n";
+        let streamed: Vec<TodoItem> = items(content.as_bytes()).collect::<Result<_, _>>().unwrap();
+        let list = TodoList::from_content(content);
+
+        assert_eq!(streamed.len(), 3);
+        for (streamed, item) in streamed.iter().zip(list.items()) {
+            assert_eq!(streamed.id, item.id);
+            assert_eq!(streamed.line(), item.line());
+            assert_eq!(streamed.raw(), item.raw());
+        }
+        assert_eq!(
+            TodoList::from_reader(content.as_bytes())
+                .unwrap()
+                .to_content(),
+            list.to_content()
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Aggregates over the `+project` and `@context` tags used in a list.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Date, TodoList};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Project,
+    Context,
+}
+
+impl TagKind {
+    pub fn sigil(self) -> char {
+        match self {
+            TagKind::Project => '+',
+            TagKind::Context => '@',
+        }
+    }
+}
+
+/// What [`TodoItem::edit_tags`] should do with a tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagEdit {
+    Keep,
+    Rename(String),
+    Remove,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagUsage {
+    pub kind: TagKind,
+    pub name: String,
+    /// Number of todos carrying the tag
+    pub count: usize,
+    /// Latest creation or completion date among those todos
+    pub last_used: Option<Date>,
+}
+
+/// Usage of every tag in `list`, projects first, each kind sorted by name.
+pub fn usage(list: &TodoList) -> Vec<TagUsage> {
+    let index = list.tag_index();
+    let items = list.items();
+    let mut usage: Vec<TagUsage> = [TagKind::Project, TagKind::Context]
+        .into_iter()
+        .flat_map(|kind| {
+            index
+                .tags(kind)
+                .map(move |(id, positions)| (kind, id, positions))
+        })
+        .map(|(kind, id, positions)| TagUsage {
+            kind,
+            name: index.table().name(id).to_string(),
+            count: positions.len(),
+            last_used: positions
+                .iter()
+                .filter_map(|&position| {
+                    let item = &items[position];
+                    item.completion_date().max(item.creation_date())
+                })
+                .max(),
+        })
+        .collect();
+    usage.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage() {
+        let mut list = TodoList::new();
+        list.add("2025-03-01 Call Bob +work @phone");
+        list.add("x 2025-03-05 2025-02-01 Email Alice +work");
+        list.add("Tidy up +home");
+
+        let date = |d| Date::from_ymd_opt(2025, 3, d);
+        assert_eq!(
+            usage(&list),
+            vec![
+                TagUsage {
+                    kind: TagKind::Project,
+                    name: "home".to_string(),
+                    count: 1,
+                    last_used: None,
+                },
+                TagUsage {
+                    kind: TagKind::Project,
+                    name: "work".to_string(),
+                    count: 2,
+                    last_used: date(5),
+                },
+                TagUsage {
+                    kind: TagKind::Context,
+                    name: "phone".to_string(),
+                    count: 1,
+                    last_used: date(1),
+                },
+            ]
+        );
+    }
+}
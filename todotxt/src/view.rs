@@ -0,0 +1,220 @@
+//! A way of looking at the list (which list, filtered how, grouped how) as
+//! a link, e.g. `todotxt://view?project=work&status=pending&group=context`,
+//! so it can be bookmarked or opened on another device. Only what differs
+//! from the defaults goes into the link.
+
+use crate::filter::{Due, Energy, Filter, Flag, Status};
+use crate::group::GroupBy;
+
+/// The scheme the app is opened with for links to a view.
+pub const SCHEME: &str = "todotxt";
+
+/// What a link to a view starts with.
+pub fn link_prefix() -> String {
+    format!("{SCHEME}://view?")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewState {
+    /// A watched list by name, the user's own list if `None`
+    pub list: Option<String>,
+    pub filter: Filter,
+    pub group: GroupBy,
+    /// The order of the groups, see [`crate::group::sort`]
+    pub order: Vec<String>,
+}
+
+impl ViewState {
+    /// The state as a query string, e.g. `project=work&group=context`.
+    pub fn to_query(&self) -> String {
+        let filter = &self.filter;
+        let mut pairs: Vec<(&str, String)> = Vec::new();
+        if let Some(list) = &self.list {
+            pairs.push(("list", list.clone()));
+        }
+        if let Some(project) = &filter.project {
+            pairs.push(("project", project.clone()));
+        }
+        if let Some(context) = &filter.context {
+            pairs.push(("context", context.clone()));
+        }
+        match filter.status {
+            Status::All => {}
+            Status::Pending => pairs.push(("status", "pending".to_string())),
+            Status::Done => pairs.push(("status", "done".to_string())),
+        }
+        if let Some(due) = filter.due {
+            pairs.push(("due", due.name().to_string()));
+        }
+        if let Some(assignee) = &filter.assignee {
+            pairs.push(("assignee", assignee.clone()));
+        }
+        // An empty list matches nothing, which a link can't tell from no list
+        for context in filter.any_context.iter().flatten() {
+            pairs.push(("near", context.clone()));
+        }
+        if let Some(energy) = filter.energy {
+            pairs.push(("energy", energy.name().to_string()));
+        }
+        if let Some(flag) = filter.flag {
+            pairs.push(("flag", flag.name().to_string()));
+        }
+        if filter.starred {
+            pairs.push(("starred", "1".to_string()));
+        }
+        for priority in &filter.priorities {
+            pairs.push(("priority", priority.map_or("-".to_string(), String::from)));
+        }
+        if let Some(id) = filter.id {
+            pairs.push(("id", id.to_string()));
+        }
+        if self.group != GroupBy::None {
+            pairs.push(("group", self.group.name().to_string()));
+        }
+        for label in &self.order {
+            pairs.push(("order", label.clone()));
+        }
+        let pairs: Vec<String> = pairs
+            .into_iter()
+            .map(|(key, value)| format!("{key}={}", encode(&value)))
+            .collect();
+        pairs.join("&")
+    }
+
+    /// The state from a query string made by [`ViewState::to_query`].
+    /// Unknown keys are skipped, so links from newer versions still open.
+    pub fn from_query(query: &str) -> Result<ViewState, String> {
+        let mut state = ViewState::default();
+        let filter = &mut state.filter;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value).ok_or_else(|| format!("{key} isn't encoded right"))?;
+            let invalid = || format!("{key} can't be {value:?}");
+            match key {
+                "list" => state.list = Some(value),
+                "project" => filter.project = Some(value),
+                "context" => filter.context = Some(value),
+                "status" => {
+                    filter.status = match value.as_str() {
+                        "pending" => Status::Pending,
+                        "done" => Status::Done,
+                        "all" => Status::All,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "due" => filter.due = Some(Due::parse(&value).ok_or_else(invalid)?),
+                "assignee" => filter.assignee = Some(value),
+                "near" => filter.any_context.get_or_insert_with(Vec::new).push(value),
+                "energy" => filter.energy = Some(Energy::parse(&value).ok_or_else(invalid)?),
+                "flag" => filter.flag = Some(Flag::parse(&value).ok_or_else(invalid)?),
+                "starred" => filter.starred = value == "1",
+                "priority" => {
+                    let mut chars = value.chars();
+                    let priority = match (chars.next(), chars.next()) {
+                        (Some('-'), None) => None,
+                        (Some(letter @ 'A'..='Z'), None) => Some(letter),
+                        _ => return Err(invalid()),
+                    };
+                    filter.priorities.push(priority);
+                }
+                "id" => filter.id = Some(value.parse().map_err(|_| invalid())?),
+                "group" => {
+                    state.group = GroupBy::ALL
+                        .into_iter()
+                        .find(|by| by.name() == value)
+                        .ok_or_else(invalid)?
+                }
+                "order" => state.order.push(value),
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
+
+    /// A link that opens the app on this view.
+    pub fn to_link(&self) -> String {
+        format!("{}{}", link_prefix(), self.to_query())
+    }
+
+    /// The state from a link made by [`ViewState::to_link`], `None` if
+    /// `link` isn't a link to a view.
+    pub fn from_link(link: &str) -> Option<Result<ViewState, String>> {
+        let query = link.trim().strip_prefix(&link_prefix())?;
+        Some(ViewState::from_query(query))
+    }
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_round_trip() {
+        let state = ViewState {
+            list: Some("Family & friends".to_string()),
+            filter: Filter {
+                project: Some("work---email".to_string()),
+                status: Status::Pending,
+                priorities: vec![Some('A'), None],
+                flag: Some(Flag::Blue),
+                ..Filter::default()
+            },
+            group: GroupBy::Context,
+            order: vec!["@todo".to_string(), "@doing".to_string()],
+        };
+        let link = state.to_link();
+        assert_eq!(
+            link,
+            "todotxt://view?list=Family%20%26%20friends&project=work---email&status=pending\
+             &flag=blue&priority=A&priority=-&group=context&order=%40todo&order=%40doing"
+        );
+        assert_eq!(ViewState::from_link(&link), Some(Ok(state)));
+        assert_eq!(
+            ViewState::from_link("todotxt://view?"),
+            Some(Ok(ViewState::default()))
+        );
+        assert!(matches!(
+            ViewState::from_link("todotxt://view?group=size"),
+            Some(Err(_))
+        ));
+        assert_eq!(ViewState::from_link("https://example.com"), None);
+    }
+}
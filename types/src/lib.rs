@@ -0,0 +1,608 @@
+//! Types exchanged between the Tauri backend and the frontend, defined once so
+//! both sides agree on the JSON every command takes and returns.
+
+pub mod settings;
+
+use serde::{Deserialize, Serialize};
+use todotxt::activity::Entry;
+use todotxt::export::ExportFormat;
+use todotxt::filter::{Filter, Flag};
+use todotxt::plugin::{Capability, Manifest};
+use todotxt::redact;
+use todotxt::report::ReportFormat;
+
+use settings::Settings;
+
+/// A todo as the list shows it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Todo {
+    pub id: usize,
+    /// The line of todo.txt it is on, see [`todotxt::TodoItem::line`]
+    #[serde(default)]
+    pub line: usize,
+    /// The subject without its `+project` and `@context` tags
+    pub subject: String,
+    pub raw: String,
+    pub finished: bool,
+    pub priority: u8,
+    pub creation_date: Option<String>,
+    pub due_date: Option<String>,
+    /// The time of day it is due as `HH:MM`, from an `at:` tag
+    #[serde(default)]
+    pub due_time: Option<String>,
+    /// The day the todo starts to matter, from a `t:` tag
+    #[serde(default)]
+    pub threshold_date: Option<String>,
+    pub assignee: Option<String>,
+    /// Estimated effort in minutes, from an `est:` tag
+    #[serde(default)]
+    pub estimate: Option<u32>,
+    /// Pinned above the other todos, from a `star:1` tag
+    #[serde(default)]
+    pub starred: bool,
+    /// Completes itself once its due date passes, from an `auto:done` tag
+    #[serde(default)]
+    pub auto_done: bool,
+    /// From a `flag:` tag
+    #[serde(default)]
+    pub flag: Option<Flag>,
+    pub contexts: Vec<String>,
+    pub projects: Vec<String>,
+}
+
+impl Todo {
+    /// The todo with lorem ipsum in place of its words and assignee, for
+    /// demo mode, see [`todotxt::redact`].
+    pub fn redacted(&self) -> Todo {
+        Todo {
+            subject: redact::line(&self.subject),
+            raw: redact::line(&self.raw),
+            assignee: self.assignee.as_deref().map(redact::line),
+            ..self.clone()
+        }
+    }
+}
+
+/// What to do with a batch of todos, e.g. the stale ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkAction {
+    /// Move them to done.txt as they are
+    Archive,
+    /// Make them due a week from today
+    Reschedule,
+    Delete,
+}
+
+/// A value read from the list, with the list's [`todotxt::TodoList::revision`]
+/// at the time. Responses to invokes can arrive out of order; one with an
+/// older revision than what's shown already is stale.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revised<T> {
+    pub revision: u64,
+    pub value: T,
+}
+
+/// What the web viewer reads from `GET /todos.json`: the list, with what
+/// the browser can't tell on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebList {
+    pub todos: Revised<Vec<Todo>>,
+    /// Today on the app's machine as `YYYY-MM-DD`, with the day ending
+    /// when the settings say
+    pub today: String,
+    pub project_separator: String,
+}
+
+/// Emitted with the [`Todo`] that was added to the list, as [`Revised`].
+pub const TODO_ADDED_EVENT: &str = "todo-added";
+/// Emitted with the new version of a [`Todo`] that changed, as [`Revised`].
+pub const TODO_UPDATED_EVENT: &str = "todo-updated";
+/// Emitted with the id of a todo that is gone, as [`Revised`].
+pub const TODO_REMOVED_EVENT: &str = "todo-removed";
+/// Emitted with the `todotxt::reminders::Notification` to show.
+pub const REMINDER_EVENT: &str = "reminder";
+/// Emitted with the [`JobProgress`] of a running job.
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+/// Emitted with the [`JobFinished`] of a job that is done, failed or was cancelled.
+pub const JOB_FINISHED_EVENT: &str = "job-finished";
+/// Emitted with the running [`FocusSession`] when one starts, and with
+/// `null` when it ends.
+pub const FOCUS_EVENT: &str = "focus-changed";
+/// Emitted with the [`settings::Settings`] whenever they are saved, for
+/// windows other than the one that saved them.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+/// Emitted with the [`Todo`]s of inbox.txt whenever it is written, see
+/// `todotxt::inbox`.
+pub const INBOX_CHANGED_EVENT: &str = "inbox-changed";
+/// Emitted with the `todotxt::diff::TaskChange`s when another program, e.g.
+/// a sync, changed todo.txt.
+pub const FILE_CHANGED_EVENT: &str = "file-changed";
+
+/// One step from an old version of the list to a new one, sent as the event
+/// named by [`TodoChange::event`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TodoChange {
+    Added(Todo),
+    Updated(Todo),
+    Removed(usize),
+}
+
+impl TodoChange {
+    /// The changes that turn `before` into `after`, matching todos by id.
+    /// Ids are line numbers, so deleting a todo updates the ones after it
+    /// and removes the last id.
+    pub fn between(before: &[Todo], after: &[Todo]) -> Vec<TodoChange> {
+        let mut changes = Vec::new();
+        for todo in after {
+            match before.iter().find(|old| old.id == todo.id) {
+                None => changes.push(TodoChange::Added(todo.clone())),
+                Some(old) if old != todo => changes.push(TodoChange::Updated(todo.clone())),
+                Some(_) => {}
+            }
+        }
+        for old in before {
+            if !after.iter().any(|todo| todo.id == old.id) {
+                changes.push(TodoChange::Removed(old.id));
+            }
+        }
+        changes
+    }
+
+    pub fn event(&self) -> &'static str {
+        match self {
+            TodoChange::Added(_) => TODO_ADDED_EVENT,
+            TodoChange::Updated(_) => TODO_UPDATED_EVENT,
+            TodoChange::Removed(_) => TODO_REMOVED_EVENT,
+        }
+    }
+
+    /// Applies the change to `todos`, which are kept sorted by id.
+    pub fn apply(self, todos: &mut Vec<Todo>) {
+        match self {
+            TodoChange::Added(todo) | TodoChange::Updated(todo) => {
+                match todos.binary_search_by_key(&todo.id, |t| t.id) {
+                    Ok(i) => todos[i] = todo,
+                    Err(i) => todos.insert(i, todo),
+                }
+            }
+            TodoChange::Removed(id) => todos.retain(|t| t.id != id),
+        }
+    }
+}
+
+/// A todo with the logged change that last touched it, for the Recently
+/// changed view.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentChange {
+    pub todo: Todo,
+    pub entry: Entry,
+}
+
+/// The todos one todo links to with `ref:` tags and the ones linking to it,
+/// see [`todotxt::links`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskLinks {
+    pub outgoing: Vec<Todo>,
+    pub backlinks: Vec<Todo>,
+}
+
+/// A running session of focused work on one todo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub id: usize,
+    pub subject: String,
+    /// When the session ends, in milliseconds since the Unix epoch
+    pub ends_at: u64,
+    /// Whether the system's Do Not Disturb mode was turned on for it
+    pub do_not_disturb: bool,
+}
+
+/// How far a long-running job in the backend got, e.g. a sync.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub id: u64,
+    pub name: String,
+    /// Steps done out of `total`
+    pub done: u32,
+    pub total: u32,
+    /// What the job is doing now
+    pub message: String,
+}
+
+/// How a long-running job ended.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobFinished {
+    pub id: u64,
+    pub name: String,
+    pub error: Option<String>,
+    pub cancelled: bool,
+}
+
+/// An installed plugin as reported by `get_plugins`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginInfo {
+    /// Name of the plugin's directory
+    pub dir: String,
+    pub manifest: Option<Manifest>,
+    /// Why the manifest couldn't be loaded
+    pub error: Option<String>,
+    /// Requested capabilities the user hasn't granted yet
+    pub missing: Vec<Capability>,
+}
+
+/// Text typed into the add dialog or an inline edit that isn't saved yet,
+/// restored on the next launch if the app closes in between.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Draft {
+    pub add: String,
+    pub edit: Option<EditDraft>,
+}
+
+impl Draft {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.edit.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditDraft {
+    pub id: usize,
+    /// The todo's line when editing started. Ids are line numbers, so the
+    /// draft is only restored if the line with `id` still reads this.
+    pub original: String,
+    pub text: String,
+}
+
+// Arguments of the commands that take any, named `<Command>Args`. Field names
+// match the command's parameter names.
+
+#[derive(Debug, Serialize)]
+pub struct QueryTodosArgs<'a> {
+    pub filter: &'a Filter,
+    /// Name of a watched list to query instead of the user's own
+    pub list: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderFilteredArgs<'a> {
+    pub filter: &'a Filter,
+    pub format: ExportFormat,
+    pub list: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddTodoArgs<'a> {
+    pub text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PickTodoArgs<'a> {
+    pub filter: &'a Filter,
+    /// Ids passed over already
+    pub skip: &'a [usize],
+    /// A random number from 0 up to 1
+    pub roll: f64,
+}
+
+/// What the migrations at this start upgraded, for the changelog.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// The settings version before
+    pub from: u32,
+    pub to: u32,
+    /// What each migration changed, in order
+    pub changes: Vec<String>,
+    /// Copies of the files as they were before
+    pub backups: Vec<String>,
+}
+
+/// Finished todos to leave in todo.txt, and whether to only preview.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveDoneArgs<'a> {
+    pub skip: &'a [usize],
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkActionArgs<'a> {
+    pub ids: &'a [usize],
+    pub action: BulkAction,
+}
+
+/// The todos to merge, the one to keep first.
+#[derive(Debug, Serialize)]
+pub struct MergeTodosArgs<'a> {
+    pub ids: &'a [usize],
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenerateReportArgs<'a> {
+    /// `YYYY-MM-DD`, like `to`
+    pub from: &'a str,
+    pub to: &'a str,
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToggleTodoArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditTodoArgs<'a> {
+    pub id: usize,
+    pub text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitTodoArgs<'a> {
+    pub id: usize,
+    /// One new todo per line
+    pub lines: &'a [String],
+    pub original: todotxt::SplitOriginal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlanTodosArgs<'a> {
+    pub ids: &'a [usize],
+    pub field: todotxt::plan::PlanField,
+    /// `None` clears the date, back to the backlog
+    pub date: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssignTodoArgs<'a> {
+    pub id: usize,
+    pub assignee: Option<&'a str>,
+}
+
+/// A `+project`, `@context` or `key:value` tag added to or removed from
+/// the todo `id`.
+#[derive(Debug, Serialize)]
+pub struct TodoTagArgs<'a> {
+    pub id: usize,
+    pub tag: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteTodoArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetActivityArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetLinksArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartFocusArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenInEditorArgs {
+    /// The todo to edit on its own, or `None` for the whole file
+    pub id: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevealInEditorArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenUrlArgs<'a> {
+    pub url: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetChecklistArgs {
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetChecklistArgs<'a> {
+    pub id: usize,
+    pub items: &'a [todotxt::checklist::CheckItem],
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreTodoArgs<'a> {
+    pub line: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UndoActivityArgs<'a> {
+    pub entry: &'a todotxt::activity::Entry,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UndoActivitiesArgs<'a> {
+    pub entries: &'a [todotxt::activity::Entry],
+}
+
+/// Arguments of both `diff_backup` and `restore_backup`.
+#[derive(Debug, Serialize)]
+pub struct BackupArgs<'a> {
+    pub name: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreLinesArgs<'a> {
+    pub lines: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewShiftDatesArgs<'a> {
+    pub filter: &'a Filter,
+    pub days: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShiftDatesArgs<'a> {
+    pub filter: &'a Filter,
+    pub days: i64,
+    pub skip: &'a [usize],
+}
+
+/// Arguments of both `preview_replace` and `replace_in_tasks`.
+#[derive(Debug, Serialize)]
+pub struct ReplaceInTasksArgs<'a> {
+    pub pattern: &'a str,
+    pub regex: bool,
+    pub replacement: &'a str,
+    pub filter: &'a Filter,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SaveDraftArgs<'a> {
+    pub draft: &'a Draft,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SaveRawArgs<'a> {
+    pub content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SaveSettingsArgs<'a> {
+    pub settings: &'a Settings,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportConfigArgs<'a> {
+    /// A file written by "Export settings"
+    pub path: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetProjectSeparatorArgs<'a> {
+    pub separator: &'a str,
+    pub migrate: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncNowArgs<'a> {
+    pub address: &'a str,
+}
+
+/// Names a command or sidebar view of a plugin, by the function it exports.
+#[derive(Debug, Serialize)]
+pub struct PluginExportArgs<'a> {
+    pub plugin: &'a str,
+    pub export: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetStarredArgs {
+    pub id: usize,
+    pub starred: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetFlagArgs {
+    pub id: usize,
+    /// `None` takes the flag off
+    pub flag: Option<Flag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReorderTodoArgs<'a> {
+    /// The todos of the view in their new order
+    pub ids: &'a [usize],
+    /// The one that was moved
+    pub id: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAutoDoneArgs {
+    pub id: usize,
+    pub auto_done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefileTodoArgs<'a> {
+    pub id: usize,
+    pub tags: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewNormalizeArgs<'a> {
+    pub settings: &'a settings::NormalizeSettings,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecentlyChangedArgs {
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelJobArgs {
+    pub id: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: usize, raw: &str) -> Todo {
+        Todo {
+            id,
+            line: id + 1,
+            subject: raw.to_string(),
+            raw: raw.to_string(),
+            finished: false,
+            priority: 0,
+            creation_date: None,
+            due_date: None,
+            due_time: None,
+            threshold_date: None,
+            assignee: None,
+            estimate: None,
+            starred: false,
+            auto_done: false,
+            flag: None,
+            contexts: Vec::new(),
+            projects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn changes_replay_to_the_new_list() {
+        let before = vec![
+            todo(0, "Call Bob"),
+            todo(1, "Buy milk"),
+            todo(2, "Water plants"),
+        ];
+        let after = vec![todo(0, "Call Bob"), todo(1, "Water plants")];
+        let changes = TodoChange::between(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                TodoChange::Updated(todo(1, "Water plants")),
+                TodoChange::Removed(2)
+            ]
+        );
+
+        let mut replayed = before.clone();
+        for change in changes {
+            change.apply(&mut replayed);
+        }
+        assert_eq!(replayed, after);
+
+        let mut replayed = after.clone();
+        for change in TodoChange::between(&after, &before) {
+            change.apply(&mut replayed);
+        }
+        assert_eq!(replayed, before);
+    }
+}
@@ -0,0 +1,911 @@
+//! The app settings, stored as JSON by the backend and edited by the frontend.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use todotxt::filter::{Due, Filter, Status};
+use todotxt::geo::ContextLocation;
+use todotxt::group::GroupBy;
+use todotxt::plugin::Capability;
+use todotxt::reminders::{self, StandingReminder};
+use todotxt::rules::WipLimit;
+use todotxt::sort::SortBy;
+use todotxt::{day, normalize, Completion, Date, TaskDefaults, TodoItem};
+
+pub const DEFAULT_PROJECT_SEPARATOR: &str = "---";
+
+/// The version of the settings file this build writes. Older files are
+/// upgraded by the backend's migrations at startup.
+pub const SETTINGS_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Compact,
+    #[default]
+    Comfortable,
+}
+
+/// The colors of priority and tag badges, see `gui/input.css`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Tells colors apart without relying on red and green
+    Deuteranopia,
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [
+        Palette::Default,
+        Palette::Deuteranopia,
+        Palette::HighContrast,
+    ];
+
+    /// The name of the palette in the stylesheet.
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Palette::Default => "Default",
+            Palette::Deuteranopia => "Color-blind safe",
+            Palette::HighContrast => "High contrast",
+        }
+    }
+}
+
+/// Which todos the project tree badges count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountMode {
+    #[default]
+    All,
+    Pending,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub density: Density,
+    pub show_creation_date: bool,
+    pub show_due_date: bool,
+    pub show_raw: bool,
+    pub show_id: bool,
+    pub project_count: CountMode,
+    /// Show lorem ipsum instead of what tasks say, also in exports, so the
+    /// app can be shown without giving away the list. Editing is off meanwhile.
+    pub demo_mode: bool,
+    /// Show bold, code and links in task text, see [`todotxt::markdown`]
+    pub render_markdown: bool,
+    /// Show a checkmark when a task is checked off
+    pub celebrate: bool,
+    /// Play a chime when a task is checked off
+    pub completion_sound: bool,
+    pub palette: Palette,
+    /// Size of the text and everything measured by it, in percent
+    pub zoom: u32,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            density: Density::Comfortable,
+            show_creation_date: false,
+            show_due_date: true,
+            show_raw: false,
+            show_id: false,
+            project_count: CountMode::All,
+            demo_mode: false,
+            render_markdown: false,
+            celebrate: true,
+            completion_sound: false,
+            palette: Palette::Default,
+            zoom: 100,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub const ZOOM_MIN: u32 = 50;
+    pub const ZOOM_MAX: u32 = 200;
+    /// How much Ctrl+= and Ctrl+- change the zoom
+    pub const ZOOM_STEP: u32 = 10;
+
+    /// Zooms in by `steps`, or out if negative, staying within the limits.
+    pub fn zoom_by(&mut self, steps: i32) {
+        let zoom = self.zoom as i64 + steps as i64 * Self::ZOOM_STEP as i64;
+        self.zoom = zoom.clamp(Self::ZOOM_MIN as i64, Self::ZOOM_MAX as i64) as u32;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub display: DisplaySettings,
+    /// Path of a CSS file applied on top of the built-in styles
+    pub custom_css: Option<String>,
+    /// Number of completions per day to aim for; `None` hides the goal.
+    pub daily_goal: Option<usize>,
+    /// Minutes of estimated work that fit into a day, more is flagged as
+    /// overbooked; `None` turns the warning off.
+    pub workday_minutes: Option<u32>,
+    /// Splits project and context names into a hierarchy, e.g. `+home---garden`.
+    pub project_separator: String,
+    /// Colors picked for tags, keyed by the tag including its sigil, e.g. `+work`.
+    /// Tags without an entry get a color derived from their name.
+    pub tag_colors: BTreeMap<String, String>,
+    pub sync: SyncSettings,
+    pub hooks: Vec<Hook>,
+    /// Capabilities granted to each plugin, keyed by plugin name
+    pub plugin_grants: BTreeMap<String, Vec<Capability>>,
+    /// Least severe messages written to the log file, applied on the next start
+    pub log_level: LogLevel,
+    pub snippets: Vec<Snippet>,
+    /// Applied to every task added through the app
+    pub task_defaults: TaskDefaults,
+    /// Text shared from other apps goes to inbox.txt to be refiled later,
+    /// rather than opening the add dialog
+    pub capture_to_inbox: bool,
+    pub watched_lists: Vec<WatchedList>,
+    pub feed: FeedSettings,
+    pub calendar: CalendarSettings,
+    /// Where contexts can be worked on, for the Here view
+    pub context_locations: Vec<ContextLocation>,
+    pub snapshot: SnapshotSettings,
+    pub reminders: ReminderSettings,
+    /// Move completions from past years out of done.txt into yearly files
+    /// (`done-2024.txt`) at every start
+    pub rotate_done: bool,
+    /// Pending tasks created more days ago than this are listed as stale
+    pub stale_after_days: u32,
+    /// When the day ends, as `HH:MM`; e.g. `03:00` keeps a task finished at
+    /// 01:00 on the day before. Due, creation and completion dates follow it.
+    pub day_ends: String,
+    /// What happens to a task when it's checked off
+    pub completion: Completion,
+    pub confirm: ConfirmSettings,
+    pub normalize: NormalizeSettings,
+    /// Caps on pending tasks per context or project, see [`todotxt::rules`]
+    pub wip_limits: Vec<WipLimit>,
+    pub group: GroupSettings,
+    pub sort: SortSettings,
+    pub focus: FocusSettings,
+    pub overlay: OverlaySettings,
+    pub keymap: Keymap,
+    /// Opens todo.txt at a todo's line for "Reveal in editor": a URI like
+    /// `vscode://file/{path}:{line}`, or a command like `gvim +{line} {path}`
+    pub reveal_template: String,
+    /// See [`SETTINGS_VERSION`]
+    pub version: u32,
+    /// Let migrations also rewrite todo.txt when one of its conventions
+    /// changes; a backup is kept
+    pub migrate_todo_file: bool,
+}
+
+/// Ready-made [`Settings::reveal_template`]s, by editor.
+pub const REVEAL_TEMPLATES: [(&str, &str); 4] = [
+    ("VS Code", "vscode://file/{path}:{line}"),
+    ("VSCodium", "vscodium://file/{path}:{line}"),
+    ("Sublime Text", "subl://open?url=file://{path}&line={line}"),
+    ("gVim", "gvim +{line} {path}"),
+];
+
+/// The overlay: a small borderless window with the pending todos that
+/// stays on top of other windows, e.g. in a corner of the screen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// How opaque its background is, in percent
+    pub opacity: u32,
+    /// Let clicks through to the windows below; it can't be clicked then,
+    /// so this is turned back off from the main window
+    pub click_through: bool,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            opacity: 85,
+            click_through: false,
+        }
+    }
+}
+
+/// Timed sessions of work on one task, counted down in the tray.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusSettings {
+    /// How long a session lasts
+    pub minutes: u32,
+    /// Turn on the system's Do Not Disturb mode during a session, where the
+    /// platform lets the app
+    pub do_not_disturb: bool,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            minutes: 25,
+            do_not_disturb: false,
+        }
+    }
+}
+
+/// Headers the list is split under, see [`todotxt::group`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupSettings {
+    pub by: GroupBy,
+    /// Priority letters in the order their groups are shown, e.g. `A`
+    pub priority_order: Vec<String>,
+    /// Projects in the order their groups are shown, with the `+`
+    pub project_order: Vec<String>,
+    /// Contexts in the order their groups are shown, with the `@`, e.g. a
+    /// sequence of statuses like `@todo @doing @review`
+    pub context_order: Vec<String>,
+}
+
+impl GroupSettings {
+    /// The order picked for the groups of [`GroupSettings::by`]; groups it
+    /// doesn't name follow by name.
+    pub fn order(&self) -> &[String] {
+        match self.by {
+            GroupBy::None => &[],
+            GroupBy::Priority => &self.priority_order,
+            GroupBy::Project => &self.project_order,
+            GroupBy::Context => &self.context_order,
+        }
+    }
+
+    pub fn order_mut(&mut self) -> Option<&mut Vec<String>> {
+        match self.by {
+            GroupBy::None => None,
+            GroupBy::Priority => Some(&mut self.priority_order),
+            GroupBy::Project => Some(&mut self.project_order),
+            GroupBy::Context => Some(&mut self.context_order),
+        }
+    }
+}
+
+/// The order of the list, see [`todotxt::sort`]. Starred tasks stay on top
+/// and groups keep it within them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortSettings {
+    pub by: SortBy,
+    pub descending: bool,
+}
+
+/// Tasks that take a second click to check off, so critical ones aren't
+/// completed by accident.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmSettings {
+    pub enabled: bool,
+    /// Tasks with priority A need confirming
+    pub priority_a: bool,
+    /// Tasks matching this need confirming too
+    pub filter: Option<Filter>,
+}
+
+impl Default for ConfirmSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            priority_a: true,
+            filter: None,
+        }
+    }
+}
+
+impl ConfirmSettings {
+    /// Whether checking off `item` needs confirming.
+    pub fn applies(&self, item: &TodoItem, separator: &str, today: Date) -> bool {
+        self.enabled
+            && ((self.priority_a && item.priority() == 0)
+                || self
+                    .filter
+                    .as_ref()
+                    .is_some_and(|filter| filter.matches(item, separator, today)))
+    }
+}
+
+/// Tidying up lines whenever the list is saved, see [`todotxt::normalize`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizeSettings {
+    pub enabled: bool,
+    /// Also move projects and contexts behind the text
+    pub order_tags: bool,
+}
+
+impl NormalizeSettings {
+    pub fn options(&self) -> normalize::Options {
+        normalize::Options {
+            order_tags: self.order_tags,
+        }
+    }
+}
+
+/// An Atom feed of the todos matching `filter`, rewritten on every change so
+/// feed readers and dashboards can subscribe to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedSettings {
+    pub enabled: bool,
+    /// Where the feed file goes, e.g. a folder served by a web server
+    pub path: String,
+    pub filter: Filter,
+}
+
+/// A JSON summary of the list for desktop widgets, rewritten on every
+/// change, see `todotxt::snapshot::Snapshot`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotSettings {
+    pub enabled: bool,
+    pub path: String,
+    /// Number of tasks listed
+    pub limit: usize,
+    /// Which tasks are listed
+    pub filter: Filter,
+    pub include_counts: bool,
+    pub include_next_due: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    /// Time of day reminders for tasks due that day go out, as `HH:MM`
+    pub time: String,
+    /// Hold back reminders from `quiet_start` to `quiet_end`; they go out
+    /// together once quiet hours are over.
+    pub quiet_hours: bool,
+    pub quiet_start: String,
+    pub quiet_end: String,
+    /// Projects never reminded of, without the `+`
+    pub muted_projects: Vec<String>,
+    /// Only remind of tasks with at least this priority
+    pub min_priority: Option<char>,
+    /// Reminders independent of tasks, sent even if `enabled` is off
+    pub standing: Vec<StandingReminder>,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "09:00".to_string(),
+            quiet_hours: false,
+            quiet_start: "22:00".to_string(),
+            quiet_end: "08:00".to_string(),
+            muted_projects: Vec::new(),
+            min_priority: None,
+            standing: Vec::new(),
+        }
+    }
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: String::new(),
+            limit: 5,
+            filter: Filter::default(),
+            include_counts: true,
+            include_next_due: true,
+        }
+    }
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: String::new(),
+            filter: Filter {
+                status: Status::Pending,
+                due: Some(Due::Today),
+                ..Filter::default()
+            },
+        }
+    }
+}
+
+/// Someone else's todo.txt, e.g. a partner's synced file, shown next to the
+/// user's own list but never written to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchedList {
+    pub name: String,
+    pub path: String,
+}
+
+/// Typing `trigger` at the end of a todo input replaces it with `expansion`,
+/// e.g. `;;w` with `@work due:friday`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub trigger: String,
+    pub expansion: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+pub const DEFAULT_SYNC_PORT: u16 = 47811;
+pub const DEFAULT_CALENDAR_PORT: u16 = 47812;
+
+/// A device to sync with, e.g. `desktop.local:47811`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncDevice {
+    pub name: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// Let paired devices on the network sync with this one
+    pub enabled: bool,
+    pub port: u16,
+    /// Shared secret; devices only sync when theirs match
+    pub pairing_code: String,
+    pub devices: Vec<SyncDevice>,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_SYNC_PORT,
+            pairing_code: String::new(),
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// The shortest pairing code sync runs with. A device posing as a paired
+/// one gets a proof of the code to guess it from offline.
+pub const MIN_PAIRING_CODE: usize = 12;
+
+impl SyncSettings {
+    /// Why sync can't run with the pairing code, if it can't.
+    pub fn pairing_code_error(&self) -> Option<String> {
+        match self.pairing_code.chars().count() {
+            0 => Some("Set a pairing code before syncing".to_string()),
+            n if n < MIN_PAIRING_CODE => Some(format!(
+                "The pairing code needs at least {MIN_PAIRING_CODE} characters"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// A calendar of due dates that calendar apps can subscribe to, served at
+/// `webcal://<host>:<port>/calendar.ics?token=<token>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Whether other devices may connect; otherwise only this computer can
+    pub network: bool,
+    /// Requests without it or one of [`CalendarSettings::tokens`] are
+    /// turned away. It may only read.
+    pub token: String,
+    /// Tokens for other clients of the server, e.g. a dashboard, each
+    /// limited to what it needs
+    pub tokens: Vec<ApiToken>,
+    /// Directory of the web viewer's build, served at `/`; empty serves
+    /// no files
+    pub web_dir: String,
+}
+
+impl Default for CalendarSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_CALENDAR_PORT,
+            network: false,
+            token: String::new(),
+            tokens: Vec::new(),
+            web_dir: String::new(),
+        }
+    }
+}
+
+impl CalendarSettings {
+    /// Whether any token lets clients in.
+    pub fn has_tokens(&self) -> bool {
+        !self.token.is_empty() || self.tokens.iter().any(|t| !t.token.is_empty())
+    }
+
+    /// Whether a request with `token` may use `endpoint`. Tokens are
+    /// compared in constant time, so how long it takes doesn't tell how
+    /// much of one a guess got right.
+    pub fn authorize(&self, token: &str, endpoint: Endpoint) -> bool {
+        if token.is_empty() {
+            return false;
+        }
+        let main = same_token(token, &self.token);
+        self.tokens.iter().fold(main, |allowed, t| {
+            allowed | (same_token(token, &t.token) & t.allows(endpoint))
+        })
+    }
+}
+
+/// Whether `a` and `b` are equal, looking at every byte either way.
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The parts of the server a token can be allowed to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endpoint {
+    /// `GET /calendar.ics`
+    Calendar,
+    /// `GET /todos.json`, read by the web viewer
+    Todos,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 2] = [Endpoint::Calendar, Endpoint::Todos];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Endpoint::Calendar => "Calendar",
+            Endpoint::Todos => "Todos (web viewer)",
+        }
+    }
+}
+
+/// A token for the server and what it's allowed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiToken {
+    /// Who it's for, e.g. `Kitchen dashboard`
+    pub name: String,
+    pub token: String,
+    /// The endpoints it may use, none if empty
+    pub endpoints: Vec<Endpoint>,
+}
+
+impl ApiToken {
+    pub fn allows(&self, endpoint: Endpoint) -> bool {
+        self.endpoints.contains(&endpoint)
+    }
+}
+
+/// What a keyboard shortcut does, see [`Keymap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShortcutAction {
+    OpenPalette,
+    AddTodo,
+    FindReplace,
+    PickTodo,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    GoToTodos,
+    GoToRaw,
+    GoToWeek,
+    GoToSettings,
+    RestoreDeleted,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 12] = [
+        ShortcutAction::OpenPalette,
+        ShortcutAction::AddTodo,
+        ShortcutAction::FindReplace,
+        ShortcutAction::PickTodo,
+        ShortcutAction::ZoomIn,
+        ShortcutAction::ZoomOut,
+        ShortcutAction::ResetZoom,
+        ShortcutAction::GoToTodos,
+        ShortcutAction::GoToRaw,
+        ShortcutAction::GoToWeek,
+        ShortcutAction::GoToSettings,
+        ShortcutAction::RestoreDeleted,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShortcutAction::OpenPalette => "Open the command palette",
+            ShortcutAction::AddTodo => "Add a todo",
+            ShortcutAction::FindReplace => "Find and replace",
+            ShortcutAction::PickTodo => "What should I do now?",
+            ShortcutAction::ZoomIn => "Zoom in",
+            ShortcutAction::ZoomOut => "Zoom out",
+            ShortcutAction::ResetZoom => "Reset zoom",
+            ShortcutAction::GoToTodos => "Go to todos",
+            ShortcutAction::GoToRaw => "Go to raw editor",
+            ShortcutAction::GoToWeek => "Plan the week",
+            ShortcutAction::GoToSettings => "Go to settings",
+            ShortcutAction::RestoreDeleted => "Restore the last deleted todo",
+        }
+    }
+
+    /// The shortcut it has unless the keymap changes it; empty for none.
+    pub fn default_keys(self) -> &'static str {
+        match self {
+            ShortcutAction::OpenPalette => "Ctrl+K",
+            ShortcutAction::AddTodo => "Ctrl+N",
+            ShortcutAction::FindReplace => "Ctrl+H",
+            ShortcutAction::PickTodo => "",
+            ShortcutAction::ZoomIn => "Ctrl+=",
+            ShortcutAction::ZoomOut => "Ctrl+-",
+            ShortcutAction::ResetZoom => "Ctrl+0",
+            ShortcutAction::GoToTodos => "Alt+1",
+            ShortcutAction::GoToRaw => "Alt+2",
+            ShortcutAction::GoToWeek => "Alt+3",
+            ShortcutAction::GoToSettings => "Ctrl+,",
+            ShortcutAction::RestoreDeleted => "U",
+        }
+    }
+}
+
+/// The keyboard shortcuts, written like `Ctrl+Shift+K`, see
+/// [`shortcut_keys`]. Ctrl also stands for Cmd on macOS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    /// Shortcuts changed from [`ShortcutAction::default_keys`]; an empty
+    /// one leaves the action without a shortcut
+    pub bindings: BTreeMap<ShortcutAction, String>,
+}
+
+impl Keymap {
+    /// The shortcut of `action`, empty if it has none.
+    pub fn keys(&self, action: ShortcutAction) -> &str {
+        self.bindings
+            .get(&action)
+            .map_or(action.default_keys(), String::as_str)
+    }
+
+    /// Gives `action` the shortcut `keys`, forgetting the change if it's
+    /// the default.
+    pub fn bind(&mut self, action: ShortcutAction, keys: &str) {
+        if keys == action.default_keys() {
+            self.bindings.remove(&action);
+        } else {
+            self.bindings.insert(action, keys.to_string());
+        }
+    }
+
+    /// The action the shortcut `keys` runs, if any.
+    pub fn action(&self, keys: &str) -> Option<ShortcutAction> {
+        ShortcutAction::ALL
+            .into_iter()
+            .find(|&action| same_keys(self.keys(action), keys))
+    }
+
+    /// The other actions with the same shortcut as `action`; only the first
+    /// of them in [`ShortcutAction::ALL`] runs.
+    pub fn conflicts(&self, action: ShortcutAction) -> Vec<ShortcutAction> {
+        let keys = self.keys(action);
+        ShortcutAction::ALL
+            .into_iter()
+            .filter(|&other| other != action && same_keys(self.keys(other), keys))
+            .collect()
+    }
+}
+
+fn same_keys(a: &str, b: &str) -> bool {
+    !a.is_empty() && a.eq_ignore_ascii_case(b)
+}
+
+/// How a key pressed with the modifiers is written in a [`Keymap`], e.g.
+/// `Ctrl+Shift+K`; `key` is what the browser reports for it. `None` for a
+/// modifier on its own.
+pub fn shortcut_keys(ctrl: bool, alt: bool, shift: bool, key: &str) -> Option<String> {
+    let key = match key {
+        "Control" | "Alt" | "Shift" | "Meta" | "AltGraph" | "" => return None,
+        " " => "Space".to_string(),
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key => key.to_string(),
+    };
+    let modifiers = [(ctrl, "Ctrl+"), (alt, "Alt+"), (shift, "Shift+")];
+    let mut keys: String = modifiers
+        .into_iter()
+        .filter(|(pressed, _)| *pressed)
+        .map(|(_, name)| name)
+        .collect();
+    keys.push_str(&key);
+    Some(keys)
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            display: DisplaySettings::default(),
+            custom_css: None,
+            daily_goal: None,
+            workday_minutes: Some(8 * 60),
+            project_separator: DEFAULT_PROJECT_SEPARATOR.to_string(),
+            tag_colors: BTreeMap::new(),
+            sync: SyncSettings::default(),
+            hooks: Vec::new(),
+            plugin_grants: BTreeMap::new(),
+            log_level: LogLevel::default(),
+            snippets: Vec::new(),
+            task_defaults: TaskDefaults::default(),
+            capture_to_inbox: false,
+            watched_lists: Vec::new(),
+            feed: FeedSettings::default(),
+            calendar: CalendarSettings::default(),
+            context_locations: Vec::new(),
+            snapshot: SnapshotSettings::default(),
+            reminders: ReminderSettings::default(),
+            rotate_done: false,
+            stale_after_days: 30,
+            day_ends: "00:00".to_string(),
+            completion: Completion::default(),
+            confirm: ConfirmSettings::default(),
+            normalize: NormalizeSettings::default(),
+            wip_limits: Vec::new(),
+            group: GroupSettings::default(),
+            sort: SortSettings::default(),
+            focus: FocusSettings::default(),
+            overlay: OverlaySettings::default(),
+            keymap: Keymap::default(),
+            reveal_template: REVEAL_TEMPLATES[0].1.to_string(),
+            version: SETTINGS_VERSION,
+            migrate_todo_file: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Today's date with the day ending at `day_ends`, or at midnight if
+    /// that isn't a valid time.
+    pub fn today(&self) -> Date {
+        let day_ends = reminders::parse_time(&self.day_ends).unwrap_or_default();
+        day::today(day_ends)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// Payload: the added task
+    TaskAdded,
+    /// Payload: the completed task
+    TaskCompleted,
+    /// Payload: the changes about to be saved. A hook exiting with an error
+    /// stops the save.
+    BeforeSave,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 3] = [
+        HookEvent::TaskAdded,
+        HookEvent::TaskCompleted,
+        HookEvent::BeforeSave,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::TaskAdded => "task-added",
+            HookEvent::TaskCompleted => "task-completed",
+            HookEvent::BeforeSave => "before-save",
+        }
+    }
+}
+
+/// A program run on `event`, see the backend's `hooks` module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hook {
+    pub event: HookEvent,
+    /// Path to the executable
+    pub command: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_only_reach_their_endpoints() {
+        let mut calendar = CalendarSettings {
+            token: "legacy".to_string(),
+            ..CalendarSettings::default()
+        };
+        assert!(calendar.authorize("legacy", Endpoint::Calendar));
+        assert!(!calendar.authorize("", Endpoint::Calendar));
+        assert!(!calendar.authorize("dashboard", Endpoint::Calendar));
+
+        calendar.tokens.push(ApiToken {
+            name: "Dashboard".to_string(),
+            token: "dashboard".to_string(),
+            endpoints: vec![Endpoint::Calendar],
+        });
+        assert!(calendar.authorize("dashboard", Endpoint::Calendar));
+        assert!(!calendar.authorize("dashboard", Endpoint::Todos));
+        // Unticking every endpoint takes away all access
+        calendar.tokens[0].endpoints.clear();
+        assert!(!calendar.authorize("dashboard", Endpoint::Calendar));
+    }
+
+    #[test]
+    fn keymap_finds_conflicts() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.action("ctrl+k"), Some(ShortcutAction::OpenPalette));
+        assert!(keymap.conflicts(ShortcutAction::PickTodo).is_empty());
+
+        keymap.bind(ShortcutAction::PickTodo, "Ctrl+K");
+        assert_eq!(
+            keymap.conflicts(ShortcutAction::PickTodo),
+            vec![ShortcutAction::OpenPalette]
+        );
+        keymap.bind(ShortcutAction::OpenPalette, "");
+        assert_eq!(keymap.action("Ctrl+K"), Some(ShortcutAction::PickTodo));
+        keymap.bind(ShortcutAction::OpenPalette, "Ctrl+K");
+        assert!(!keymap.bindings.contains_key(&ShortcutAction::OpenPalette));
+
+        assert_eq!(
+            shortcut_keys(true, false, true, "p").as_deref(),
+            Some("Ctrl+Shift+P")
+        );
+        assert_eq!(
+            shortcut_keys(false, true, false, " ").as_deref(),
+            Some("Alt+Space")
+        );
+        assert_eq!(shortcut_keys(true, false, false, "Control"), None);
+    }
+
+    #[test]
+    fn zoom_stays_within_limits() {
+        let mut display = DisplaySettings::default();
+        display.zoom_by(2);
+        assert_eq!(display.zoom, 120);
+        display.zoom_by(-100);
+        assert_eq!(display.zoom, DisplaySettings::ZOOM_MIN);
+        display.zoom = 195;
+        display.zoom_by(1);
+        assert_eq!(display.zoom, DisplaySettings::ZOOM_MAX);
+    }
+}